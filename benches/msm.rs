@@ -0,0 +1,51 @@
+//! Compares Pippenger's MSM and wNAF scalar multiplication against the naive approach.
+//!
+//! Run with `cargo bench --bench msm`.
+#![feature(test)]
+extern crate test;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use ronkathon::{
+  algebra::{field::prime::PlutoScalarField, group::FiniteCyclicGroup},
+  curve::{
+    msm::{msm, naive_msm, wnaf_mul, wnaf_window_size},
+    pluto_curve::PlutoExtendedCurve,
+    AffinePoint,
+  },
+};
+use test::{black_box, Bencher};
+
+const SIZE: usize = 64;
+
+fn instance() -> (Vec<AffinePoint<PlutoExtendedCurve>>, Vec<PlutoScalarField>) {
+  let mut rng = StdRng::seed_from_u64(0);
+  let g = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+  let bases = (0..SIZE).map(|_| g * PlutoScalarField::new(rng.gen_range(0..17))).collect();
+  let scalars = (0..SIZE).map(|_| PlutoScalarField::new(rng.gen_range(0..17))).collect();
+  (bases, scalars)
+}
+
+#[bench]
+fn msm_naive(b: &mut Bencher) {
+  let (bases, scalars) = instance();
+  b.iter(|| naive_msm(black_box(&bases), black_box(&scalars)));
+}
+
+#[bench]
+fn msm_pippenger(b: &mut Bencher) {
+  let (bases, scalars) = instance();
+  b.iter(|| msm(black_box(&bases), black_box(&scalars)));
+}
+
+#[bench]
+fn scalar_mul_naive(b: &mut Bencher) {
+  let g = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+  b.iter(|| black_box(g) * black_box(PlutoScalarField::new(15)));
+}
+
+#[bench]
+fn scalar_mul_wnaf(b: &mut Bencher) {
+  let g = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+  let w = wnaf_window_size(5);
+  b.iter(|| wnaf_mul(black_box(g), black_box(PlutoScalarField::new(15)), w));
+}
@@ -127,6 +127,22 @@ impl FiniteField for PlutoBaseFieldExtension {
   const PRIMITIVE_ELEMENT: Self = Self::new([PlutoBaseField::new(14), PlutoBaseField::new(9)]);
 }
 
+impl SqrtField for PlutoBaseFieldExtension {
+  fn is_square(&self) -> bool { *self == Self::ZERO || self.euler_criterion() }
+
+  fn sqrt(&self) -> Option<(Self, Self)> {
+    if !self.is_square() {
+      return None;
+    }
+    PlutoBaseFieldExtension::sqrt(self)
+  }
+
+  fn sgn0(&self) -> bool {
+    let (sign_0, zero_0) = (self.coeffs[0].sgn0(), self.coeffs[0] == PlutoBaseField::ZERO);
+    sign_0 || (zero_0 && self.coeffs[1].sgn0())
+  }
+}
+
 impl<const N: usize, const P: usize> Distribution<GaloisField<N, P>> for Standard {
   #[inline]
   fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> GaloisField<N, P> {
@@ -164,6 +164,25 @@ impl<const N: usize, const P: usize> GaloisField<N, P> {
   pub const fn new(coeffs: [PrimeField<P>; N]) -> Self { Self { coeffs } }
 }
 
+/// Elements are encoded as the concatenation of the encodings of their coefficients, highest degree
+/// first.
+impl<const N: usize, const P: usize> FieldBytes for GaloisField<N, P> {
+  const BYTES: usize = N * PrimeField::<P>::BYTES;
+
+  fn to_bytes(&self) -> Vec<u8> { self.coeffs.iter().rev().flat_map(|c| c.to_bytes()).collect() }
+
+  fn from_bytes(bytes: &[u8]) -> Option<Self> {
+    if bytes.len() != Self::BYTES {
+      return None;
+    }
+    let mut coeffs = [PrimeField::<P>::ZERO; N];
+    for (coeff, chunk) in coeffs.iter_mut().rev().zip(bytes.chunks(PrimeField::<P>::BYTES)) {
+      *coeff = PrimeField::<P>::from_bytes(chunk)?;
+    }
+    Some(Self { coeffs })
+  }
+}
+
 /// Convert from a [`FiniteField`] element into the [`GaloisField`] field element in the natural
 /// way.
 impl<const N: usize, const P: usize> From<PrimeField<P>> for GaloisField<N, P> {
@@ -50,6 +50,34 @@ pub trait Field:
   fn pow(self, power: usize) -> Self;
 }
 
+/// A [`FiniteField`] in which square roots can be computed.
+pub trait SqrtField: FiniteField {
+  /// Returns `true` if the element is a square in the field, i.e. a quadratic residue or zero.
+  fn is_square(&self) -> bool;
+
+  /// Returns both square roots `(r, -r)` of the element, ordered so that `r` is the smaller one, or
+  /// `None` if the element is not a square.
+  fn sqrt(&self) -> Option<(Self, Self)>;
+
+  /// The "sign" of an element as defined in [RFC 9380 §4.1](https://www.rfc-editor.org/rfc/rfc9380.html#section-4.1):
+  /// the parity of the first non-zero coefficient. For non-zero `x`, exactly one of `x` and `-x`
+  /// has sign `1`, which makes it a convenient way to pick one of the two square roots.
+  fn sgn0(&self) -> bool;
+}
+
+/// A canonical fixed length big-endian byte encoding of field elements.
+pub trait FieldBytes: Sized {
+  /// Number of bytes in the encoding of a single element.
+  const BYTES: usize;
+
+  /// Encodes the element as exactly [`FieldBytes::BYTES`] bytes.
+  fn to_bytes(&self) -> Vec<u8>;
+
+  /// Decodes an element, returning `None` if `bytes` has the wrong length or is not the canonical
+  /// encoding of an element.
+  fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
 /// fields with a finite number of elements.
 pub trait FiniteField: Finite + Field {
   /// Returns a multiplicative generator of the field.
@@ -34,7 +34,9 @@ impl<const P: usize> SubAssign for PrimeField<P> {
 impl<const P: usize> Mul for PrimeField<P> {
   type Output = Self;
 
-  fn mul(self, rhs: Self) -> Self { Self { value: (self.value * rhs.value) % Self::ORDER } }
+  fn mul(self, rhs: Self) -> Self {
+    Self { value: ((self.value as u128 * rhs.value as u128) % Self::ORDER as u128) as usize }
+  }
 }
 
 impl<const P: usize> MulAssign for PrimeField<P> {
@@ -133,6 +135,16 @@ mod tests {
     assert_eq!(a * b, expected);
   }
 
+  #[test]
+  fn large_modulus() {
+    // 2^40 - 87: products of elements don't fit a `u64`
+    type F = PrimeField<1_099_511_627_689>;
+    assert_eq!(F::new(1_099_511_627_687) * F::new(1_099_511_627_686), F::new(6));
+    assert_eq!(F::new(1_099_511_627_687).inverse(), Some(F::new(549_755_813_844)));
+    assert_eq!(F::new(3).pow(1_099_511_627_688), F::ONE);
+    assert_eq!(F::new(12345).pow(1000), F::new(278_890_537_043));
+  }
+
   fn combined_arithmetic_check<const P: usize>() {
     let mut rng = rand::thread_rng();
     let x = rng.gen::<PrimeField<P>>();
@@ -79,12 +79,15 @@ impl<const P: usize> PrimeField<P> {
   pub fn euler_criterion(&self) -> bool { self.pow((P - 1) / 2).value == 1 }
 
   /// Computes the square root of a field element using the [Tonelli-Shanks algorithm](https://en.wikipedia.org/wiki/Tonelli–Shanks_algorithm).
+  /// Returns `None` if the element is not a quadratic residue.
   pub fn sqrt(&self) -> Option<(Self, Self)> {
     if *self == Self::ZERO {
       return Some((Self::ZERO, Self::ZERO));
     }
 
-    assert!(self.euler_criterion(), "Element is not a quadratic residue");
+    if !self.euler_criterion() {
+      return None;
+    }
 
     // First define the Q and S values for the prime number P.
     let q: usize;
@@ -156,15 +159,16 @@ impl<const P: usize> const Field for PrimeField<P> {
   }
 
   fn pow(self, power: usize) -> Self {
-    if power == 0 {
-      Self::ONE
-    } else if power == 1 {
-      self
-    } else if power % 2 == 0 {
-      Self::new((self.pow(power / 2).value * self.pow(power / 2).value) % Self::ORDER)
-    } else {
-      Self::new((self.pow(power / 2).value * self.pow(power / 2).value * self.value) % Self::ORDER)
+    // square and multiply, with products in `u128` so that they don't overflow for large `P`
+    let (mut base, mut power, mut result) = (self.value as u128, power, 1 % P as u128);
+    while power > 0 {
+      if power % 2 == 1 {
+        result = result * base % P as u128;
+      }
+      base = base * base % P as u128;
+      power /= 2;
     }
+    Self { value: result as usize }
   }
 }
 
@@ -173,6 +177,33 @@ impl<const P: usize> FiniteField for PrimeField<P> {
     if P == 2 { Self::ONE } else { Self::new(find_primitive_element::<P>()) };
 }
 
+impl<const P: usize> SqrtField for PrimeField<P> {
+  fn is_square(&self) -> bool { *self == Self::ZERO || self.euler_criterion() }
+
+  fn sqrt(&self) -> Option<(Self, Self)> { PrimeField::sqrt(self) }
+
+  fn sgn0(&self) -> bool { self.value % 2 == 1 }
+}
+
+impl<const P: usize> FieldBytes for PrimeField<P> {
+  const BYTES: usize = (usize::BITS - (P - 1).leading_zeros()).div_ceil(8) as usize;
+
+  fn to_bytes(&self) -> Vec<u8> {
+    let bytes = self.value.to_be_bytes();
+    bytes[bytes.len() - Self::BYTES..].to_vec()
+  }
+
+  fn from_bytes(bytes: &[u8]) -> Option<Self> {
+    if bytes.len() != Self::BYTES {
+      return None;
+    }
+    let mut buf = [0u8; std::mem::size_of::<usize>()];
+    buf[std::mem::size_of::<usize>() - Self::BYTES..].copy_from_slice(bytes);
+    let value = usize::from_be_bytes(buf);
+    (value < P).then_some(Self { value })
+  }
+}
+
 const fn is_prime(n: usize) {
   let mut i = 2;
   while i * i <= n {
@@ -0,0 +1,285 @@
+//! Compiles a circuit written in the DSL of the `compiler` module and prints its gates and
+//! selectors. Given an input file, it generates the witness and checks that it satisfies the
+//! circuit, and it can write and verify PLONK proofs.
+//!
+//! The circuit is compiled over the smallest group order that fits its rows. Proofs use an SRS
+//! computed from a known `τ`, so they are only meant for learning.
+use std::{env, fmt::Display, fs, process};
+
+use rand::thread_rng;
+use ronkathon::{
+  algebra::field::{
+    prime::{PlutoScalarField, PrimeField},
+    FiniteField,
+  },
+  compiler::{
+    parser::{parse_circuit, Circuit},
+    program::{CommonPreprocessedInput, Program},
+    witness::{parse_inputs, Witness},
+  },
+  kzg::Srs,
+  plonk::{prove, verify, Proof, VerifyingKey},
+};
+
+const USAGE: &str = "usage: ronkathon-circuit <circuit> [<inputs>] [options]
+
+Compiles the DSL circuit and prints its gates and selectors. With <inputs>, a file of
+`name = value` lines or a flat JSON object, generates the witness and checks it.
+
+options:
+  --field <17|257>  order of the field of the circuit, 17 by default
+  --prove <proof>   writes a proof of the witness to <proof> and verifies it
+  --verify <proof>  verifies <proof> for the public inputs of <inputs>
+
+Proofs are only supported over 17, for circuits of at most 4 rows.";
+
+/// Secret of the SRS of the proofs, the same as [`ronkathon::kzg::setup()`].
+const TAU: usize = 2;
+
+/// Most rows of a proof over the field of order 17: over more rows, the cosets that label the
+/// wires of the three columns overlap, see
+/// [`cosets_are_disjoint`](ronkathon::compiler::program::cosets_are_disjoint).
+const MAX_PROOF_ROWS: usize = 4;
+
+/// Arguments of the command line.
+#[derive(Debug, Default)]
+struct Options {
+  circuit: String,
+  inputs:  Option<String>,
+  field:   usize,
+  prove:   Option<String>,
+  verify:  Option<String>,
+}
+
+/// Parses the arguments after the name of the binary.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+  let mut options = Options { field: 17, ..Default::default() };
+  let mut paths = vec![];
+  while let Some(arg) = args.next() {
+    let mut value = || args.next().ok_or_else(|| format!("missing value of {}", arg));
+    match arg.as_str() {
+      "--field" =>
+        options.field = value()?.parse().map_err(|_| String::from("invalid field order"))?,
+      "--prove" => options.prove = Some(value()?),
+      "--verify" => options.verify = Some(value()?),
+      _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+      _ => paths.push(arg),
+    }
+  }
+  let mut paths = paths.into_iter();
+  options.circuit = paths.next().ok_or("missing circuit")?;
+  options.inputs = paths.next();
+  if let Some(path) = paths.next() {
+    return Err(format!("unexpected argument {}", path));
+  }
+  if (options.prove.is_some() || options.verify.is_some()) && options.inputs.is_none() {
+    return Err(String::from("proofs need an inputs file"));
+  }
+  Ok(options)
+}
+
+fn read(path: &str) -> Result<String, String> {
+  fs::read_to_string(path).map_err(|error| format!("can't read {}: {}", path, error))
+}
+
+/// Prints `rows` below `header`, with columns padded to their widest cell.
+fn print_table(header: &[&str], rows: &[Vec<String>]) {
+  let mut widths: Vec<usize> = header.iter().map(|title| title.chars().count()).collect();
+  for row in rows {
+    for (width, cell) in widths.iter_mut().zip(row) {
+      *width = (*width).max(cell.chars().count());
+    }
+  }
+  let print_row = |cells: Vec<&str>| {
+    let padded: Vec<String> =
+      cells.iter().zip(&widths).map(|(cell, &width)| format!("{:<width$}", cell)).collect();
+    println!("{}", padded.join("  ").trim_end());
+  };
+  print_row(header.to_vec());
+  for row in rows {
+    print_row(row.iter().map(String::as_str).collect());
+  }
+}
+
+/// Prints the selector and permutation polynomials of every row.
+fn print_polynomials<const N: usize, F: FiniteField + Display>(
+  cpi: &CommonPreprocessedInput<N, F>,
+) {
+  let columns = [
+    &cpi.ql,
+    &cpi.qr,
+    &cpi.qm,
+    &cpi.qo,
+    &cpi.qc,
+    &cpi.qbool,
+    &cpi.qrange,
+    &cpi.qlookup,
+    &cpi.qtable,
+    &cpi.s1,
+    &cpi.s2,
+    &cpi.s3,
+  ];
+  let rows: Vec<Vec<String>> = (0..N)
+    .map(|row| {
+      let values = columns.iter().map(|poly| poly.coefficients[row].to_string());
+      [row.to_string()].into_iter().chain(values).collect()
+    })
+    .collect();
+  let header =
+    ["row", "qL", "qR", "qM", "qO", "qC", "qBool", "qRange", "qLookup", "qTable", "S1", "S2", "S3"];
+  print_table(&header, &rows);
+}
+
+/// Compiles `circuit`, prints its tables and, unless only a proof is verified, generates the
+/// witness of the inputs and checks it.
+fn compile<'a, const N: usize, F>(
+  circuit: Circuit<'a>,
+  options: &Options,
+) -> Result<(Program<'a, N, F>, Option<Witness<N, F>>), String>
+where
+  F: FiniteField + Display,
+  usize: From<F>,
+{
+  let program = Program::<N, F>::from_circuit(circuit).map_err(|error| error.to_string())?;
+  let gates = program.constraints();
+  println!("{} gates over {} rows, in the field of order {}\n", gates.len(), N, F::ORDER);
+
+  let wire = |wire: &Option<_>| wire.as_deref().unwrap_or("-").to_string();
+  let rows: Vec<Vec<String>> = gates
+    .iter()
+    .enumerate()
+    .map(|(row, gate)| {
+      let wires = gate.wires.iter().map(wire);
+      [row.to_string()].into_iter().chain(wires).chain([gate.to_string()]).collect()
+    })
+    .collect();
+  print_table(&["row", "a", "b", "c", "constraint"], &rows);
+  println!();
+
+  match program.common_preprocessed_input() {
+    Ok(cpi) => print_polynomials(&cpi),
+    // the gates and the witness are still checked without a permutation
+    Err(error) => println!("no permutation polynomials: {}", error),
+  }
+
+  // verifying alone only needs the public inputs
+  let only_verify = options.verify.is_some() && options.prove.is_none();
+  let Some(path) = options.inputs.as_deref().filter(|_| !only_verify) else {
+    return Ok((program, None));
+  };
+  let inputs = parse_inputs::<F>(&read(path)?).map_err(|error| format!("{}: {}", path, error))?;
+  let witness = program.generate_witness(&inputs).map_err(|error| error.to_string())?;
+  println!();
+  let rows: Vec<Vec<String>> = (0..gates.len())
+    .map(|row| {
+      let values = witness.trace.iter().map(|column| column[row].to_string());
+      [row.to_string()].into_iter().chain(values).collect()
+    })
+    .collect();
+  print_table(&["row", "a", "b", "c"], &rows);
+  println!("\nthe witness satisfies all {} constraints", gates.len());
+  Ok((program, Some(witness)))
+}
+
+/// Compiles over the field of order 17, and proves or verifies.
+fn run_pluto<const N: usize>(circuit: Circuit, options: &Options) -> Result<(), String> {
+  let (program, witness) = compile::<N, PlutoScalarField>(circuit, options)?;
+  if options.prove.is_none() && options.verify.is_none() {
+    return Ok(());
+  }
+  let srs = Srs::from_secret(PlutoScalarField::new(TAU), N + 2, 1);
+  let cpi = program.common_preprocessed_input().map_err(|error| error.to_string())?;
+  let vk = VerifyingKey::new(&srs, &cpi).map_err(|error| error.to_string())?;
+
+  let mut proofs = vec![];
+  if let (Some(path), Some(witness)) = (&options.prove, &witness) {
+    let proof = prove(&srs, &cpi, &witness.public_inputs, &witness.trace, &mut thread_rng())
+      .map_err(|error| error.to_string())?;
+    fs::write(path, hex::encode(proof.to_bytes()) + "\n")
+      .map_err(|error| format!("can't write {}: {}", path, error))?;
+    println!("\nwrote proof to {}", path);
+    proofs.push(path);
+  }
+  proofs.extend(&options.verify);
+
+  let public_inputs = match witness {
+    Some(witness) => witness.public_inputs,
+    None => {
+      let path = options.inputs.as_deref().unwrap_or_default();
+      let inputs = parse_inputs::<PlutoScalarField>(&read(path)?)
+        .map_err(|error| format!("{}: {}", path, error))?;
+      let names = program.public_assignments().map_err(|error| error.to_string())?;
+      names
+        .iter()
+        .map(|name| inputs.get(name).copied().ok_or(format!("missing public input {}", name)))
+        .collect::<Result<_, _>>()?
+    },
+  };
+  for path in proofs {
+    let bytes = hex::decode(read(path)?.trim()).map_err(|error| format!("{}: {}", path, error))?;
+    let proof = Proof::from_bytes(&bytes).map_err(|error| format!("{}: {}", path, error))?;
+    if !verify(&srs, &vk, &public_inputs, &proof) {
+      return Err(format!("{}: proof is invalid", path));
+    }
+    println!("{}: proof is valid", path);
+  }
+  Ok(())
+}
+
+/// Compiles over the field of order 257, which has room for the standard gadgets.
+fn run_257<const N: usize>(circuit: Circuit, options: &Options) -> Result<(), String> {
+  compile::<N, PrimeField<257>>(circuit, options).map(|_| ())
+}
+
+/// Calls `$run::<N>` with the smallest group order `N` that fits `$rows` rows, or the last one,
+/// which reports the circuit as too large.
+macro_rules! with_group_order {
+  ($rows:expr, [$($n:literal),*; $last:literal], $run:ident $args:tt) => {
+    match $rows {
+      $(rows if rows <= $n => $run::<$n> $args,)*
+      _ => $run::<$last> $args,
+    }
+  };
+}
+
+fn run(options: &Options) -> Result<(), String> {
+  let source = read(&options.circuit)?;
+  let circuit = parse_circuit(&[&source])
+    .map_err(|error| error.diagnostic.replacen("--> ", &format!("--> {}:", options.circuit), 1))?;
+  let table_rows = circuit.tables.iter().map(|table| table.rows().len()).sum();
+  let rows = circuit.gates.len().max(table_rows);
+
+  match options.field {
+    17 => {
+      if (options.prove.is_some() || options.verify.is_some()) && rows > MAX_PROOF_ROWS {
+        return Err(format!(
+          "proofs need at most {} rows over the field of order 17, the circuit has {}",
+          MAX_PROOF_ROWS, rows
+        ));
+      }
+      with_group_order!(rows, [1, 2, 4, 8; 16], run_pluto(circuit, options))
+    },
+    257 => {
+      if options.prove.is_some() || options.verify.is_some() {
+        return Err(String::from("proofs are only supported over the field of order 17"));
+      }
+      with_group_order!(rows, [1, 2, 4, 8, 16, 32, 64, 128; 256], run_257(circuit, options))
+    },
+    order => Err(format!("unsupported field of order {}, use 17 or 257", order)),
+  }
+}
+
+fn main() {
+  let options = parse_args(env::args().skip(1)).unwrap_or_else(|error| {
+    eprintln!("error: {}\n\n{}", error, USAGE);
+    process::exit(2);
+  });
+  if let Err(error) = run(&options) {
+    // parser diagnostics already start with `error: `
+    match error.starts_with("error: ") {
+      true => eprintln!("{}", error),
+      false => eprintln!("error: {}", error),
+    }
+    process::exit(1);
+  }
+}
@@ -0,0 +1,128 @@
+//! The Bulletproofs inner product argument.
+//!
+//! For generators `G, H ∈ 𝔾^n`, `U ∈ 𝔾` and a point `P`, the prover convinces the verifier that it
+//! knows `a, b ∈ F^n` with
+//! $$
+//! P = ⟨a, G⟩ + ⟨b, H⟩ + ⟨a, b⟩ U.
+//! $$
+//! Each round halves `n`: the prover sends the cross terms
+//! - `L = ⟨a_lo, G_hi⟩ + ⟨b_hi, H_lo⟩ + ⟨a_lo, b_hi⟩ U`,
+//! - `R = ⟨a_hi, G_lo⟩ + ⟨b_lo, H_hi⟩ + ⟨a_hi, b_lo⟩ U`,
+//!
+//! and for a challenge `x` both sides fold
+//! - `a' = x a_lo + x^{-1} a_hi` and `b' = x^{-1} b_lo + x b_hi`,
+//! - `G' = x^{-1} G_lo + x G_hi` and `H' = x H_lo + x^{-1} H_hi`,
+//! - `P' = x^2 L + P + x^{-2} R`,
+//!
+//! so that `P' = ⟨a', G'⟩ + ⟨b', H'⟩ + ⟨a', b'⟩ U` still holds. After `log n` rounds the prover
+//! reveals the two remaining scalars, so the proof has `2 log n` points and two scalars.
+use super::*;
+
+/// A proof for the relation `P = ⟨a, G⟩ + ⟨b, H⟩ + ⟨a, b⟩ U`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InnerProductProof<C: BulletproofCurve> {
+  /// The left cross terms, one per round.
+  pub l: Vec<AffinePoint<C>>,
+  /// The right cross terms, one per round.
+  pub r: Vec<AffinePoint<C>>,
+  /// The folded `a`.
+  pub a: C::ScalarField,
+  /// The folded `b`.
+  pub b: C::ScalarField,
+}
+
+/// `x a_lo + y a_hi`, element-wise.
+fn fold_scalars<F: Field>(v: &[F], x: F, y: F) -> Vec<F> {
+  let (lo, hi) = v.split_at(v.len() / 2);
+  lo.iter().zip(hi).map(|(&lo, &hi)| lo * x + hi * y).collect()
+}
+
+/// `x G_lo + y G_hi`, element-wise.
+fn fold_points<C: BulletproofCurve>(
+  v: &[AffinePoint<C>],
+  x: C::ScalarField,
+  y: C::ScalarField,
+) -> Vec<AffinePoint<C>> {
+  let (lo, hi) = v.split_at(v.len() / 2);
+  lo.iter().zip(hi).map(|(&lo, &hi)| lo * x + hi * y).collect()
+}
+
+impl<C: BulletproofCurve> InnerProductProof<C> {
+  /// Proves knowledge of `a` and `b` for `P = ⟨a, G⟩ + ⟨b, H⟩ + ⟨a, b⟩ U`. The cross terms are
+  /// appended to `transcript`, which should already contain `P`.
+  ///
+  /// ## Panics
+  /// Panics if the vectors don't all have the same length, or if it is not a power of two.
+  pub fn prove(
+    transcript: &mut Transcript,
+    g: &[AffinePoint<C>],
+    h: &[AffinePoint<C>],
+    u: AffinePoint<C>,
+    a: &[C::ScalarField],
+    b: &[C::ScalarField],
+  ) -> Self {
+    let n = a.len();
+    assert!(n.is_power_of_two(), "vector length must be a power of two");
+    assert!(g.len() == n && h.len() == n && b.len() == n, "vectors must have the same length");
+    transcript.append_message(b"n", &(n as u64).to_be_bytes());
+
+    let (mut g, mut h, mut a, mut b) = (g.to_vec(), h.to_vec(), a.to_vec(), b.to_vec());
+    let (mut l_vec, mut r_vec) = (vec![], vec![]);
+    while a.len() > 1 {
+      let half = a.len() / 2;
+      let (a_lo, a_hi) = a.split_at(half);
+      let (b_lo, b_hi) = b.split_at(half);
+      let (g_lo, g_hi) = g.split_at(half);
+      let (h_lo, h_hi) = h.split_at(half);
+
+      let l = msm(g_hi, a_lo) + msm(h_lo, b_hi) + u * inner_product(a_lo, b_hi);
+      let r = msm(g_lo, a_hi) + msm(h_hi, b_lo) + u * inner_product(a_hi, b_lo);
+      transcript.append_point(b"L", &l);
+      transcript.append_point(b"R", &r);
+      l_vec.push(l);
+      r_vec.push(r);
+
+      let x: C::ScalarField = transcript.challenge_excluding(b"x", &[]);
+      let x_inv = x.inverse().unwrap();
+      a = fold_scalars(&a, x, x_inv);
+      b = fold_scalars(&b, x_inv, x);
+      g = fold_points(&g, x_inv, x);
+      h = fold_points(&h, x, x_inv);
+    }
+    Self { l: l_vec, r: r_vec, a: a[0], b: b[0] }
+  }
+
+  /// Verifies the proof for `P`, replaying the challenges from `transcript`.
+  ///
+  /// The generators are folded round by round, which costs `O(n)` group operations per round.
+  pub fn verify(
+    &self,
+    transcript: &mut Transcript,
+    g: &[AffinePoint<C>],
+    h: &[AffinePoint<C>],
+    u: AffinePoint<C>,
+    p: AffinePoint<C>,
+  ) -> bool {
+    let n = g.len();
+    if !n.is_power_of_two()
+      || h.len() != n
+      || self.l.len() != n.trailing_zeros() as usize
+      || self.r.len() != self.l.len()
+    {
+      return false;
+    }
+    transcript.append_message(b"n", &(n as u64).to_be_bytes());
+
+    let (mut g, mut h, mut p) = (g.to_vec(), h.to_vec(), p);
+    for (&l, &r) in self.l.iter().zip(&self.r) {
+      transcript.append_point(b"L", &l);
+      transcript.append_point(b"R", &r);
+      let x: C::ScalarField = transcript.challenge_excluding(b"x", &[]);
+      let x_inv = x.inverse().unwrap();
+      g = fold_points(&g, x_inv, x);
+      h = fold_points(&h, x, x_inv);
+      p = l * (x * x) + p + r * (x_inv * x_inv);
+    }
+    p == g[0] * self.a + h[0] * self.b + u * (self.a * self.b)
+  }
+}
@@ -0,0 +1,74 @@
+//! Transparent commitments and range proofs from the discrete logarithm assumption.
+//!
+//! - [`pedersen`] commits to vectors of scalars with independent generators.
+//! - [`ipa`] proves knowledge of two vectors and their inner product with `2 log n` points.
+//! - [`range`] proves that committed values lie in `[0, 2^n)`, aggregating several values into a
+//!   single proof.
+//!
+//! Every generator is derived with [`hash_to_curve`], so nobody knows a discrete logarithm relation
+//! between them and no trusted setup is needed. Challenges come from a [`Transcript`].
+#![doc = include_str!("./README.md")]
+#[cfg(test)] mod tests;
+
+pub mod ipa;
+pub mod pedersen;
+pub mod range;
+
+pub use ipa::InnerProductProof;
+pub use pedersen::PedersenGenerators;
+use rand::Rng;
+pub use range::{BulletproofGenerators, RangeProof, RangeProofError};
+
+use crate::{
+  algebra::{
+    field::{FieldBytes, FiniteField, SqrtField},
+    Finite,
+  },
+  curve::{
+    hash_to_curve::{hash_to_curve, HashToCurve, HashToField},
+    msm::msm,
+    AffinePoint,
+  },
+  hashes::transcript::Transcript,
+  Field,
+};
+
+/// Curves the proofs of this module work over: generators can be hashed to the curve, and points
+/// and scalars can be appended to a [`Transcript`].
+pub trait BulletproofCurve:
+  HashToCurve<BaseField: HashToField + SqrtField + FieldBytes, ScalarField: FieldBytes>
+{
+}
+
+impl<C> BulletproofCurve for C
+where
+  C: HashToCurve,
+  C::BaseField: HashToField + SqrtField + FieldBytes,
+  C::ScalarField: FieldBytes,
+{
+}
+
+/// Derives `count` generators from `label`, as `H(label || i)` for increasing `i`, skipping the
+/// point at infinity.
+pub fn hash_generators<C: BulletproofCurve>(label: &[u8], count: usize) -> Vec<AffinePoint<C>> {
+  (0u64..)
+    .map(|i| hash_to_curve::<C>(&[label, &i.to_be_bytes()].concat(), b"RONKATHON_BULLETPROOFS"))
+    .filter(|point| *point != AffinePoint::Infinity)
+    .take(count)
+    .collect()
+}
+
+/// `⟨a, b⟩ = Σ a_i b_i`
+pub fn inner_product<F: Field>(a: &[F], b: &[F]) -> F {
+  a.iter().zip(b).fold(F::ZERO, |acc, (&a, &b)| acc + a * b)
+}
+
+/// `[1, x, x^2, .., x^{n-1}]`
+pub fn powers<F: Field>(x: F, n: usize) -> Vec<F> {
+  std::iter::successors(Some(F::ONE), |&p| Some(p * x)).take(n).collect()
+}
+
+/// A uniformly random scalar.
+fn random_scalar<F: FiniteField, R: Rng + ?Sized>(rng: &mut R) -> F {
+  F::from(rng.gen_range(0..F::ORDER))
+}
@@ -0,0 +1,47 @@
+//! Pedersen vector commitments.
+//!
+//! With generators `G_1, .., G_n, H` of a group where discrete logarithms are hard, the commitment
+//! to `v_1, .., v_n` with blinding `r` is `C = Σ v_i G_i + r H`. It is
+//! - hiding: for a uniform `r`, `C` is uniform and reveals nothing about the values,
+//! - binding: opening `C` to two different vectors gives a discrete logarithm relation between the
+//!   generators,
+//! - additively homomorphic: the sum of two commitments commits to the sum of the vectors.
+use super::*;
+use crate::curve::CurveGroup;
+
+/// Generators `G_1, .., G_n` for the values and `H` for the blinding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PedersenGenerators<G: CurveGroup> {
+  /// The generators of the values.
+  pub g: Vec<G>,
+  /// The generator of the blinding factor.
+  pub h: G,
+}
+
+impl<G: CurveGroup> PedersenGenerators<G>
+where G::Scalar: Into<usize> + Copy
+{
+  /// Commits to `values` with the blinding factor `blinding`.
+  ///
+  /// ## Panics
+  /// Panics if there are more values than generators.
+  pub fn commit(&self, values: &[G::Scalar], blinding: G::Scalar) -> G {
+    assert!(values.len() <= self.g.len(), "not enough generators for {} values", values.len());
+    msm(&self.g[..values.len()], values) + self.h * blinding
+  }
+
+  /// Checks that `commitment` opens to `values` with `blinding`.
+  pub fn verify(&self, commitment: G, values: &[G::Scalar], blinding: G::Scalar) -> bool {
+    values.len() <= self.g.len() && self.commit(values, blinding) == commitment
+  }
+}
+
+impl<C: BulletproofCurve> PedersenGenerators<AffinePoint<C>> {
+  /// Derives `n` value generators and the blinding generator from `label` with
+  /// [`hash_generators`].
+  pub fn new(label: &[u8], n: usize) -> Self {
+    let mut g = hash_generators(label, n + 1);
+    let h = g.pop().unwrap();
+    Self { g, h }
+  }
+}
@@ -0,0 +1,300 @@
+//! Aggregated Bulletproofs range proofs.
+//!
+//! The prover commits to `m` values `V_j = v_j G + γ_j H` and shows that every `v_j` lies in
+//! `[0, 2^n)`, with a single proof of `2 log(nm) + 4` points and five scalars.
+//!
+//! The bits `a_L` of all values are concatenated and `a_R = a_L - 1`, so that
+//! - `⟨a_L, 2^n⟩ = v_j` on the `j`-th block,
+//! - `a_L ∘ a_R = 0`, i.e. every entry of `a_L` is a bit.
+//!
+//! For challenges `y, z` these constraints are combined into the single inner product
+//! $$
+//! ⟨a_L - z 1, y^{nm} ∘ (a_R + z 1) + Σ_j z^{2+j} (0^{jn} \| 2^n \| 0^{(m-1-j)n})⟩ = Σ_j z^{2+j}
+//! v_j + δ(y, z),
+//! $$
+//! with `δ(y, z) = (z - z^2)⟨1, y^{nm}⟩ - Σ_j z^{3+j}⟨1, 2^n⟩`. Blinding both vectors with `s_L X`
+//! and `s_R X` turns it into a polynomial `t(X) = ⟨l(X), r(X)⟩`, whose non-constant coefficients
+//! the prover commits to in `T_1, T_2`. The final vectors `l(x), r(x)` are not sent but proven with
+//! an [`InnerProductProof`].
+use std::{error::Error, fmt::Display};
+
+use super::*;
+
+/// Errors from creating a range proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeProofError {
+  /// The number of bits or the number of values is not a power of two, or `2^n` is larger than
+  /// the scalar field.
+  InvalidParameters {
+    /// Number of bits.
+    bits:   usize,
+    /// Number of values.
+    values: usize,
+  },
+  /// There are more bits in total than generators.
+  NotEnoughGenerators {
+    /// Number of generators needed, `n * m`.
+    required: usize,
+    /// Number of generators available.
+    found:    usize,
+  },
+  /// The values and blinding factors have different lengths.
+  MismatchedBlindings,
+  /// The value at this index is not smaller than `2^n`.
+  ValueOutOfRange(usize),
+}
+
+impl Error for RangeProofError {}
+
+impl Display for RangeProofError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      RangeProofError::InvalidParameters { bits, values } =>
+        write!(f, "invalid parameters: {} values of {} bits", values, bits),
+      RangeProofError::NotEnoughGenerators { required, found } =>
+        write!(f, "{} generators required but only {} available", required, found),
+      RangeProofError::MismatchedBlindings =>
+        write!(f, "values and blinding factors have different lengths"),
+      RangeProofError::ValueOutOfRange(i) => write!(f, "value {} is out of range", i),
+    }
+  }
+}
+
+/// The generators of a range proof.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BulletproofGenerators<C: BulletproofCurve> {
+  /// `G` for the values, and `H` for their blinding factors.
+  pub pedersen: PedersenGenerators<AffinePoint<C>>,
+  /// `G_1, .., G_{nm}` for the bits.
+  pub g:        Vec<AffinePoint<C>>,
+  /// `H_1, .., H_{nm}` for the bits.
+  pub h:        Vec<AffinePoint<C>>,
+  /// `U` for the inner product.
+  pub u:        AffinePoint<C>,
+}
+
+impl<C: BulletproofCurve> BulletproofGenerators<C> {
+  /// Derives generators for proofs of up to `capacity` bits in total with [`hash_generators`].
+  pub fn new(capacity: usize) -> Self {
+    Self {
+      pedersen: PedersenGenerators::new(b"pedersen", 1),
+      g:        hash_generators(b"G", capacity),
+      h:        hash_generators(b"H", capacity),
+      u:        hash_generators(b"U", 1)[0],
+    }
+  }
+
+  /// Commits to a single value, `v G + γ H`.
+  pub fn commit(&self, value: u64, blinding: C::ScalarField) -> AffinePoint<C> {
+    self.pedersen.commit(&[C::ScalarField::from(value as usize)], blinding)
+  }
+}
+
+/// A proof that committed values are in `[0, 2^n)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RangeProof<C: BulletproofCurve> {
+  /// Commitment to the bits `a_L, a_R`.
+  pub a:     AffinePoint<C>,
+  /// Commitment to the blinding vectors `s_L, s_R`.
+  pub s:     AffinePoint<C>,
+  /// Commitment to the linear coefficient of `t(X)`.
+  pub t1:    AffinePoint<C>,
+  /// Commitment to the quadratic coefficient of `t(X)`.
+  pub t2:    AffinePoint<C>,
+  /// Blinding factor of `t(x)`.
+  pub tau_x: C::ScalarField,
+  /// Blinding factor of `A + xS`.
+  pub mu:    C::ScalarField,
+  /// `t(x) = ⟨l(x), r(x)⟩`
+  pub t_hat: C::ScalarField,
+  /// Proof that `l(x)` and `r(x)` have inner product `t(x)`.
+  pub ipa:   InnerProductProof<C>,
+}
+
+/// Checks the parameters, returning the total number of bits.
+fn check_parameters<C: BulletproofCurve>(
+  gens: &BulletproofGenerators<C>,
+  bits: usize,
+  values: usize,
+) -> Result<usize, RangeProofError> {
+  if !bits.is_power_of_two()
+    || !values.is_power_of_two()
+    || bits >= usize::BITS as usize
+    || 1 << bits > C::ScalarField::ORDER
+  {
+    return Err(RangeProofError::InvalidParameters { bits, values });
+  }
+  let required = bits * values;
+  let found = gens.g.len().min(gens.h.len());
+  if required > found {
+    return Err(RangeProofError::NotEnoughGenerators { required, found });
+  }
+  Ok(required)
+}
+
+/// The challenges `y` and `z`, after absorbing the parameters, the commitments, `A` and `S`.
+fn bit_challenges<C: BulletproofCurve>(
+  transcript: &mut Transcript,
+  bits: usize,
+  commitments: &[AffinePoint<C>],
+  a: &AffinePoint<C>,
+  s: &AffinePoint<C>,
+) -> (C::ScalarField, C::ScalarField) {
+  transcript.append_message(b"bits", &(bits as u64).to_be_bytes());
+  transcript.append_message(b"values", &(commitments.len() as u64).to_be_bytes());
+  for commitment in commitments {
+    transcript.append_point(b"V", commitment);
+  }
+  transcript.append_point(b"A", a);
+  transcript.append_point(b"S", s);
+  (transcript.challenge_excluding(b"y", &[]), transcript.challenge_excluding(b"z", &[]))
+}
+
+/// `z^{2+j} 2^i` at position `jn + i`, the part of `r(X)` that checks the values.
+fn value_weights<F: Field>(z: F, bits: usize, values: usize) -> Vec<F> {
+  let two = powers(F::ONE + F::ONE, bits);
+  (0..values).flat_map(|j| two.iter().map(move |&t| t * z.pow(2 + j))).collect()
+}
+
+impl<C: BulletproofCurve> RangeProof<C> {
+  /// Proves that every value is in `[0, 2^bits)`, returning the proof and the commitments
+  /// `v_j G + γ_j H` to the values.
+  ///
+  /// ## Errors
+  /// - [`RangeProofError::InvalidParameters`] if `bits` or the number of values is not a power of
+  ///   two, or if `2^bits` does not fit in the scalar field.
+  /// - [`RangeProofError::NotEnoughGenerators`] if `gens` has fewer than `bits * values.len()`
+  ///   generators.
+  /// - [`RangeProofError::MismatchedBlindings`] if there is not one blinding factor per value.
+  /// - [`RangeProofError::ValueOutOfRange`] if a value is not smaller than `2^bits`.
+  pub fn prove<R: Rng + ?Sized>(
+    gens: &BulletproofGenerators<C>,
+    transcript: &mut Transcript,
+    values: &[u64],
+    blindings: &[C::ScalarField],
+    bits: usize,
+    rng: &mut R,
+  ) -> Result<(Self, Vec<AffinePoint<C>>), RangeProofError> {
+    let nm = check_parameters(gens, bits, values.len())?;
+    if blindings.len() != values.len() {
+      return Err(RangeProofError::MismatchedBlindings);
+    }
+    if let Some(i) = values.iter().position(|&v| v >> bits != 0) {
+      return Err(RangeProofError::ValueOutOfRange(i));
+    }
+    let (g, h) = (&gens.g[..nm], &gens.h[..nm]);
+    let (base_g, base_h) = (gens.pedersen.g[0], gens.pedersen.h);
+    let commitments: Vec<_> =
+      values.iter().zip(blindings).map(|(&v, &gamma)| gens.commit(v, gamma)).collect();
+
+    let one = C::ScalarField::ONE;
+    let a_l: Vec<C::ScalarField> = values
+      .iter()
+      .flat_map(|&v| (0..bits).map(move |i| C::ScalarField::from(((v >> i) & 1) as usize)))
+      .collect();
+    let a_r: Vec<_> = a_l.iter().map(|&bit| bit - one).collect();
+    let alpha = random_scalar(rng);
+    let a = msm(g, &a_l) + msm(h, &a_r) + base_h * alpha;
+
+    let s_l: Vec<C::ScalarField> = (0..nm).map(|_| random_scalar(rng)).collect();
+    let s_r: Vec<C::ScalarField> = (0..nm).map(|_| random_scalar(rng)).collect();
+    let rho = random_scalar(rng);
+    let s = msm(g, &s_l) + msm(h, &s_r) + base_h * rho;
+
+    let (y, z) = bit_challenges(transcript, bits, &commitments, &a, &s);
+    let y_powers = powers(y, nm);
+    let weights = value_weights(z, bits, values.len());
+
+    // l(X) = l0 + l1 X and r(X) = r0 + r1 X
+    let l0: Vec<_> = a_l.iter().map(|&bit| bit - z).collect();
+    let l1 = s_l;
+    let r0: Vec<_> = (0..nm).map(|i| y_powers[i] * (a_r[i] + z) + weights[i]).collect();
+    let r1: Vec<_> = (0..nm).map(|i| y_powers[i] * s_r[i]).collect();
+    let t1 = inner_product(&l0, &r1) + inner_product(&l1, &r0);
+    let t2 = inner_product(&l1, &r1);
+
+    let (tau1, tau2) = (random_scalar(rng), random_scalar(rng));
+    let t1_commitment = base_g * t1 + base_h * tau1;
+    let t2_commitment = base_g * t2 + base_h * tau2;
+    transcript.append_point(b"T1", &t1_commitment);
+    transcript.append_point(b"T2", &t2_commitment);
+    let x: C::ScalarField = transcript.challenge_excluding(b"x", &[]);
+
+    let l: Vec<_> = l0.iter().zip(&l1).map(|(&l0, &l1)| l0 + l1 * x).collect();
+    let r: Vec<_> = r0.iter().zip(&r1).map(|(&r0, &r1)| r0 + r1 * x).collect();
+    let t_hat = inner_product(&l, &r);
+    let tau_x = tau2 * x * x
+      + tau1 * x
+      + blindings
+        .iter()
+        .enumerate()
+        .fold(C::ScalarField::ZERO, |acc, (j, &gamma)| acc + z.pow(2 + j) * gamma);
+    let mu = alpha + rho * x;
+    transcript.append_scalar(b"t_hat", &t_hat);
+    transcript.append_scalar(b"tau_x", &tau_x);
+    transcript.append_scalar(b"mu", &mu);
+
+    let w: C::ScalarField = transcript.challenge_excluding(b"w", &[]);
+    let h_prime = scaled_h(h, y);
+    let ipa = InnerProductProof::prove(transcript, g, &h_prime, gens.u * w, &l, &r);
+
+    let proof = Self { a, s, t1: t1_commitment, t2: t2_commitment, tau_x, mu, t_hat, ipa };
+    Ok((proof, commitments))
+  }
+
+  /// Verifies that every commitment opens to a value in `[0, 2^bits)`.
+  pub fn verify(
+    &self,
+    gens: &BulletproofGenerators<C>,
+    transcript: &mut Transcript,
+    commitments: &[AffinePoint<C>],
+    bits: usize,
+  ) -> bool {
+    let Ok(nm) = check_parameters(gens, bits, commitments.len()) else {
+      return false;
+    };
+    let (g, h) = (&gens.g[..nm], &gens.h[..nm]);
+    let (base_g, base_h) = (gens.pedersen.g[0], gens.pedersen.h);
+
+    let (y, z) = bit_challenges(transcript, bits, commitments, &self.a, &self.s);
+    transcript.append_point(b"T1", &self.t1);
+    transcript.append_point(b"T2", &self.t2);
+    let x: C::ScalarField = transcript.challenge_excluding(b"x", &[]);
+    transcript.append_scalar(b"t_hat", &self.t_hat);
+    transcript.append_scalar(b"tau_x", &self.tau_x);
+    transcript.append_scalar(b"mu", &self.mu);
+    let w: C::ScalarField = transcript.challenge_excluding(b"w", &[]);
+
+    // t(x) = Σ z^{2+j} v_j + δ(y, z) + t1 x + t2 x^2
+    let y_powers = powers(y, nm);
+    let sum_y = y_powers.iter().fold(C::ScalarField::ZERO, |acc, &p| acc + p);
+    let sum_two = powers(C::ScalarField::ONE + C::ScalarField::ONE, bits)
+      .into_iter()
+      .fold(C::ScalarField::ZERO, |acc, p| acc + p);
+    let delta = (z - z * z) * sum_y
+      - (0..commitments.len()).fold(C::ScalarField::ZERO, |acc, j| acc + z.pow(3 + j) * sum_two);
+    let z_powers: Vec<_> = (0..commitments.len()).map(|j| z.pow(2 + j)).collect();
+    let lhs = base_g * self.t_hat + base_h * self.tau_x;
+    let rhs = msm(commitments, &z_powers) + base_g * delta + self.t1 * x + self.t2 * (x * x);
+    if lhs != rhs {
+      return false;
+    }
+
+    // P = A + xS - z⟨1, G⟩ + ⟨z y^{nm} + weights, H'⟩ - μ H + t(x) wU = ⟨l, G⟩ + ⟨r, H'⟩ + ⟨l, r⟩
+    // wU
+    let h_prime = scaled_h(h, y);
+    let weights = value_weights(z, bits, commitments.len());
+    let h_scalars: Vec<_> = (0..nm).map(|i| z * y_powers[i] + weights[i]).collect();
+    let u = gens.u * w;
+    let p = self.a + self.s * x - msm(g, &vec![z; nm]) + msm(&h_prime, &h_scalars)
+      - base_h * self.mu
+      + u * self.t_hat;
+    self.ipa.verify(transcript, g, &h_prime, u, p)
+  }
+}
+
+/// `H'_i = y^{-i} H_i`, so that `⟨y^{nm} ∘ r, H⟩ = ⟨r, H'⟩`.
+fn scaled_h<C: BulletproofCurve>(h: &[AffinePoint<C>], y: C::ScalarField) -> Vec<AffinePoint<C>> {
+  let y_inv = y.inverse().unwrap();
+  h.iter().zip(powers(y_inv, h.len())).map(|(&h, p)| h * p).collect()
+}
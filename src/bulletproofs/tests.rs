@@ -0,0 +1,180 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::*;
+use crate::{algebra::group::FiniteCyclicGroup, PlutoBaseCurve, PlutoScalarField};
+
+type Point = AffinePoint<PlutoBaseCurve>;
+
+#[test]
+fn generators_are_deterministic() {
+  let gens = hash_generators::<PlutoBaseCurve>(b"test", 4);
+  assert_eq!(gens.len(), 4);
+  assert_eq!(gens, hash_generators::<PlutoBaseCurve>(b"test", 4));
+  assert!(gens.iter().all(|&g| g != Point::Infinity && g.is_torsion(17)));
+  assert_ne!(gens, hash_generators::<PlutoBaseCurve>(b"other", 4));
+}
+
+#[test]
+fn pedersen_commitment() {
+  let gens = PedersenGenerators::<Point>::new(b"test", 3);
+  let values = [1, 2, 3].map(PlutoScalarField::new);
+  let blinding = PlutoScalarField::new(5);
+  let commitment = gens.commit(&values, blinding);
+  assert!(gens.verify(commitment, &values, blinding));
+  assert!(!gens.verify(commitment, &[1, 2, 4].map(PlutoScalarField::new), blinding));
+  assert!(!gens.verify(commitment, &values, PlutoScalarField::new(6)));
+
+  // commitments are additively homomorphic
+  let other = [4, 5, 6].map(PlutoScalarField::new);
+  let sum: Vec<_> = values.iter().zip(&other).map(|(&a, &b)| a + b).collect();
+  let blinding_sum = blinding + PlutoScalarField::new(7);
+  assert_eq!(
+    commitment + gens.commit(&other, PlutoScalarField::new(7)),
+    gens.commit(&sum, blinding_sum)
+  );
+}
+
+#[test]
+fn pedersen_over_curve_group() {
+  let gens = PedersenGenerators {
+    g: vec![Point::GENERATOR],
+    h: Point::GENERATOR * PlutoScalarField::new(3),
+  };
+  let commitment = gens.commit(&[PlutoScalarField::new(2)], PlutoScalarField::new(1));
+  assert_eq!(commitment, Point::GENERATOR * PlutoScalarField::new(5));
+}
+
+fn ipa_instance(
+  n: usize,
+  seed: u64,
+) -> (Vec<Point>, Vec<Point>, Point, Vec<PlutoScalarField>, Vec<PlutoScalarField>, Point) {
+  let mut rng = StdRng::seed_from_u64(seed);
+  let g = hash_generators::<PlutoBaseCurve>(b"G", n);
+  let h = hash_generators::<PlutoBaseCurve>(b"H", n);
+  let u = hash_generators::<PlutoBaseCurve>(b"U", 1)[0];
+  let a: Vec<PlutoScalarField> = (0..n).map(|_| random_scalar(&mut rng)).collect();
+  let b: Vec<PlutoScalarField> = (0..n).map(|_| random_scalar(&mut rng)).collect();
+  let p = msm(&g, &a) + msm(&h, &b) + u * inner_product(&a, &b);
+  (g, h, u, a, b, p)
+}
+
+#[test]
+fn inner_product_argument() {
+  for (n, seed) in [(1, 0), (2, 1), (4, 2), (8, 3)] {
+    let (g, h, u, a, b, p) = ipa_instance(n, seed);
+    let proof = InnerProductProof::prove(&mut Transcript::new(b"ipa"), &g, &h, u, &a, &b);
+    assert_eq!(proof.l.len(), n.trailing_zeros() as usize);
+    assert!(proof.verify(&mut Transcript::new(b"ipa"), &g, &h, u, p));
+  }
+}
+
+#[test]
+fn inner_product_argument_rejects_wrong_statement() {
+  let (g, h, u, a, b, p) = ipa_instance(8, 4);
+  let proof = InnerProductProof::prove(&mut Transcript::new(b"ipa"), &g, &h, u, &a, &b);
+
+  assert!(!proof.verify(&mut Transcript::new(b"ipa"), &g, &h, u, p + u));
+  assert!(!proof.verify(&mut Transcript::new(b"other"), &g, &h, u, p));
+  assert!(!proof.verify(&mut Transcript::new(b"ipa"), &g[..4], &h[..4], u, p));
+
+  let mut tampered = proof.clone();
+  tampered.a += PlutoScalarField::ONE;
+  assert!(!tampered.verify(&mut Transcript::new(b"ipa"), &g, &h, u, p));
+
+  let mut tampered = proof;
+  tampered.l[0] += u;
+  assert!(!tampered.verify(&mut Transcript::new(b"ipa"), &g, &h, u, p));
+}
+
+#[test]
+fn range_proof() {
+  let gens = BulletproofGenerators::<PlutoBaseCurve>::new(8);
+  let mut rng = StdRng::seed_from_u64(5);
+  for value in 0..16 {
+    let blinding = random_scalar(&mut rng);
+    let (proof, commitments) =
+      RangeProof::prove(&gens, &mut Transcript::new(b"range"), &[value], &[blinding], 4, &mut rng)
+        .unwrap();
+    assert_eq!(commitments, [gens.commit(value, blinding)]);
+    assert!(proof.verify(&gens, &mut Transcript::new(b"range"), &commitments, 4));
+  }
+}
+
+#[test]
+fn aggregated_range_proof() {
+  let gens = BulletproofGenerators::<PlutoBaseCurve>::new(8);
+  let mut rng = StdRng::seed_from_u64(6);
+  let values = [3, 14];
+  let blindings = [PlutoScalarField::new(2), PlutoScalarField::new(9)];
+  let (proof, commitments) =
+    RangeProof::prove(&gens, &mut Transcript::new(b"range"), &values, &blindings, 4, &mut rng)
+      .unwrap();
+  assert_eq!(proof.ipa.l.len(), 3);
+  assert!(proof.verify(&gens, &mut Transcript::new(b"range"), &commitments, 4));
+
+  // the proof is bound to the commitments, their order and the number of bits
+  let swapped = [commitments[1], commitments[0]];
+  assert!(!proof.verify(&gens, &mut Transcript::new(b"range"), &swapped, 4));
+  assert!(!proof.verify(&gens, &mut Transcript::new(b"range"), &commitments[..1], 4));
+  assert!(!proof.verify(&gens, &mut Transcript::new(b"range"), &commitments, 2));
+  assert!(!proof.verify(&gens, &mut Transcript::new(b"other"), &commitments, 4));
+}
+
+#[test]
+fn range_proof_rejects_tampering() {
+  let gens = BulletproofGenerators::<PlutoBaseCurve>::new(8);
+  let mut rng = StdRng::seed_from_u64(7);
+  let (proof, commitments) = RangeProof::prove(
+    &gens,
+    &mut Transcript::new(b"range"),
+    &[5, 6],
+    &[PlutoScalarField::new(1), PlutoScalarField::new(2)],
+    4,
+    &mut rng,
+  )
+  .unwrap();
+  let verify = |proof: &RangeProof<PlutoBaseCurve>| {
+    proof.verify(&gens, &mut Transcript::new(b"range"), &commitments, 4)
+  };
+  assert!(verify(&proof));
+
+  let mut tampered = proof.clone();
+  tampered.t_hat += PlutoScalarField::ONE;
+  assert!(!verify(&tampered));
+
+  let mut tampered = proof.clone();
+  tampered.tau_x += PlutoScalarField::ONE;
+  assert!(!verify(&tampered));
+
+  let mut tampered = proof.clone();
+  tampered.mu += PlutoScalarField::ONE;
+  assert!(!verify(&tampered));
+
+  let mut tampered = proof;
+  tampered.ipa.b += PlutoScalarField::ONE;
+  assert!(!verify(&tampered));
+}
+
+#[test]
+fn range_proof_errors() {
+  let gens = BulletproofGenerators::<PlutoBaseCurve>::new(8);
+  let mut rng = StdRng::seed_from_u64(8);
+  let one = [PlutoScalarField::ONE];
+  let mut prove = |values: &[u64], blindings: &[PlutoScalarField], bits| {
+    RangeProof::prove(&gens, &mut Transcript::new(b"range"), values, blindings, bits, &mut rng)
+      .map(|_| ())
+  };
+
+  assert_eq!(prove(&[16], &one, 4), Err(RangeProofError::ValueOutOfRange(0)));
+  assert_eq!(prove(&[1], &one, 3), Err(RangeProofError::InvalidParameters { bits: 3, values: 1 }));
+  assert_eq!(prove(&[1], &one, 8), Err(RangeProofError::InvalidParameters { bits: 8, values: 1 }));
+  assert_eq!(
+    prove(&[1, 2, 3], &[PlutoScalarField::ONE; 3], 2),
+    Err(RangeProofError::InvalidParameters { bits: 2, values: 3 })
+  );
+  assert_eq!(
+    prove(&[1, 2, 3, 4], &[PlutoScalarField::ONE; 4], 4),
+    Err(RangeProofError::NotEnoughGenerators { required: 16, found: 8 })
+  );
+  assert_eq!(prove(&[1, 2], &one, 2), Err(RangeProofError::MismatchedBlindings));
+}
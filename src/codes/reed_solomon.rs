@@ -51,6 +51,15 @@ impl<const K: usize, const P: usize> Message<K, P> {
     }
   }
 
+  /// Evaluates the message polynomial at the `n`-th roots of unity `ω^0, .., ω^{n-1}`, which are
+  /// the `y` coordinates of [`Message::encode`] for `N = n`, with `n` only known at runtime.
+  pub fn evaluations(&self, n: usize) -> Vec<PrimeField<P>> {
+    assert!(n >= K, "Code size must be greater than or equal to K");
+    let primitive_root = PrimeField::<P>::primitive_root_of_unity(n);
+    let polynomial = Polynomial::from(self.clone());
+    (0..n).map(|pow| polynomial.evaluate(primitive_root.pow(pow))).collect()
+  }
+
   /// Decodes the message from a [`Codeword`].
   pub fn decode<const M: usize>(codeword: Codeword<M, K, P>) -> Self {
     assert_ge::<M, K>();
@@ -216,4 +225,12 @@ mod tests {
     assert_eq!(decoded.data[3], PrimeField::<P>::new(4));
     assert_eq!(decoded.data[4], PrimeField::<P>::new(5));
   }
+
+  #[test]
+  fn evaluations_match_encoding() {
+    let message = Message::new([1, 2, 3].map(PrimeField::<P>::new));
+    let codeword = message.clone().encode::<N>();
+    let evaluations = message.evaluations(N);
+    assert_eq!(evaluations, codeword.data.map(|c| c.y));
+  }
 }
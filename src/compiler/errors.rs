@@ -1,70 +1,234 @@
+//! Errors from parsing the DSL and evaluating programs.
 use std::{error::Error, fmt::Display};
 
-use crate::PlutoScalarField;
+use super::lexer::Span;
+use crate::{algebra::field::FiniteField, PlutoScalarField};
 
-/// Errors from parsing the DSL
+/// Errors from creating and evaluating a [`Program`](super::program::Program) over the field `F`
 #[derive(Debug)]
-pub enum ProgramError<'a> {
+pub enum ProgramError<'a, F: FiniteField = PlutoScalarField> {
+  /// a public declaration comes after a constraint
   PublicAssignmentInvalidStatement,
-  CircuitEvaluationOutputMismatch(PlutoScalarField, PlutoScalarField),
+  /// an output wire was already assigned another value: the assigned value and the computed one
+  CircuitEvaluationOutputMismatch(F, F),
+  /// a wire has no value
+  MissingAssignment(String),
+  /// the DSL couldn't be parsed
   ParserError(ParserError<'a>),
+  /// an input file entry that isn't a name with an integer value, or a name given twice
+  InvalidInput(String),
+  /// an input that isn't a variable of the circuit
+  UnknownInput(String),
+  /// a constraint that the witness doesn't satisfy: its row and the constraint with its values
+  UnsatisfiedConstraint(usize, String),
+  /// the group order doesn't divide the order of the multiplicative group of the field: both
+  /// orders
+  InvalidGroupOrder(usize, usize),
+  /// more gates than rows in the execution trace: the number of gates and the group order
+  TooManyConstraints(usize, usize),
+  /// more table rows than rows in the execution trace: the number of table rows and the group
+  /// order
+  TooManyTableRows(usize, usize),
+  /// the cosets $H$, $gH$ and $g^2H$ of the subgroup of the group order, which label the cells of
+  /// the three columns of the permutation, aren't disjoint: the group order
+  OverlappingCosets(usize),
+  /// a custom gate or lookup that can't be compiled, like to an R1CS
+  UnsupportedGate(String),
+  /// an R1CS file that isn't valid JSON or doesn't describe a constraint system over the field
+  InvalidR1CS(String),
 }
 
-impl<'a> Error for ProgramError<'a> {}
+impl<'a, F: FiniteField + Display> Error for ProgramError<'a, F> {}
 
-impl<'a> Display for ProgramError<'a> {
+impl<'a, F: FiniteField + Display> Display for ProgramError<'a, F> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match *self {
       ProgramError::PublicAssignmentInvalidStatement =>
         write!(f, "public statements should be at the beginning"),
-      ProgramError::CircuitEvaluationOutputMismatch(out_input_value, output_value) =>
+      ProgramError::CircuitEvaluationOutputMismatch(ref out_input_value, ref output_value) =>
         write!(f, "output value doesn't match: {} {}", out_input_value, output_value),
+      ProgramError::MissingAssignment(ref var) => write!(f, "no value assigned to: {}", var),
       ProgramError::ParserError(ref parser_error) =>
         write!(f, "program initialisation parser error: {}", parser_error),
+      ProgramError::InvalidInput(ref entry) => write!(f, "invalid input: {}", entry),
+      ProgramError::UnknownInput(ref name) => write!(f, "unknown input: {}", name),
+      ProgramError::UnsatisfiedConstraint(row, ref constraint) =>
+        write!(f, "constraint {} is not satisfied: {}", row, constraint),
+      ProgramError::InvalidGroupOrder(group_order, multiplicative_order) => write!(
+        f,
+        "group order {} doesn't divide the order of the multiplicative group: {}",
+        group_order, multiplicative_order
+      ),
+      ProgramError::TooManyConstraints(gates, group_order) =>
+        write!(f, "{} gates don't fit in group order {}", gates, group_order),
+      ProgramError::TooManyTableRows(rows, group_order) =>
+        write!(f, "{} table rows don't fit in group order {}", rows, group_order),
+      ProgramError::OverlappingCosets(group_order) => write!(
+        f,
+        "cosets of the subgroup of order {} overlap, so the permutation can't tell columns apart",
+        group_order
+      ),
+      ProgramError::UnsupportedGate(ref gate) => write!(f, "unsupported gate: {}", gate),
+      ProgramError::InvalidR1CS(ref reason) => write!(f, "invalid R1CS: {}", reason),
     }
   }
 }
 
-#[derive(Debug)]
-pub enum ParserError<'a> {
+/// What went wrong while parsing the DSL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParserErrorKind<'a> {
+  /// a character that can't start any token
+  UnexpectedCharacter(&'a str),
+  /// a `/*` comment without `*/`
+  UnterminatedComment,
+  /// a token that can't start an operand, like an operator following another one
   EvaluateInvalidExpression(&'a str),
-  EvaluateMultipleSubExpression(String),
-  ConstraintsMaxVariables(Vec<&'a str>),
+  /// an operand that doesn't follow an operator
+  EvaluateMultipleSubExpression(&'a str),
+  /// an integer or a coefficient that doesn't fit an `i128`
   ConstraintsInvalidCoefficientValues(String),
+  /// a statement that is neither a public declaration nor a constraint
   ConstraintsUnsupportedValue(&'a str),
+  /// something else than a variable name where a variable is expected
   ConstraintsInvalidVariableName(&'a str),
+  /// a `(` without `)`, or a `)` without `(`
+  UnbalancedParentheses,
+  /// a `{` without `}`
+  UnbalancedBraces,
+  /// a statement that ends in the middle of an expression
+  UnexpectedEnd,
+  /// a table declared twice
+  TableDuplicateName(&'a str),
+  /// a table whose size is zero or too large
+  TableInvalidSize(&'a str),
+  /// a lookup in a table that isn't declared before
+  LookupUnknownTable(&'a str),
+  /// a lookup of a number of values that isn't the width of the table: both numbers
+  LookupInvalidWidth(usize, usize),
+  /// a gadget defined twice, or with the name of a standard gadget
+  GadgetDuplicateName(&'a str),
+  /// a call of a gadget that isn't defined before
+  GadgetUnknown(&'a str),
+  /// a gadget called with a wrong number of arguments: the given and expected numbers
+  GadgetInvalidArguments(&'a str, usize, usize),
+  /// a gadget assigned to a wrong number of outputs: the given and expected numbers
+  GadgetInvalidOutputs(&'a str, usize, usize),
+  /// a gadget that calls itself while being expanded
+  GadgetRecursion(&'a str),
+  /// a public declaration, table or gadget definition in the body of a gadget
+  GadgetInvalidStatement(&'a str),
+  /// an error in the body of a gadget, reported at its call
+  InGadget(&'a str, Box<ParserErrorKind<'a>>),
+  /// a hint bit index that isn't a number below 64
+  HintInvalidBit(&'a str),
 }
 
-impl<'a> Error for ParserError<'a> {}
-
-impl<'a> Display for ParserError<'a> {
+impl<'a> Display for ParserErrorKind<'a> {
   fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     match *self {
-      ParserError::EvaluateInvalidExpression(expr) => write!(f, "invalid expression: {}", expr),
-      ParserError::EvaluateMultipleSubExpression(ref expr) =>
-        write!(f, "No ops: expected sub expr to be a unit: {}", expr),
-      ParserError::ConstraintsMaxVariables(ref vars) =>
-        write!(f, "max 2 variables allowed: {}", vars.join(" ")),
-      ParserError::ConstraintsInvalidCoefficientValues(ref coeff_key) =>
+      ParserErrorKind::UnexpectedCharacter(c) => write!(f, "unexpected character: {}", c),
+      ParserErrorKind::UnterminatedComment => write!(f, "unterminated block comment"),
+      ParserErrorKind::EvaluateInvalidExpression(token) =>
+        write!(f, "invalid expression: {}", token),
+      ParserErrorKind::EvaluateMultipleSubExpression(token) =>
+        write!(f, "expected an operator before: {}", token),
+      ParserErrorKind::ConstraintsInvalidCoefficientValues(ref coeff_key) =>
         write!(f, "invalid coefficient value: {}", coeff_key),
-      ParserError::ConstraintsUnsupportedValue(constraint) =>
-        write!(f, "unsupported constraint token: {}", constraint),
-      ParserError::ConstraintsInvalidVariableName(var) =>
+      ParserErrorKind::ConstraintsUnsupportedValue(constraint) =>
+        write!(f, "unsupported constraint: {}", constraint),
+      ParserErrorKind::ConstraintsInvalidVariableName(var) =>
         write!(f, "invalid variable name: {}", var),
+      ParserErrorKind::UnbalancedParentheses => write!(f, "unbalanced parentheses"),
+      ParserErrorKind::UnbalancedBraces => write!(f, "unbalanced braces"),
+      ParserErrorKind::UnexpectedEnd => write!(f, "unexpected end of statement"),
+      ParserErrorKind::TableDuplicateName(name) => write!(f, "table declared twice: {}", name),
+      ParserErrorKind::TableInvalidSize(size) => write!(f, "invalid table size: {}", size),
+      ParserErrorKind::LookupUnknownTable(name) => write!(f, "unknown table: {}", name),
+      ParserErrorKind::LookupInvalidWidth(values, width) =>
+        write!(f, "lookup of {} values in a table of width {}", values, width),
+      ParserErrorKind::GadgetDuplicateName(name) => write!(f, "gadget defined twice: {}", name),
+      ParserErrorKind::GadgetUnknown(name) => write!(f, "unknown gadget: {}", name),
+      ParserErrorKind::GadgetInvalidArguments(name, given, expected) =>
+        write!(f, "gadget {} called with {} arguments instead of {}", name, given, expected),
+      ParserErrorKind::GadgetInvalidOutputs(name, given, expected) =>
+        write!(f, "gadget {} assigned to {} outputs instead of {}", name, given, expected),
+      ParserErrorKind::GadgetRecursion(name) => write!(f, "recursive gadget: {}", name),
+      ParserErrorKind::GadgetInvalidStatement(statement) =>
+        write!(f, "not allowed in a gadget: {}", statement),
+      ParserErrorKind::InGadget(name, ref kind) => write!(f, "in gadget {}: {}", name, kind),
+      ParserErrorKind::HintInvalidBit(bit) => write!(f, "invalid bit index: {}", bit),
     }
   }
 }
 
+/// Errors from parsing the DSL, pointing at the offending source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParserError<'a> {
+  /// what went wrong
+  pub kind:       ParserErrorKind<'a>,
+  /// position of the offending source, relative to the string it was parsed from
+  pub span:       Span,
+  /// line of the start of the span, starting at 1
+  pub line:       usize,
+  /// column of the start of the span in characters, starting at 1
+  pub column:     usize,
+  /// the error with the source line and the span underlined, for display to the author
+  pub diagnostic: String,
+}
+
+impl<'a> ParserError<'a> {
+  /// Creates an error at `span` of `source`, whose first line is line `first_line` of the file.
+  pub(crate) fn new(
+    kind: ParserErrorKind<'a>,
+    span: Span,
+    source: &str,
+    first_line: usize,
+  ) -> Self {
+    let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[span.start..].find('\n').map_or(source.len(), |i| span.start + i);
+    let line = first_line + source[..span.start].matches('\n').count();
+    let column = source[line_start..span.start].chars().count() + 1;
+    let underline = source[span.start..span.end.min(line_end)].chars().count().max(1);
+
+    let gutter = " ".repeat(line.to_string().len());
+    let diagnostic = format!(
+      "error: {}\n{}--> {}:{}\n{} |\n{} | {}\n{} | {}{}",
+      kind,
+      gutter,
+      line,
+      column,
+      gutter,
+      line,
+      &source[line_start..line_end],
+      gutter,
+      " ".repeat(column - 1),
+      "^".repeat(underline),
+    );
+    Self { kind, span, line, column, diagnostic }
+  }
+}
+
+impl<'a> Error for ParserError<'a> {}
+
+impl<'a> Display for ParserError<'a> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}:{}: {}", self.line, self.column, self.kind)
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
-  use crate::compiler::{parser::parse_constraints, program::Program};
+  use crate::compiler::{
+    parser::{parse_constraints, parse_source},
+    program::Program,
+  };
 
   #[test]
   fn program_error() {
     let constraints =
       &["a public", "d === 9", "b <== a * a + 5", "b public", "c <== -2 * b - a * b"];
-    let program = Program::<5>::new(constraints).unwrap();
+    let program = Program::<8>::new(constraints).unwrap();
 
     let public_vars = program.public_assignments();
 
@@ -76,11 +240,31 @@ mod tests {
 
   #[test]
   fn parser_error() {
-    let constraint = "a <== b * c + d";
-    let wire_values = parse_constraints(constraint);
+    let wire_values = parse_constraints(&["a <== (b * c + d"]);
     assert!(
-      matches!(wire_values, Err(ParserError::ConstraintsMaxVariables(_))),
-      "max 2 variables allowed",
+      matches!(wire_values, Err(ParserError { kind: ParserErrorKind::UnbalancedParentheses, .. })),
+      "parentheses should be balanced",
+    );
+  }
+
+  #[test]
+  fn diagnostic() {
+    let source = "x public\n\n// the output\nout <== x * * x\n";
+    let error = parse_source(source).unwrap_err();
+    assert_eq!((error.line, error.column), (4, 13));
+    assert_eq!(error.to_string(), "4:13: invalid expression: *");
+    assert_eq!(
+      error.diagnostic,
+      "error: invalid expression: *\n --> 4:13\n  |\n4 | out <== x * * x\n  |             ^"
     );
   }
+
+  #[test]
+  fn diagnostic_line_of_constraint() {
+    let error = parse_constraints(&["a public", "b <== a +\n  ñ * (a"]).unwrap_err();
+    assert_eq!(error.kind, ParserErrorKind::UnbalancedParentheses);
+    assert_eq!((error.line, error.column), (3, 7));
+    assert_eq!(error.span, Span::new(17, 18));
+    assert!(error.diagnostic.ends_with("3 |   ñ * (a\n  |       ^"));
+  }
 }
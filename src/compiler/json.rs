@@ -0,0 +1,319 @@
+//! Minimal JSON values, for the circom compatible R1CS and witness files of [`r1cs`](super::r1cs).
+//!
+//! Numbers are kept as their source text, since field elements are written as decimal strings or
+//! numbers of any size.
+
+use std::fmt::{self, Display, Formatter};
+
+/// Width of the lines of indented JSON, see [`Json::write_pretty`].
+const LINE_WIDTH: usize = 80;
+
+/// A JSON value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Json {
+  Null,
+  Bool(bool),
+  Number(String),
+  String(String),
+  Array(Vec<Json>),
+  /// entries in source order
+  Object(Vec<(String, Json)>),
+}
+
+impl Json {
+  /// Parses a whole JSON document, or returns what went wrong and where.
+  pub(crate) fn parse(source: &str) -> Result<Self, String> {
+    let mut parser = JsonParser { source, position: 0 };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    match parser.position == source.len() {
+      true => Ok(value),
+      false => Err(parser.error("end of input")),
+    }
+  }
+
+  /// Value of `key` in an object.
+  pub(crate) fn get(&self, key: &str) -> Option<&Json> {
+    match self {
+      Json::Object(entries) => entries.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn as_array(&self) -> Option<&[Json]> {
+    match self {
+      Json::Array(values) => Some(values),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn as_object(&self) -> Option<&[(String, Json)]> {
+    match self {
+      Json::Object(entries) => Some(entries),
+      _ => None,
+    }
+  }
+
+  /// Text of a number or a string, like field elements, which are written either way.
+  pub(crate) fn as_number(&self) -> Option<&str> {
+    match self {
+      Json::Number(text) | Json::String(text) => Some(text),
+      _ => None,
+    }
+  }
+
+  /// Value of a number or a string holding a non-negative integer that fits a `usize`.
+  pub(crate) fn as_usize(&self) -> Option<usize> { self.as_number()?.parse().ok() }
+
+  /// Writes `self` at the given indentation level: arrays and objects that fit in a line of
+  /// [`LINE_WIDTH`] are written compactly, and others with an entry per line.
+  fn write_pretty(&self, f: &mut Formatter<'_>, level: usize) -> fmt::Result {
+    let indent = |level: usize| "  ".repeat(level);
+    let compact = self.to_string();
+    if compact.len() + 2 * level <= LINE_WIDTH {
+      return write!(f, "{}", compact);
+    }
+    let (open, close, items): (_, _, Vec<(Option<&String>, &Json)>) = match self {
+      Json::Array(values) => ('[', ']', values.iter().map(|value| (None, value)).collect()),
+      Json::Object(entries) =>
+        ('{', '}', entries.iter().map(|(key, value)| (Some(key), value)).collect()),
+      _ => return write!(f, "{}", compact),
+    };
+    writeln!(f, "{}", open)?;
+    for (i, (key, value)) in items.iter().enumerate() {
+      write!(f, "{}", indent(level + 1))?;
+      if let Some(key) = key {
+        write!(f, "{}: ", Json::String(key.to_string()))?;
+      }
+      value.write_pretty(f, level + 1)?;
+      writeln!(f, "{}", if i + 1 < items.len() { "," } else { "" })?;
+    }
+    write!(f, "{}{}", indent(level), close)
+  }
+}
+
+/// Compact JSON, or indented JSON with `{:#}`.
+impl Display for Json {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    if f.alternate() {
+      return self.write_pretty(f, 0);
+    }
+    match self {
+      Json::Null => write!(f, "null"),
+      Json::Bool(value) => write!(f, "{}", value),
+      Json::Number(text) => write!(f, "{}", text),
+      Json::String(text) => {
+        write!(f, "\"")?;
+        for c in text.chars() {
+          match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\t' => write!(f, "\\t")?,
+            c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+          }
+        }
+        write!(f, "\"")
+      },
+      Json::Array(values) => {
+        write!(f, "[")?;
+        for (i, value) in values.iter().enumerate() {
+          write!(f, "{}{}", if i > 0 { ", " } else { "" }, value)?;
+        }
+        write!(f, "]")
+      },
+      Json::Object(entries) => {
+        write!(f, "{{")?;
+        for (i, (key, value)) in entries.iter().enumerate() {
+          let separator = if i > 0 { ", " } else { "" };
+          write!(f, "{}{}: {}", separator, Json::String(key.clone()), value)?;
+        }
+        write!(f, "}}")
+      },
+    }
+  }
+}
+
+/// Recursive descent parser of JSON values.
+struct JsonParser<'a> {
+  source:   &'a str,
+  position: usize,
+}
+
+impl<'a> JsonParser<'a> {
+  fn error(&self, expected: &str) -> String {
+    format!("expected {} at byte {}", expected, self.position)
+  }
+
+  fn skip_whitespace(&mut self) {
+    let rest = &self.source[self.position..];
+    self.position += rest.len() - rest.trim_start().len();
+  }
+
+  fn peek(&self) -> Option<char> { self.source[self.position..].chars().next() }
+
+  /// Consumes `expected` after optional whitespace.
+  fn expect(&mut self, expected: char) -> Result<(), String> {
+    self.skip_whitespace();
+    match self.peek() == Some(expected) {
+      true => {
+        self.position += expected.len_utf8();
+        Ok(())
+      },
+      false => Err(self.error(&format!("'{}'", expected))),
+    }
+  }
+
+  fn value(&mut self) -> Result<Json, String> {
+    self.skip_whitespace();
+    let rest = &self.source[self.position..];
+    match self.peek() {
+      Some('{') => self.object(),
+      Some('[') => self.array(),
+      Some('"') => self.string().map(Json::String),
+      Some('-' | '0'..='9') => {
+        let length = rest
+          .find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')))
+          .unwrap_or(rest.len());
+        self.position += length;
+        Ok(Json::Number(rest[..length].to_string()))
+      },
+      _ => {
+        let literals =
+          [("null", Json::Null), ("true", Json::Bool(true)), ("false", Json::Bool(false))];
+        match literals.into_iter().find(|(literal, _)| rest.starts_with(literal)) {
+          Some((literal, value)) => {
+            self.position += literal.len();
+            Ok(value)
+          },
+          None => Err(self.error("a value")),
+        }
+      },
+    }
+  }
+
+  /// Parses comma separated items until `close`, after the opening bracket.
+  fn items(
+    &mut self,
+    close: char,
+    mut item: impl FnMut(&mut Self) -> Result<(), String>,
+  ) -> Result<(), String> {
+    self.position += 1;
+    self.skip_whitespace();
+    if self.peek() == Some(close) {
+      self.position += 1;
+      return Ok(());
+    }
+    loop {
+      item(self)?;
+      self.skip_whitespace();
+      match self.peek() {
+        Some(',') => self.position += 1,
+        Some(c) if c == close => {
+          self.position += 1;
+          return Ok(());
+        },
+        _ => return Err(self.error(&format!("',' or '{}'", close))),
+      }
+    }
+  }
+
+  fn array(&mut self) -> Result<Json, String> {
+    let mut values = vec![];
+    self.items(']', |parser| {
+      values.push(parser.value()?);
+      Ok(())
+    })?;
+    Ok(Json::Array(values))
+  }
+
+  fn object(&mut self) -> Result<Json, String> {
+    let mut entries = vec![];
+    self.items('}', |parser| {
+      parser.skip_whitespace();
+      let key = parser.string()?;
+      parser.expect(':')?;
+      entries.push((key, parser.value()?));
+      Ok(())
+    })?;
+    Ok(Json::Object(entries))
+  }
+
+  fn string(&mut self) -> Result<String, String> {
+    if self.peek() != Some('"') {
+      return Err(self.error("a string"));
+    }
+    let mut chars = self.source[self.position + 1..].char_indices();
+    let mut text = String::new();
+    while let Some((i, c)) = chars.next() {
+      match c {
+        '"' => {
+          self.position += i + 2;
+          return Ok(text);
+        },
+        '\\' => {
+          let escaped = match chars.next().map(|(_, c)| c) {
+            Some('n') => '\n',
+            Some('t') => '\t',
+            Some('r') => '\r',
+            Some('b') => '\u{8}',
+            Some('f') => '\u{c}',
+            Some('u') => {
+              let digits: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+              let code = u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32);
+              code.ok_or_else(|| self.error("a unicode escape"))?
+            },
+            Some(c @ ('"' | '\\' | '/')) => c,
+            _ => return Err(self.error("an escape sequence")),
+          };
+          text.push(escaped);
+        },
+        c => text.push(c),
+      }
+    }
+    Err(self.error("a closing '\"'"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rstest::rstest;
+
+  use super::*;
+
+  #[rstest]
+  #[case("null", "null")]
+  #[case(" [1, -2.5e3, true,false ] ", "[1, -2.5e3, true, false]")]
+  #[case("{\"a\":{\"b\":[]},\"c\":\"d\\\"\\u0041\"}", "{\"a\": {\"b\": []}, \"c\": \"d\\\"A\"}")]
+  #[case("{}", "{}")]
+  fn parse_and_display(#[case] source: &str, #[case] expected: &str) {
+    let json = Json::parse(source).unwrap();
+    assert_eq!(json.to_string(), expected);
+    assert_eq!(Json::parse(expected).unwrap(), json);
+  }
+
+  #[rstest]
+  #[case("", "expected a value at byte 0")]
+  #[case("[1 2]", "expected ',' or ']' at byte 3")]
+  #[case("{\"a\" 1}", "expected ':' at byte 5")]
+  #[case("{1: 2}", "expected a string at byte 1")]
+  #[case("\"open", "expected a closing '\"' at byte 0")]
+  #[case("[] []", "expected end of input at byte 3")]
+  fn parse_errors(#[case] source: &str, #[case] expected: &str) {
+    assert_eq!(Json::parse(source).unwrap_err(), expected);
+  }
+
+  #[test]
+  fn pretty() {
+    let long = Json::Array((0..30).map(|i| Json::Number(i.to_string())).collect());
+    let json = Json::Object(vec![
+      (String::from("short"), Json::Array(vec![Json::Null])),
+      (String::from("long"), long),
+    ]);
+    let pretty = format!("{:#}", json);
+    assert!(pretty.starts_with("{\n  \"short\": [null],\n  \"long\": [\n    0,\n    1,\n"));
+    assert!(pretty.ends_with("    29\n  ]\n}"));
+    assert_eq!(Json::parse(&pretty).unwrap(), json);
+  }
+}
@@ -0,0 +1,215 @@
+//! Splits DSL source files into [`Token`]s with their [`Span`]s.
+//!
+//! - `//` starts a comment until the end of the line, and `/* .. */` a block comment that can span
+//!   several lines.
+//! - Line breaks are kept as [`TokenKind::Newline`] tokens since they end statements, see
+//!   [`parser`](super::parser). Blank lines and lines with only comments produce no other token.
+//! - Any other whitespace only separates tokens, which don't need to be separated otherwise.
+//! - `public`, `bool`, `range`, `table`, `xor`, `in` and `gadget` are keywords, which can't be
+//!   variable names.
+
+use super::errors::{ParserError, ParserErrorKind};
+
+/// Byte range `start..end` of a token or statement in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+  /// offset of the first byte
+  pub start: usize,
+  /// offset after the last byte
+  pub end:   usize,
+}
+
+impl Span {
+  /// Creates a span from `start` to `end`.
+  pub fn new(start: usize, end: usize) -> Self { Self { start, end } }
+
+  /// The smallest span covering both `self` and `other`.
+  pub fn to(self, other: Span) -> Span {
+    Span::new(self.start.min(other.start), self.end.max(other.end))
+  }
+}
+
+/// Kinds of tokens of the DSL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+  /// variable name, starting with a letter and followed by letters or digits
+  Identifier,
+  /// non-negative integer
+  Number,
+  /// `public` keyword
+  Public,
+  /// `bool` keyword, for boolean gates
+  Bool,
+  /// `range` keyword, for range gates and range tables
+  Range,
+  /// `table` keyword, declaring a lookup table
+  Table,
+  /// `xor` keyword, for XOR tables
+  Xor,
+  /// `in` keyword, for lookups
+  In,
+  /// `gadget` keyword, defining a gadget
+  Gadget,
+  /// `<==`
+  Assign,
+  /// `<--`, for hints
+  Hint,
+  /// `===`
+  Equal,
+  /// `+`
+  Plus,
+  /// `-`
+  Minus,
+  /// `*`
+  Star,
+  /// `(`
+  LeftParen,
+  /// `)`
+  RightParen,
+  /// `,`
+  Comma,
+  /// `{`
+  LeftBrace,
+  /// `}`
+  RightBrace,
+  /// `->`, before the outputs of a gadget
+  Arrow,
+  /// line break
+  Newline,
+}
+
+/// A token with its text and position in the source.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+  /// kind of the token
+  pub kind: TokenKind,
+  /// text of the token in the source
+  pub text: &'a str,
+  /// position of the token in the source
+  pub span: Span,
+}
+
+/// Splits `source` into tokens, reporting errors with their position in `source`.
+pub fn tokenize(source: &str) -> Result<Vec<Token>, ParserError> {
+  lex(source).map_err(|(kind, span)| ParserError::new(kind, span, source, 1))
+}
+
+/// [`tokenize`] with errors that are not rendered yet.
+pub(super) fn lex(source: &str) -> Result<Vec<Token>, (ParserErrorKind, Span)> {
+  let mut tokens = vec![];
+  let mut chars = source.char_indices().peekable();
+  while let Some((start, c)) = chars.next() {
+    let rest = &source[start..];
+    let mut end = start + c.len_utf8();
+    let kind = match c {
+      '\n' => TokenKind::Newline,
+      c if c.is_whitespace() => continue,
+      '/' if rest.starts_with("//") => {
+        while chars.next_if(|&(_, c)| c != '\n').is_some() {}
+        continue;
+      },
+      '/' if rest.starts_with("/*") => {
+        let Some(length) = rest[2..].find("*/") else {
+          return Err((ParserErrorKind::UnterminatedComment, Span::new(start, source.len())));
+        };
+        end = start + length + 4;
+        while chars.next_if(|&(i, _)| i < end).is_some() {}
+        continue;
+      },
+      '<' | '='
+        if rest.starts_with("<==") || rest.starts_with("===") || rest.starts_with("<--") =>
+      {
+        end = start + 3;
+        chars.nth(1);
+        match &rest[..3] {
+          "<==" => TokenKind::Assign,
+          "<--" => TokenKind::Hint,
+          _ => TokenKind::Equal,
+        }
+      },
+      '-' if rest.starts_with("->") => {
+        end = start + 2;
+        chars.next();
+        TokenKind::Arrow
+      },
+      '+' => TokenKind::Plus,
+      '-' => TokenKind::Minus,
+      '*' => TokenKind::Star,
+      '(' => TokenKind::LeftParen,
+      ')' => TokenKind::RightParen,
+      ',' => TokenKind::Comma,
+      '{' => TokenKind::LeftBrace,
+      '}' => TokenKind::RightBrace,
+      c if c.is_ascii_digit() => {
+        while let Some((i, c)) = chars.next_if(|(_, c)| c.is_ascii_digit()) {
+          end = i + c.len_utf8();
+        }
+        TokenKind::Number
+      },
+      c if c.is_alphanumeric() => {
+        while let Some((i, c)) = chars.next_if(|(_, c)| c.is_alphanumeric()) {
+          end = i + c.len_utf8();
+        }
+        match &source[start..end] {
+          "public" => TokenKind::Public,
+          "bool" => TokenKind::Bool,
+          "range" => TokenKind::Range,
+          "table" => TokenKind::Table,
+          "xor" => TokenKind::Xor,
+          "in" => TokenKind::In,
+          "gadget" => TokenKind::Gadget,
+          _ => TokenKind::Identifier,
+        }
+      },
+      _ =>
+        return Err((
+          ParserErrorKind::UnexpectedCharacter(&source[start..end]),
+          Span::new(start, end),
+        )),
+    };
+    tokens.push(Token { kind, text: &source[start..end], span: Span::new(start, end) });
+  }
+  Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+  use rstest::rstest;
+
+  use super::{TokenKind::*, *};
+
+  #[rstest]
+  #[case("a <== b * c", &[Identifier, Assign, Identifier, Star, Identifier])]
+  #[case("-out<==(x2+1)*-x", &[Minus, Identifier, Assign, LeftParen, Identifier, Plus, Number, RightParen, Star, Minus, Identifier])]
+  #[case("a public\n", &[Identifier, Public, Newline])]
+  #[case("d===10*a1 // comment * with = symbols", &[Identifier, Equal, Number, Star, Identifier])]
+  #[case("a /* block\ncomment */ public", &[Identifier, Public])]
+  #[case("\n\n  // only comments\n", &[Newline, Newline, Newline])]
+  #[case("x1 1x", &[Identifier, Number, Identifier])]
+  #[case("table t xor 4\n(a, b,c) in t", &[Table, Identifier, Xor, Number, Newline, LeftParen, Identifier, Comma, Identifier, Comma, Identifier, RightParen, In, Identifier])]
+  #[case("x bool\nx range\nboolean ranges", &[Identifier, Bool, Newline, Identifier, Range, Newline, Identifier, Identifier])]
+  #[case("gadget f(a) -> b {\n}", &[Gadget, Identifier, LeftParen, Identifier, RightParen, Arrow, Identifier, LeftBrace, Newline, RightBrace])]
+  #[case("b<--bit(x,1)\na-->b", &[Identifier, Hint, Identifier, LeftParen, Identifier, Comma, Number, RightParen, Newline, Identifier, Minus, Arrow, Identifier])]
+  fn token_kinds(#[case] source: &str, #[case] expected: &[TokenKind]) {
+    let kinds: Vec<TokenKind> = tokenize(source).unwrap().iter().map(|token| token.kind).collect();
+    assert_eq!(kinds, expected);
+  }
+
+  #[test]
+  fn token_spans() {
+    let source = "x2 <== x*x\n";
+    let tokens = tokenize(source).unwrap();
+    let texts: Vec<&str> =
+      tokens.iter().map(|token| &source[token.span.start..token.span.end]).collect();
+    assert_eq!(texts, ["x2", "<==", "x", "*", "x", "\n"]);
+    assert!(tokens.iter().all(|token| token.text == &source[token.span.start..token.span.end]));
+  }
+
+  #[rstest]
+  #[case("a <== b / c", ParserErrorKind::UnexpectedCharacter("/"), Span::new(8, 9))]
+  #[case("a <== b\n/* open", ParserErrorKind::UnterminatedComment, Span::new(8, 15))]
+  fn lexer_errors(#[case] source: &str, #[case] kind: ParserErrorKind, #[case] span: Span) {
+    let error = tokenize(source).unwrap_err();
+    assert_eq!((error.kind, error.span), (kind, span));
+  }
+}
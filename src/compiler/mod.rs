@@ -13,7 +13,9 @@
 //!
 //! Each line of DSL is a separate constraint. It's parsed and converted to corresponding
 //! `WireValues`, i.e. variables and coefficients. Vanilla PLONK supports fan-in 2 arithmetic (add
-//! and mul) gates, so each constraint can only support a maximum of 1 output and 2 input variables.
+//! and mul) gates, so each gate can only support a maximum of 1 output and 2 input variables.
+//! Constraints with more variables or a higher degree, like `out <== x * x * x + 5`, are split into
+//! several gates with intermediate wires.
 //!
 //! Note: Read [`parser`] for DSL rules.
 //!
@@ -24,9 +26,16 @@
 //!     - selector polynomials: `[QM,QR,QM,QO,QC]`
 //!     - permutation helpers: `[S1,S2,S3]`
 //! - public inputs
-//! - witness: `[a,b,c]`
+//! - witness: `[a,b,c]`, solved from public and private inputs by [`witness`]
+//!
+//! Gates can be reduced by the passes of [`optimizer`] before compiling them.
 #![doc = include_str!("./README.md")]
-mod errors;
+pub mod errors;
+mod json;
+pub mod lexer;
+pub mod optimizer;
 pub mod parser;
 pub mod program;
+pub mod r1cs;
 mod utils;
+pub mod witness;
@@ -0,0 +1,501 @@
+//! Optimisation passes over the gates of a [`Circuit`], which remove gates without changing what
+//! the circuit proves about its public variables.
+//!
+//! Every arithmetic gate `q_L a + q_R b + q_M ab + q_O c + q_C = 0` is read as the polynomial it
+//! constrains to zero, with the integer coefficients of the DSL, so that the passes hold in every
+//! field. The passes run until none of them removes a gate:
+//! - **constant folding**: a gate `w === k` fixes the value of `w`, which is substituted in every
+//!   other gate, like `y === w * x` becoming `y === k * x`.
+//! - **duplicate and tautological constraints**: a gate that holds for any values, like `a === a`,
+//!   or that is the same as an earlier gate up to sign, like `b === a * a` and `-b === -a * a`.
+//! - **linear-combination merging**: a wire defined by a linear gate `t === a + b` and used in a
+//!   single other gate is replaced by its definition there, like `y === t * 3` becoming `y === 3 *
+//!   a + 3 * b`, if the result still fits a fan-in 2 gate.
+//! - **dead-wire elimination**: a gate that only determines a wire that no other gate uses, like `z
+//!   === y + 1` when nothing uses `z`.
+//!
+//! Public variables are never folded, merged or removed, and public declarations, boolean and
+//! range gates and lookups are kept as they are, along with the wires they use and the wires of
+//! [hints](super::parser::Hint): the optimised circuit has the same public inputs. Outputs of the
+//! circuit should be declared public, since private wires that no constraint uses are dead. Wires
+//! are only solved from gates where their coefficient is `1` or `-1`, which have a single solution
+//! in every field.
+
+use std::{
+  borrow::Cow,
+  collections::{HashMap, HashSet},
+  fmt::{self, Display, Formatter},
+};
+
+use super::{
+  parser::{
+    checked_add, checked_scale, fits_gate, gate_from_terms, variables, Circuit, Terms, WireCoeffs,
+  },
+  program::Program,
+  utils::get_product_key,
+};
+use crate::algebra::field::FiniteField;
+
+/// Numbers of gates before and after [`optimize`], and of gates removed by each pass.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct OptimizationReport {
+  /// gates of the circuit, including public declarations and custom gates
+  pub gates_before:        usize,
+  /// gates of the optimised circuit
+  pub gates_after:         usize,
+  /// gates `w === k` whose wire was substituted with its constant value
+  pub folded_constants:    usize,
+  /// duplicate or tautological constraints
+  pub removed_constraints: usize,
+  /// linear gates merged into the gate that uses their output
+  pub merged_gates:        usize,
+  /// gates of wires that no other gate uses
+  pub dead_gates:          usize,
+}
+
+impl Display for OptimizationReport {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{} gates before optimisation, {} after: {} constants folded, {} duplicate or tautological \
+       constraints removed, {} gates merged, {} dead gates removed",
+      self.gates_before,
+      self.gates_after,
+      self.folded_constants,
+      self.removed_constraints,
+      self.merged_gates,
+      self.dead_gates
+    )
+  }
+}
+
+/// A row of the circuit.
+enum Row<'a> {
+  /// a public declaration, custom gate or lookup, which is kept as is
+  Fixed(WireCoeffs<'a>),
+  /// an arithmetic gate, with the polynomial it constrains to zero
+  Arithmetic(Terms<'a>, WireCoeffs<'a>),
+}
+
+impl<'a> Row<'a> {
+  /// Reads a gate, keeping it as is unless it is an arithmetic gate whose polynomial has
+  /// coefficients that fit an `i128`.
+  fn new(gate: WireCoeffs<'a>) -> Self {
+    let is_public = gate.coeffs.get("$public") == Some(&1);
+    if is_public || gate.custom_gate().is_some() {
+      return Row::Fixed(gate);
+    }
+    match polynomial(&gate) {
+      Some(terms) => Row::Arithmetic(terms, gate),
+      None => Row::Fixed(gate),
+    }
+  }
+
+  /// Wires of the row.
+  fn wires(&self) -> Vec<Cow<'a, str>> {
+    match self {
+      Row::Fixed(gate) => gate.wires.iter().flatten().cloned().collect(),
+      Row::Arithmetic(terms, _) => variables(terms),
+    }
+  }
+
+  fn into_gate(self) -> WireCoeffs<'a> {
+    match self {
+      Row::Fixed(gate) | Row::Arithmetic(_, gate) => gate,
+    }
+  }
+}
+
+/// `-coeff * monomial`, for the coefficients of the DSL, which are negated in gates.
+fn negated(monomial: Vec<Cow<'_, str>>, coeff: i128) -> Option<Terms<'_>> {
+  checked_add(Terms::new(), Terms::from([(monomial, coeff.checked_neg()?)])).ok()
+}
+
+/// The polynomial `q_L a + q_R b + q_M ab + q_O c + q_C` of an arithmetic gate, with integer
+/// coefficients, or `None` if a coefficient overflows.
+fn polynomial<'a>(gate: &WireCoeffs<'a>) -> Option<Terms<'a>> {
+  let coeff = |key: &str| gate.coeffs.get(key).copied().unwrap_or(0);
+  let (a, b, c) = (gate.wires[0].clone(), gate.wires[1].clone(), gate.wires[2].clone());
+
+  let mut terms = negated(vec![], coeff("$constant"))?;
+  if let Some(c) = c {
+    let output_coeff = gate.coeffs.get("$output_coeffs").copied().unwrap_or(1);
+    terms = checked_add(terms, Terms::from([(vec![c], output_coeff)])).ok()?;
+  }
+  if let Some(a) = a.clone() {
+    terms = checked_add(terms, negated(vec![a.clone()], coeff(&a))?).ok()?;
+  }
+  if let (Some(a), Some(b)) = (a, b) {
+    if a != b {
+      terms = checked_add(terms, negated(vec![b.clone()], coeff(&b))?).ok()?;
+    }
+    let product = get_product_key(&a, &b);
+    let mut monomial = vec![a, b];
+    monomial.sort();
+    terms = checked_add(terms, negated(monomial, coeff(&product))?).ok()?;
+  }
+  Some(terms)
+}
+
+/// Writes `terms = 0` as a fan-in 2 gate, with `output` as output wire if possible, or another
+/// wire that appears linearly and in no product. Returns `None` if no gate computes `terms`.
+fn to_gate<'a>(terms: Terms<'a>, output: Option<&str>) -> Option<WireCoeffs<'a>> {
+  if terms.keys().any(|monomial| monomial.len() > 2) {
+    return None;
+  }
+  let is_output = |wire: &Cow<str>| {
+    terms.contains_key(&vec![wire.clone()])
+      && !terms.keys().any(|monomial| monomial.len() == 2 && monomial.contains(wire))
+  };
+  let mut candidates: Vec<Cow<str>> = variables(&terms).into_iter().filter(is_output).collect();
+  candidates.sort_by_key(|wire| Some(wire.as_ref()) != output);
+
+  for wire in candidates {
+    let mut rest = terms.clone();
+    let coeff = rest.remove(&vec![wire.clone()]).unwrap_or_default();
+    if fits_gate(&rest) {
+      // `coeff * wire = -rest`, with a positive output coefficient
+      let (coeff, rest) = match coeff < 0 {
+        true => (coeff.checked_neg()?, rest),
+        false => (coeff, checked_scale(rest, -1).ok()?),
+      };
+      return Some(gate_from_terms(Some(wire), coeff, rest));
+    }
+  }
+  fits_gate(&terms).then(|| gate_from_terms(None, 0, terms))
+}
+
+/// Replaces `wire` by `value` in `terms`, or returns `None` if a coefficient overflows.
+fn substitute<'a>(terms: &Terms<'a>, wire: &str, value: &Terms<'a>) -> Option<Terms<'a>> {
+  let mut result = Terms::new();
+  for (monomial, &coeff) in terms {
+    let mut product = Terms::from([(vec![], coeff)]);
+    for factor in monomial {
+      let factor_terms = match factor == wire {
+        true => value.clone(),
+        false => Terms::from([(vec![factor.clone()], 1)]),
+      };
+      product = multiply(&product, &factor_terms)?;
+    }
+    result = checked_add(result, product).ok()?;
+  }
+  Some(result)
+}
+
+/// `lhs * rhs`, or `None` if a coefficient overflows.
+fn multiply<'a>(lhs: &Terms<'a>, rhs: &Terms<'a>) -> Option<Terms<'a>> {
+  let mut result = Terms::new();
+  for (lhs_monomial, &lhs_coeff) in lhs {
+    for (rhs_monomial, &rhs_coeff) in rhs {
+      let mut monomial = [lhs_monomial.clone(), rhs_monomial.clone()].concat();
+      monomial.sort();
+      let term = Terms::from([(monomial, lhs_coeff.checked_mul(rhs_coeff)?)]);
+      result = checked_add(result, term).ok()?;
+    }
+  }
+  Some(result)
+}
+
+/// If `terms` is `±wire + definition` for a wire in no product, returns `definition` with
+/// `wire = definition`.
+fn definition<'a>(terms: &Terms<'a>, wire: &Cow<'a, str>) -> Option<Terms<'a>> {
+  if terms.keys().any(|monomial| monomial.len() == 2 && monomial.contains(wire)) {
+    return None;
+  }
+  let mut rest = terms.clone();
+  match rest.remove(&vec![wire.clone()])? {
+    // wire + rest = 0
+    1 => checked_scale(rest, -1).ok(),
+    // -wire + rest = 0
+    -1 => Some(rest),
+    _ => None,
+  }
+}
+
+/// Runs the passes of the [module documentation](self) over the rows until none of them changes.
+struct Optimizer<'a> {
+  rows:       Vec<Row<'a>>,
+  /// inputs and outputs of hints
+  hint_wires: HashSet<Cow<'a, str>>,
+  report:     OptimizationReport,
+}
+
+impl<'a> Optimizer<'a> {
+  /// Variables of public declarations, wires of custom gates and lookups, and wires of hints,
+  /// which are kept.
+  fn kept_wires(&self) -> HashSet<Cow<'a, str>> {
+    let fixed = self.rows.iter().filter(|row| matches!(row, Row::Fixed(_))).flat_map(Row::wires);
+    fixed.chain(self.hint_wires.iter().cloned()).collect()
+  }
+
+  /// Number of rows using each wire.
+  fn uses(&self) -> HashMap<Cow<'a, str>, usize> {
+    let mut uses = HashMap::new();
+    for wire in self.rows.iter().flat_map(Row::wires) {
+      *uses.entry(wire).or_default() += 1;
+    }
+    uses
+  }
+
+  /// Replaces `wire` by `value` in every arithmetic row but `skip`, if every result fits a gate.
+  fn substitute_everywhere(&mut self, wire: &str, value: &Terms<'a>, skip: usize) -> bool {
+    let mut substituted = vec![];
+    for (i, row) in self.rows.iter().enumerate() {
+      let Row::Arithmetic(terms, gate) = row else { continue };
+      if i == skip || !variables(terms).iter().any(|variable| variable == wire) {
+        continue;
+      }
+      let Some(terms) = substitute(terms, wire, value) else { return false };
+      match to_gate(terms.clone(), gate.wires[2].as_deref()) {
+        Some(gate) => substituted.push((i, Row::Arithmetic(terms, gate))),
+        None => return false,
+      }
+    }
+    for (i, row) in substituted {
+      self.rows[i] = row;
+    }
+    true
+  }
+
+  /// Substitutes wires whose value a gate `±w + k = 0` fixes, and removes that gate.
+  fn fold_constants(&mut self) -> bool {
+    let kept = self.kept_wires();
+    for i in 0..self.rows.len() {
+      let Row::Arithmetic(terms, _) = &self.rows[i] else { continue };
+      let [wire] = &variables(terms)[..] else { continue };
+      if kept.contains(wire) || terms.keys().any(|monomial| monomial.len() == 2) {
+        continue;
+      }
+      let (wire, Some(value)) = (wire.clone(), definition(terms, wire)) else { continue };
+      if self.substitute_everywhere(&wire, &value, i) {
+        self.rows.remove(i);
+        self.report.folded_constants += 1;
+        return true;
+      }
+    }
+    false
+  }
+
+  /// Removes gates that always hold, and gates that are the same as an earlier one up to sign.
+  fn remove_redundant_constraints(&mut self) -> bool {
+    let mut seen = HashSet::new();
+    let before = self.rows.len();
+    self.rows.retain(|row| match row {
+      Row::Fixed(_) => true,
+      Row::Arithmetic(terms, _) => {
+        let negated = checked_scale(terms.clone(), -1).ok();
+        let is_duplicate = seen.contains(terms) || negated.is_some_and(|n| seen.contains(&n));
+        seen.insert(terms.clone());
+        !terms.is_empty() && !is_duplicate
+      },
+    });
+    self.report.removed_constraints += before - self.rows.len();
+    before != self.rows.len()
+  }
+
+  /// Replaces a wire defined by a linear gate in the only other gate that uses it.
+  fn merge_linear_combinations(&mut self) -> bool {
+    let (kept, uses) = (self.kept_wires(), self.uses());
+    for i in 0..self.rows.len() {
+      let Row::Arithmetic(terms, _) = &self.rows[i] else { continue };
+      if terms.keys().any(|monomial| monomial.len() == 2) {
+        continue;
+      }
+      let terms = terms.clone();
+      for wire in variables(&terms) {
+        if kept.contains(&wire) || uses[&wire] != 2 {
+          continue;
+        }
+        let Some(value) = definition(&terms, &wire) else { continue };
+        if self.substitute_everywhere(&wire, &value, i) {
+          self.rows.remove(i);
+          self.report.merged_gates += 1;
+          return true;
+        }
+      }
+    }
+    false
+  }
+
+  /// Removes gates that only determine a wire that no other gate uses.
+  fn remove_dead_wires(&mut self) -> bool {
+    let (kept, uses) = (self.kept_wires(), self.uses());
+    let is_dead = |row: &Row<'a>| match row {
+      Row::Fixed(_) => false,
+      Row::Arithmetic(terms, _) => variables(terms)
+        .iter()
+        .any(|wire| !kept.contains(wire) && uses[wire] == 1 && definition(terms, wire).is_some()),
+    };
+    let before = self.rows.len();
+    self.rows.retain(|row| !is_dead(row));
+    self.report.dead_gates += before - self.rows.len();
+    before != self.rows.len()
+  }
+}
+
+/// Optimises the gates of `circuit` with the passes of the [module documentation](self), and
+/// reports the number of gates before and after.
+pub fn optimize(circuit: Circuit<'_>) -> (Circuit<'_>, OptimizationReport) {
+  let Circuit { gates, tables, hints } = circuit;
+  let report = OptimizationReport { gates_before: gates.len(), ..Default::default() };
+  let rows = gates.into_iter().map(Row::new).collect();
+  let hint_wires =
+    hints.iter().flat_map(|hint| [hint.input.clone(), hint.output.clone()]).collect();
+  let mut optimizer = Optimizer { rows, hint_wires, report };
+  while optimizer.fold_constants()
+    || optimizer.remove_redundant_constraints()
+    || optimizer.merge_linear_combinations()
+    || optimizer.remove_dead_wires()
+  {}
+
+  let gates: Vec<WireCoeffs> = optimizer.rows.into_iter().map(Row::into_gate).collect();
+  let report = OptimizationReport { gates_after: gates.len(), ..optimizer.report };
+  (Circuit { gates, tables, hints }, report)
+}
+
+impl<'a, const GROUP_ORDER: usize, F: FiniteField> Program<'a, GROUP_ORDER, F> {
+  /// Returns the program with the gates optimised by [`optimize`], with the same public inputs.
+  pub fn optimize(&self) -> (Self, OptimizationReport) {
+    let circuit = Circuit {
+      gates:  self.constraints().to_vec(),
+      tables: self.tables().to_vec(),
+      hints:  self.hints().to_vec(),
+    };
+    let (circuit, report) = optimize(circuit);
+    let program = Self::from_circuit(circuit).expect("optimisation doesn't add gates or tables");
+    (program, report)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use rstest::rstest;
+
+  use super::*;
+  use crate::{compiler::parser::parse_circuit, PlutoScalarField};
+
+  /// Optimises `constraints` and returns the optimised gates as DSL.
+  fn optimized(constraints: &[&str]) -> (Vec<String>, OptimizationReport) {
+    let (circuit, report) = optimize(parse_circuit(constraints).unwrap());
+    (circuit.gates.iter().map(ToString::to_string).collect(), report)
+  }
+
+  #[rstest]
+  #[case::constant_folding(
+    &["x public", "y public", "c === 3", "d <== c + 2", "y === d * x"],
+    &["x public", "y public", "y === 5 * x"]
+  )]
+  #[case::folded_constant_in_product(
+    &["x public", "y public", "c === -2", "y <== c * c * x"],
+    &["x public", "y public", "y === 4 * x"]
+  )]
+  #[case::tautologies(
+    &["a public", "b public", "b <== a * a", "a === a", "-b <== -a * a", "b === a * a"],
+    &["a public", "b public", "b === a * a"]
+  )]
+  #[case::merge_linear_combination(
+    &["a public", "b public", "y public", "t <== a + b", "y <== t * 3"],
+    &["a public", "b public", "y public", "y === 3 * a + 3 * b"]
+  )]
+  #[case::merge_into_product(
+    &["a public", "y public", "t <== 2 * a", "y <== t * a - 1"],
+    &["a public", "y public", "y === 2 * a * a - 1"]
+  )]
+  #[case::product_does_not_fit(
+    &["a public", "b public", "c public", "y public", "t <== a + b", "y <== t * c"],
+    &["a public", "b public", "c public", "y public", "t === a + b", "y === c * t"]
+  )]
+  #[case::dead_wires(
+    &["x public", "y <== x * x", "z <== y + 1"],
+    &["x public"]
+  )]
+  #[case::public_wires_are_kept(
+    &["x public", "c public", "c === 3", "y public", "y <== c * x"],
+    &["x public", "c public", "c === 3", "y public", "y === c * x"]
+  )]
+  #[case::custom_gates_are_kept(
+    &["a public", "b public", "t <== a + b", "t bool", "u <== a * b", "u range"],
+    &["a public", "b public", "t === a + b", "t bool", "u === a * b", "u range"]
+  )]
+  #[case::unsatisfiable_constants_are_kept(
+    &["x public", "c === 2", "c === 3"],
+    &["x public", "0 === -1"]
+  )]
+  fn passes(#[case] constraints: &[&str], #[case] expected: &[&str]) {
+    let (gates, report) = optimized(constraints);
+    assert_eq!(gates, expected);
+    assert_eq!(report.gates_after, expected.len());
+  }
+
+  #[test]
+  fn hint_wires_are_kept() {
+    let constraints = ["x public", "y <== x + 1", "z <-- bit(y, 0)", "boolean(z)", "y === 2 * z"];
+    let circuit = parse_circuit(&constraints).unwrap();
+    let (kept, report) = optimize(circuit.clone());
+    assert_eq!(kept, circuit);
+    assert_eq!(report.gates_after, 4);
+
+    // without the hint, `y` is merged
+    let (gates, _) = optimized(&["x public", "y <== x + 1", "boolean(z)", "y === 2 * z"]);
+    assert_eq!(gates, ["x public", "z === z * z", "x === 2 * z - 1"]);
+  }
+
+  #[test]
+  fn report() {
+    let constraints = &[
+      "a public",
+      "y public",
+      "c === 3",
+      "t <== a + c",
+      "y <== t * a",
+      "y === a * t",
+      "z <== y + 1",
+    ];
+    let (gates, report) = optimized(constraints);
+    assert_eq!(gates, ["a public", "y public", "y === a * a + 3 * a"]);
+    assert_eq!(report, OptimizationReport {
+      gates_before:        7,
+      gates_after:         3,
+      folded_constants:    1,
+      removed_constraints: 1,
+      merged_gates:        1,
+      dead_gates:          1,
+    });
+    assert_eq!(
+      report.to_string(),
+      "7 gates before optimisation, 3 after: 1 constants folded, 1 duplicate or tautological \
+       constraints removed, 1 gates merged, 1 dead gates removed"
+    );
+  }
+
+  #[test]
+  fn same_public_interface() {
+    let source = "
+      x public
+      out public
+      two === 2
+      x2 <== x * x
+      x3 <== x2 * x
+      out <== x3 + two * x + 3 + two
+      unused <== x3 * out
+    ";
+    let program = Program::<16>::from_source(source).unwrap();
+    let (optimized, report) = program.optimize();
+    assert_eq!((report.gates_before, report.gates_after), (9, 4));
+
+    let inputs = HashMap::from([(String::from("x"), PlutoScalarField::new(3))]);
+    let inputs_with_out = |out| {
+      let mut inputs = inputs.clone();
+      inputs.insert(String::from("out"), PlutoScalarField::new(out));
+      inputs
+    };
+    // 27 + 6 + 5 = 38 = 4 mod 17
+    let witness = program.generate_witness(&inputs_with_out(4)).unwrap();
+    let optimized_witness = optimized.generate_witness(&inputs_with_out(4)).unwrap();
+    assert_eq!(witness.public_inputs, optimized_witness.public_inputs);
+    assert_eq!(program.public_assignments().unwrap(), optimized.public_assignments().unwrap());
+    assert!(optimized.generate_witness(&inputs_with_out(5)).is_err());
+  }
+}
@@ -2,288 +2,1174 @@
 //! ## Rules:
 //! - supports `<==` for assignment and `===` for arithmetic equality checks
 //! - mark variables as public in the beginning.
-//! - only supports quadratic constraints, i.e. don't support `y <== x * x * x`.
-//! - each token should be separated by space.
+//! - expressions are polynomials in the variables built from integers, `+`, `-`, `*` and
+//!   parentheses, with the usual precedence: `*` binds tighter than `+` and `-`, and `-` can
+//!   negate.
+//! - tokens don't need to be separated by spaces.
+//! - [`parse_source`] parses whole files: a statement ends with its line, unless inside parentheses
+//!   or after an operator. `//` and `/* */` comments and blank lines are ignored.
+//! - errors point at the offending source, with its line and column, see [`ParserError`].
+//! - `x bool` and `x range` constrain `x` to `0..2` and `0..4`, and `x in t` or `(a, b, c) in t`
+//!   looks values up in a [`Table`] declared with `table t range <size>` or `table t xor <size>`,
+//!   see [`parse_circuit`].
+//! - `gadget name(a, b) -> (x, y) { .. }` defines a [gadget](#gadgets), whose calls expand into the
+//!   gates of its body, and `b <-- bit(x, i)` declares a [`Hint`].
 //!
 //! Outputs parsed output in form of [`WireCoeffs`] values and coefficients.
 //! - `wires`: represent variables corresponding to gate wires in each constraint.
 //! - `coefficients`: coefficient corresponding to each variable.
 //!
+//! A constraint that doesn't fit a single fan-in 2 [`Gate`], like `y <== x * x * x`, is decomposed
+//! into several gates by introducing intermediate wires named `$1`, `$2`, …, in order of
+//! appearance in the program:
+//! - `y <== x * x * x` => `$1 <== x * x` and `y <== $1 * x`
+//!
 //! ## Note
 //!
 //! Some default coefficients are used for certain constraints:
 //! - `$constant`: for constant variables
 //! - `$output_coeffs`: for output variables. Example: `-a <== b * b` has `$output_coeffs` as `-1`
 //! - `$public`: for public variable declarations
+//! - `$bool`, `$range`: for boolean and range gates, and `$lookup`: the id of the table of a lookup
 //!
 //! ## Example
 //! - `a public` =>                    `(['a', None, None], {'$public': 1, 'a': -1,
 //!   '$output_coeffs': 0}`
 //! - `b <== a * c` =>                 `(['a', 'c', 'b'], {'a*c': 1})`
 //! - `d <== a * c - 45 * a + 987` =>  `(['a', 'c', 'd'], {'a*c': 1, 'a': -45, '': 987})`
+//!
+//! ## Gadgets
+//!
+//! A gadget is a macro of the DSL: `gadget name(parameters) -> outputs { body }`, where the outputs
+//! are a variable, variables in parentheses, or left out with the arrow. A call expands into the
+//! gates of the body, with the parameters replaced by the arguments of the call and the outputs by
+//! the variables it is assigned to:
+//! - `(q, r) <== name(a, 2 * b)` assigns every output, and `name(a, b)` a gadget without outputs.
+//! - `y <== name(a, b) + 1` uses a gadget with a single output in an expression, like arguments of
+//!   other calls.
+//!
+//! Other variables of the body are local to each call, and named after the gadget and the number of
+//! the call, like `lt4.1.d`. Gadgets are defined before their calls, can call other gadgets but
+//! not themselves, and can't declare public variables or tables.
+//!
+//! The gadgets of [`STANDARD_GADGETS`] are available in every circuit: `boolean`, `bits4`,
+//! `bits8`, `range4`, `range8`, `lt4`, `select`, `sbox`, `mix`, `fullRound`, `partialRound`,
+//! `poseidon`, `hash` and `merkleStep`.
 
 use std::{
-  collections::{HashMap, HashSet},
+  borrow::Cow,
+  collections::{BTreeMap, BTreeSet, HashMap},
+  fmt::{self, Display, Formatter},
   iter,
 };
 
 use super::{
-  errors::ParserError,
-  utils::{get_product_key, is_valid_var_name},
+  errors::{ParserError, ParserErrorKind},
+  lexer::{lex, Span, Token, TokenKind},
+  utils::{coefficient, get_product_key},
 };
-use crate::{Field, PlutoScalarField};
+use crate::{algebra::field::FiniteField, PlutoScalarField};
+
+/// Source of the gadgets available in every circuit: bit decomposition, boolean constraints, range
+/// checks, comparison, conditional select, a Poseidon permutation and hash, and Merkle path
+/// verification.
+pub const STANDARD_GADGETS: &str = include_str!("./gadgets.circuit");
 
 /// Fan-in 2 Gate representing a constraint in the computation.
 /// Each constraint satisfies PLONK's arithmetic equation: `a(X)QL(X) + b(X)QR(X) + a(X)b(X)QM(X) +
 /// o(X)QO(X) + QC(X) = 0`.
-pub struct Gate {
+pub struct Gate<F: FiniteField = PlutoScalarField> {
   /// left wire value
-  pub l: PlutoScalarField,
+  pub l: F,
   /// right wire value
-  pub r: PlutoScalarField,
+  pub r: F,
   /// output wire, represented as `$output_coeffs` in wire coefficients
-  pub o: PlutoScalarField,
+  pub o: F,
   /// multiplication wire
-  pub m: PlutoScalarField,
+  pub m: F,
   /// constant wire, represented as `$constant` in coefficients
-  pub c: PlutoScalarField,
+  pub c: F,
 }
 
 /// Values of wires with coefficients of each wire name
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct WireCoeffs<'a> {
-  /// variable used in each wire
-  pub wires:  Vec<Option<&'a str>>,
+  /// variable used in each wire, borrowed from the source or owned for intermediate wires
+  pub wires:  Vec<Option<Cow<'a, str>>>,
   /// coefficients of variables in wires and [`Gate`]
-  pub coeffs: HashMap<String, i32>,
+  pub coeffs: HashMap<String, i128>,
 }
 
 impl<'a> WireCoeffs<'a> {
-  fn l(&self) -> PlutoScalarField {
-    match self.wires[0] {
+  fn l<F: FiniteField>(&self) -> F {
+    match self.wires[0].as_deref() {
       Some(wire) => match self.coeffs.get(wire) {
         // negation is done to satisfy constraint equation of vanilla plonk
-        Some(val) => -PlutoScalarField::from(*val),
-        None => PlutoScalarField::ZERO,
+        Some(val) => -coefficient::<F>(*val),
+        None => F::ZERO,
       },
-      None => PlutoScalarField::ZERO,
+      None => F::ZERO,
     }
   }
 
-  fn r(&self) -> PlutoScalarField {
-    if self.wires[0].is_some() && self.wires[1].is_some() && self.wires[0] != self.wires[1] {
-      match self.coeffs.get(self.wires[1].unwrap()) {
+  fn r<F: FiniteField>(&self) -> F {
+    match (self.wires[0].as_deref(), self.wires[1].as_deref()) {
+      (Some(a), Some(b)) if a != b => match self.coeffs.get(b) {
         // negation is done to satisfy constraint equation of vanilla plonk
-        Some(val) => -PlutoScalarField::from(*val),
-        None => PlutoScalarField::ZERO,
-      }
-    } else {
-      PlutoScalarField::ZERO
+        Some(val) => -coefficient::<F>(*val),
+        None => F::ZERO,
+      },
+      _ => F::ZERO,
     }
   }
 
-  fn o(&self) -> PlutoScalarField {
+  fn o<F: FiniteField>(&self) -> F {
     match self.coeffs.get("$output_coeffs") {
-      Some(val) => PlutoScalarField::from(*val),
-      None => PlutoScalarField::ONE,
+      Some(val) => coefficient::<F>(*val),
+      None => F::ONE,
     }
   }
 
-  fn c(&self) -> PlutoScalarField {
+  fn c<F: FiniteField>(&self) -> F {
     match self.coeffs.get("$constant") {
-      Some(val) => -PlutoScalarField::from(*val),
-      None => PlutoScalarField::ZERO,
+      Some(val) => -coefficient::<F>(*val),
+      None => F::ZERO,
     }
   }
 
-  fn m(&self) -> PlutoScalarField {
-    match (self.wires[0], self.wires[1]) {
+  fn m<F: FiniteField>(&self) -> F {
+    match (self.wires[0].as_deref(), self.wires[1].as_deref()) {
       (Some(a), Some(b)) => match self.coeffs.get(&get_product_key(a, b)) {
-        Some(val) => -PlutoScalarField::from(*val),
-        None => PlutoScalarField::ZERO,
+        Some(val) => -coefficient::<F>(*val),
+        None => F::ZERO,
       },
-      _ => PlutoScalarField::ZERO,
+      _ => F::ZERO,
     }
   }
 
-  /// sends gate activation coefficients from each wires.
-  pub fn gate(&self) -> Gate {
+  /// returns the kind of custom gate of a row: `$bool`, `$range` or `$lookup`, if any.
+  pub fn custom_gate(&self) -> Option<&'static str> {
+    ["$bool", "$range", "$lookup"].into_iter().find(|key| self.coeffs.contains_key(*key))
+  }
+
+  /// returns the values looked up by a lookup row, like `x` or `(a, b, c)`.
+  pub(crate) fn lookup_values(&self) -> String {
+    let values: Vec<&str> = self.wires.iter().flatten().map(|wire| wire.as_ref()).collect();
+    match values.as_slice() {
+      [value] => value.to_string(),
+      values => format!("({})", values.join(", ")),
+    }
+  }
+
+  /// sends gate activation coefficients from each wires, as elements of the field `F`.
+  pub fn gate<F: FiniteField>(&self) -> Gate<F> {
     Gate { l: self.l(), r: self.r(), o: self.o(), m: self.m(), c: self.c() }
   }
 }
 
-/// Converts an arithmetic expression containing numbers, variables and `{+, -, *}`
-/// into a mapping of term to coefficient
-///
-/// For example:
-/// `['a', '+', 'b', '*', 'c', '*', '5']` becomes `{'a': 1, 'b*c': 5}`
-///
-/// Note that this is a recursive algo, so the input can be a mix of tokens and
-/// mapping expressions.
-fn evaluate<'a>(
-  exprs: &[&'a str],
-  first_is_neg: bool,
-) -> Result<HashMap<String, i32>, ParserError<'a>> {
-  let index_plus = exprs.iter().position(|&r| r == "+");
-  if let Some(index) = index_plus {
-    let l = evaluate(&exprs[..index], first_is_neg)?;
-    let r = evaluate(&exprs[index + 1..], false)?;
-    let l_keys: Vec<String> = l.keys().cloned().collect();
-    let r_keys: Vec<String> = r.keys().cloned().collect();
-    let mut key_set: HashSet<String> = HashSet::from_iter(l_keys);
-    key_set.extend(r_keys);
-
-    let mut res = HashMap::new();
-    for key in key_set.into_iter() {
-      let l_val = l.get(&key).unwrap_or(&0);
-      let r_val = r.get(&key).unwrap_or(&0);
-      res.insert(key.clone(), l_val + r_val);
-    }
-    return Ok(res);
-  }
-
-  let index_minus = exprs.iter().position(|&r| r == "-");
-  if let Some(index) = index_minus {
-    let l = evaluate(&exprs[..index], first_is_neg)?;
-    let r = evaluate(&exprs[index + 1..], true)?;
-    let l_keys: Vec<String> = l.keys().cloned().collect();
-    let r_keys: Vec<String> = r.keys().cloned().collect();
-    let mut key_set: HashSet<String> = HashSet::from_iter(l_keys);
-    key_set.extend(r_keys);
-
-    let mut res = HashMap::new();
-    for key in key_set.into_iter() {
-      let l_val = l.get(&key).unwrap_or(&0);
-      let r_val = r.get(&key).unwrap_or(&0);
-      res.insert(key.clone(), l_val + r_val);
-    }
-    return Ok(res);
-  }
-
-  let index_mul = exprs.iter().position(|&r| r == "*");
-  if let Some(index) = index_mul {
-    let l = evaluate(&exprs[..index], first_is_neg)?;
-    let r = evaluate(&exprs[index + 1..], false)?;
-
-    let mut res = HashMap::new();
-    for (k1, v1) in l.iter() {
-      for (k2, v2) in r.iter() {
-        res.insert(get_product_key(k1, k2), v1 * v2);
+/// Writes a sum of `coefficient * variable` terms, with an empty variable for the constant.
+fn write_terms(f: &mut Formatter<'_>, terms: &[(i128, &str)]) -> fmt::Result {
+  let terms: Vec<_> = terms.iter().filter(|(coeff, _)| *coeff != 0).collect();
+  if terms.is_empty() {
+    return write!(f, "0");
+  }
+  for (i, &&(coeff, var)) in terms.iter().enumerate() {
+    match (i, coeff < 0) {
+      (0, true) => write!(f, "-")?,
+      (0, false) => {},
+      (_, true) => write!(f, " - ")?,
+      (_, false) => write!(f, " + ")?,
+    }
+    match (coeff.unsigned_abs(), var) {
+      (abs, "") => write!(f, "{}", abs)?,
+      (1, var) => write!(f, "{}", var)?,
+      (abs, var) => write!(f, "{} * {}", abs, var)?,
+    }
+  }
+  Ok(())
+}
+
+/// Writes the constraint of a gate back in the DSL, like `c === -2 * b - a * b`.
+impl<'a> Display for WireCoeffs<'a> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    let wire = |i: usize| self.wires[i].as_deref();
+    if self.coeffs.get("$public") == Some(&1) {
+      return write!(f, "{} public", wire(0).unwrap_or_default());
+    }
+    if self.coeffs.get("$bool") == Some(&1) {
+      return write!(f, "{} bool", wire(0).unwrap_or_default());
+    }
+    if self.coeffs.get("$range") == Some(&1) {
+      return write!(f, "{} range", wire(0).unwrap_or_default());
+    }
+    if let Some(table) = self.coeffs.get("$lookup") {
+      return write!(f, "{} in table {}", self.lookup_values(), table);
+    }
+
+    let coeff = |key: &str| self.coeffs.get(key).copied().unwrap_or(0);
+    let mut terms = vec![];
+    let product;
+    if let (Some(a), Some(b)) = (wire(0), wire(1)) {
+      product = format!("{} * {}", a, b);
+      terms.push((coeff(&get_product_key(a, b)), product.as_str()));
+    }
+    if let Some(a) = wire(0) {
+      terms.push((coeff(a), a));
+    }
+    if let Some(b) = wire(1).filter(|&b| Some(b) != wire(0)) {
+      terms.push((coeff(b), b));
+    }
+    terms.push((coeff("$constant"), ""));
+
+    let output = self.coeffs.get("$output_coeffs").copied().unwrap_or(1);
+    write_terms(f, &wire(2).map(|out| vec![(output, out)]).unwrap_or_default())?;
+    write!(f, " === ")?;
+    write_terms(f, &terms)
+  }
+}
+
+/// Kinds of lookup tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableKind {
+  /// values `0..size`, in one column
+  Range,
+  /// rows `(a, b, a ^ b)` for `a` and `b` in `0..size`, in three columns
+  Xor,
+}
+
+/// A lookup table, declared with `table <name> range <size>` or `table <name> xor <size>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Table<'a> {
+  /// name of the table in lookups
+  pub name: &'a str,
+  /// kind of the table
+  pub kind: TableKind,
+  /// range of the values of the table
+  pub size: usize,
+}
+
+impl<'a> Table<'a> {
+  /// returns the number of values in each row, i.e. of wires in a lookup.
+  pub fn width(&self) -> usize {
+    match self.kind {
+      TableKind::Range => 1,
+      TableKind::Xor => 3,
+    }
+  }
+
+  /// returns the rows of the table, padded with zeros to three columns.
+  pub fn rows(&self) -> Vec<[usize; 3]> {
+    match self.kind {
+      TableKind::Range => (0..self.size).map(|value| [value, 0, 0]).collect(),
+      TableKind::Xor =>
+        (0..self.size).flat_map(|a| (0..self.size).map(move |b| [a, b, a ^ b])).collect(),
+    }
+  }
+}
+
+/// A value that witness generation computes outside the constraints, declared with
+/// `b <-- bit(x, i)`: bit `i` of the integer representative of `x`. Hints constrain nothing, so the
+/// value must be constrained by gates too, like in the `bits4` gadget.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint<'a> {
+  /// wire given the value of the bit
+  pub output: Cow<'a, str>,
+  /// wire whose bit is taken
+  pub input:  Cow<'a, str>,
+  /// index of the bit, from the least significant one
+  pub bit:    u32,
+}
+
+impl<'a> Display for Hint<'a> {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(f, "{} <-- bit({}, {})", self.output, self.input, self.bit)
+  }
+}
+
+/// Gates, lookup tables and hints of a circuit.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Circuit<'a> {
+  /// gates, one per row of the execution trace
+  pub gates:  Vec<WireCoeffs<'a>>,
+  /// lookup tables in declaration order. Lookup rows refer to them by their position, starting at
+  /// 1, in the `$lookup` coefficient.
+  pub tables: Vec<Table<'a>>,
+  /// hints for witness generation, in declaration order
+  pub hints:  Vec<Hint<'a>>,
+}
+
+/// An arithmetic expression over variables and integer constants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expression<'a> {
+  Constant(i128),
+  Variable(&'a str),
+  Negate(Box<Expression<'a>>),
+  Add(Box<Expression<'a>>, Box<Expression<'a>>),
+  Subtract(Box<Expression<'a>>, Box<Expression<'a>>),
+  Multiply(Box<Expression<'a>>, Box<Expression<'a>>),
+  /// call of the gadget named by the token
+  Call(Token<'a>, Vec<Expression<'a>>),
+}
+
+/// A parser error that is not rendered yet.
+type SpannedError<'a> = (ParserErrorKind<'a>, Span);
+
+/// Recursive descent parser of expressions, from lowest to highest precedence:
+/// - `expression := term (('+' | '-') term)*`
+/// - `term := factor ('*' factor)*`
+/// - `factor := '-' factor | integer | variable | call | '(' expression ')'`
+/// - `call := variable '(' (expression (',' expression)*)? ')'`
+struct ExpressionParser<'a, 'b> {
+  tokens:   &'b [Token<'a>],
+  position: usize,
+  /// empty span after the last token, where the statement ends
+  end:      Span,
+}
+
+impl<'a, 'b> ExpressionParser<'a, 'b> {
+  /// Parses all tokens as a single expression.
+  fn parse(tokens: &'b [Token<'a>], end: Span) -> Result<Expression<'a>, SpannedError<'a>> {
+    let mut parser = Self { tokens, position: 0, end };
+    let expression = parser.expression()?;
+    match parser.peek() {
+      None => Ok(expression),
+      Some(token) if token.kind == TokenKind::RightParen =>
+        Err((ParserErrorKind::UnbalancedParentheses, token.span)),
+      Some(token) => Err((ParserErrorKind::EvaluateMultipleSubExpression(token.text), token.span)),
+    }
+  }
+
+  fn peek(&self) -> Option<Token<'a>> { self.tokens.get(self.position).copied() }
+
+  fn next(&mut self) -> Option<Token<'a>> {
+    let token = self.peek();
+    self.position += 1;
+    token
+  }
+
+  /// Consumes the next token if it has the given kind.
+  fn next_if(&mut self, kind: TokenKind) -> Option<Token<'a>> {
+    self.peek().filter(|token| token.kind == kind).inspect(|_| self.position += 1)
+  }
+
+  fn expression(&mut self) -> Result<Expression<'a>, SpannedError<'a>> {
+    let mut expression = self.term()?;
+    loop {
+      expression = if self.next_if(TokenKind::Plus).is_some() {
+        Expression::Add(Box::new(expression), Box::new(self.term()?))
+      } else if self.next_if(TokenKind::Minus).is_some() {
+        Expression::Subtract(Box::new(expression), Box::new(self.term()?))
+      } else {
+        return Ok(expression);
+      };
+    }
+  }
+
+  fn term(&mut self) -> Result<Expression<'a>, SpannedError<'a>> {
+    let mut expression = self.factor()?;
+    while self.next_if(TokenKind::Star).is_some() {
+      expression = Expression::Multiply(Box::new(expression), Box::new(self.factor()?));
+    }
+    Ok(expression)
+  }
+
+  fn factor(&mut self) -> Result<Expression<'a>, SpannedError<'a>> {
+    let Some(token) = self.next() else {
+      return Err((ParserErrorKind::UnexpectedEnd, self.end));
+    };
+    match token.kind {
+      TokenKind::Minus => Ok(Expression::Negate(Box::new(self.factor()?))),
+      TokenKind::LeftParen => {
+        let expression = self.expression()?;
+        match self.next_if(TokenKind::RightParen) {
+          Some(_) => Ok(expression),
+          None => Err((ParserErrorKind::UnbalancedParentheses, token.span)),
+        }
+      },
+      TokenKind::Number => token.text.parse().map(Expression::Constant).map_err(|_| {
+        (ParserErrorKind::ConstraintsInvalidCoefficientValues(token.text.to_string()), token.span)
+      }),
+      TokenKind::Identifier => match self.next_if(TokenKind::LeftParen) {
+        Some(paren) => self.call(token, paren),
+        None => Ok(Expression::Variable(token.text)),
+      },
+      _ => Err((ParserErrorKind::EvaluateInvalidExpression(token.text), token.span)),
+    }
+  }
+
+  /// Parses the arguments of a call of the gadget `name`, after its `(`.
+  fn call(
+    &mut self,
+    name: Token<'a>,
+    paren: Token<'a>,
+  ) -> Result<Expression<'a>, SpannedError<'a>> {
+    let mut arguments = vec![];
+    if self.next_if(TokenKind::RightParen).is_none() {
+      loop {
+        arguments.push(self.expression()?);
+        if self.next_if(TokenKind::Comma).is_some() {
+          continue;
+        }
+        match self.next_if(TokenKind::RightParen) {
+          Some(_) => break,
+          None => return Err((ParserErrorKind::UnbalancedParentheses, paren.span)),
+        }
       }
     }
-    return Ok(res);
+    Ok(Expression::Call(name, arguments))
   }
+}
+
+/// Product of at most two wires, sorted, with no wire for the constant term.
+pub(crate) type Monomial<'a> = Vec<Cow<'a, str>>;
+
+/// Sum of monomials with their coefficients.
+pub(crate) type Terms<'a> = BTreeMap<Monomial<'a>, i128>;
+
+/// Linear combination `Σ coeff · variable` of a rank-1 constraint, with no variable for the
+/// constant one.
+pub(crate) type LinearTerms<'a> = Vec<(Option<&'a str>, i128)>;
 
-  if exprs.len() > 1 {
-    return Err(ParserError::EvaluateMultipleSubExpression(exprs.join(" ").to_string()));
-  } else if exprs[0].starts_with('-') {
-    return evaluate(&[&exprs[0][1..]], !first_is_neg);
-  } else if exprs[0].trim().parse::<i32>().is_ok() {
-    let num = exprs[0].trim().parse::<i32>().unwrap_or(0);
-    let sign = if first_is_neg { -1 } else { 1 };
-    return Ok(HashMap::from([("$constant".to_string(), num * sign)]));
-  } else if is_valid_var_name(exprs[0]) {
-    let sign = if first_is_neg { -1 } else { 1 };
-    return Ok(HashMap::from([(exprs[0].to_string(), sign)]));
-  } else {
-    return Err(ParserError::EvaluateInvalidExpression(exprs[0]));
+/// Returns the constant value of `terms` if no wire appears in it.
+fn constant_value(terms: &Terms) -> Option<i128> {
+  match terms.len() {
+    0 => Some(0),
+    1 => terms.get(&vec![]).copied(),
+    _ => None,
   }
 }
 
-/// Parse constraints into [`WireCoeffs`] containing wires and corresponding coefficients.
-///
-/// ## Example
-///
-/// valid equations, and output:
-/// - `a === 9` =>                     `([None, None, 'a'], {'': 9})`
-/// - `a public` =>                    `(['a', None, None], {'$public': 1, 'a': -1,
-///   '$output_coeffs': 0}`
-/// - `b <== a * c` =>                 `(['a', 'c', 'b'], {'a*c': 1})`
-/// - `d <== a * c - 45 * a + 987` =>  `(['a', 'c', 'd'], {'a*c': 1, 'a': -45, '$constant': 987})`
-///
-/// invalid equations:
-/// - `7 === 7`             =>         # Can't assign to non-variable
-/// - `a <== b * * c`       =>         # Two times signs in a row
-/// - `e <== a + b * c * d` =>         # Multiplicative degree > 2
-pub fn parse_constraints(constraint: &str) -> Result<WireCoeffs, ParserError> {
-  // split into tokens by spaces
-  let tokens: Vec<&str> = constraint.trim().trim_end_matches('\n').split(' ').collect();
+/// Returns the wire `w` if `terms` is `w`.
+fn single_wire<'a>(terms: &Terms<'a>) -> Option<Cow<'a, str>> {
+  match (terms.len(), terms.iter().next()) {
+    (1, Some((monomial, 1))) => monomial.first().filter(|_| monomial.len() == 1).cloned(),
+    _ => None,
+  }
+}
+
+/// Distinct wires appearing in `terms`, sorted.
+pub(crate) fn variables<'a>(terms: &Terms<'a>) -> Vec<Cow<'a, str>> {
+  terms.keys().flatten().cloned().collect::<BTreeSet<_>>().into_iter().collect()
+}
+
+/// Whether `terms` can be computed by a single [`Gate`]: at most two input wires, and only their
+/// product as quadratic term.
+pub(crate) fn fits_gate(terms: &Terms) -> bool {
+  let variables = variables(terms);
+  variables.len() <= 2
+    && terms.keys().filter(|monomial| monomial.len() == 2).all(|monomial| match variables.len() {
+      1 => true,
+      _ => monomial == &variables,
+    })
+}
 
-  // parse assignment or arithmetic checks
-  if tokens[1] == "<==" || tokens[1] == "===" {
-    // first token is the output
-    let mut out = tokens[0];
+/// Whether `terms` is linear with at most one wire, so that it can be added to another wire.
+fn is_linear_in_one_variable(terms: &Terms) -> bool {
+  terms.keys().all(|monomial| monomial.len() < 2) && variables(terms).len() <= 1
+}
 
-    // parse rest of the constraints
-    let mut coeffs = evaluate(&tokens[2..], false)?;
+/// `scalar * terms`, or an error if a coefficient overflows.
+pub(crate) fn checked_scale(
+  terms: Terms<'_>,
+  scalar: i128,
+) -> Result<Terms<'_>, ParserErrorKind<'_>> {
+  if scalar == 0 {
+    return Ok(Terms::new());
+  }
+  terms
+    .into_iter()
+    .map(|(monomial, coeff)| {
+      let coeff = coeff.checked_mul(scalar).ok_or_else(|| overflow(&monomial))?;
+      Ok((monomial, coeff))
+    })
+    .collect()
+}
+
+/// `lhs + rhs` without zero coefficients, or an error if a coefficient overflows.
+pub(crate) fn checked_add<'a>(
+  mut lhs: Terms<'a>,
+  rhs: Terms<'a>,
+) -> Result<Terms<'a>, ParserErrorKind<'a>> {
+  for (monomial, coeff) in rhs {
+    let sum =
+      lhs.get(&monomial).unwrap_or(&0).checked_add(coeff).ok_or_else(|| overflow(&monomial))?;
+    match sum {
+      0 => lhs.remove(&monomial),
+      _ => lhs.insert(monomial, sum),
+    };
+  }
+  Ok(lhs)
+}
+
+/// Error for a coefficient of `monomial` that doesn't fit an `i128`.
+fn overflow<'a>(monomial: &Monomial) -> ParserErrorKind<'a> {
+  let key = match monomial.as_slice() {
+    [] => String::from("constant term"),
+    [a] => a.to_string(),
+    [a, b] => get_product_key(a, b),
+    _ => unreachable!("monomials have degree at most 2"),
+  };
+  ParserErrorKind::ConstraintsInvalidCoefficientValues(key)
+}
 
-    // handle output's negative coefficient
-    if out.starts_with('-') {
-      out = &out[1..];
-      coeffs.insert("$output_coeffs".to_string(), -1);
+/// Returns the gate `output_coeff * output = terms`, where `terms` fits a single gate, or
+/// `0 = terms` without output.
+pub(crate) fn gate_from_terms<'a>(
+  output: Option<Cow<'a, str>>,
+  output_coeff: i128,
+  terms: Terms<'a>,
+) -> WireCoeffs<'a> {
+  let mut variables = variables(&terms);
+  if variables.len() == 1 {
+    variables.push(variables[0].clone());
+  }
+  let mut coeffs: HashMap<String, i128> = terms
+    .into_iter()
+    .map(|(monomial, coeff)| match monomial.as_slice() {
+      [] => (String::from("$constant"), coeff),
+      [a] => (a.to_string(), coeff),
+      [a, b] => (get_product_key(a, b), coeff),
+      _ => unreachable!("monomials have degree at most 2"),
+    })
+    .collect();
+  if output_coeff != 1 {
+    coeffs.insert(String::from("$output_coeffs"), output_coeff);
+  }
+
+  let variables_len = variables.len();
+  let wires = variables
+    .into_iter()
+    .map(Some)
+    .chain(iter::repeat(None).take(2 - variables_len))
+    .chain(iter::once(output))
+    .collect();
+  WireCoeffs { wires, coeffs }
+}
+
+/// A gadget defined with `gadget <name>(<parameters>) -> <outputs> { <body> }`.
+#[derive(Debug, Clone)]
+struct Gadget<'a> {
+  name:       &'a str,
+  parameters: Vec<&'a str>,
+  outputs:    Vec<&'a str>,
+  /// statements of the body
+  body:       Vec<Vec<Token<'a>>>,
+  /// source of the definition, which the tokens of the body point into
+  source:     &'a str,
+}
+
+/// Variables of a call of a gadget being expanded.
+struct Scope<'a> {
+  gadget:   &'a str,
+  /// prefix of the wires local to the call, like `select.1`
+  prefix:   String,
+  /// terms of the parameters, outputs and local variables
+  bindings: HashMap<&'a str, Terms<'a>>,
+}
+
+/// Lowers expressions into fan-in 2 gates, introducing intermediate wires when an expression
+/// doesn't fit a single gate, and expands calls of gadgets.
+#[derive(Default)]
+struct GateBuilder<'a> {
+  gates:              Vec<WireCoeffs<'a>>,
+  tables:             Vec<Table<'a>>,
+  hints:              Vec<Hint<'a>>,
+  gadgets:            Vec<Gadget<'a>>,
+  /// scopes of the gadgets being expanded, innermost last
+  scopes:             Vec<Scope<'a>>,
+  /// number of calls of each gadget
+  calls:              HashMap<&'a str, usize>,
+  intermediate_wires: usize,
+}
+
+impl<'a> GateBuilder<'a> {
+  /// Adds the gate `output_coeff * output = terms`, see [`gate_from_terms`].
+  fn push_gate(&mut self, output: Option<Cow<'a, str>>, output_coeff: i128, terms: Terms<'a>) {
+    self.gates.push(gate_from_terms(output, output_coeff, terms));
+  }
+
+  /// Returns a single wire `w` and coefficient `k` with `k * w = terms`, adding a gate with a new
+  /// intermediate wire unless `terms` is already a multiple of a wire.
+  fn wire(&mut self, terms: Terms<'a>) -> (Cow<'a, str>, i128) {
+    if terms.len() == 1 {
+      let (monomial, coeff) = terms.into_iter().next().unwrap();
+      match <[_; 1]>::try_from(monomial) {
+        Ok([wire]) => (wire, coeff),
+        Err(monomial) => (self.intermediate_wire(Terms::from([(monomial, 1)])), coeff),
+      }
+    } else {
+      (self.intermediate_wire(terms), 1)
     }
+  }
+
+  /// Returns a new intermediate wire `$n`.
+  fn fresh_wire(&mut self) -> Cow<'a, str> {
+    self.intermediate_wires += 1;
+    Cow::Owned(format!("${}", self.intermediate_wires))
+  }
 
-    // handle valid output variable name
-    if !is_valid_var_name(out) {
-      return Err(ParserError::ConstraintsInvalidVariableName(out));
+  /// Adds the gate `$n = terms` for a new intermediate wire `$n`.
+  fn intermediate_wire(&mut self, terms: Terms<'a>) -> Cow<'a, str> {
+    let wire = self.fresh_wire();
+    self.push_gate(Some(wire.clone()), 1, terms);
+    wire
+  }
+
+  /// Terms of the variable `name`: the variable itself outside gadgets, and in a gadget the
+  /// argument of a parameter, the wire of an output, or a wire local to the call.
+  fn resolve(&mut self, name: &'a str) -> Terms<'a> {
+    let Some(scope) = self.scopes.last_mut() else {
+      return Terms::from([(vec![Cow::Borrowed(name)], 1)]);
+    };
+    let local = || Terms::from([(vec![Cow::Owned(format!("{}.{}", scope.prefix, name))], 1)]);
+    scope.bindings.entry(name).or_insert_with(local).clone()
+  }
+
+  /// Wire of the variable `name`, adding a gate for it if it is bound to other terms than a wire.
+  fn variable(&mut self, name: &'a str) -> Cow<'a, str> {
+    let terms = self.resolve(name);
+    if let Some(wire) = single_wire(&terms) {
+      return wire;
     }
+    let wire = self.intermediate_wire(terms);
+    if let Some(scope) = self.scopes.last_mut() {
+      scope.bindings.insert(name, Terms::from([(vec![wire.clone()], 1)]));
+    }
+    wire
+  }
 
-    // parse all valid variables
-    let mut variables: Vec<&str> = tokens
-      .into_iter()
-      .skip(2)
-      .map(|var| var.trim_start_matches('-'))
-      .filter(|name| is_valid_var_name(name))
-      .collect::<HashSet<&str>>()
-      .into_iter()
-      .collect();
-    variables.sort();
-
-    // create set of all allowed variables
-    let mut allowed_coeffs_set: HashSet<String> =
-      HashSet::from_iter(variables.iter().map(|var| var.to_string()));
-    allowed_coeffs_set.extend(["$output_coeffs".to_string(), "$constant".to_string()]);
-    match variables.len() {
-      0 => {},
-      1 => {
-        variables.push(variables[0]);
-        allowed_coeffs_set.insert(get_product_key(variables[0], variables[0]));
+  /// Expands a call of the gadget `name`, with `outputs` as the wires of its outputs.
+  fn call(
+    &mut self,
+    name: &'a str,
+    arguments: &[Expression<'a>],
+    outputs: Vec<Cow<'a, str>>,
+  ) -> Result<(), ParserErrorKind<'a>> {
+    let Some(gadget) = self.gadgets.iter().find(|gadget| gadget.name == name).cloned() else {
+      return Err(ParserErrorKind::GadgetUnknown(name));
+    };
+    if arguments.len() != gadget.parameters.len() {
+      let expected = gadget.parameters.len();
+      return Err(ParserErrorKind::GadgetInvalidArguments(name, arguments.len(), expected));
+    }
+    if outputs.len() != gadget.outputs.len() {
+      let expected = gadget.outputs.len();
+      return Err(ParserErrorKind::GadgetInvalidOutputs(name, outputs.len(), expected));
+    }
+    if self.scopes.iter().any(|scope| scope.gadget == name) {
+      return Err(ParserErrorKind::GadgetRecursion(name));
+    }
+
+    let mut bindings = HashMap::new();
+    for (parameter, argument) in gadget.parameters.iter().zip(arguments) {
+      // anything but a constant or a multiple of a wire is computed once, in a wire
+      let terms = self.lower(argument)?;
+      let terms = match terms.len() <= 1 && terms.keys().all(|monomial| monomial.len() <= 1) {
+        true => terms,
+        false => Terms::from([(vec![self.intermediate_wire(terms)], 1)]),
+      };
+      bindings.insert(*parameter, terms);
+    }
+    for (output, wire) in gadget.outputs.iter().zip(outputs) {
+      bindings.insert(*output, Terms::from([(vec![wire], 1)]));
+    }
+    let calls = self.calls.entry(name).or_default();
+    *calls += 1;
+    let prefix = format!("{}.{}", name, calls);
+
+    self.scopes.push(Scope { gadget: name, prefix, bindings });
+    let expanded =
+      gadget.body.iter().try_for_each(|statement| self.statement(statement, gadget.source));
+    self.scopes.pop();
+    expanded.map_err(|(kind, _)| ParserErrorKind::InGadget(name, Box::new(kind)))
+  }
+
+  /// Lowers `expression` into terms that fit a single gate, adding gates for the intermediate wires
+  /// it needs.
+  fn lower(&mut self, expression: &Expression<'a>) -> Result<Terms<'a>, ParserErrorKind<'a>> {
+    match expression {
+      Expression::Constant(0) => Ok(Terms::new()),
+      Expression::Constant(value) => Ok(Terms::from([(vec![], *value)])),
+      Expression::Negate(inner) => {
+        let inner = self.lower(inner)?;
+        checked_scale(inner, -1)
+      },
+      Expression::Add(lhs, rhs) => {
+        let (lhs, rhs) = (self.lower(lhs)?, self.lower(rhs)?);
+        self.add(lhs, rhs)
+      },
+      Expression::Subtract(lhs, rhs) => {
+        let (lhs, rhs) = (self.lower(lhs)?, self.lower(rhs)?);
+        let rhs = checked_scale(rhs, -1)?;
+        self.add(lhs, rhs)
+      },
+      Expression::Multiply(lhs, rhs) => {
+        let (lhs, rhs) = (self.lower(lhs)?, self.lower(rhs)?);
+        self.multiply(lhs, rhs)
+      },
+      Expression::Variable(name) => Ok(self.resolve(name)),
+      Expression::Call(name, arguments) => {
+        let output = self.fresh_wire();
+        self.call(name.text, arguments, vec![output.clone()])?;
+        Ok(Terms::from([(vec![output], 1)]))
+      },
+    }
+  }
+
+  /// `lhs * rhs`, moving both sides into wires unless either is constant.
+  fn multiply(&mut self, lhs: Terms<'a>, rhs: Terms<'a>) -> Result<Terms<'a>, ParserErrorKind<'a>> {
+    if let Some(scalar) = constant_value(&lhs) {
+      return checked_scale(rhs, scalar);
+    }
+    if let Some(scalar) = constant_value(&rhs) {
+      return checked_scale(lhs, scalar);
+    }
+    let (a, a_coeff) = self.wire(lhs);
+    let (b, b_coeff) = self.wire(rhs);
+    let coeff =
+      a_coeff.checked_mul(b_coeff).ok_or_else(|| overflow(&vec![a.clone(), b.clone()]))?;
+    let mut monomial = vec![a, b];
+    monomial.sort();
+    Ok(Terms::from([(monomial, coeff)]))
+  }
+
+  /// Sums a linear combination one term at a time, so that the sum fits a gate.
+  fn linear_combination(
+    &mut self,
+    terms: &LinearTerms<'a>,
+  ) -> Result<Terms<'a>, ParserErrorKind<'a>> {
+    terms.iter().try_fold(Terms::new(), |sum, &(variable, coeff)| {
+      let monomial = variable.map(Cow::Borrowed).into_iter().collect();
+      self.add(sum, Terms::from([(monomial, coeff)]))
+    })
+  }
+
+  /// Adds the gates of the rank-1 constraint `a * b = c`, with a last gate `0 = a * b - c` without
+  /// output, unless the constraint always holds.
+  fn rank_one(&mut self, [a, b, c]: &[LinearTerms<'a>; 3]) -> Result<(), ParserErrorKind<'a>> {
+    let (a, b) = (self.linear_combination(a)?, self.linear_combination(b)?);
+    let product = self.multiply(a, b)?;
+    let c = self.linear_combination(c)?;
+    let terms = self.add(product, checked_scale(c, -1)?)?;
+    if !terms.is_empty() {
+      self.push_gate(None, 0, terms);
+    }
+    Ok(())
+  }
+
+  /// [`Self::wire`] as terms.
+  fn wire_terms(&mut self, terms: Terms<'a>) -> Terms<'a> {
+    let (wire, coeff) = self.wire(terms);
+    Terms::from([(vec![wire], coeff)])
+  }
+
+  /// `lhs + rhs`, moving either side or both into intermediate wires if the sum doesn't fit a gate.
+  fn add(&mut self, lhs: Terms<'a>, rhs: Terms<'a>) -> Result<Terms<'a>, ParserErrorKind<'a>> {
+    let sum = checked_add(lhs.clone(), rhs.clone())?;
+    if fits_gate(&sum) {
+      return Ok(sum);
+    }
+    // keep a side as is if it is linear in a single wire, which fits next to any other wire
+    let lhs = match is_linear_in_one_variable(&lhs) && !is_linear_in_one_variable(&rhs) {
+      true => lhs,
+      false => self.wire_terms(lhs),
+    };
+    let rhs = match fits_gate(&checked_add(lhs.clone(), rhs.clone())?) {
+      true => rhs,
+      false => self.wire_terms(rhs),
+    };
+    checked_add(lhs, rhs)
+  }
+
+  /// Parses a statement and adds its gates.
+  fn statement(&mut self, tokens: &[Token<'a>], source: &'a str) -> Result<(), SpannedError<'a>> {
+    let span = tokens[0].span.to(tokens[tokens.len() - 1].span);
+    let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind).collect();
+    let in_gadget = !self.scopes.is_empty();
+    if in_gadget
+      && matches!(kinds[..], [_, TokenKind::Public] | [TokenKind::Table | TokenKind::Gadget, ..])
+    {
+      let statement = &source[span.start..span.end];
+      return Err((ParserErrorKind::GadgetInvalidStatement(statement), span));
+    }
+    match kinds.as_slice() {
+      [TokenKind::Identifier, TokenKind::Public] => {
+        let name = tokens[0].text;
+        // parse public constraint
+        let coeffs = HashMap::from([
+          (name.to_string(), -1),
+          (String::from("$output_coeffs"), 0),
+          (String::from("$public"), 1),
+        ]);
+        self.gates.push(WireCoeffs { wires: vec![Some(Cow::Borrowed(name)), None, None], coeffs });
+        Ok(())
+      },
+      [TokenKind::Minus, _, TokenKind::Assign | TokenKind::Equal, ..]
+      | [_, TokenKind::Assign | TokenKind::Equal, ..] => {
+        // handle output's negative coefficient
+        let (output_coeff, tokens) = match kinds[0] {
+          TokenKind::Minus => (-1, &tokens[1..]),
+          _ => (1, tokens),
+        };
+
+        // handle valid output variable name
+        let out = tokens[0];
+        if out.kind != TokenKind::Identifier {
+          return Err((ParserErrorKind::ConstraintsInvalidVariableName(out.text), out.span));
+        }
+
+        let expression_tokens = &tokens[2..];
+        let end = Span::new(span.end, span.end);
+        let expression = ExpressionParser::parse(expression_tokens, end)?;
+        let expression_span = Span::new(expression_tokens[0].span.start, span.end);
+        let at_expression = |kind| (kind, expression_span);
+        if let (1, Expression::Call(name, arguments)) = (output_coeff, &expression) {
+          // the assigned variable is the output of the gadget itself
+          let output = self.variable(out.text);
+          return self.call(name.text, arguments, vec![output]).map_err(at_expression);
+        }
+        let terms = self.lower(&expression).map_err(at_expression)?;
+        let output = self.variable(out.text);
+        self.push_gate(Some(output), output_coeff, terms);
+        Ok(())
+      },
+      [TokenKind::Identifier, TokenKind::Bool] => {
+        self.custom_gate(tokens[0].text, "$bool");
+        Ok(())
       },
-      2 => {
-        allowed_coeffs_set.insert(get_product_key(variables[0], variables[1]));
+      [TokenKind::Identifier, TokenKind::Range] => {
+        self.custom_gate(tokens[0].text, "$range");
+        Ok(())
       },
-      _ => return Err(ParserError::ConstraintsMaxVariables(variables)),
+      [TokenKind::LeftParen, ..]
+        if kinds.iter().any(|kind| matches!(kind, TokenKind::Assign | TokenKind::Equal)) =>
+        self.tuple_assignment(tokens, span, source),
+      [TokenKind::Identifier, TokenKind::LeftParen, ..] => {
+        let expression = ExpressionParser::parse(tokens, Span::new(span.end, span.end))?;
+        match &expression {
+          Expression::Call(name, arguments) =>
+            self.call(name.text, arguments, vec![]).map_err(|kind| (kind, span)),
+          _ =>
+            Err((ParserErrorKind::ConstraintsUnsupportedValue(&source[span.start..span.end]), span)),
+        }
+      },
+      [TokenKind::Identifier, TokenKind::Hint, TokenKind::Identifier, TokenKind::LeftParen, TokenKind::Identifier, TokenKind::Comma, TokenKind::Number, TokenKind::RightParen]
+        if tokens[2].text == "bit" =>
+        self.hint(tokens),
+      [TokenKind::Gadget, ..] => self.define(tokens, span, source),
+      [TokenKind::Table, TokenKind::Identifier, TokenKind::Range | TokenKind::Xor, TokenKind::Number] =>
+        self.table(tokens),
+      [TokenKind::Identifier, TokenKind::In, TokenKind::Identifier] => self.lookup(tokens),
+      [TokenKind::LeftParen, .., TokenKind::RightParen, TokenKind::In, TokenKind::Identifier] =>
+        self.lookup(tokens),
+      _ => Err((ParserErrorKind::ConstraintsUnsupportedValue(&source[span.start..span.end]), span)),
     }
+  }
+
+  /// Adds a custom gate on the left wire, selected by the coefficient `key`.
+  fn custom_gate(&mut self, name: &'a str, key: &str) {
+    let coeffs = HashMap::from([(String::from("$output_coeffs"), 0), (key.to_string(), 1)]);
+    let wire = self.variable(name);
+    self.gates.push(WireCoeffs { wires: vec![Some(wire), None, None], coeffs });
+  }
 
-    // check if valid coeff values
-    for key in coeffs.keys() {
-      if !allowed_coeffs_set.contains::<String>(key) {
-        return Err(ParserError::ConstraintsInvalidCoefficientValues(key.clone()));
+  /// Expands `(a, b) <== gadget(..)`, with the variables in parentheses as outputs of the gadget.
+  fn tuple_assignment(
+    &mut self,
+    tokens: &[Token<'a>],
+    span: Span,
+    source: &'a str,
+  ) -> Result<(), SpannedError<'a>> {
+    let assign = tokens
+      .iter()
+      .position(|token| matches!(token.kind, TokenKind::Assign | TokenKind::Equal))
+      .expect("tuple assignments have an assignment");
+    let outputs = variable_list(&tokens[..assign], tokens[assign].span)?;
+    let expression_tokens = &tokens[assign + 1..];
+    let expression = ExpressionParser::parse(expression_tokens, Span::new(span.end, span.end))?;
+    let Expression::Call(name, arguments) = &expression else {
+      return Err((
+        ParserErrorKind::ConstraintsUnsupportedValue(&source[span.start..span.end]),
+        span,
+      ));
+    };
+    let expression_span = Span::new(expression_tokens[0].span.start, span.end);
+    let outputs = outputs.iter().map(|output| self.variable(output.text)).collect();
+    self.call(name.text, arguments, outputs).map_err(|kind| (kind, expression_span))
+  }
+
+  /// Adds the hint `b <-- bit(x, i)`.
+  fn hint(&mut self, tokens: &[Token<'a>]) -> Result<(), SpannedError<'a>> {
+    let index = tokens[6];
+    let bit = index.text.parse().ok().filter(|&bit| bit < usize::BITS);
+    let bit = bit.ok_or((ParserErrorKind::HintInvalidBit(index.text), index.span))?;
+    let input = self.variable(tokens[4].text);
+    let output = self.variable(tokens[0].text);
+    self.hints.push(Hint { output, input, bit });
+    Ok(())
+  }
+
+  /// Defines the gadget of `gadget <name>(<parameters>) -> <outputs> { <body> }`.
+  fn define(
+    &mut self,
+    tokens: &[Token<'a>],
+    span: Span,
+    source: &'a str,
+  ) -> Result<(), SpannedError<'a>> {
+    let unsupported =
+      || (ParserErrorKind::ConstraintsUnsupportedValue(&source[span.start..span.end]), span);
+    let open = tokens.iter().position(|token| token.kind == TokenKind::LeftBrace);
+    let open = open.ok_or_else(unsupported)?;
+    if tokens[tokens.len() - 1].kind != TokenKind::RightBrace {
+      return Err((ParserErrorKind::UnbalancedBraces, tokens[open].span));
+    }
+    let (header, body) = (&tokens[..open], &tokens[open + 1..tokens.len() - 1]);
+    let (signature, outputs) = match header.iter().position(|token| token.kind == TokenKind::Arrow)
+    {
+      Some(arrow) => (&header[..arrow], variable_list(&header[arrow + 1..], header[arrow].span)?),
+      None => (header, vec![]),
+    };
+    let kinds: Vec<TokenKind> = signature.iter().map(|token| token.kind).collect();
+    let [TokenKind::Gadget, name_kind, TokenKind::LeftParen, .., TokenKind::RightParen] = kinds[..]
+    else {
+      return Err(unsupported());
+    };
+    let name = signature[1];
+    if name_kind != TokenKind::Identifier {
+      return Err((ParserErrorKind::ConstraintsInvalidVariableName(name.text), name.span));
+    }
+    let parameters = identifiers(&signature[3..signature.len() - 1])?;
+    let variables: Vec<&Token> = parameters.iter().chain(&outputs).collect();
+    for (i, variable) in variables.iter().enumerate() {
+      if variables[..i].iter().any(|other| other.text == variable.text) {
+        return Err((
+          ParserErrorKind::ConstraintsInvalidVariableName(variable.text),
+          variable.span,
+        ));
       }
     }
+    if self.gadgets.iter().any(|gadget| gadget.name == name.text) {
+      return Err((ParserErrorKind::GadgetDuplicateName(name.text), name.span));
+    }
 
-    let variables_len = variables.len();
+    self.gadgets.push(Gadget {
+      name: name.text,
+      parameters: parameters.iter().map(|token| token.text).collect(),
+      outputs: outputs.iter().map(|token| token.text).collect(),
+      body: statements(body),
+      source,
+    });
+    Ok(())
+  }
 
-    // get wire variable names
-    let mut wires: Vec<Option<&str>> =
-      variables.into_iter().map(Some).chain(iter::repeat(None).take(2 - variables_len)).collect();
+  /// Declares the table of `table <name> <kind> <size>`.
+  fn table(&mut self, tokens: &[Token<'a>]) -> Result<(), SpannedError<'a>> {
+    let (name, size) = (tokens[1], tokens[3]);
+    if self.tables.iter().any(|table| table.name == name.text) {
+      return Err((ParserErrorKind::TableDuplicateName(name.text), name.span));
+    }
+    let kind = match tokens[2].kind {
+      TokenKind::Range => TableKind::Range,
+      _ => TableKind::Xor,
+    };
+    let rows = |size: usize| match kind {
+      TableKind::Range => Some(size),
+      TableKind::Xor => size.checked_mul(size),
+    };
+    let size_value = size.text.parse().ok().filter(|&size| size > 0 && rows(size).is_some());
+    let size_value = size_value.ok_or((ParserErrorKind::TableInvalidSize(size.text), size.span))?;
+    self.tables.push(Table { name: name.text, kind, size: size_value });
+    Ok(())
+  }
 
-    // output variable is in last wire
-    wires.push(Some(out));
+  /// Adds the lookup row of `x in <table>` or `(a, b, c) in <table>`.
+  fn lookup(&mut self, tokens: &[Token<'a>]) -> Result<(), SpannedError<'a>> {
+    let (values, name) = tokens.split_at(tokens.len() - 2);
+    let name = name[1];
+    let Some(id) = self.tables.iter().position(|table| table.name == name.text) else {
+      return Err((ParserErrorKind::LookupUnknownTable(name.text), name.span));
+    };
 
-    Ok(WireCoeffs { wires, coeffs })
-  } else if tokens[1] == "public" {
-    // parse public constraint
+    let values_span = values[0].span.to(values[values.len() - 1].span);
+    let values = variable_list(values, values_span)?;
+    let width = self.tables[id].width();
+    if values.len() != width {
+      return Err((ParserErrorKind::LookupInvalidWidth(values.len(), width), values_span));
+    }
+
+    let mut wires = vec![];
+    for value in values {
+      wires.push(Some(self.variable(value.text)));
+    }
+    wires.resize(3, None);
     let coeffs = HashMap::from([
-      (tokens[0].to_string(), -1),
       (String::from("$output_coeffs"), 0),
-      (String::from("$public"), 1),
+      (String::from("$lookup"), id as i128 + 1),
     ]);
+    self.gates.push(WireCoeffs { wires, coeffs });
+    Ok(())
+  }
+
+  /// Parses every statement of `source` and adds their gates. Line numbers of errors start at
+  /// `first_line`.
+  fn source(&mut self, source: &'a str, first_line: usize) -> Result<(), ParserError<'a>> {
+    let render = |(kind, span)| ParserError::new(kind, span, source, first_line);
+    let tokens = lex(source).map_err(render)?;
+    for statement in statements(&tokens) {
+      self.statement(&statement, source).map_err(render)?;
+    }
+    Ok(())
+  }
+}
+
+/// Parses variables separated by commas, like `a, b, c`.
+fn identifiers<'a>(tokens: &[Token<'a>]) -> Result<Vec<Token<'a>>, SpannedError<'a>> {
+  let mut variables = vec![];
+  for (i, token) in tokens.iter().enumerate() {
+    match (i % 2, token.kind) {
+      (0, TokenKind::Identifier) => variables.push(*token),
+      (1, TokenKind::Comma) if i + 1 < tokens.len() => {},
+      (0, _) =>
+        return Err((ParserErrorKind::ConstraintsInvalidVariableName(token.text), token.span)),
+      _ => return Err((ParserErrorKind::EvaluateInvalidExpression(token.text), token.span)),
+    }
+  }
+  Ok(variables)
+}
+
+/// Parses a variable, or variables separated by commas in parentheses, like `x` or `(a, b, c)`.
+/// `before` is the span before the tokens, where missing variables are reported.
+fn variable_list<'a>(
+  tokens: &[Token<'a>],
+  before: Span,
+) -> Result<Vec<Token<'a>>, SpannedError<'a>> {
+  match tokens {
+    [token] if token.kind == TokenKind::Identifier => Ok(vec![*token]),
+    [open, inner @ .., close]
+      if open.kind == TokenKind::LeftParen && close.kind == TokenKind::RightParen =>
+      identifiers(inner),
+    [token, ..] => Err((ParserErrorKind::ConstraintsInvalidVariableName(token.text), token.span)),
+    [] => Err((ParserErrorKind::UnexpectedEnd, Span::new(before.end, before.end))),
+  }
+}
+
+/// Splits tokens into statements, one per line. A statement continues on the next line inside
+/// parentheses or braces, or after a line ending with an operator.
+fn statements<'a>(tokens: &[Token<'a>]) -> Vec<Vec<Token<'a>>> {
+  let mut statements = vec![];
+  let (mut start, mut depth, mut braces) = (0, 0i32, 0i32);
+  for (i, token) in tokens.iter().enumerate() {
+    match token.kind {
+      TokenKind::LeftParen => depth += 1,
+      TokenKind::RightParen => depth -= 1,
+      TokenKind::LeftBrace => braces += 1,
+      TokenKind::RightBrace => braces -= 1,
+      TokenKind::Newline => {
+        let continues = depth > 0
+          || braces > 0
+          || tokens[start..i].last().is_some_and(|last| {
+            matches!(
+              last.kind,
+              TokenKind::Assign
+                | TokenKind::Equal
+                | TokenKind::Plus
+                | TokenKind::Minus
+                | TokenKind::Star
+                | TokenKind::Newline
+            )
+          });
+        if !continues {
+          statements.push(&tokens[start..i]);
+          start = i + 1;
+        }
+      },
+      _ => {},
+    }
+  }
+  statements.push(&tokens[start..]);
+
+  // line breaks inside statements have served their purpose, except in the bodies of gadgets,
+  // which are split into statements in turn
+  statements
+    .into_iter()
+    .map(|statement| {
+      let mut braces = 0;
+      let mut in_body = |token: &&Token| {
+        match token.kind {
+          TokenKind::LeftBrace => braces += 1,
+          TokenKind::RightBrace => braces -= 1,
+          _ => {},
+        }
+        token.kind != TokenKind::Newline || braces > 0
+      };
+      statement.iter().filter(&mut in_body).copied().collect::<Vec<_>>()
+    })
+    .filter(|statement| !statement.is_empty())
+    .collect()
+}
+
+/// Parse constraints into [`WireCoeffs`] containing wires and corresponding coefficients, one per
+/// fan-in 2 gate. Each string can hold one or several statements, see [`parse_source`], and is
+/// numbered as if the strings were the consecutive lines of a file in error messages.
+///
+/// Constraints that don't fit a single gate are decomposed into several gates, with intermediate
+/// wires numbered across the whole list of constraints.
+///
+/// ## Example
+///
+/// valid equations, and output:
+/// - `a === 9` =>                     `([None, None, 'a'], {'$constant': 9})`
+/// - `a public` =>                    `(['a', None, None], {'$public': 1, 'a': -1,
+///   '$output_coeffs': 0}`
+/// - `b <== a * c` =>                 `(['a', 'c', 'b'], {'a*c': 1})`
+/// - `d <== a * c - 45 * a + 987` =>  `(['a', 'c', 'd'], {'a*c': 1, 'a': -45, '$constant': 987})`
+/// - `e <== (a + b) * c` =>           `(['a', 'b', '$1'], {'a': 1, 'b': 1})`, then `(['$1', 'c',
+///   'e'], {'$1*c': 1})`
+///
+/// invalid equations:
+/// - `7 === 7`             =>         # Can't assign to non-variable
+/// - `a <== b * * c`       =>         # Two times signs in a row
+/// - `a <== (b + c`        =>         # Unbalanced parentheses
+pub fn parse_constraints<'a>(
+  constraints: &[&'a str],
+) -> Result<Vec<WireCoeffs<'a>>, ParserError<'a>> {
+  Ok(parse_circuit(constraints)?.gates)
+}
 
-    return Ok(WireCoeffs { wires: vec![Some(tokens[0]), None, None], coeffs });
-  } else {
-    return Err(ParserError::ConstraintsUnsupportedValue(constraint));
+/// Parses a whole DSL source file into [`WireCoeffs`], like [`parse_constraints`].
+///
+/// Statements end at the end of the line, except inside parentheses or after an operator, so long
+/// expressions can be split over several lines. Comments start with `//` and last until the end of
+/// the line, or are enclosed in `/* */`. Errors carry the [`Span`] of the offending source and a
+/// rendered diagnostic, see [`ParserError`].
+pub fn parse_source(source: &str) -> Result<Vec<WireCoeffs>, ParserError> {
+  parse_constraints(&[source])
+}
+
+/// Parses constraints like [`parse_constraints`], into a [`Circuit`] with the lookup tables they
+/// declare.
+pub fn parse_circuit<'a>(constraints: &[&'a str]) -> Result<Circuit<'a>, ParserError<'a>> {
+  let mut builder = GateBuilder::default();
+  builder.source(STANDARD_GADGETS, 1).expect("standard gadgets are valid");
+  let mut line = 1;
+  for constraint in constraints {
+    builder.source(constraint, line)?;
+    line += constraint.trim_end_matches('\n').matches('\n').count() + 1;
   }
+  Ok(Circuit { gates: builder.gates, tables: builder.tables, hints: builder.hints })
+}
+
+/// Lowers rank-1 constraints `⟨a, w⟩ · ⟨b, w⟩ = ⟨c, w⟩` into fan-in 2 gates, like the constraints
+/// of the DSL, with intermediate wires `$1`, `$2`, …. The last gate of each constraint has no
+/// output wire. Fails with the index of the constraint whose coefficients overflow.
+pub(crate) fn rank_one_gates<'a>(
+  constraints: &[[LinearTerms<'a>; 3]],
+) -> Result<Vec<WireCoeffs<'a>>, (usize, ParserErrorKind<'a>)> {
+  let mut builder = GateBuilder::default();
+  for (i, constraint) in constraints.iter().enumerate() {
+    builder.rank_one(constraint).map_err(|kind| (i, kind))?;
+  }
+  Ok(builder.gates)
 }
 
 #[cfg(test)]
@@ -292,18 +1178,24 @@ mod tests {
   use rstest::rstest;
 
   use super::*;
+  use crate::Field;
+
+  /// Coefficients by key, for readable expected gates.
+  type Coeffs<'a> = Vec<(&'a str, i128)>;
+
+  fn wire(name: &str) -> Option<Cow<str>> { Some(Cow::Borrowed(name)) }
 
   #[test]
   fn wire_values() {
     let wire_values = WireCoeffs {
-      wires:  vec![Some("a"), Some("b"), Some("c")],
+      wires:  vec![wire("a"), wire("b"), wire("c")],
       coeffs: HashMap::from([
         (String::from("$output_coeffs"), 2),
         (String::from("a"), -1),
         (String::from("$constant"), 9),
       ]),
     };
-    let gate = wire_values.gate();
+    let gate = wire_values.gate::<PlutoScalarField>();
     assert_eq!(gate.l, -PlutoScalarField::from(-1));
     assert_eq!(gate.r, PlutoScalarField::ZERO);
     assert_eq!(gate.m, PlutoScalarField::ZERO);
@@ -311,10 +1203,10 @@ mod tests {
     assert_eq!(gate.c, -PlutoScalarField::from(9));
 
     let wire_values = WireCoeffs {
-      wires:  vec![Some("a"), Some("b"), Some("c")],
+      wires:  vec![wire("a"), wire("b"), wire("c")],
       coeffs: HashMap::from([(String::from("b"), -1), (String::from("a*b"), -9)]),
     };
-    let gate = wire_values.gate();
+    let gate = wire_values.gate::<PlutoScalarField>();
     assert_eq!(gate.l, -PlutoScalarField::ZERO);
     assert_eq!(gate.r, -PlutoScalarField::from(-1));
     assert_eq!(gate.m, -PlutoScalarField::from(-9));
@@ -322,14 +1214,14 @@ mod tests {
     assert_eq!(gate.c, -PlutoScalarField::ZERO);
 
     let wire_values = WireCoeffs {
-      wires:  vec![Some("a"), None, None],
+      wires:  vec![wire("a"), None, None],
       coeffs: HashMap::from([
         (String::from("$output"), 1),
         (String::from("a"), -1),
         (String::from("$output_coeffs"), 0),
       ]),
     };
-    let gate = wire_values.gate();
+    let gate = wire_values.gate::<PlutoScalarField>();
     assert_eq!(gate.l, PlutoScalarField::ONE);
     assert_eq!(gate.r, PlutoScalarField::ZERO);
     assert_eq!(gate.m, PlutoScalarField::ZERO);
@@ -338,45 +1230,273 @@ mod tests {
   }
 
   #[rstest]
-  #[case(&["a", "+", "b", "*", "c", "*", "5"], HashMap::from([("a".to_string(), 1), ("b*c".to_string(), 5)]))]
-  #[case(&["a"], HashMap::from([("a".to_string(), 1)]))]
-  #[case(&["a", "*", "b", "*", "c", "*", "d"], HashMap::from([("a*b*c*d".to_string(), 1)]))]
-  #[case(&["a", "+", "b", "-", "-c", "*", "-d"], HashMap::from([("a".to_string(), 1), ("b".to_string(), 1), ("c*d".to_string(), -1)]))]
-  #[case(&["-10", "+", "c", "*", "-8", "-", "11"], HashMap::from([("c".to_string(), -8), ("$constant".to_string(), -21)]))]
-  #[case(&["-2", "*", "b", "-", "a", "*", "b"], HashMap::from([("a*b".to_string(), -1), ("b".to_string(), -2)]))]
-  #[should_panic]
-  #[case(&["a", "+", "b", "c"], HashMap::from([]))]
-  #[should_panic(expected = "assertion")]
-  #[case(&["b", "/", "+"], HashMap::from([]))]
-  fn evaluate_expression(#[case] expr: &[&str], #[case] expected: HashMap<String, i32>) {
-    let res = evaluate(expr, false);
-    assert!(res.is_ok());
-    assert_eq!(res.unwrap(), expected);
+  #[case("a + b * c", "Add(Variable(\"a\"), Multiply(Variable(\"b\"), Variable(\"c\")))")]
+  #[case("(a + b) * c", "Multiply(Add(Variable(\"a\"), Variable(\"b\")), Variable(\"c\"))")]
+  #[case("a - b - c", "Subtract(Subtract(Variable(\"a\"), Variable(\"b\")), Variable(\"c\"))")]
+  #[case("-a * --2", "Multiply(Negate(Variable(\"a\")), Negate(Negate(Constant(2))))")]
+  fn expression_precedence(#[case] expression: &str, #[case] expected: &str) {
+    let tokens = lex(expression).unwrap();
+    let expression = ExpressionParser::parse(&tokens, Span::default()).unwrap();
+    assert_eq!(format!("{:?}", expression), expected);
   }
 
   #[rstest]
-  #[case("a <== b * c", vec![Some("b"), Some("c"), Some("a")], HashMap::from([(String::from("b*c"), 1)]))]
-  #[case("a public", vec![Some("a"), None, None], HashMap::from([(String::from("$output_coeffs"), 0), (String::from("$public"), 1), (String::from("a"), -1)]))]
-  #[case("a === 9", vec![None, None, Some("a")], HashMap::from([(String::from("$constant"), 9)]))]
-  #[case("b <== a + 9 * 10", vec![Some("a"), Some("a"), Some("b")], HashMap::from([(String::from("a"), 1), (String::from("$constant"), 90)]))]
-  #[case("-a <== b * -c * -9 - 10", vec![Some("b"), Some("c"), Some("a")], HashMap::from([(String::from("$output_coeffs"), -1), (String::from("b*c"), 9), (String::from("$constant"), -10)]))]
-  #[case("x2 <== x * x", vec![Some("x"), Some("x"), Some("x2")], HashMap::from([(String::from("x*x"), 1)]))]
-  #[should_panic]
-  #[case("a <== b * c + d", vec![], HashMap::from([]))]
+  #[case("a <== b * c", vec![wire("b"), wire("c"), wire("a")], HashMap::from([(String::from("b*c"), 1)]))]
+  #[case("a public", vec![wire("a"), None, None], HashMap::from([(String::from("$output_coeffs"), 0), (String::from("$public"), 1), (String::from("a"), -1)]))]
+  #[case("a === 9", vec![None, None, wire("a")], HashMap::from([(String::from("$constant"), 9)]))]
+  #[case("b <== a + 9 * 10", vec![wire("a"), wire("a"), wire("b")], HashMap::from([(String::from("a"), 1), (String::from("$constant"), 90)]))]
+  #[case("-a <== b * -c * -9 - 10", vec![wire("b"), wire("c"), wire("a")], HashMap::from([(String::from("$output_coeffs"), -1), (String::from("b*c"), 9), (String::from("$constant"), -10)]))]
+  #[case("x2 <== x * x", vec![wire("x"), wire("x"), wire("x2")], HashMap::from([(String::from("x*x"), 1)]))]
+  #[case("c<==-2*(b+a*b)", vec![wire("a"), wire("b"), wire("c")], HashMap::from([(String::from("b"), -2), (String::from("a*b"), -2)]))]
   #[should_panic(expected = "assertion")]
   #[case("8 === 9", vec![], HashMap::from([]))]
-  #[should_panic(expected = "index")]
+  #[should_panic(expected = "assertion")]
   #[case("a <== b * * c", vec![], HashMap::from([]))]
   fn circuit_parse_constraints(
     #[case] constraint: &str,
-    #[case] expected_wires: Vec<Option<&str>>,
-    #[case] expected_coeffs: HashMap<String, i32>,
+    #[case] expected_wires: Vec<Option<Cow<str>>>,
+    #[case] expected_coeffs: HashMap<String, i128>,
   ) {
-    let wire_values = parse_constraints(constraint);
+    let wire_values = parse_constraints(&[constraint]);
     assert!(wire_values.is_ok());
-    assert_eq!(wire_values.unwrap(), WireCoeffs {
+    assert_eq!(wire_values.unwrap(), vec![WireCoeffs {
       wires:  expected_wires,
       coeffs: expected_coeffs,
-    });
+    }]);
+  }
+
+  #[rstest]
+  #[case("y <== x * x * x", vec![
+    (vec![wire("x"), wire("x"), wire("$1")], vec![("x*x", 1)]),
+    (vec![wire("$1"), wire("x"), wire("y")], vec![("$1*x", 1)]),
+  ])]
+  #[case("a <== b * c + d", vec![
+    (vec![wire("b"), wire("c"), wire("$1")], vec![("b*c", 1)]),
+    (vec![wire("$1"), wire("d"), wire("a")], vec![("$1", 1), ("d", 1)]),
+  ])]
+  #[case("e <== (a + b) * -3 * c", vec![
+    (vec![wire("a"), wire("b"), wire("$1")], vec![("a", -3), ("b", -3)]),
+    (vec![wire("$1"), wire("c"), wire("e")], vec![("$1*c", 1)]),
+  ])]
+  #[case("f <== 2 * a * b + c * d - 7", vec![
+    (vec![wire("a"), wire("b"), wire("$1")], vec![("a*b", 1)]),
+    (vec![wire("c"), wire("d"), wire("$2")], vec![("c*d", 1)]),
+    (vec![wire("$1"), wire("$2"), wire("f")], vec![("$1", 2), ("$2", 1), ("$constant", -7)]),
+  ])]
+  #[case("g <== a * b + b * a + 1", vec![
+    (vec![wire("a"), wire("b"), wire("g")], vec![("a*b", 2), ("$constant", 1)]),
+  ])]
+  fn decompose_constraints(
+    #[case] constraint: &str,
+    #[case] expected: Vec<(Vec<Option<Cow<str>>>, Coeffs)>,
+  ) {
+    let expected: Vec<WireCoeffs> = expected
+      .into_iter()
+      .map(|(wires, coeffs)| WireCoeffs {
+        wires,
+        coeffs: coeffs.into_iter().map(|(key, coeff)| (key.to_string(), coeff)).collect(),
+      })
+      .collect();
+    assert_eq!(parse_constraints(&[constraint]).unwrap(), expected);
+  }
+
+  #[test]
+  fn intermediate_wires_are_unique() {
+    let gates = parse_constraints(&["a <== x * x * x", "b <== y * y * y"]).unwrap();
+    let outputs: Vec<_> = gates.iter().map(|gate| gate.wires[2].as_deref().unwrap()).collect();
+    assert_eq!(outputs, ["$1", "a", "$2", "b"]);
+  }
+
+  #[rstest]
+  #[case("a <== (b + c", "1:7: unbalanced parentheses")]
+  #[case("a <== b + c)", "1:12: unbalanced parentheses")]
+  #[case("a <== b +", "1:10: unexpected end of statement")]
+  #[case("a <== b / c", "1:9: unexpected character: /")]
+  #[case("a <== b c", "1:9: expected an operator before: c")]
+  #[case(
+    "a <== 999999999999999999999999999999999999999 * b",
+    "1:7: invalid coefficient value: 999999999999999999999999999999999999999"
+  )]
+  #[case(
+    "a <== 18446744073709551616 * 18446744073709551616 * b",
+    "1:7: invalid coefficient value: constant term"
+  )]
+  #[case("8 <== b", "1:1: invalid variable name: 8")]
+  #[case("a", "1:1: unsupported constraint: a")]
+  #[case("a b public", "1:1: unsupported constraint: a b public")]
+  #[case("a <== b\n/* unterminated", "2:1: unterminated block comment")]
+  #[case("table t range 4\ntable t xor 2", "2:7: table declared twice: t")]
+  #[case("table t range 0", "1:15: invalid table size: 0")]
+  #[case("table t xor 99999999999", "1:13: invalid table size: 99999999999")]
+  #[case("x in t", "1:6: unknown table: t")]
+  #[case("table t xor 4\nx in t", "2:1: lookup of 1 values in a table of width 3")]
+  #[case("table t range 4\n(a, b) in t", "2:1: lookup of 2 values in a table of width 1")]
+  #[case("table t xor 4\n(a, b c) in t", "2:7: invalid expression: c")]
+  #[case("table t xor 4\n(a, 1, c) in t", "2:5: invalid variable name: 1")]
+  #[case("x bool bool", "1:1: unsupported constraint: x bool bool")]
+  #[case("y <== f(x)", "1:7: unknown gadget: f")]
+  #[case("y <== select(c, a)", "1:7: gadget select called with 2 arguments instead of 3")]
+  #[case("(y, z) <== select(c, a, b)", "1:12: gadget select assigned to 2 outputs instead of 1")]
+  #[case("y <== boolean(x)", "1:7: gadget boolean assigned to 1 outputs instead of 0")]
+  #[case("y <== select(c, a, b", "1:13: unbalanced parentheses")]
+  #[case("(y, 1) <== select(c, a, b)", "1:5: invalid variable name: 1")]
+  #[case("(y, z) <== a + b", "1:1: unsupported constraint: (y, z) <== a + b")]
+  #[case(
+    "gadget f(a) {\n  a public\n}\nf(x)",
+    "4:1: in gadget f: not allowed in a gadget: a public"
+  )]
+  #[case("gadget f(a) {\n  f(a)\n}\nf(x)", "4:1: in gadget f: recursive gadget: f")]
+  #[case("gadget f(a) {\n  b <== a / 2\n}", "2:11: unexpected character: /")]
+  #[case("gadget f(a) -> b {\n  b <== g(a)\n}\ny <== f(x)", "4:7: in gadget f: unknown gadget: g")]
+  #[case("gadget f(a) {\n  a bool\n", "1:13: unbalanced braces")]
+  #[case("gadget f(a, a) {}", "1:13: invalid variable name: a")]
+  #[case("gadget f(a) -> (a) {}", "1:17: invalid variable name: a")]
+  #[case("gadget f(a) -> {}", "1:15: unexpected end of statement")]
+  #[case("gadget select(a) {}", "1:8: gadget defined twice: select")]
+  #[case("gadget f {}", "1:1: unsupported constraint: gadget f {}")]
+  #[case("b <-- bit(x, 64)", "1:14: invalid bit index: 64")]
+  #[case("b <-- bits(x, 1)", "1:1: unsupported constraint: b <-- bits(x, 1)")]
+  fn parse_errors(#[case] constraint: &str, #[case] expected: &str) {
+    assert_eq!(parse_constraints(&[constraint]).unwrap_err().to_string(), expected);
+  }
+
+  #[rstest]
+  #[case("a public", &["a public"])]
+  #[case("d === 9", &["d === 9"])]
+  #[case("c <== -2 * b - a * b", &["c === -a * b - 2 * b"])]
+  #[case("-e <== a * 3 - 7", &["-e === 3 * a - 7"])]
+  #[case("a === b", &["a === b"])]
+  #[case("y <== x * x * x", &["$1 === x * x", "y === $1 * x"])]
+  #[case("x bool", &["x bool"])]
+  #[case("table t xor 4\n(a, b, c) in t", &["(a, b, c) in table 1"])]
+  #[case("y <== select(c, a, 3)", &["c === c * c", "$1 === a - 3", "y === $1 * c + 3"])]
+  #[case("y <== 2 * select(c, a, b)", &["c === c * c", "$2 === a - b", "$3 === $2 * c", "$1 === $3 + b", "y === 2 * $1"])]
+  fn display_constraints(#[case] constraint: &str, #[case] expected: &[&str]) {
+    let gates = parse_constraints(&[constraint]).unwrap();
+    let displayed: Vec<String> = gates.iter().map(ToString::to_string).collect();
+    assert_eq!(displayed, expected);
+  }
+
+  #[test]
+  fn source_file() {
+    let source = "
+      // computes x^3 + x + 5
+      x public
+
+      /* the output is split
+         over several lines */
+      out <== x * x * x +
+        x + (
+          5
+        )
+      -y <== out * -x // negated output
+    ";
+    let gates = parse_source(source).unwrap();
+    let constraints =
+      parse_constraints(&["x public", "out <== x * x * x + x + 5", "-y <== out * -x"]);
+    assert_eq!(gates, constraints.unwrap());
+    assert_eq!(gates.len(), 4);
+  }
+
+  #[test]
+  fn custom_gates_and_tables() {
+    let source = "
+      table bits range 4
+      table x4 xor 4
+      x bool
+      y range
+      y in bits
+      (x, y, z) in x4
+    ";
+    let circuit = parse_circuit(&[source]).unwrap();
+    assert_eq!(circuit.tables, [Table { name: "bits", kind: TableKind::Range, size: 4 }, Table {
+      name: "x4",
+      kind: TableKind::Xor,
+      size: 4,
+    },]);
+    let gate = |wires: Vec<Option<Cow<'static, str>>>, key: &str, value: i128| WireCoeffs {
+      wires,
+      coeffs: HashMap::from([(String::from("$output_coeffs"), 0), (key.to_string(), value)]),
+    };
+    assert_eq!(circuit.gates, [
+      gate(vec![wire("x"), None, None], "$bool", 1),
+      gate(vec![wire("y"), None, None], "$range", 1),
+      gate(vec![wire("y"), None, None], "$lookup", 1),
+      gate(vec![wire("x"), wire("y"), wire("z")], "$lookup", 2),
+    ]);
+    // custom rows have no arithmetic gate
+    for gate in circuit.gates.iter().map(WireCoeffs::gate::<PlutoScalarField>) {
+      let zero = PlutoScalarField::ZERO;
+      assert_eq!([gate.l, gate.r, gate.m, gate.o, gate.c], [zero; 5]);
+    }
+
+    assert_eq!(circuit.tables[0].rows(), [[0, 0, 0], [1, 0, 0], [2, 0, 0], [3, 0, 0]]);
+    assert_eq!(circuit.tables[1].rows().len(), 16);
+    assert!(circuit.tables[1].rows().contains(&[2, 3, 1]));
+  }
+
+  #[test]
+  fn gadgets() {
+    let source = "
+      gadget square(x) -> y {
+        y <== x * x
+      }
+      // several outputs, and a local variable
+      gadget sumDifference(a, b) -> (s, d) {
+        s <== a + b
+        d <== a - b
+        t <== s * d
+      }
+      z <== square(a + 1)
+      (p, q) <== sumDifference(z, 2 * b)
+      r <== square(p) * 3 + sumDifference(p, q)
+    ";
+    let error = parse_source(source).unwrap_err();
+    assert_eq!(error.to_string(), "13:13: gadget sumDifference assigned to 1 outputs instead of 2");
+
+    let source = source.replace(" + sumDifference(p, q)", "\n(u, v) <== sumDifference(p, q)");
+    let gates = parse_source(&source).unwrap();
+    let displayed: Vec<String> = gates.iter().map(ToString::to_string).collect();
+    assert_eq!(displayed, [
+      "$1 === a + 1",
+      "z === $1 * $1",
+      "p === 2 * b + z",
+      "q === -2 * b + z",
+      "sumDifference.1.t === p * q",
+      "$2 === p * p",
+      "r === 3 * $2",
+      "u === p + q",
+      "v === p - q",
+      "sumDifference.2.t === u * v",
+    ]);
+  }
+
+  #[test]
+  fn hints() {
+    let circuit = parse_circuit(&["(a, b, c, d) <== bits4(x)", "e <-- bit(x, 7)"]).unwrap();
+    let hints: Vec<String> = circuit.hints.iter().map(ToString::to_string).collect();
+    let expected = ["bit(x, 0)", "bit(x, 1)", "bit(x, 2)", "bit(x, 3)", "bit(x, 7)"];
+    let expected: Vec<String> = ["a", "b", "c", "d", "e"]
+      .iter()
+      .zip(expected)
+      .map(|(b, bit)| format!("{} <-- {}", b, bit))
+      .collect();
+    assert_eq!(hints, expected);
+    // hints don't add gates
+    assert_eq!(circuit.gates.len(), 7);
+
+    let circuit = parse_circuit(&["range4(x)"]).unwrap();
+    assert_eq!(circuit.hints[0].to_string(), "range4.1.b0 <-- bit(x, 0)");
+  }
+
+  #[rstest]
+  #[case("a <== b\nc <== d", 2)]
+  #[case("a <==\n b\n\nc <== d", 2)]
+  #[case("a <== (\nb\n)\n-c <== d", 2)]
+  #[case("a <== b -\n c <== d", 1)]
+  #[case("\n// nothing\n\n", 0)]
+  #[case("gadget f(a) {\n  a bool\n\n  a range\n}\nf(b)", 2)]
+  fn statement_lines(#[case] source: &str, #[case] expected: usize) {
+    assert_eq!(statements(&lex(source).unwrap()).len(), expected);
   }
 }
@@ -7,17 +7,20 @@
 //! For permutation helpers, each variable usage over the constraints is accumulated and then placed
 //! into respective [`Column`].
 
-use std::collections::{HashMap, HashSet};
+use std::{
+  collections::{HashMap, HashSet},
+  marker::PhantomData,
+};
 
 use super::{
-  errors::{ParserError, ProgramError},
-  utils::get_product_key,
+  errors::ProgramError,
+  utils::{coefficient, get_product_key},
 };
 use crate::{
   algebra::field::FiniteField,
-  compiler::parser::{parse_constraints, WireCoeffs},
+  compiler::parser::{parse_circuit, Circuit, Hint, Table, WireCoeffs},
   polynomial::{Lagrange, Polynomial},
-  Field, PlutoScalarField,
+  PlutoScalarField,
 };
 
 /// Column represents all three columns in the execution trace which a variable
@@ -53,104 +56,203 @@ pub struct Cell {
   pub column: Column,
 }
 
+impl Column {
+  /// Coset shift $k_j = g^{j - 1}$ of the column, for the primitive element $g$ of the field: the
+  /// cells of the column are labelled $k_j \omega^i$.
+  pub fn coset_shift<F: FiniteField>(self) -> F { F::PRIMITIVE_ELEMENT.pow(self as usize - 1) }
+}
+
 impl Cell {
   /// Assign a domain value to a cell where `row` represents power of primitive root of unity and
   /// `column` represents coset value: $k*\omega^(row)$
-  fn label(&self, group_order: usize) -> PlutoScalarField {
-    let col: u32 = self.column as u32;
-    PlutoScalarField::from(col)
-      * PlutoScalarField::primitive_root_of_unity(group_order).pow(self.row as usize)
+  fn label<F: FiniteField>(&self, group_order: usize) -> F {
+    self.column.coset_shift::<F>() * F::primitive_root_of_unity(group_order).pow(self.row as usize)
   }
 }
 
+/// Returns whether the cosets $H$, $gH$ and $g^2H$ of the subgroup $H$ of order `group_order` are
+/// disjoint, so that the labels $k \omega^i$ of the cells of the three columns are all distinct.
+/// Two cosets $aH$ and $bH$ are equal exactly when $(a / b)^n = 1$, so this holds when $g^n$ has
+/// an order of at least 3, that is when $(p - 1) / n \geq 3$.
+pub fn cosets_are_disjoint<F: FiniteField>(group_order: usize) -> bool {
+  let g = F::PRIMITIVE_ELEMENT;
+  [g, g * g].iter().all(|k| k.pow(group_order) != F::ONE)
+}
+
 /// `Program` represents constraints used while defining the arithmetic on the inputs
-/// and group order of primitive roots of unity in the field.
+/// and group order of primitive roots of unity in the field `F`.
 #[derive(Debug, PartialEq)]
-pub struct Program<'a, const GROUP_ORDER: usize> {
+pub struct Program<'a, const GROUP_ORDER: usize, F: FiniteField = PlutoScalarField> {
   /// `constraints` defined during arithmetic evaluation on inputs in the circuit
   constraints: Vec<WireCoeffs<'a>>,
+  /// lookup tables, which lookup rows refer to by their position starting at 1
+  tables:      Vec<Table<'a>>,
+  /// hints for witness generation
+  hints:       Vec<Hint<'a>>,
   // order of multiplicative group formed by primitive roots of unity in the scalar field
   // group_order: usize,
+  /// field the circuit is compiled to
+  field:       PhantomData<F>,
 }
 
 /// Represents circuit related input which is apriori known to `Prover` and `Verifier` involved in
 /// the process.
-pub struct CommonPreprocessedInput<const GROUP_ORDER: usize> {
+pub struct CommonPreprocessedInput<const GROUP_ORDER: usize, F: FiniteField = PlutoScalarField> {
   /// multiplicative group order
   // group_order: usize,
   /// Q_L(X): left wire selector polynomial
-  pub ql: Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
+  pub ql:      Polynomial<Lagrange<F>, F, GROUP_ORDER>,
   /// Q_R(X): right wire selector polynomial
-  pub qr: Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
+  pub qr:      Polynomial<Lagrange<F>, F, GROUP_ORDER>,
   /// Q_M(X): multiplication gate selector polynomial
-  pub qm: Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
+  pub qm:      Polynomial<Lagrange<F>, F, GROUP_ORDER>,
   /// Q_O(X): output wire selector polynomial
-  pub qo: Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
+  pub qo:      Polynomial<Lagrange<F>, F, GROUP_ORDER>,
   /// Q_C(X): constant selector polynomial
-  pub qc: Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
+  pub qc:      Polynomial<Lagrange<F>, F, GROUP_ORDER>,
   /// S_σ1(X): first permutation polynomial
-  pub s1: Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
+  pub s1:      Polynomial<Lagrange<F>, F, GROUP_ORDER>,
   /// S_σ2(X): second permutation polynomial
-  pub s2: Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
+  pub s2:      Polynomial<Lagrange<F>, F, GROUP_ORDER>,
   /// S_σ3(X): third permutation polynomial
-  pub s3: Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
+  pub s3:      Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+  /// Q_bool(X): boolean gate selector polynomial, for `a(a - 1) = 0`
+  pub qbool:   Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+  /// Q_range(X): range gate selector polynomial, for `a(a - 1)(a - 2)(a - 3) = 0`
+  pub qrange:  Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+  /// Q_lookup(X): lookup selector polynomial, one on rows whose wires are looked up in a table
+  pub qlookup: Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+  /// Q_table(X): id of the table looked up in each lookup row, starting at 1
+  pub qtable:  Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+  /// T_1(X), T_2(X), T_3(X): columns of the rows of every table, and T_4(X): their table ids
+  pub tables:  [Polynomial<Lagrange<F>, F, GROUP_ORDER>; 4],
 }
 
-impl<'a, const GROUP_ORDER: usize> Program<'a, GROUP_ORDER> {
+impl<'a, const GROUP_ORDER: usize, F: FiniteField> Program<'a, GROUP_ORDER, F> {
   /// create a new [`Program`] from list of constraints and group order. Converts constraints into
   /// variables and their corresponding activation coefficients
   ///
-  /// Assumes: group_order >= constraints.len()
-  pub fn new(constraints: &[&'a str]) -> Result<Self, ProgramError<'a>> {
-    let assembly: Result<Vec<WireCoeffs>, ParserError> =
-      constraints.iter().map(|constraint| parse_constraints(constraint)).collect();
-
-    let assembly = match assembly {
-      Ok(wire_coeffs) => wire_coeffs,
+  /// Fails if the group order doesn't divide the order of the multiplicative group of `F`, i.e.
+  /// there are no roots of unity of that order, or if there are more gates or table rows than the
+  /// group order.
+  pub fn new(constraints: &[&'a str]) -> Result<Self, ProgramError<'a, F>> {
+    let circuit = match parse_circuit(constraints) {
+      Ok(circuit) => circuit,
       Err(parser_error) => return Err(ProgramError::ParserError(parser_error)),
     };
 
-    Ok(Self { constraints: assembly })
+    Self::from_circuit(circuit)
+  }
+
+  /// create a new [`Program`] from the DSL source of a whole file, see
+  /// [`parse_source`](super::parser::parse_source)
+  pub fn from_source(source: &'a str) -> Result<Self, ProgramError<'a, F>> { Self::new(&[source]) }
+
+  /// create a new [`Program`] from parsed gates and tables, checking that they fit the group order
+  pub fn from_circuit(circuit: Circuit<'a>) -> Result<Self, ProgramError<'a, F>> {
+    if (F::ORDER - 1) % GROUP_ORDER != 0 {
+      return Err(ProgramError::InvalidGroupOrder(GROUP_ORDER, F::ORDER - 1));
+    }
+    let Circuit { gates: constraints, tables, hints } = circuit;
+    if constraints.len() > GROUP_ORDER {
+      return Err(ProgramError::TooManyConstraints(constraints.len(), GROUP_ORDER));
+    }
+    let table_rows = tables.iter().map(|table| table.rows().len()).sum();
+    if table_rows > GROUP_ORDER {
+      return Err(ProgramError::TooManyTableRows(table_rows, GROUP_ORDER));
+    }
+    Ok(Self { constraints, tables, hints, field: PhantomData })
   }
 
+  /// returns the gates of the program, one per row of the execution trace
+  pub fn constraints(&self) -> &[WireCoeffs<'a>] { &self.constraints }
+
+  /// returns the lookup tables of the program
+  pub fn tables(&self) -> &[Table<'a>] { &self.tables }
+
+  /// returns the hints of the program, see [`Hint`]
+  pub fn hints(&self) -> &[Hint<'a>] { &self.hints }
+
   /// returns selector polynomial used in execution trace for a gate
   #[allow(clippy::type_complexity)]
   fn selector_polynomials(
     &self,
   ) -> (
-    Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
-    Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
-    Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
-    Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
-    Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
+    Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+    Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+    Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+    Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+    Polynomial<Lagrange<F>, F, GROUP_ORDER>,
   ) {
-    let mut l = [PlutoScalarField::ZERO; GROUP_ORDER];
-    let mut r = [PlutoScalarField::ZERO; GROUP_ORDER];
-    let mut m = [PlutoScalarField::ZERO; GROUP_ORDER];
-    let mut o = [PlutoScalarField::ZERO; GROUP_ORDER];
-    let mut c = [PlutoScalarField::ZERO; GROUP_ORDER];
+    let mut l = [F::ZERO; GROUP_ORDER];
+    let mut r = [F::ZERO; GROUP_ORDER];
+    let mut m = [F::ZERO; GROUP_ORDER];
+    let mut o = [F::ZERO; GROUP_ORDER];
+    let mut c = [F::ZERO; GROUP_ORDER];
 
     // iterate through the constraints and assign each selector value
     for (i, constraint) in self.constraints.iter().enumerate() {
-      let gate = constraint.gate();
+      let gate = constraint.gate::<F>();
       (l[i], r[i], m[i], o[i], c[i]) = (gate.l, gate.r, gate.m, gate.o, gate.c);
     }
-    let poly_l = Polynomial::<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>::new(l);
-    let poly_r = Polynomial::<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>::new(r);
-    let poly_m = Polynomial::<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>::new(m);
-    let poly_o = Polynomial::<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>::new(o);
-    let poly_c = Polynomial::<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>::new(c);
+    let poly_l = Polynomial::<Lagrange<F>, F, GROUP_ORDER>::new(l);
+    let poly_r = Polynomial::<Lagrange<F>, F, GROUP_ORDER>::new(r);
+    let poly_m = Polynomial::<Lagrange<F>, F, GROUP_ORDER>::new(m);
+    let poly_o = Polynomial::<Lagrange<F>, F, GROUP_ORDER>::new(o);
+    let poly_c = Polynomial::<Lagrange<F>, F, GROUP_ORDER>::new(c);
     (poly_l, poly_r, poly_m, poly_o, poly_c)
   }
 
+  /// returns the custom gate selector polynomials `[Q_bool, Q_range, Q_lookup, Q_table]`
+  fn custom_selector_polynomials(&self) -> [Polynomial<Lagrange<F>, F, GROUP_ORDER>; 4] {
+    let mut selectors = [[F::ZERO; GROUP_ORDER]; 4];
+    for (i, constraint) in self.constraints.iter().enumerate() {
+      match constraint.custom_gate() {
+        Some("$bool") => selectors[0][i] = F::ONE,
+        Some("$range") => selectors[1][i] = F::ONE,
+        Some(_) => {
+          selectors[2][i] = F::ONE;
+          selectors[3][i] = coefficient(constraint.coeffs["$lookup"]);
+        },
+        None => {},
+      }
+    }
+    selectors.map(Polynomial::<Lagrange<F>, F, GROUP_ORDER>::new)
+  }
+
+  /// returns the table polynomials `[T_1, T_2, T_3, T_4]`: the rows of every table one after the
+  /// other, with the table id in the last column, padded by repeating the last row so that padding
+  /// only adds values that are already in the tables.
+  fn table_polynomials(&self) -> [Polynomial<Lagrange<F>, F, GROUP_ORDER>; 4] {
+    let rows: Vec<[F; 4]> = self
+      .tables
+      .iter()
+      .enumerate()
+      .flat_map(|(i, table)| {
+        table.rows().into_iter().map(move |[a, b, c]| [a, b, c, i + 1].map(F::from))
+      })
+      .collect();
+
+    let mut columns = [[F::ZERO; GROUP_ORDER]; 4];
+    if let Some(&last) = rows.last() {
+      for row in 0..GROUP_ORDER {
+        let values = rows.get(row).unwrap_or(&last);
+        for (column, value) in columns.iter_mut().zip(values) {
+          column[row] = *value;
+        }
+      }
+    }
+    columns.map(Polynomial::<Lagrange<F>, F, GROUP_ORDER>::new)
+  }
+
   /// Returns `S1,S2,S3` polynomials used for creating permutation argument in PLONK
   #[allow(clippy::type_complexity)]
   fn s_polynomials(
     &self,
   ) -> (
-    Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
-    Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
-    Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>,
+    Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+    Polynomial<Lagrange<F>, F, GROUP_ORDER>,
+    Polynomial<Lagrange<F>, F, GROUP_ORDER>,
   ) {
     // captures uses of a variable in constraints where each new constraint defines a new row in
     // execution trace and columns represent left, right and output wires in a gate.
@@ -163,7 +265,8 @@ impl<'a, const GROUP_ORDER: usize> Program<'a, GROUP_ORDER> {
       // iterate through wires representing l, r, o columns
       for (column, value) in constraint.wires.iter().enumerate() {
         // get the variable used for that wire in respective constraint
-        match variable_uses.get_mut(value) {
+        let value = value.as_deref();
+        match variable_uses.get_mut(&value) {
           // if already mapped, then add new (row, column) tuple to set
           Some(set) => {
             set.insert(Cell { row: row as u32, column: Column::from((column + 1) as u32) });
@@ -172,7 +275,7 @@ impl<'a, const GROUP_ORDER: usize> Program<'a, GROUP_ORDER> {
           None => {
             let mut set = HashSet::new();
             set.insert(Cell { row: row as u32, column: Column::from((column + 1) as u32) });
-            variable_uses.insert(*value, set);
+            variable_uses.insert(value, set);
           },
         };
       }
@@ -187,7 +290,7 @@ impl<'a, const GROUP_ORDER: usize> Program<'a, GROUP_ORDER> {
     }
 
     // $S_i$ polynomial in evaluation form
-    let mut s: [[PlutoScalarField; GROUP_ORDER]; 3] = [[PlutoScalarField::ZERO; GROUP_ORDER]; 3];
+    let mut s: [[F; GROUP_ORDER]; 3] = [[F::ZERO; GROUP_ORDER]; 3];
 
     // shift each polynomial value right by 1 and assign domain. for example:
     // let's say, usage of variable in execution trace looks like:
@@ -208,25 +311,67 @@ impl<'a, const GROUP_ORDER: usize> Program<'a, GROUP_ORDER> {
     }
 
     // create polynomials in lagrange basis from variable values as evaluations
-    let poly_s1 =
-      Polynomial::<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>::new(s[0]);
-    let poly_s2 =
-      Polynomial::<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>::new(s[1]);
-    let poly_s3 =
-      Polynomial::<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>::new(s[2]);
+    let poly_s1 = Polynomial::<Lagrange<F>, F, GROUP_ORDER>::new(s[0]);
+    let poly_s2 = Polynomial::<Lagrange<F>, F, GROUP_ORDER>::new(s[1]);
+    let poly_s3 = Polynomial::<Lagrange<F>, F, GROUP_ORDER>::new(s[2]);
     (poly_s1, poly_s2, poly_s3)
   }
 
   /// creates selector and permutation helper polynomials from constraints as part of circuit
   /// preprocessing
-  pub fn common_preprocessed_input(&self) -> CommonPreprocessedInput<GROUP_ORDER> {
+  ///
+  /// Fails if the cosets labelling the three columns overlap, see [`cosets_are_disjoint`], as a
+  /// permutation over them wouldn't tell a wire of one column from a wire of another: over
+  /// `PlutoScalarField` for more than 4 rows, or over `PrimeField<257>` for more than 8.
+  pub fn common_preprocessed_input(
+    &self,
+  ) -> Result<CommonPreprocessedInput<GROUP_ORDER, F>, ProgramError<'a, F>> {
+    if !cosets_are_disjoint::<F>(GROUP_ORDER) {
+      return Err(ProgramError::OverlappingCosets(GROUP_ORDER));
+    }
     let (s1, s2, s3) = self.s_polynomials();
     let (ql, qr, qm, qo, qc) = self.selector_polynomials();
-    CommonPreprocessedInput { ql, qr, qm, qo, qc, s1, s2, s3 }
+    let [qbool, qrange, qlookup, qtable] = self.custom_selector_polynomials();
+    let tables = self.table_polynomials();
+    Ok(CommonPreprocessedInput {
+      ql,
+      qr,
+      qm,
+      qo,
+      qc,
+      s1,
+      s2,
+      s3,
+      qbool,
+      qrange,
+      qlookup,
+      qtable,
+      tables,
+    })
+  }
+
+  /// Lays out wire values in the execution trace: one row per constraint with the values of its
+  /// left, right and output wires, the same layout as [`CommonPreprocessedInput`]. Rows after the
+  /// last constraint and unused wires are zero.
+  pub fn execution_trace(
+    &self,
+    assignments: &HashMap<Option<&'a str>, F>,
+  ) -> Result<[[F; GROUP_ORDER]; 3], ProgramError<'a, F>> {
+    let mut trace = [[F::ZERO; GROUP_ORDER]; 3];
+    for (row, constraint) in self.constraints.iter().enumerate() {
+      for (column, wire) in constraint.wires.iter().enumerate() {
+        if let Some(var) = wire {
+          trace[column][row] = *assignments
+            .get(&Some(var.as_ref()))
+            .ok_or_else(|| ProgramError::MissingAssignment(var.to_string()))?;
+        }
+      }
+    }
+    Ok(trace)
   }
 
   /// returns public variables assigned in the circuit
-  pub fn public_assignments(&self) -> Result<Vec<String>, ProgramError> {
+  pub fn public_assignments(&self) -> Result<Vec<String>, ProgramError<'a, F>> {
     let mut variables = Vec::new();
     let mut flag = false;
     for wire_values in self.constraints.iter() {
@@ -254,31 +399,30 @@ impl<'a, const GROUP_ORDER: usize> Program<'a, GROUP_ORDER> {
   /// Evaluates the circuit and fill intermediate variable assignments
   pub fn evaluate_circuit(
     &'a self,
-    starting_assignments: HashMap<Option<&'a str>, PlutoScalarField>,
-  ) -> Result<HashMap<Option<&'a str>, PlutoScalarField>, ProgramError> {
+    starting_assignments: HashMap<Option<&'a str>, F>,
+  ) -> Result<HashMap<Option<&'a str>, F>, ProgramError<'a, F>> {
     let mut out = starting_assignments.clone();
-    out.insert(None, PlutoScalarField::ZERO);
+    out.insert(None, F::ZERO);
 
     for constraint in self.constraints.iter() {
-      let in_l = constraint.wires[0];
-      let in_r = constraint.wires[1];
-      let output = constraint.wires[2];
+      let in_l = constraint.wires[0].as_deref();
+      let in_r = constraint.wires[1].as_deref();
+      let output = constraint.wires[2].as_deref();
 
       let out_coeff = constraint.coeffs.get("$output_coeffs").unwrap_or(&1);
       let product_key = get_product_key(in_l.unwrap_or(""), in_r.unwrap_or(""));
       if output.is_some() && (*out_coeff == 1 || *out_coeff == -1) {
         let l_value = *out.get(&in_l).unwrap()
-          * PlutoScalarField::from(*constraint.coeffs.get(in_l.unwrap_or("")).unwrap_or(&0));
+          * coefficient::<F>(*constraint.coeffs.get(in_l.unwrap_or("")).unwrap_or(&0));
         let r_value = *out.get(&in_r).unwrap()
-          * PlutoScalarField::from(*constraint.coeffs.get(in_r.unwrap_or("")).unwrap_or(&0))
-          * PlutoScalarField::from((in_l != in_r) as u32);
-        let c_value = PlutoScalarField::from(*constraint.coeffs.get("$constant").unwrap_or(&0));
+          * coefficient::<F>(*constraint.coeffs.get(in_r.unwrap_or("")).unwrap_or(&0))
+          * F::from((in_l != in_r) as usize);
+        let c_value = coefficient::<F>(*constraint.coeffs.get("$constant").unwrap_or(&0));
         let m_value = *out.get(&in_l).unwrap()
           * *out.get(&in_r).unwrap()
-          * PlutoScalarField::from(*constraint.coeffs.get(&product_key).unwrap_or(&0));
+          * coefficient::<F>(*constraint.coeffs.get(&product_key).unwrap_or(&0));
 
-        let output_value =
-          (l_value + r_value + c_value + m_value) * PlutoScalarField::from(*out_coeff);
+        let output_value = (l_value + r_value + c_value + m_value) * coefficient::<F>(*out_coeff);
 
         match out.get(&output) {
           Some(out_value) =>
@@ -303,6 +447,7 @@ mod tests {
   use rstest::{fixture, rstest};
 
   use super::*;
+  use crate::{algebra::field::prime::PrimeField, compiler::errors::ParserError, Field};
 
   #[fixture]
   fn constraint1<'a>() -> &'a [&'a str] {
@@ -316,22 +461,22 @@ mod tests {
   fn cell_label(#[case] row: u32, #[case] column: Column, #[case] group_order: usize) {
     let cell = Cell { row, column };
     assert_eq!(
-      cell.label(group_order),
+      cell.label::<PlutoScalarField>(group_order),
       PlutoScalarField::primitive_root_of_unity(group_order).pow(row as usize)
-        * PlutoScalarField::from(column as u32)
+        * PlutoScalarField::PRIMITIVE_ELEMENT.pow(column as usize - 1)
     )
   }
 
   #[test]
   fn new_program() {
     let constraints = &["a public", "b <== a * a"];
-    let program = Program::<5>::new(constraints);
+    let program = Program::<8>::new(constraints);
     assert!(program.is_ok());
 
     assert_eq!(program.unwrap(), Program {
       constraints: Vec::from([
         WireCoeffs {
-          wires:  vec![Some("a"), None, None],
+          wires:  vec![Some("a".into()), None, None],
           coeffs: HashMap::from([
             (String::from("$public"), 1),
             (String::from("a"), -1),
@@ -339,13 +484,116 @@ mod tests {
           ]),
         },
         WireCoeffs {
-          wires:  vec![Some("a"), Some("a"), Some("b")],
+          wires:  vec![Some("a".into()), Some("a".into()), Some("b".into())],
           coeffs: HashMap::from([(String::from("a*a"), 1)]),
         }
       ]),
+      tables:      vec![],
+      hints:       vec![],
+      field:       PhantomData,
     })
   }
 
+  #[test]
+  fn program_from_source() {
+    let source = "// cube of x\nx public\n\ny <== x * x * x /* two gates */ + 5\n";
+    let program = Program::<4>::from_source(source).unwrap();
+    let inputs = HashMap::from([(Some("x"), PlutoScalarField::new(2))]);
+    let outputs = program.evaluate_circuit(inputs).unwrap();
+    assert_eq!(outputs.get(&Some("y")), Some(&PlutoScalarField::new(13)));
+
+    let error = Program::<4>::from_source("x public\ny <== x +").unwrap_err();
+    assert!(matches!(error, ProgramError::ParserError(ParserError { line: 2, .. })));
+  }
+
+  #[test]
+  fn group_order() {
+    let constraints = &["x public", "y <== x * x", "z <== y + 1"];
+    assert!(matches!(Program::<5>::new(constraints), Err(ProgramError::InvalidGroupOrder(5, 16))));
+    assert!(matches!(
+      Program::<32>::new(constraints),
+      Err(ProgramError::InvalidGroupOrder(32, 16))
+    ));
+    assert!(matches!(Program::<2>::new(constraints), Err(ProgramError::TooManyConstraints(3, 2))));
+    // 32 divides 257 - 1
+    assert!(Program::<32, PrimeField<257>>::new(constraints).is_ok());
+  }
+
+  #[test]
+  fn disjoint_cosets() {
+    assert!(cosets_are_disjoint::<PlutoScalarField>(4));
+    // g^8 = -1 in the field of order 17, so g^2 H = H
+    assert!(!cosets_are_disjoint::<PlutoScalarField>(8));
+    assert!(!cosets_are_disjoint::<PlutoScalarField>(16));
+    assert!(cosets_are_disjoint::<PrimeField<257>>(8));
+    assert!(cosets_are_disjoint::<PrimeField<257>>(64));
+    assert!(!cosets_are_disjoint::<PrimeField<257>>(128));
+    assert!(!cosets_are_disjoint::<PrimeField<257>>(256));
+    // (p - 1) / n = 3
+    assert!(cosets_are_disjoint::<PrimeField<13>>(4));
+    assert!(!cosets_are_disjoint::<PrimeField<13>>(6));
+  }
+
+  #[test]
+  fn overlapping_cosets() {
+    let constraints = &["x public", "y <== x * x", "z <== y + 1"];
+    // g^16 = 1 in the field of order 17, so the cosets H and g^2 H are equal
+    let program = Program::<8>::new(constraints).unwrap();
+    assert!(matches!(program.common_preprocessed_input(), Err(ProgramError::OverlappingCosets(8))));
+    assert!(Program::<4>::new(constraints).unwrap().common_preprocessed_input().is_ok());
+
+    // the program itself is fine for witness generation over the larger domain
+    let inputs = HashMap::from([(String::from("x"), PlutoScalarField::new(3))]);
+    assert!(program.generate_witness(&inputs).is_ok());
+
+    // (257 - 1) / 64 = 4 cosets fit in the multiplicative group of the field of order 257
+    let program = Program::<64, PrimeField<257>>::new(constraints).unwrap();
+    assert!(program.common_preprocessed_input().is_ok());
+    let program = Program::<256, PrimeField<257>>::new(constraints).unwrap();
+    assert!(matches!(
+      program.common_preprocessed_input(),
+      Err(ProgramError::OverlappingCosets(256))
+    ));
+  }
+
+  #[rstest]
+  #[case("y <== 3000000000 * x", 43825 * 3)]
+  // 65536 = -1 modulo 65537
+  #[case("y <== 65536 * 65536 * x", 3)]
+  fn constants_beyond_i32(#[case] constraint: &str, #[case] expected: usize) {
+    type F = PrimeField<65537>;
+    let program = Program::<4, F>::new(&["x public", constraint]).unwrap();
+    let inputs = HashMap::from([(String::from("x"), F::new(3))]);
+    assert_eq!(program.generate_witness(&inputs).unwrap().assignments["y"], F::new(expected));
+  }
+
+  #[test]
+  fn larger_prime_field() {
+    type F = PrimeField<65537>;
+    // 1000 and 8980 would wrap around modulo 17
+    let program = Program::<4, F>::new(&["x public", "y <== 1000 * x * x - 20"]).unwrap();
+    let inputs = HashMap::from([(String::from("x"), F::new(3))]);
+    let witness = program.generate_witness(&inputs).unwrap();
+    assert_eq!(witness.assignments["y"], F::new(8980));
+
+    let cpi = program.common_preprocessed_input().unwrap();
+    assert_eq!(cpi.qm.coefficients[1], -F::new(1000));
+    assert_eq!(cpi.qc.coefficients[1], F::new(20));
+
+    // the permutation polynomials only move the labels `k * ω^i` of the cells around
+    let omega = F::primitive_root_of_unity(4);
+    let labels =
+      (0..3).flat_map(|j| (0..4).map(move |i| (F::PRIMITIVE_ELEMENT.pow(j) * omega.pow(i)).into()));
+    let mut labels: Vec<usize> = labels.collect();
+    let mut permuted: Vec<usize> = [cpi.s1, cpi.s2, cpi.s3]
+      .iter()
+      .flat_map(|s| s.coefficients.iter().map(|&label| label.into()))
+      .collect();
+    labels.sort();
+    permuted.sort();
+    assert_eq!(labels, permuted);
+  }
+
   #[rstest]
   fn s_polys(constraint1: &[&str]) {
     // TODO: make this more robust
@@ -358,23 +606,23 @@ mod tests {
 
     assert_eq!(s1.coefficients.to_vec(), vec![
       PlutoScalarField::from(4),
-      PlutoScalarField::from(3),
+      PlutoScalarField::from(9),
       PlutoScalarField::from(1),
-      PlutoScalarField::from(15),
+      PlutoScalarField::from(3),
     ]);
 
     assert_eq!(s2.coefficients.to_vec(), vec![
-      PlutoScalarField::from(9),
+      PlutoScalarField::from(12),
       PlutoScalarField::from(13),
       PlutoScalarField::from(16),
-      PlutoScalarField::from(14),
+      PlutoScalarField::from(8),
     ]);
 
     assert_eq!(s3.coefficients.to_vec(), vec![
-      PlutoScalarField::from(2),
+      PlutoScalarField::from(14),
+      PlutoScalarField::from(15),
       PlutoScalarField::from(5),
-      PlutoScalarField::from(8),
-      PlutoScalarField::from(12),
+      PlutoScalarField::from(2),
     ]);
   }
 
@@ -419,6 +667,50 @@ mod tests {
     ]);
   }
 
+  #[test]
+  fn custom_selector_and_table_polys() {
+    // tables need more than the 4 rows over which `PlutoScalarField` has disjoint cosets
+    type F = PrimeField<257>;
+    let constraints = &["x public", "table t range 3", "x bool", "y range", "y in t"];
+    let cpi = Program::<8, F>::new(constraints).unwrap().common_preprocessed_input().unwrap();
+    let column = |values: [u32; 8]| values.map(F::from).to_vec();
+    assert_eq!(cpi.qbool.coefficients.to_vec(), column([0, 1, 0, 0, 0, 0, 0, 0]));
+    assert_eq!(cpi.qrange.coefficients.to_vec(), column([0, 0, 1, 0, 0, 0, 0, 0]));
+    assert_eq!(cpi.qlookup.coefficients.to_vec(), column([0, 0, 0, 1, 0, 0, 0, 0]));
+    assert_eq!(cpi.qtable.coefficients.to_vec(), column([0, 0, 0, 1, 0, 0, 0, 0]));
+    // custom rows are no arithmetic gates
+    assert_eq!(cpi.qo.coefficients.to_vec(), column([0; 8]));
+
+    // rows of the table, padded with its last row
+    let [t1, t2, t3, t4] = &cpi.tables;
+    assert_eq!(t1.coefficients.to_vec(), column([0, 1, 2, 2, 2, 2, 2, 2]));
+    assert_eq!(t2.coefficients.to_vec(), column([0; 8]));
+    assert_eq!(t3.coefficients.to_vec(), column([0; 8]));
+    assert_eq!(t4.coefficients.to_vec(), column([1; 8]));
+
+    let cpi = Program::<4, F>::new(&["x bool"]).unwrap().common_preprocessed_input().unwrap();
+    assert!(cpi.tables.iter().all(|t| t.coefficients == [F::ZERO; 4]));
+  }
+
+  #[test]
+  fn several_tables() {
+    type F = PrimeField<257>;
+    let constraints = &["table a range 2", "table b xor 2", "(x, y, z) in b"];
+    let program = Program::<8, F>::new(constraints).unwrap();
+    assert_eq!(program.tables().len(), 2);
+    let cpi = program.common_preprocessed_input().unwrap();
+    let column = |values: [u32; 8]| values.map(F::from).to_vec();
+    assert_eq!(cpi.qtable.coefficients.to_vec(), column([2, 0, 0, 0, 0, 0, 0, 0]));
+    let [t1, t2, t3, t4] = &cpi.tables;
+    assert_eq!(t1.coefficients.to_vec(), column([0, 1, 0, 0, 1, 1, 1, 1]));
+    assert_eq!(t2.coefficients.to_vec(), column([0, 0, 0, 1, 0, 1, 1, 1]));
+    assert_eq!(t3.coefficients.to_vec(), column([0, 0, 0, 1, 1, 0, 0, 0]));
+    assert_eq!(t4.coefficients.to_vec(), column([1, 1, 2, 2, 2, 2, 2, 2]));
+
+    let error = Program::<4>::new(&["table a range 2", "table b xor 2"]).unwrap_err();
+    assert!(matches!(error, ProgramError::TooManyTableRows(6, 4)));
+  }
+
   #[rstest]
   #[case(&["a public", "d === 9", "b <== a * a + 5", "c <== -2 * b - a * b"], vec![String::from("a")])]
   #[case(&["d === 9"], vec![])]
@@ -426,7 +718,7 @@ mod tests {
   #[should_panic]
   #[case(&["a public", "d === 9", "b <== a * a + 5", "b public", "c <== -2 * b - a * b"], vec![])]
   fn public_vars(#[case] constraint: &[&str], #[case] expected: Vec<String>) {
-    let program = Program::<5>::new(constraint);
+    let program = Program::<8>::new(constraint);
     assert!(program.is_ok());
 
     let program = program.unwrap();
@@ -506,4 +798,19 @@ mod tests {
 
     assert_eq!(evaluations.unwrap(), expected);
   }
+
+  #[test]
+  fn evaluate_decomposed_circuit() {
+    let constraints = &["x public", "y <== (x + 1) * (x - 2) * x + 3 * x * x - 7"];
+    let program = Program::<8>::new(constraints).unwrap();
+    assert_eq!(program.constraints.len(), 7);
+
+    let x = PlutoScalarField::new(5);
+    let evaluations = program.evaluate_circuit(HashMap::from([(Some("x"), x)])).unwrap();
+    let y = (x + PlutoScalarField::ONE) * (x - PlutoScalarField::new(2)) * x
+      + PlutoScalarField::new(3) * x * x
+      - PlutoScalarField::new(7);
+    assert_eq!(evaluations[&Some("y")], y);
+    assert_eq!(evaluations[&Some("$1")], x + PlutoScalarField::ONE);
+  }
 }
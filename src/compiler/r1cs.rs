@@ -0,0 +1,663 @@
+//! Rank-1 constraint systems built from the same DSL constraints as [`Program`], over any
+//! [`FiniteField`] and [`PlutoScalarField`] by default.
+//!
+//! An R1CS is a list of constraints `⟨A_i, w⟩ · ⟨B_i, w⟩ = ⟨C_i, w⟩` over a witness vector `w`,
+//! where `A_i`, `B_i` and `C_i` are sparse rows of coefficients. The witness is laid out as
+//! `[1, public inputs.., private variables..]`, with the constant `1` as first variable so that
+//! constants can appear in the linear combinations.
+//!
+//! Every PLONK [`Gate`](super::parser::Gate) `q_L a + q_R b + q_M ab + q_O c + q_C = 0` becomes
+//! one constraint:
+//! - `a · b = -(q_L a + q_R b + q_O c + q_C) / q_M` if it has a multiplication,
+//! - `(q_L a + q_R b + q_O c + q_C) · 1 = 0` otherwise.
+//!
+//! A boolean gate `x bool` becomes `x · x = x`. Range gates and lookups have no single rank-1
+//! constraint and are rejected with [`ProgramError::UnsupportedGate`].
+//!
+//! Public declarations don't need a constraint: their variables are simply placed first in the
+//! witness.
+//!
+//! ## JSON files
+//!
+//! [`R1CS::to_json`] and [`witness_to_json`] write the JSON dumps of circom's `.r1cs` and `.wtns`
+//! files, as exported by `snarkjs r1cs export json` and `snarkjs wtns export json`, and
+//! [`R1CS::from_json`] and [`witness_from_json`] read them back. Field elements are decimal
+//! strings. An R1CS is an object:
+//!
+//! ```json
+//! {
+//!   "n8": 8,
+//!   "prime": "17",
+//!   "nVars": 4,
+//!   "nOutputs": 0,
+//!   "nPubInputs": 1,
+//!   "nPrvInputs": 0,
+//!   "nLabels": 4,
+//!   "nConstraints": 2,
+//!   "useCustomGates": false,
+//!   "constraints": [
+//!     [{"1": "1"}, {"1": "1"}, {"2": "1"}],
+//!     [{"1": "1"}, {"2": "1"}, {"3": "1", "0": "12"}]
+//!   ],
+//!   "map": [0, 1, 2, 3],
+//!   "customGates": [],
+//!   "customGatesUses": []
+//! }
+//! ```
+//!
+//! - `n8`: size of a field element in bytes, rounded up to a multiple of 8 bytes, i.e. to whole
+//!   64-bit words: 8 for [`PlutoScalarField`], 32 for the BN254 scalar field. `prime`: order of the
+//!   field.
+//! - `nVars`: length of the witness, including the constant `1` at index 0.
+//! - `nOutputs`, `nPubInputs`, `nPrvInputs`: number of public outputs, public inputs and private
+//!   inputs, which follow the constant in the witness in that order. Exported circuits have no
+//!   outputs, since the DSL doesn't tell them apart from other public variables, and imported ones
+//!   have `nOutputs + nPubInputs` public inputs. The private inputs of a DSL circuit are the
+//!   variables that [`Program::generate_witness`] can't solve from the public inputs, see
+//!   [`R1CS::num_private`].
+//! - `constraints`: `[A_i, B_i, C_i]` for each constraint `⟨A_i, w⟩ · ⟨B_i, w⟩ = ⟨C_i, w⟩`, with
+//!   nonzero coefficients by witness index.
+//! - `nLabels` and `map`: signals of the circom source and their witness indices, which are the
+//!   witness indices themselves here.
+//!
+//! A witness is an array of field elements, `["1", "3", "9", "15"]` for the constraints above. The
+//! prime must be the order of the field `F` of the [`R1CS`] on import, and custom gates are
+//! unsupported.
+//!
+//! Imported constraint systems are evaluated by turning them into the gates of a [`Program`] with
+//! [`R1CS::circuit`], and their witnesses into inputs of [`Program::generate_witness`] with
+//! [`R1CS::assignments`].
+//!
+//! [`Program`]: super::program::Program
+//! [`Program::generate_witness`]: super::program::Program::generate_witness
+
+use std::{borrow::Cow, collections::HashMap, iter};
+
+use super::{
+  errors::ProgramError,
+  json::Json,
+  parser::{parse_constraints, rank_one_gates, Circuit, LinearTerms, WireCoeffs},
+  witness::parse_value,
+};
+use crate::{algebra::field::FiniteField, PlutoScalarField};
+
+/// A sparse row of an R1CS matrix: pairs of variable index and coefficient.
+pub type LinearCombination<F = PlutoScalarField> = Vec<(usize, F)>;
+
+/// A single constraint `⟨a, w⟩ · ⟨b, w⟩ = ⟨c, w⟩`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct R1CSConstraint<F: FiniteField = PlutoScalarField> {
+  /// left factor
+  pub a: LinearCombination<F>,
+  /// right factor
+  pub b: LinearCombination<F>,
+  /// product
+  pub c: LinearCombination<F>,
+}
+
+/// A rank-1 constraint system with named variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct R1CS<F: FiniteField = PlutoScalarField> {
+  /// Names of the variables in witness order. The first one is the constant `1`, named `"one"`.
+  pub variables:   Vec<String>,
+  /// Number of public inputs, which are the variables `1..=num_public`.
+  pub num_public:  usize,
+  /// Number of private inputs, which follow the public inputs. Other variables are solved from the
+  /// inputs and the constraints: when no constraint has a single unknown variable, the first
+  /// unknown one is taken as a private input, as witness generation needs it to go on.
+  pub num_private: usize,
+  /// The constraints, in the order of the DSL lines they come from.
+  pub constraints: Vec<R1CSConstraint<F>>,
+}
+
+/// Adds `coeff * variable` to a linear combination, merging it with an existing term.
+fn add_term<F: FiniteField>(lc: &mut LinearCombination<F>, variable: usize, coeff: F) {
+  if coeff == F::ZERO {
+    return;
+  }
+  match lc.iter_mut().find(|(v, _)| *v == variable) {
+    Some((_, c)) => *c += coeff,
+    None => lc.push((variable, coeff)),
+  }
+  lc.retain(|&(_, c)| c != F::ZERO);
+}
+
+/// Returns the indices of the variables that witness generation needs as private inputs, when the
+/// variables `0..=num_public` are known, in the order they are needed. A variable is solved by a
+/// gate whose only unknown variable it is, unless the gate squares it, and custom gates solve
+/// nothing.
+fn private_inputs<F: FiniteField>(
+  gates: &[&WireCoeffs],
+  index: &HashMap<&str, usize>,
+  num_public: usize,
+) -> Vec<usize> {
+  let mut known = vec![false; index.len() + 1];
+  known[..=num_public].fill(true);
+  let mut private = vec![];
+  loop {
+    let solvable = gates.iter().filter(|gate| gate.custom_gate().is_none()).find_map(|gate| {
+      let var = |i: usize| gate.wires[i].as_deref().map(|name| index[name]);
+      let mut unknowns: Vec<usize> = (0..3).filter_map(var).filter(|&v| !known[v]).collect();
+      unknowns.sort();
+      unknowns.dedup();
+      let squared = |v| var(0) == Some(v) && var(1) == Some(v) && gate.gate::<F>().m != F::ZERO;
+      match unknowns[..] {
+        [v] if !squared(v) => Some(v),
+        _ => None,
+      }
+    });
+    let variable = match solvable {
+      Some(variable) => variable,
+      None => match known.iter().position(|&known| !known) {
+        Some(variable) => {
+          private.push(variable);
+          variable
+        },
+        None => return private,
+      },
+    };
+    known[variable] = true;
+  }
+}
+
+/// `⟨lc, w⟩`
+fn evaluate<F: FiniteField>(lc: &LinearCombination<F>, witness: &[F]) -> F {
+  lc.iter().map(|&(v, c)| c * witness[v]).sum()
+}
+
+impl<F: FiniteField> R1CS<F>
+where usize: From<F>
+{
+  /// Parses DSL constraints, see [`parser`](super::parser), into an R1CS.
+  ///
+  /// Fails with [`ProgramError::UnsupportedGate`] on range gates and lookups.
+  pub fn new<'a>(constraints: &[&'a str]) -> Result<Self, ProgramError<'a, F>> {
+    let assembly = parse_constraints(constraints).map_err(ProgramError::ParserError)?;
+
+    let mut variables = vec![String::from("one")];
+    let mut index = HashMap::new();
+    let (public, gates): (Vec<_>, Vec<_>) =
+      assembly.iter().partition(|wire_coeffs| wire_coeffs.coeffs.get("$public") == Some(&1));
+    for wire_coeffs in public.iter().chain(&gates) {
+      for var in wire_coeffs.wires.iter().flatten() {
+        index.entry(var.as_ref()).or_insert_with(|| {
+          variables.push(var.to_string());
+          variables.len() - 1
+        });
+      }
+    }
+
+    // the private inputs come right after the public ones in the witness
+    let private = private_inputs::<F>(&gates, &index, public.len());
+    let rest = (public.len() + 1..variables.len()).filter(|v| !private.contains(v));
+    let order: Vec<usize> = (0..=public.len()).chain(private.iter().copied()).chain(rest).collect();
+    let variables: Vec<String> = order.iter().map(|&v| variables[v].clone()).collect();
+    let index: HashMap<&str, usize> =
+      variables.iter().enumerate().skip(1).map(|(v, name)| (name.as_str(), v)).collect();
+
+    let constraints = gates
+      .iter()
+      .map(|wire_coeffs| {
+        let gate = wire_coeffs.gate::<F>();
+        let var = |i: usize| wire_coeffs.wires[i].as_deref().map(|name| index[name]);
+        match (wire_coeffs.custom_gate(), var(0)) {
+          (None, _) => {},
+          (Some("$bool"), Some(x)) => {
+            let x = vec![(x, F::ONE)];
+            return Ok(R1CSConstraint { a: x.clone(), b: x.clone(), c: x });
+          },
+          _ => return Err(ProgramError::UnsupportedGate(wire_coeffs.to_string())),
+        }
+        let mut linear = LinearCombination::new();
+        for (wire, coeff) in [(var(0), gate.l), (var(1), gate.r), (var(2), gate.o)] {
+          if let Some(wire) = wire {
+            add_term(&mut linear, wire, coeff);
+          }
+        }
+        add_term(&mut linear, 0, gate.c);
+
+        Ok(match (var(0), var(1)) {
+          (Some(a), Some(b)) if gate.m != F::ZERO => {
+            let scale = -gate.m.inverse().unwrap();
+            R1CSConstraint {
+              a: vec![(a, F::ONE)],
+              b: vec![(b, F::ONE)],
+              c: linear.into_iter().map(|(v, c)| (v, c * scale)).collect(),
+            }
+          },
+          _ => R1CSConstraint { a: linear, b: vec![(0, F::ONE)], c: vec![] },
+        })
+      })
+      .collect::<Result<_, _>>()?;
+
+    Ok(Self { variables, num_public: public.len(), num_private: private.len(), constraints })
+  }
+
+  /// Lays out the witness vector `[1, public inputs.., private variables..]` from the variable
+  /// assignments returned by
+  /// [`Program::evaluate_circuit`](super::program::Program::evaluate_circuit).
+  pub fn witness(
+    &self,
+    assignments: &HashMap<Option<&str>, F>,
+  ) -> Result<Vec<F>, ProgramError<'_, F>> {
+    let mut witness = vec![F::ONE];
+    for var in &self.variables[1..] {
+      witness.push(
+        *assignments
+          .get(&Some(var.as_str()))
+          .ok_or_else(|| ProgramError::MissingAssignment(var.clone()))?,
+      );
+    }
+    Ok(witness)
+  }
+
+  /// Returns the index of the first constraint that `witness` does not satisfy, if any.
+  pub fn unsatisfied_constraint(&self, witness: &[F]) -> Option<usize> {
+    self.constraints.iter().position(|constraint| {
+      evaluate(&constraint.a, witness) * evaluate(&constraint.b, witness)
+        != evaluate(&constraint.c, witness)
+    })
+  }
+
+  /// Returns the sparse matrices `[A, B, C]`, whose `i`-th rows are the linear combinations of the
+  /// `i`-th constraint.
+  pub fn matrices(&self) -> [Vec<LinearCombination<F>>; 3] {
+    let rows = |matrix: fn(&R1CSConstraint<F>) -> &LinearCombination<F>| {
+      self.constraints.iter().map(|constraint| matrix(constraint).clone()).collect()
+    };
+    [
+      rows(|constraint| &constraint.a),
+      rows(|constraint| &constraint.b),
+      rows(|constraint| &constraint.c),
+    ]
+  }
+
+  /// Writes the constraints as circom's R1CS JSON, see the [module documentation](self).
+  pub fn to_json(&self) -> String {
+    let number = |value: usize| Json::Number(value.to_string());
+    let linear_combination = |lc: &LinearCombination<F>| {
+      Json::Object(lc.iter().map(|&(v, c)| (v.to_string(), element_json(c))).collect())
+    };
+    let constraints = self
+      .constraints
+      .iter()
+      .map(|constraint| {
+        Json::Array([&constraint.a, &constraint.b, &constraint.c].map(linear_combination).to_vec())
+      })
+      .collect();
+    let json = Json::Object(vec![
+      (String::from("n8"), number(8 * (F::ORDER.ilog2() + 1).div_ceil(64) as usize)),
+      (String::from("prime"), Json::String(F::ORDER.to_string())),
+      (String::from("nVars"), number(self.variables.len())),
+      (String::from("nOutputs"), number(0)),
+      (String::from("nPubInputs"), number(self.num_public)),
+      (String::from("nPrvInputs"), number(self.num_private)),
+      (String::from("nLabels"), number(self.variables.len())),
+      (String::from("nConstraints"), number(self.constraints.len())),
+      (String::from("useCustomGates"), Json::Bool(false)),
+      (String::from("constraints"), Json::Array(constraints)),
+      (String::from("map"), Json::Array((0..self.variables.len()).map(number).collect())),
+      (String::from("customGates"), Json::Array(vec![])),
+      (String::from("customGatesUses"), Json::Array(vec![])),
+    ]);
+    format!("{:#}\n", json)
+  }
+
+  /// Reads circom's R1CS JSON, see the [module documentation](self). The variables are named
+  /// `one`, then `w1`, `w2`, … by witness index.
+  pub fn from_json(source: &str) -> Result<Self, ProgramError<'static, F>> {
+    let invalid = |reason: String| ProgramError::InvalidR1CS(reason);
+    let json = Json::parse(source).map_err(invalid)?;
+    let field = |key: &str| json.get(key).ok_or_else(|| invalid(format!("missing {}", key)));
+    let count =
+      |key: &str| field(key)?.as_usize().ok_or_else(|| invalid(format!("{} is not a count", key)));
+
+    let prime = field("prime")?.as_number().unwrap_or_default();
+    if prime != F::ORDER.to_string() {
+      return Err(invalid(format!("prime {} is not the order {} of the field", prime, F::ORDER)));
+    }
+    if json.get("useCustomGates") == Some(&Json::Bool(true)) {
+      return Err(invalid(String::from("custom gates are not supported")));
+    }
+    let num_variables = count("nVars")?;
+    let num_public = count("nOutputs")? + count("nPubInputs")?;
+    if num_variables == 0 || num_public >= num_variables {
+      return Err(invalid(format!("{} public inputs in {} variables", num_public, num_variables)));
+    }
+
+    let linear_combination = |json: &Json| {
+      let entries =
+        json.as_object().ok_or_else(|| invalid(format!("{} is not an object", json)))?;
+      let mut lc = LinearCombination::new();
+      for (variable, coeff) in entries {
+        let index = variable.parse().ok().filter(|&index| index < num_variables);
+        let index = index.ok_or_else(|| invalid(format!("no variable {}", variable)))?;
+        let value = coeff.as_number().and_then(parse_value);
+        let value = value.ok_or_else(|| invalid(format!("invalid coefficient {}", coeff)))?;
+        add_term(&mut lc, index, value);
+      }
+      Ok(lc)
+    };
+    let constraints = field("constraints")?
+      .as_array()
+      .ok_or_else(|| invalid(String::from("constraints are not an array")))?
+      .iter()
+      .map(|constraint| match constraint.as_array() {
+        Some([a, b, c]) => Ok(R1CSConstraint {
+          a: linear_combination(a)?,
+          b: linear_combination(b)?,
+          c: linear_combination(c)?,
+        }),
+        _ => Err(invalid(format!("constraint {} is not [A, B, C]", constraint))),
+      })
+      .collect::<Result<_, _>>()?;
+
+    let num_private = count("nPrvInputs")?;
+    if num_public + num_private >= num_variables {
+      return Err(invalid(format!(
+        "{} private inputs in {} variables",
+        num_private, num_variables
+      )));
+    }
+
+    let variables = variable_names(num_variables).collect();
+    Ok(Self { variables, num_public, num_private, constraints })
+  }
+
+  /// Returns the gates of the constraints, for a [`Program`](super::program::Program) that
+  /// evaluates the constraint system: public declarations for the public inputs, then the fan-in 2
+  /// gates of each constraint.
+  pub fn circuit(&self) -> Result<Circuit<'_>, ProgramError<'_, F>> {
+    let name = |variable: usize| (variable > 0).then(|| self.variables[variable].as_str());
+    let terms = |lc: &LinearCombination<F>| -> LinearTerms {
+      lc.iter().map(|&(variable, coeff)| (name(variable), small_coefficient(coeff))).collect()
+    };
+    let constraints: Vec<_> = self
+      .constraints
+      .iter()
+      .map(|constraint| [terms(&constraint.a), terms(&constraint.b), terms(&constraint.c)])
+      .collect();
+    let gates = rank_one_gates(&constraints)
+      .map_err(|(i, kind)| ProgramError::InvalidR1CS(format!("constraint {}: {}", i, kind)))?;
+
+    let public = self.variables[1..=self.num_public].iter().map(|name| WireCoeffs {
+      wires:  vec![Some(Cow::Borrowed(name.as_str())), None, None],
+      coeffs: HashMap::from([
+        (name.clone(), -1),
+        (String::from("$output_coeffs"), 0),
+        (String::from("$public"), 1),
+      ]),
+    });
+    Ok(Circuit { gates: public.chain(gates).collect(), tables: vec![], hints: vec![] })
+  }
+
+  /// Returns the value of each variable but the constant in `witness`, by name, as inputs of
+  /// [`Program::generate_witness`](super::program::Program::generate_witness) for the program of
+  /// [`R1CS::circuit`].
+  pub fn assignments(&self, witness: &[F]) -> HashMap<String, F> {
+    self.variables.iter().cloned().zip(witness.iter().copied()).skip(1).collect()
+  }
+
+  /// Checks that `witness` satisfies every constraint.
+  pub fn is_satisfied(&self, witness: &[F]) -> bool {
+    witness.len() == self.variables.len()
+      && witness[0] == F::ONE
+      && self.unsatisfied_constraint(witness).is_none()
+  }
+}
+
+/// Names of imported variables: `one`, then `w1`, `w2`, ….
+fn variable_names(num_variables: usize) -> impl Iterator<Item = String> {
+  iter::once(String::from("one")).chain((1..num_variables).map(|i| format!("w{}", i)))
+}
+
+/// A field element as a JSON decimal string.
+fn element_json<F: FiniteField>(value: F) -> Json
+where usize: From<F> {
+  Json::String(usize::from(value).to_string())
+}
+
+/// The coefficient of the DSL for a field element: the element or its opposite, if smaller.
+fn small_coefficient<F: FiniteField>(value: F) -> i128
+where usize: From<F> {
+  let (value, opposite) = (usize::from(value), usize::from(-value));
+  match value <= opposite {
+    true => value as i128,
+    false => -(opposite as i128),
+  }
+}
+
+/// Writes a witness vector as circom's witness JSON: an array of decimal strings.
+pub fn witness_to_json<F: FiniteField>(witness: &[F]) -> String
+where usize: From<F> {
+  format!("{:#}\n", Json::Array(witness.iter().copied().map(element_json).collect()))
+}
+
+/// Reads a witness vector from circom's witness JSON.
+pub fn witness_from_json<F: FiniteField>(source: &str) -> Result<Vec<F>, ProgramError<'static, F>> {
+  let json = Json::parse(source).map_err(ProgramError::InvalidInput)?;
+  let values = json.as_array().ok_or_else(|| ProgramError::InvalidInput(json.to_string()))?;
+  values
+    .iter()
+    .map(|value| {
+      value
+        .as_number()
+        .and_then(parse_value)
+        .ok_or_else(|| ProgramError::InvalidInput(value.to_string()))
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use rstest::rstest;
+
+  use super::*;
+  use crate::{algebra::field::prime::PrimeField, compiler::program::Program, Field};
+
+  fn solve(r1cs: &R1CS, constraints: &[&str], public: &[(&str, u32)]) -> Vec<PlutoScalarField> {
+    let program = Program::<4>::new(constraints).unwrap();
+    let start = public.iter().map(|&(name, value)| (Some(name), PlutoScalarField::from(value)));
+    let assignments = program.evaluate_circuit(start.collect()).unwrap();
+    r1cs.witness(&assignments).unwrap()
+  }
+
+  #[test]
+  fn cubic_circuit() {
+    let constraints = &["x public", "x2 <== x * x", "out <== x2 * x + 5"];
+    let r1cs: R1CS = R1CS::new(constraints).unwrap();
+    assert_eq!(r1cs.variables, ["one", "x", "x2", "out"]);
+    assert_eq!(r1cs.num_public, 1);
+    let one = PlutoScalarField::ONE;
+    assert_eq!(r1cs.constraints, [
+      // x * x = x2
+      R1CSConstraint { a: vec![(1, one)], b: vec![(1, one)], c: vec![(2, one)] },
+      // x * x2 = out - 5
+      R1CSConstraint {
+        a: vec![(1, one)],
+        b: vec![(2, one)],
+        c: vec![(3, one), (0, -PlutoScalarField::new(5))],
+      },
+    ]);
+
+    let witness = solve(&r1cs, constraints, &[("x", 3)]);
+    assert_eq!(witness, [1, 3, 9, 32].map(PlutoScalarField::new));
+    assert!(r1cs.is_satisfied(&witness));
+  }
+
+  #[test]
+  fn linear_constraints_and_constants() {
+    let constraints = &["a public", "d === 9", "b <== a * a + 5", "c <== -2 * b - a * b"];
+    let r1cs: R1CS = R1CS::new(constraints).unwrap();
+    assert_eq!(r1cs.variables, ["one", "a", "d", "b", "c"]);
+    assert_eq!(r1cs.constraints[0], R1CSConstraint {
+      a: vec![(2, PlutoScalarField::ONE), (0, -PlutoScalarField::new(9))],
+      b: vec![(0, PlutoScalarField::ONE)],
+      c: vec![],
+    });
+
+    let witness = solve(&r1cs, constraints, &[("a", 2)]);
+    assert!(r1cs.is_satisfied(&witness));
+
+    let mut wrong = witness.clone();
+    wrong[4] += PlutoScalarField::ONE;
+    assert_eq!(r1cs.unsatisfied_constraint(&wrong), Some(2));
+    assert!(!r1cs.is_satisfied(&wrong));
+  }
+
+  #[test]
+  fn custom_gates() {
+    let r1cs: R1CS = R1CS::new(&["x public", "x bool"]).unwrap();
+    let x = vec![(1, PlutoScalarField::ONE)];
+    assert_eq!(r1cs.constraints, [R1CSConstraint { a: x.clone(), b: x.clone(), c: x }]);
+    assert!(r1cs.is_satisfied(&[PlutoScalarField::ONE, PlutoScalarField::ONE]));
+    assert!(!r1cs.is_satisfied(&[PlutoScalarField::ONE, PlutoScalarField::new(2)]));
+
+    let error =
+      R1CS::<PlutoScalarField>::new(&["x public", "table t range 8", "x in t"]).unwrap_err();
+    assert!(matches!(error, ProgramError::UnsupportedGate(gate) if gate == "x in table 1"));
+    let error = R1CS::<PlutoScalarField>::new(&["x public", "x range"]).unwrap_err();
+    assert!(matches!(error, ProgramError::UnsupportedGate(gate) if gate == "x range"));
+  }
+
+  #[test]
+  fn private_inputs_follow_public_ones() {
+    let constraints = &["y public", "t <== a * a", "y <== t + b"];
+    let r1cs: R1CS = R1CS::new(constraints).unwrap();
+    // `a` is needed to solve `t`, and `b` is then solved backwards from `y`
+    assert_eq!(r1cs.variables, ["one", "y", "a", "t", "b"]);
+    assert_eq!((r1cs.num_public, r1cs.num_private), (1, 1));
+    assert!(r1cs.to_json().contains("  \"nPubInputs\": 1,\n  \"nPrvInputs\": 1,\n"));
+
+    let r1cs: R1CS = R1CS::new(&["out public", "out <== x * x * x + 5"]).unwrap();
+    assert_eq!(r1cs.variables[..3], ["one", "out", "x"]);
+    assert_eq!(r1cs.num_private, 1);
+    let r1cs: R1CS = R1CS::new(&["x public", "x2 <== x * x", "out <== x2 * x + 5"]).unwrap();
+    assert_eq!(r1cs.num_private, 0);
+  }
+
+  #[test]
+  fn json_export() {
+    let r1cs: R1CS = R1CS::new(&["x public", "x2 <== x * x", "out <== x2 * x + 5"]).unwrap();
+    let json = r1cs.to_json();
+    assert!(json.starts_with("{\n  \"n8\": 8,\n  \"prime\": \"17\",\n  \"nVars\": 4,\n"));
+    assert!(json.contains(
+      "  \"constraints\": [\n    [{\"1\": \"1\"}, {\"1\": \"1\"}, {\"2\": \"1\"}],\n    [{\"1\": \
+       \"1\"}, {\"2\": \"1\"}, {\"3\": \"1\", \"0\": \"12\"}]\n  ],\n"
+    ));
+
+    let imported: R1CS = R1CS::from_json(&json).unwrap();
+    assert_eq!(imported.variables, ["one", "w1", "w2", "w3"]);
+    assert_eq!((imported.num_public, &imported.constraints), (r1cs.num_public, &r1cs.constraints));
+
+    let witness = [1, 3, 9, 15].map(PlutoScalarField::new);
+    assert_eq!(witness_to_json(&witness), "[\"1\", \"3\", \"9\", \"15\"]\n");
+    assert_eq!(witness_from_json::<PlutoScalarField>(&witness_to_json(&witness)).unwrap(), witness);
+  }
+
+  #[test]
+  fn matrices() {
+    let r1cs: R1CS = R1CS::new(&["x public", "y <== x * x", "z === y + 2"]).unwrap();
+    let one = PlutoScalarField::ONE;
+    let [a, b, c] = r1cs.matrices();
+    assert_eq!(a, [vec![(1, one)], vec![(2, -one), (3, one), (0, -PlutoScalarField::new(2))]]);
+    assert_eq!(b, [vec![(1, one)], vec![(0, one)]]);
+    assert_eq!(c, [vec![(2, one)], vec![]]);
+  }
+
+  /// `(w1 + 2 w2 + 3) * (w3 - w1) = 5 w2 + w3 + 10` and `w1 * w1 = w1`, as circom would write it
+  const EXTERNAL: &str = r#"{
+    "n8": 32, "prime": "17", "nVars": 4, "nOutputs": 0, "nPubInputs": 1, "nPrvInputs": 2,
+    "nLabels": 6, "nConstraints": 2, "useCustomGates": false,
+    "constraints": [
+      [{"1": "1", "2": "2", "0": "3"}, {"3": "1", "1": "16"}, {"2": "5", "3": "1", "0": "10"}],
+      [{"1": "1"}, {"1": "1"}, {"1": "1"}]
+    ],
+    "map": [0, 1, 2, 3, 5, 4], "customGates": [], "customGatesUses": []
+  }"#;
+
+  #[test]
+  fn json_round_trip_with_private_inputs() {
+    let imported: R1CS = R1CS::from_json(EXTERNAL).unwrap();
+    assert_eq!((imported.num_public, imported.num_private), (1, 2));
+
+    let exported = imported.to_json();
+    assert!(exported.contains("  \"nPubInputs\": 1,\n  \"nPrvInputs\": 2,\n"));
+    assert!(exported.starts_with("{\n  \"n8\": 8,\n  \"prime\": \"17\",\n  \"nVars\": 4,\n"));
+    assert_eq!(R1CS::from_json(&exported).unwrap(), imported);
+  }
+
+  #[test]
+  fn import_over_another_field() {
+    type F = PrimeField<257>;
+    let source = EXTERNAL.replace("\"17\"", "\"257\"").replace("\"16\"", "\"256\"");
+    let r1cs: R1CS<F> = R1CS::from_json(&source).unwrap();
+    assert_eq!(r1cs.constraints[0].b, [(3, F::ONE), (1, -F::ONE)]);
+    // (1 + 2 * 100 + 3) * (101 - 1) = 5 * 100 + 101 + 10 = 97 modulo 257
+    let witness = witness_from_json::<F>(r#"["1", "1", "100", "101"]"#).unwrap();
+    assert!(r1cs.is_satisfied(&witness));
+    assert_eq!(witness_to_json(&witness), "[\"1\", \"1\", \"100\", \"101\"]\n");
+
+    let exported = r1cs.to_json();
+    assert!(exported.starts_with("{\n  \"n8\": 8,\n  \"prime\": \"257\",\n"));
+    assert_eq!(R1CS::from_json(&exported).unwrap(), r1cs);
+    let error = R1CS::<PlutoScalarField>::from_json(&source).unwrap_err();
+    assert_eq!(error.to_string(), "invalid R1CS: prime 257 is not the order 17 of the field");
+  }
+
+  #[test]
+  fn evaluate_imported_circuit() {
+    let r1cs: R1CS = R1CS::from_json(EXTERNAL).unwrap();
+    let witness = witness_from_json(r#"["1", "1", "2", "4"]"#).unwrap();
+    assert!(r1cs.is_satisfied(&witness));
+
+    let program = Program::<8>::from_circuit(r1cs.circuit().unwrap()).unwrap();
+    let gates: Vec<String> = program.constraints().iter().map(ToString::to_string).collect();
+    assert_eq!(gates, [
+      "w1 public",
+      "$1 === w1 + 2 * w2 + 3",
+      "$2 === -w1 + w3",
+      "$3 === $1 * $2",
+      // the constant 10 of `C` is -7
+      "$4 === -5 * w2 - w3 + 7",
+      "0 === $3 + $4",
+      "0 === w1 * w1 - w1",
+    ]);
+    let generated = program.generate_witness(&r1cs.assignments(&witness)).unwrap();
+    assert_eq!(generated.public_inputs, [PlutoScalarField::ONE]);
+
+    // the private inputs `w2` and `w3` are not determined by the public input
+    let inputs = HashMap::from([(String::from("w1"), witness[1])]);
+    let error = program.generate_witness(&inputs).unwrap_err();
+    assert!(matches!(error, ProgramError::MissingAssignment(wire) if wire == "w2"));
+
+    let mut wrong = witness.clone();
+    wrong[1] = PlutoScalarField::new(2);
+    assert_eq!(r1cs.unsatisfied_constraint(&wrong), Some(0));
+    let error = program.generate_witness(&r1cs.assignments(&wrong)).unwrap_err();
+    assert!(matches!(error, ProgramError::UnsatisfiedConstraint(_, _)));
+  }
+
+  #[rstest]
+  #[case("{\"prime\": \"17\"", "invalid R1CS: expected ',' or '}' at byte 14")]
+  #[case(&EXTERNAL.replace("\"17\"", "\"7\""), "invalid R1CS: prime 7 is not the order 17 of the field")]
+  #[case(&EXTERNAL.replace("\"nVars\": 4", "\"nVars\": 3"), "invalid R1CS: no variable 3")]
+  #[case(&EXTERNAL.replace("\"nVars\": 4,", ""), "invalid R1CS: missing nVars")]
+  #[case(&EXTERNAL.replace("\"16\"", "\"x\""), "invalid R1CS: invalid coefficient \"x\"")]
+  #[case(&EXTERNAL.replace("\"nPubInputs\": 1", "\"nPubInputs\": 4"), "invalid R1CS: 4 public inputs in 4 variables")]
+  #[case(&EXTERNAL.replace("\"nPrvInputs\": 2", "\"nPrvInputs\": 3"), "invalid R1CS: 3 private inputs in 4 variables")]
+  #[case(&EXTERNAL.replace("\"useCustomGates\": false", "\"useCustomGates\": true"), "invalid R1CS: custom gates are not supported")]
+  #[case(&EXTERNAL.replace("[{\"1\": \"1\"}, {\"1\": \"1\"}, {\"1\": \"1\"}]", "[]"), "invalid R1CS: constraint [] is not [A, B, C]")]
+  fn invalid_json(#[case] source: &str, #[case] expected: &str) {
+    assert_eq!(R1CS::<PlutoScalarField>::from_json(source).unwrap_err().to_string(), expected);
+  }
+
+  #[test]
+  fn missing_assignment() {
+    let r1cs: R1CS = R1CS::new(&["x public", "y <== x * x"]).unwrap();
+    let assignments = HashMap::from([(Some("x"), PlutoScalarField::new(2))]);
+    assert!(
+      matches!(r1cs.witness(&assignments), Err(ProgramError::MissingAssignment(var)) if var == "y")
+    );
+  }
+}
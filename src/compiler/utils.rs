@@ -1,13 +1,20 @@
 //! Utilities for parsing
 
-/// Checks whether a variable name is valid.
-/// - len > 0
-/// - chars are alphanumeric
-/// - 1st element is not a number
-pub(crate) fn is_valid_var_name(name: &str) -> bool {
-  !name.is_empty()
-    && name.chars().all(char::is_alphanumeric)
-    && !(48u8..=57u8).contains(&name.as_bytes()[0])
+use crate::Field;
+
+/// converts a coefficient of the DSL into a field element, reducing it modulo the order of the
+/// field and mapping negative coefficients to their additive inverses.
+pub(crate) fn coefficient<F: Field>(value: i128) -> F {
+  // `usize` may not hold an `i128`, so the absolute value is read in 32 bit limbs
+  let abs = value.unsigned_abs();
+  let abs = (0..4).rev().fold(F::ZERO, |acc, limb| {
+    acc * F::from(1usize << 32) + F::from((abs >> (32 * limb)) as u32 as usize)
+  });
+  if value < 0 {
+    -abs
+  } else {
+    abs
+  }
 }
 
 /// returns product key required for coefficient mapping in plonk's multiplication gate variable.
@@ -33,6 +40,16 @@ mod tests {
   use rstest::rstest;
 
   use super::*;
+  use crate::algebra::field::prime::PrimeField;
+
+  #[rstest]
+  #[case(-7, 65530)]
+  #[case(3000000000, 43825)]
+  #[case(-(1 << 100), 65521)]
+  #[case(i128::MAX, 32768)]
+  fn coefficients(#[case] value: i128, #[case] expected: usize) {
+    assert_eq!(coefficient::<PrimeField<65537>>(value), PrimeField::new(expected));
+  }
 
   #[rstest]
   #[case("a", "b", "a*b")]
@@ -44,14 +61,4 @@ mod tests {
   fn product_key(#[case] a: &str, #[case] b: &str, #[case] expected: &str) {
     assert_eq!(get_product_key(a, b), expected);
   }
-
-  #[rstest]
-  #[case("a", true)]
-  #[case("abcd", true)]
-  #[case("", false)]
-  #[case("1", false)]
-  #[case("1a", false)]
-  fn valid_var_name(#[case] var: &str, #[case] expected: bool) {
-    assert_eq!(is_valid_var_name(var), expected);
-  }
 }
@@ -0,0 +1,430 @@
+//! Witness generation: solves every wire of a [`Program`] from named public and private inputs.
+//!
+//! Inputs are read with [`parse_inputs`] from either:
+//! - `name = value` lines, with blank lines and `#` comments ignored:
+//!
+//!   ```text
+//!   # public
+//!   out = 13
+//!   # private
+//!   x = 2
+//!   ```
+//!
+//! - a flat JSON object: `{ "out": 13, "x": "2" }`.
+//!
+//! Values are integers, possibly negative or larger than the field, reduced modulo its order.
+//!
+//! Every public variable must be given. The other inputs are private: any variable of the circuit
+//! that the constraints don't determine, like the `x` whose cube is `out` above. Wires are then
+//! solved one at a time: a gate whose wires are all known but one that it constrains linearly gives
+//! the value of that wire, until no gate does. Once every wire is known, each gate is checked and
+//! the first one that doesn't hold is reported with its constraint and the values of its wires.
+//!
+//! Boolean and range gates and lookups don't determine any wire, so their wires must be solved by
+//! other constraints or given as inputs; they are only checked. [`Hint`]s give the bits of solved
+//! wires to wires that the constraints don't determine, like the bits of a bit decomposition.
+//!
+//! [`Hint`]: super::parser::Hint
+
+use std::{collections::HashMap, fmt::Display};
+
+use super::{errors::ProgramError, json::Json, parser::WireCoeffs, program::Program};
+use crate::{algebra::field::FiniteField, PlutoScalarField};
+
+/// Solved wires of a [`Program`], laid out for the PLONK prover.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Witness<const GROUP_ORDER: usize, F: FiniteField = PlutoScalarField> {
+  /// values of the public variables, in declaration order
+  pub public_inputs: Vec<F>,
+  /// value of every variable, including intermediate wires
+  pub assignments:   HashMap<String, F>,
+  /// execution trace columns `[a, b, c]`: values of the left, right and output wires of each
+  /// gate, in the row order of [`CommonPreprocessedInput`], with zeros in unused cells
+  ///
+  /// [`CommonPreprocessedInput`]: super::program::CommonPreprocessedInput
+  pub trace:         [[F; GROUP_ORDER]; 3],
+}
+
+/// Parses an integer into the field, reducing it modulo the order of the field.
+pub(crate) fn parse_value<F: FiniteField>(value: &str) -> Option<F> {
+  let (negative, digits) = match value.strip_prefix('-') {
+    Some(digits) => (true, digits),
+    None => (false, value),
+  };
+  if digits.is_empty() || !digits.bytes().all(|digit| digit.is_ascii_digit()) {
+    return None;
+  }
+  let value = digits
+    .bytes()
+    .fold(F::ZERO, |value, digit| value * F::from(10) + F::from((digit - b'0') as usize));
+  Some(if negative { -value } else { value })
+}
+
+/// Parses circuit inputs from `name = value` lines or a flat JSON object, see the
+/// [module documentation](self).
+pub fn parse_inputs<F: FiniteField>(
+  source: &str,
+) -> Result<HashMap<String, F>, ProgramError<'static, F>> {
+  let source = source.trim();
+  let json;
+  let entries: Vec<(&str, &str)> = if source.starts_with('{') {
+    json = Json::parse(source).map_err(ProgramError::InvalidInput)?;
+    let entries = json.as_object().ok_or_else(|| ProgramError::InvalidInput(json.to_string()))?;
+    entries
+      .iter()
+      .map(|(name, value)| {
+        let invalid = || ProgramError::InvalidInput(format!("{} = {}", name, value));
+        Ok((name.as_str(), value.as_number().ok_or_else(invalid)?))
+      })
+      .collect::<Result<_, _>>()?
+  } else {
+    source
+      .lines()
+      .map(|line| line.split('#').next().unwrap_or_default().trim())
+      .filter(|line| !line.is_empty())
+      .map(|line| {
+        let (name, value) =
+          line.split_once('=').ok_or_else(|| ProgramError::InvalidInput(line.to_string()))?;
+        Ok((name.trim(), value.trim()))
+      })
+      .collect::<Result<_, _>>()?
+  };
+
+  let mut inputs = HashMap::new();
+  for (name, value) in entries {
+    // names of internal wires start with `$`, other unknown names are reported by
+    // `generate_witness`
+    let valid_name = !name.is_empty() && !name.starts_with('$');
+    let parsed = match parse_value(value) {
+      Some(parsed) if valid_name => parsed,
+      _ => return Err(ProgramError::InvalidInput(format!("{} = {}", name, value))),
+    };
+    if inputs.insert(name.to_string(), parsed).is_some() {
+      return Err(ProgramError::InvalidInput(format!("{} is given twice", name)));
+    }
+  }
+  Ok(inputs)
+}
+
+/// Variables of the wires of a constraint.
+fn wires<'c>(constraint: &'c WireCoeffs) -> impl Iterator<Item = &'c str> {
+  constraint.wires.iter().flatten().map(|wire| wire.as_ref())
+}
+
+/// Value of `q_L a + q_R b + q_M ab + q_O c + q_C` for a constraint, where `value` gives the
+/// value of each wire.
+fn gate_value<F: FiniteField>(constraint: &WireCoeffs, value: impl Fn(usize) -> F) -> F {
+  let gate = constraint.gate::<F>();
+  let (a, b, c) = (value(0), value(1), value(2));
+  gate.l * a + gate.r * b + gate.m * a * b + gate.o * c + gate.c
+}
+
+/// Solves the only unknown wire of a constraint, if the constraint is linear in it.
+fn solve<F: FiniteField>(
+  constraint: &WireCoeffs,
+  unknown: &str,
+  assignments: &HashMap<String, F>,
+) -> Option<F> {
+  let is_public = constraint.coeffs.get("$public") == Some(&1);
+  let wires: Vec<Option<&str>> = constraint.wires.iter().map(|wire| wire.as_deref()).collect();
+  let is_square = wires[0] == Some(unknown) && wires[1] == Some(unknown);
+  if is_public || (is_square && constraint.gate::<F>().m != F::ZERO) {
+    return None;
+  }
+
+  // the gate is `slope * unknown + at_zero`
+  let at = |x: F| {
+    gate_value(constraint, |i| match wires[i] {
+      Some(wire) if wire == unknown => x,
+      Some(wire) => assignments[wire],
+      None => F::ZERO,
+    })
+  };
+  let at_zero = at(F::ZERO);
+  let slope = at(F::ONE) - at_zero;
+  slope.inverse().map(|inverse| -at_zero * inverse)
+}
+
+impl<'a, const GROUP_ORDER: usize, F: FiniteField + Display> Program<'a, GROUP_ORDER, F>
+where usize: From<F>
+{
+  /// Solves every wire from named public and private `inputs`, for example read with
+  /// [`parse_inputs`], and lays out the execution trace.
+  ///
+  /// Fails if a public variable or a wire that the constraints don't determine has no value, if
+  /// an input isn't a variable of the circuit, or with the first constraint that doesn't hold.
+  ///
+  /// Assumes: group_order >= constraints.len()
+  pub fn generate_witness(
+    &self,
+    inputs: &HashMap<String, F>,
+  ) -> Result<Witness<GROUP_ORDER, F>, ProgramError<'a, F>> {
+    let constraints = self.constraints();
+    let variables: Vec<&str> = constraints.iter().flat_map(wires).collect();
+    let is_variable = |name: &String| !name.starts_with('$') && variables.contains(&name.as_str());
+    if let Some(name) = inputs.keys().find(|name| !is_variable(name)) {
+      return Err(ProgramError::UnknownInput(name.clone()));
+    }
+
+    let public_inputs = self
+      .public_assignments()?
+      .into_iter()
+      .map(|name| inputs.get(&name).copied().ok_or(ProgramError::MissingAssignment(name)))
+      .collect::<Result<_, _>>()?;
+
+    // solve wires one at a time until no constraint has a single unknown wire left
+    let mut assignments = inputs.clone();
+    let mut solved = true;
+    while solved {
+      solved = false;
+      for constraint in constraints {
+        let mut unknowns: Vec<&str> =
+          wires(constraint).filter(|wire| !assignments.contains_key(*wire)).collect();
+        unknowns.sort();
+        unknowns.dedup();
+        if let [unknown] = unknowns[..] {
+          if let Some(value) = solve(constraint, unknown, &assignments) {
+            assignments.insert(unknown.to_string(), value);
+            solved = true;
+          }
+        }
+      }
+      for hint in self.hints() {
+        if let (Some(&input), false) =
+          (assignments.get(hint.input.as_ref()), assignments.contains_key(hint.output.as_ref()))
+        {
+          let bit = (usize::from(input) >> hint.bit) & 1;
+          assignments.insert(hint.output.to_string(), F::from(bit));
+          solved = true;
+        }
+      }
+    }
+    if let Some(wire) = variables.iter().find(|wire| !assignments.contains_key(**wire)) {
+      return Err(ProgramError::MissingAssignment(wire.to_string()));
+    }
+
+    let mut trace = [[F::ZERO; GROUP_ORDER]; 3];
+    for (row, constraint) in constraints.iter().enumerate() {
+      let value = |column: usize| {
+        constraint.wires[column].as_deref().map_or(F::ZERO, |wire| assignments[wire])
+      };
+      for (column, values) in trace.iter_mut().enumerate() {
+        values[row] = value(column);
+      }
+
+      let is_public = constraint.coeffs.get("$public") == Some(&1);
+      let a = value(0);
+      let (holds, description) = match constraint.custom_gate() {
+        Some("$bool") => (a * (a - F::ONE) == F::ZERO, constraint.to_string()),
+        Some("$range") =>
+          ((0..4).map(|limb| a - F::from(limb)).product::<F>() == F::ZERO, constraint.to_string()),
+        Some(_) => {
+          let table = &self.tables()[constraint.coeffs["$lookup"] as usize - 1];
+          let values = [value(0), value(1), value(2)];
+          let holds = table.rows().into_iter().any(|row| row.map(F::from) == values);
+          (holds, format!("{} in {}", constraint.lookup_values(), table.name))
+        },
+        None => (is_public || gate_value(constraint, value) == F::ZERO, constraint.to_string()),
+      };
+      if !holds {
+        let mut names: Vec<&str> = wires(constraint).collect();
+        names.sort();
+        names.dedup();
+        let values: Vec<String> =
+          names.iter().map(|name| format!("{} = {}", name, assignments[*name])).collect();
+        let constraint = format!("{} with {}", description, values.join(", "));
+        return Err(ProgramError::UnsatisfiedConstraint(row, constraint));
+      }
+    }
+
+    Ok(Witness { public_inputs, assignments, trace })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use rstest::rstest;
+
+  use super::*;
+  use crate::{algebra::field::prime::PrimeField, hashes::poseidon::Poseidon, Field};
+
+  fn inputs(values: &[(&str, i32)]) -> HashMap<String, PlutoScalarField> {
+    values.iter().map(|&(name, value)| (name.to_string(), PlutoScalarField::from(value))).collect()
+  }
+
+  #[rstest]
+  #[case("x = 3\ny=-1\n", &[("x", 3), ("y", -1)])]
+  #[case("# public\nout = 13 # comment\n\n  # private\nx = 2", &[("out", 13), ("x", 2)])]
+  #[case("{ \"out\": 13, \"x\": \"2\" }", &[("out", 13), ("x", 2)])]
+  #[case("{\n  \"a\": -4\n}\n", &[("a", -4)])]
+  #[case("{}", &[])]
+  #[case("x_1 = 3", &[("x_1", 3)])]
+  #[case("{ \"x_1\": 3 }", &[("x_1", 3)])]
+  #[case("big = 1000000000000000000000", &[("big", 0)])]
+  fn input_files(#[case] source: &str, #[case] expected: &[(&str, i32)]) {
+    let mut expected = inputs(expected);
+    if let Some(big) = expected.get_mut("big") {
+      // 10^21 = (10^3)^7 = (-3)^7 mod 17
+      *big = PlutoScalarField::from(-3).pow(7);
+    }
+    assert_eq!(parse_inputs(source).unwrap(), expected);
+  }
+
+  #[rstest]
+  #[case("x 3", "x 3")]
+  #[case("x = 3.5", "x = 3.5")]
+  #[case("x = ", "x = ")]
+  #[case("$1 = 3", "$1 = 3")]
+  #[case("x = 1\nx = 2", "x is given twice")]
+  #[case(" = 3", " = 3")]
+  #[case("{ \"x\": 1, y: 2 }", "expected a string at byte 10")]
+  #[case("{ \"x\": 1", "expected ',' or '}' at byte 8")]
+  #[case("{\"x\": 3,}", "expected a string at byte 8")]
+  #[case("{\"y\": \"4,5\"}", "y = 4,5")]
+  #[case("{\"y\": [4]}", "y = [4]")]
+  #[case("{\"$1\": 3}", "$1 = 3")]
+  fn invalid_input_files(#[case] source: &str, #[case] entry: &str) {
+    assert!(
+      matches!(parse_inputs::<PlutoScalarField>(source), Err(ProgramError::InvalidInput(e)) if e == entry)
+    );
+  }
+
+  #[test]
+  fn public_inputs_only() {
+    let constraints = &["a public", "d === 9", "b <== a * a + 5", "c <== -2 * b - a * b"];
+    let program = Program::<4>::new(constraints).unwrap();
+    let witness = program.generate_witness(&inputs(&[("a", 2)])).unwrap();
+    assert_eq!(witness.public_inputs, [PlutoScalarField::new(2)]);
+    assert_eq!(witness.assignments, inputs(&[("a", 2), ("d", 9), ("b", 9), ("c", -36)]));
+
+    let assignments =
+      program.evaluate_circuit(HashMap::from([(Some("a"), PlutoScalarField::new(2))]));
+    assert_eq!(witness.trace, program.execution_trace(&assignments.unwrap()).unwrap());
+  }
+
+  #[test]
+  fn private_inputs() {
+    let program = Program::<4>::new(&["out public", "out <== x * x * x + 5"]).unwrap();
+    let witness = program.generate_witness(&inputs(&[("out", 13), ("x", 2)])).unwrap();
+    let [out, x, x2] = [13, 2, 4].map(PlutoScalarField::new);
+    let zero = PlutoScalarField::ZERO;
+    assert_eq!(witness.public_inputs, [out]);
+    assert_eq!(witness.trace, [[out, x, x2, zero], [zero, x, x, zero], [zero, x2, out, zero]]);
+  }
+
+  #[test]
+  fn wires_are_solved_backwards() {
+    // `x` is only determined by the last constraint, from `y`
+    let program = Program::<4>::new(&["y public", "z <== 2 * x", "y <== x + 3"]).unwrap();
+    let witness = program.generate_witness(&inputs(&[("y", 10)])).unwrap();
+    assert_eq!(witness.assignments, inputs(&[("y", 10), ("x", 7), ("z", 14)]));
+  }
+
+  #[rstest]
+  #[case(&[], ProgramError::MissingAssignment(String::from("x")))]
+  #[case(&[("x", 2)], ProgramError::MissingAssignment(String::from("w")))]
+  #[case(&[("x", 4), ("w", 2), ("v", 1)], ProgramError::UnknownInput(String::from("v")))]
+  #[case(&[("x", 4), ("w", 2), ("$1", 1)], ProgramError::UnknownInput(String::from("$1")))]
+  fn missing_and_unknown_inputs(#[case] values: &[(&str, i32)], #[case] expected: ProgramError) {
+    // `w` is a square root of `x`, which the constraint can't solve for
+    let program = Program::<4>::new(&["x public", "x === w * w"]).unwrap();
+    let error = program.generate_witness(&inputs(values)).unwrap_err();
+    assert_eq!(error.to_string(), expected.to_string());
+  }
+
+  #[test]
+  fn unsatisfied_constraint() {
+    let program = Program::<4>::new(&["x public", "y <== x + 1", "x === w * w"]).unwrap();
+    assert!(program.generate_witness(&inputs(&[("x", 4), ("w", -2)])).is_ok());
+
+    let error = program.generate_witness(&inputs(&[("x", 5), ("w", 2)])).unwrap_err();
+    assert_eq!(error.to_string(), "constraint 2 is not satisfied: x === w * w with w = 2, x = 5");
+  }
+
+  #[rstest]
+  #[case(&[("x", 1), ("y", 3)], None)]
+  #[case(&[("x", 0), ("y", 0)], None)]
+  #[case(&[("x", 2), ("y", 3)], Some("constraint 1 is not satisfied: x bool with x = 2"))]
+  #[case(&[("x", 1), ("y", 4)], Some("constraint 2 is not satisfied: y range with y = 4"))]
+  #[case(&[("x", 1), ("y", -1)], Some("constraint 2 is not satisfied: y range with y = 16"))]
+  fn custom_gates(#[case] values: &[(&str, i32)], #[case] expected: Option<&str>) {
+    let program = Program::<4>::new(&["x public", "x bool", "y range"]).unwrap();
+    let result = program.generate_witness(&inputs(values));
+    assert_eq!(result.err().map(|error| error.to_string()).as_deref(), expected);
+  }
+
+  #[rstest]
+  #[case(&[("a", 1), ("b", 1), ("c", 0)], None)]
+  #[case(&[("a", 1), ("b", 0), ("c", 0)], Some("constraint 1 is not satisfied: (a, b, c) in xor2 with a = 1, b = 0, c = 0"))]
+  #[case(&[("a", 4), ("b", 0), ("c", 4)], Some("constraint 0 is not satisfied: a in bits with a = 4"))]
+  fn lookups(#[case] values: &[(&str, i32)], #[case] expected: Option<&str>) {
+    let source = "table bits range 4\ntable xor2 xor 2\na in bits\n(a, b, c) in xor2";
+    let program = Program::<8>::from_source(source).unwrap();
+    let result = program.generate_witness(&inputs(values));
+    assert_eq!(result.err().map(|error| error.to_string()).as_deref(), expected);
+  }
+
+  /// Field of more than 32 elements with roots of unity of order 256, for the standard gadgets.
+  type F257 = PrimeField<257>;
+
+  /// Solves `source` over [`F257`] from integer inputs.
+  fn solve_gadgets<'a>(
+    source: &'a str,
+    values: &[(&str, usize)],
+  ) -> Result<HashMap<String, F257>, ProgramError<'a, F257>> {
+    let program = Program::<256, F257>::from_source(source).unwrap();
+    let inputs = values.iter().map(|&(name, value)| (name.to_string(), F257::from(value)));
+    program.generate_witness(&inputs.collect()).map(|witness| witness.assignments)
+  }
+
+  #[rstest]
+  #[case("(b0, b1, b2, b3) <== bits4(x)", &[("x", 11)], Some(&[("b0", 1), ("b1", 1), ("b2", 0), ("b3", 1)][..]))]
+  #[case("(b0, b1, b2, b3) <== bits4(x)", &[("x", 16)], None)]
+  #[case("range8(x)", &[("x", 255)], Some(&[][..]))]
+  #[case("range8(x)", &[("x", 256)], None)]
+  #[case("y <== lt4(a, b)", &[("a", 3), ("b", 5)], Some(&[("y", 1)][..]))]
+  #[case("y <== lt4(a, b)", &[("a", 5), ("b", 3)], Some(&[("y", 0)][..]))]
+  #[case("y <== lt4(a, b)", &[("a", 4), ("b", 4)], Some(&[("y", 0)][..]))]
+  #[case("y <== lt4(a, b)", &[("a", 15), ("b", 0)], Some(&[("y", 0)][..]))]
+  #[case("y <== lt4(a, b)", &[("a", 16), ("b", 3)], None)]
+  #[case("y <== select(c, a, b)", &[("c", 1), ("a", 7), ("b", 9)], Some(&[("y", 7)][..]))]
+  #[case("y <== select(c, a, b)", &[("c", 0), ("a", 7), ("b", 9)], Some(&[("y", 9)][..]))]
+  #[case("y <== select(c, a, b)", &[("c", 2), ("a", 7), ("b", 9)], None)]
+  fn standard_gadgets(
+    #[case] source: &str,
+    #[case] values: &[(&str, usize)],
+    #[case] expected: Option<&[(&str, usize)]>,
+  ) {
+    let result = solve_gadgets(source, values);
+    match expected {
+      Some(expected) =>
+        for &(name, value) in expected {
+          assert_eq!(result.as_ref().unwrap()[name], F257::from(value));
+        },
+      None => assert!(matches!(result, Err(ProgramError::UnsatisfiedConstraint(..)))),
+    }
+  }
+
+  #[test]
+  fn poseidon_and_merkle_gadgets() {
+    let mds = vec![vec![2, 1, 1], vec![1, 2, 1], vec![1, 1, 2]];
+    let mds = mds.into_iter().map(|row| row.into_iter().map(F257::from).collect()).collect();
+    let round_constants = (1..=21).map(F257::from).collect();
+    let poseidon = Poseidon::<F257>::new(3, 5, 3, 4, round_constants, mds);
+    let hash = |l: usize, r: usize| poseidon.clone().hash(vec![F257::from(l), F257::from(r)]);
+
+    let assignments = solve_gadgets("h <== hash(l, r)", &[("l", 3), ("r", 200)]).unwrap();
+    assert_eq!(assignments["h"], hash(3, 200));
+
+    // the leaf 42 is the right child of 7, whose parent is the left child of 99
+    let root = hash(usize::from(hash(7, 42)), 99);
+    let source = "root public\nroot <== merkleStep(merkleStep(leaf, s0, b0), s1, b1)";
+    let path = [("leaf", 42), ("s0", 7), ("b0", 1), ("s1", 99), ("b1", 0)];
+    let valid = [&[("root", usize::from(root))], &path[..]].concat();
+    assert!(solve_gadgets(source, &valid).is_ok());
+
+    let wrong_bit = [&[("root", usize::from(root))], &path[..4], &[("b1", 1)]].concat();
+    let error = solve_gadgets(source, &wrong_bit).unwrap_err();
+    assert!(matches!(error, ProgramError::UnsatisfiedConstraint(..)));
+  }
+}
@@ -0,0 +1,496 @@
+//! The tower of fields used by the BN254 curve and its pairing.
+//!
+//! The base field [`Fq`] and scalar field [`Fr`] are 254-bit prime fields represented in
+//! Montgomery form using [`crypto_bigint`]. The extensions needed for the pairing are built as a
+//! tower so that every multiplication reduces to a handful of base field operations:
+//! - `Fq2  = Fq[u] / (u^2 + 1)`
+//! - `Fq6  = Fq2[v] / (v^3 - ξ)` where `ξ = 9 + u` is neither a square nor a cube in `Fq2`
+//! - `Fq12 = Fq6[w] / (w^2 - v)`
+//!
+//! so that `w^6 = ξ`. The sextic twist used for `G2` lives over [`Fq2`] and the pairing output
+//! lives in [`Fq12`].
+use std::{
+  fmt::Debug,
+  hash::{Hash, Hasher},
+  iter::{Product, Sum},
+  ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, Sub, SubAssign},
+};
+
+use crypto_bigint::{
+  impl_modulus,
+  modular::{ConstMontyForm, ConstMontyParams},
+  Uint, U256, U384, U64,
+};
+
+use crate::{
+  algebra::field::Field,
+  curve::{hash_to_curve::HashToField, msm::ScalarBits},
+};
+
+// `Q`: Prime number defining the base field
+impl_modulus!(Q, U256, "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47");
+
+// `R`: Prime number defining the scalar field, i.e. the order of `G1` and `G2`
+impl_modulus!(R, U256, "30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001");
+
+/// Type representing a 254-bit element of the BN254 base field.
+pub type Fq = ConstMontyForm<Q, { U256::LIMBS }>;
+
+/// Type representing a 254-bit element of the BN254 scalar field.
+///
+/// Unlike [`Fq`] it wraps the Montgomery form in a struct of its own, so that it can implement
+/// [`Field`] and be the scalar field of the [`Bn254`](super::Bn254) pairing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Fr(ConstMontyForm<R, { U256::LIMBS }>);
+
+/// `R` minus 2. Used for calculation of the inverse.
+const R_2: U256 =
+  U256::from_be_hex("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593efffffff");
+
+/// `Q` minus 2. Used for calculation of the inverse.
+const Q_2: U256 =
+  U256::from_be_hex("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45");
+
+/// Minimal set of operations needed from the coordinate fields of the BN254 curve and its twist,
+/// as well as from the pairing target field.
+pub trait BnField:
+  Copy
+  + Debug
+  + Eq
+  + Add<Output = Self>
+  + Sub<Output = Self>
+  + Mul<Output = Self>
+  + Neg<Output = Self>
+{
+  /// The additive identity element.
+  const ZERO: Self;
+  /// The multiplicative identity element.
+  const ONE: Self;
+
+  /// Gets the multiplicative inverse of the field element (if it exists).
+  fn inverse(&self) -> Option<Self>;
+
+  /// Squares the field element.
+  fn square(&self) -> Self { *self * *self }
+
+  /// Raises the element to the power given by a big integer using square-and-multiply.
+  fn pow<const L: usize>(&self, exp: &Uint<L>) -> Self {
+    let mut res = Self::ONE;
+    for i in (0..exp.bits_vartime()).rev() {
+      res = res.square();
+      if exp.bit_vartime(i) {
+        res = res * *self;
+      }
+    }
+    res
+  }
+}
+
+impl BnField for Fq {
+  const ONE: Self = ConstMontyForm::ONE;
+  const ZERO: Self = ConstMontyForm::ZERO;
+
+  /// Inverse of a element in the base field calculated as: x^(-1) = x^(Q-2) (mod Q).
+  fn inverse(&self) -> Option<Self> {
+    if *self == <Self as BnField>::ZERO {
+      return None;
+    }
+    Some(ConstMontyForm::pow(self, &Q_2))
+  }
+
+  fn square(&self) -> Self { ConstMontyForm::square(self) }
+}
+
+/// Reduces 48 uniform bytes, 128 bits more than `Q`, so that the result is close to uniform.
+impl HashToField for Fq {
+  const DEGREE: usize = 1;
+  const L: usize = 48;
+
+  fn from_uniform_bytes(bytes: &[u8]) -> Self {
+    let modulus = Q::MODULUS.get().resize::<{ U384::LIMBS }>().to_nz().unwrap();
+    let (_, value) = U384::from_be_slice(bytes).div_rem(&modulus);
+    Fq::new(&value.resize())
+  }
+}
+
+/// Creates an [`Fq`] element from a big endian hex string.
+pub(crate) const fn fq(hex: &str) -> Fq { Fq::new(&U256::from_be_hex(hex)) }
+
+/// Element `c0 + c1 * u` of the quadratic extension `Fq2 = Fq[u] / (u^2 + 1)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fq2 {
+  /// Constant coefficient.
+  pub c0: Fq,
+  /// Coefficient of `u`.
+  pub c1: Fq,
+}
+
+/// Element `c0 + c1 * v + c2 * v^2` of the cubic extension `Fq6 = Fq2[v] / (v^3 - ξ)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fq6 {
+  /// Constant coefficient.
+  pub c0: Fq2,
+  /// Coefficient of `v`.
+  pub c1: Fq2,
+  /// Coefficient of `v^2`.
+  pub c2: Fq2,
+}
+
+/// Element `c0 + c1 * w` of the quadratic extension `Fq12 = Fq6[w] / (w^2 - v)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fq12 {
+  /// Constant coefficient.
+  pub c0: Fq6,
+  /// Coefficient of `w`.
+  pub c1: Fq6,
+}
+
+/// The non-residue `ξ = 9 + u` used to build [`Fq6`] and the sextic twist.
+pub const XI: Fq2 = Fq2::new(Fq::new(&U256::from_u8(9)), ConstMontyForm::ONE);
+
+/// Constants `ξ^(i * (q - 1) / 6)` for `i = 0..6`, used to compute the Frobenius map `x -> x^q` on
+/// [`Fq6`] and [`Fq12`] since `w^(q - 1) = ξ^((q - 1) / 6)`.
+pub const FROBENIUS_COEFFS: [Fq2; 6] = [
+  Fq2::new(ConstMontyForm::ONE, ConstMontyForm::ZERO),
+  Fq2::new(
+    fq("1284b71c2865a7dfe8b99fdd76e68b605c521e08292f2176d60b35dadcc9e470"),
+    fq("246996f3b4fae7e6a6327cfe12150b8e747992778eeec7e5ca5cf05f80f362ac"),
+  ),
+  Fq2::new(
+    fq("2fb347984f7911f74c0bec3cf559b143b78cc310c2c3330c99e39557176f553d"),
+    fq("16c9e55061ebae204ba4cc8bd75a079432ae2a1d0b7c9dce1665d51c640fcba2"),
+  ),
+  Fq2::new(
+    fq("063cf305489af5dcdc5ec698b6e2f9b9dbaae0eda9c95998dc54014671a0135a"),
+    fq("07c03cbcac41049a0704b5a7ec796f2b21807dc98fa25bd282d37f632623b0e3"),
+  ),
+  Fq2::new(
+    fq("05b54f5e64eea80180f3c0b75a181e84d33365f7be94ec72848a1f55921ea762"),
+    fq("2c145edbe7fd8aee9f3a80b03b0b1c923685d2ea1bdec763c13b4711cd2b8126"),
+  ),
+  Fq2::new(
+    fq("0183c1e74f798649e93a3661a4353ff4425c459b55aa1bd32ea2c810eab7692f"),
+    fq("12acf2ca76fd0675a27fb246c7729f7db080cb99678e2ac024c6b8ee6e0c2c4b"),
+  ),
+];
+
+///////////////////////////////////////////////////////////////////////////////////////////////
+/// ## Fq2
+
+impl Fq2 {
+  /// Creates the element `c0 + c1 * u`.
+  pub const fn new(c0: Fq, c1: Fq) -> Self { Self { c0, c1 } }
+
+  /// Conjugation `c0 - c1 * u`, which is also the Frobenius map `x -> x^q`.
+  pub fn conjugate(&self) -> Self { Self::new(self.c0, -self.c1) }
+
+  /// Multiplies by the non-residue `ξ = 9 + u`.
+  pub fn mul_by_nonresidue(&self) -> Self {
+    let nine = Fq::new(&U256::from_u8(9));
+    Self::new(nine * self.c0 - self.c1, self.c0 + nine * self.c1)
+  }
+
+  /// Computes `x^(q^power)`.
+  pub fn frobenius_map(&self, power: usize) -> Self {
+    if power % 2 == 1 {
+      self.conjugate()
+    } else {
+      *self
+    }
+  }
+
+  /// Multiplies both coefficients by a base field element.
+  pub fn scale(&self, k: Fq) -> Self { Self::new(self.c0 * k, self.c1 * k) }
+}
+
+impl BnField for Fq2 {
+  const ONE: Self = Self::new(ConstMontyForm::ONE, ConstMontyForm::ZERO);
+  const ZERO: Self = Self::new(ConstMontyForm::ZERO, ConstMontyForm::ZERO);
+
+  /// `1 / (c0 + c1 * u) = (c0 - c1 * u) / (c0^2 + c1^2)`
+  fn inverse(&self) -> Option<Self> {
+    let norm = self.c0.square() + self.c1.square();
+    BnField::inverse(&norm).map(|norm_inv| self.conjugate().scale(norm_inv))
+  }
+}
+
+impl Add for Fq2 {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self { Self::new(self.c0 + rhs.c0, self.c1 + rhs.c1) }
+}
+
+impl Sub for Fq2 {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self { Self::new(self.c0 - rhs.c0, self.c1 - rhs.c1) }
+}
+
+impl Neg for Fq2 {
+  type Output = Self;
+
+  fn neg(self) -> Self { Self::new(-self.c0, -self.c1) }
+}
+
+impl Mul for Fq2 {
+  type Output = Self;
+
+  /// `(a0 + a1 * u)(b0 + b1 * u) = (a0b0 - a1b1) + (a0b1 + a1b0) * u` since `u^2 = -1`.
+  fn mul(self, rhs: Self) -> Self {
+    Self::new(self.c0 * rhs.c0 - self.c1 * rhs.c1, self.c0 * rhs.c1 + self.c1 * rhs.c0)
+  }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////
+/// ## Fq6
+
+impl Fq6 {
+  /// Creates the element `c0 + c1 * v + c2 * v^2`.
+  pub const fn new(c0: Fq2, c1: Fq2, c2: Fq2) -> Self { Self { c0, c1, c2 } }
+
+  /// Multiplies by `v`, using `v^3 = ξ`.
+  pub fn mul_by_nonresidue(&self) -> Self {
+    Self::new(self.c2.mul_by_nonresidue(), self.c0, self.c1)
+  }
+
+  /// Multiplies every coefficient by an [`Fq2`] element.
+  pub fn scale(&self, k: Fq2) -> Self { Self::new(self.c0 * k, self.c1 * k, self.c2 * k) }
+
+  /// Computes `x^(q^power)`. Each coefficient is conjugated and `v^i` picks up the factor
+  /// `v^(i * (q - 1)) = ξ^(i * (q - 1) / 3)`.
+  pub fn frobenius_map(&self, power: usize) -> Self {
+    let mut res = *self;
+    for _ in 0..power {
+      res = Self::new(
+        res.c0.conjugate(),
+        res.c1.conjugate() * FROBENIUS_COEFFS[2],
+        res.c2.conjugate() * FROBENIUS_COEFFS[4],
+      );
+    }
+    res
+  }
+}
+
+impl BnField for Fq6 {
+  const ONE: Self = Self::new(Fq2::ONE, Fq2::ZERO, Fq2::ZERO);
+  const ZERO: Self = Self::new(Fq2::ZERO, Fq2::ZERO, Fq2::ZERO);
+
+  /// Inverse via the adjugate of the multiplication-by-`x` matrix, see Algorithm 17 of
+  /// [Guide to Pairing-Based Cryptography](https://eprint.iacr.org/2010/354.pdf).
+  fn inverse(&self) -> Option<Self> {
+    let t0 = self.c0.square() - (self.c1 * self.c2).mul_by_nonresidue();
+    let t1 = self.c2.square().mul_by_nonresidue() - self.c0 * self.c1;
+    let t2 = self.c1.square() - self.c0 * self.c2;
+    let den = self.c0 * t0 + (self.c2 * t1 + self.c1 * t2).mul_by_nonresidue();
+    den.inverse().map(|den_inv| Self::new(t0 * den_inv, t1 * den_inv, t2 * den_inv))
+  }
+}
+
+impl Add for Fq6 {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self {
+    Self::new(self.c0 + rhs.c0, self.c1 + rhs.c1, self.c2 + rhs.c2)
+  }
+}
+
+impl Sub for Fq6 {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self {
+    Self::new(self.c0 - rhs.c0, self.c1 - rhs.c1, self.c2 - rhs.c2)
+  }
+}
+
+impl Neg for Fq6 {
+  type Output = Self;
+
+  fn neg(self) -> Self { Self::new(-self.c0, -self.c1, -self.c2) }
+}
+
+impl Mul for Fq6 {
+  type Output = Self;
+
+  /// Schoolbook multiplication followed by reduction with `v^3 = ξ`.
+  fn mul(self, rhs: Self) -> Self {
+    let (a0, a1, a2) = (self.c0, self.c1, self.c2);
+    let (b0, b1, b2) = (rhs.c0, rhs.c1, rhs.c2);
+    Self::new(
+      a0 * b0 + (a1 * b2 + a2 * b1).mul_by_nonresidue(),
+      a0 * b1 + a1 * b0 + (a2 * b2).mul_by_nonresidue(),
+      a0 * b2 + a1 * b1 + a2 * b0,
+    )
+  }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////
+/// ## Fq12
+
+impl Fq12 {
+  /// Creates the element `c0 + c1 * w`.
+  pub const fn new(c0: Fq6, c1: Fq6) -> Self { Self { c0, c1 } }
+
+  /// Conjugation `c0 - c1 * w`, which equals `x^(q^6)`. For elements of the cyclotomic subgroup
+  /// (e.g. after the easy part of the final exponentiation) this is the inverse.
+  pub fn conjugate(&self) -> Self { Self::new(self.c0, -self.c1) }
+
+  /// Computes `x^(q^power)`. The coefficient of `w` picks up the extra factor
+  /// `w^(q - 1) = ξ^((q - 1) / 6)`.
+  pub fn frobenius_map(&self, power: usize) -> Self {
+    let mut res = *self;
+    for _ in 0..power {
+      res = Self::new(res.c0.frobenius_map(1), res.c1.frobenius_map(1).scale(FROBENIUS_COEFFS[1]));
+    }
+    res
+  }
+}
+
+impl BnField for Fq12 {
+  const ONE: Self = Self::new(Fq6::ONE, Fq6::ZERO);
+  const ZERO: Self = Self::new(Fq6::ZERO, Fq6::ZERO);
+
+  /// `1 / (c0 + c1 * w) = (c0 - c1 * w) / (c0^2 - c1^2 * v)`
+  fn inverse(&self) -> Option<Self> {
+    let den = self.c0.square() - self.c1.square().mul_by_nonresidue();
+    den.inverse().map(|den_inv| Self::new(self.c0 * den_inv, -self.c1 * den_inv))
+  }
+}
+
+impl Add for Fq12 {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self { Self::new(self.c0 + rhs.c0, self.c1 + rhs.c1) }
+}
+
+impl Sub for Fq12 {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self { Self::new(self.c0 - rhs.c0, self.c1 - rhs.c1) }
+}
+
+impl Neg for Fq12 {
+  type Output = Self;
+
+  fn neg(self) -> Self { Self::new(-self.c0, -self.c1) }
+}
+
+impl Mul for Fq12 {
+  type Output = Self;
+
+  /// `(a0 + a1 * w)(b0 + b1 * w) = (a0b0 + a1b1 * v) + (a0b1 + a1b0) * w` since `w^2 = v`.
+  fn mul(self, rhs: Self) -> Self {
+    Self::new(
+      self.c0 * rhs.c0 + (self.c1 * rhs.c1).mul_by_nonresidue(),
+      self.c0 * rhs.c1 + self.c1 * rhs.c0,
+    )
+  }
+}
+
+macro_rules! impl_assign_ops {
+  ($($t:ty),*) => {
+    $(
+      impl AddAssign for $t {
+        fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; }
+      }
+
+      impl SubAssign for $t {
+        fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs; }
+      }
+
+      impl MulAssign for $t {
+        fn mul_assign(&mut self, rhs: Self) { *self = *self * rhs; }
+      }
+    )*
+  };
+}
+
+impl_assign_ops!(Fq2, Fq6, Fq12, Fr);
+
+///////////////////////////////////////////////////////////////////////////////////////////////
+/// ## Fr
+
+impl Fr {
+  /// Creates the element of the scalar field congruent to `integer`.
+  pub const fn new(integer: &U256) -> Self { Self(ConstMontyForm::new(integer)) }
+
+  /// The canonical representative of the element, in `[0, r)`.
+  pub const fn retrieve(&self) -> U256 { self.0.retrieve() }
+}
+
+impl Field for Fr {
+  const ONE: Self = Self(ConstMontyForm::ONE);
+  const ZERO: Self = Self(ConstMontyForm::ZERO);
+
+  /// Inverse of a element in the scalar field calculated as: x^(-1) = x^(R-2) (mod R).
+  fn inverse(&self) -> Option<Self> {
+    if *self == Self::ZERO {
+      return None;
+    }
+    Some(Self(self.0.pow(&R_2)))
+  }
+
+  fn pow(self, power: usize) -> Self { Self(self.0.pow(&U64::from_u64(power as u64))) }
+}
+
+impl ScalarBits for Fr {
+  fn bits(&self) -> usize { self.retrieve().bits_vartime() as usize }
+
+  fn bit(&self, i: usize) -> bool { self.retrieve().bit_vartime(i as u32) }
+}
+
+impl From<usize> for Fr {
+  fn from(value: usize) -> Self { Self::new(&U256::from_u64(value as u64)) }
+}
+
+impl Hash for Fr {
+  fn hash<H: Hasher>(&self, state: &mut H) { self.retrieve().as_words().hash(state) }
+}
+
+impl Add for Fr {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self { Self(self.0 + rhs.0) }
+}
+
+impl Sub for Fr {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self { Self(self.0 - rhs.0) }
+}
+
+impl Mul for Fr {
+  type Output = Self;
+
+  fn mul(self, rhs: Self) -> Self { Self(self.0 * rhs.0) }
+}
+
+impl Div for Fr {
+  type Output = Self;
+
+  #[allow(clippy::suspicious_arithmetic_impl)]
+  fn div(self, rhs: Self) -> Self { self * rhs.inverse().unwrap() }
+}
+
+impl Rem for Fr {
+  type Output = Self;
+
+  fn rem(self, rhs: Self) -> Self { self - (self / rhs) * rhs }
+}
+
+impl Neg for Fr {
+  type Output = Self;
+
+  fn neg(self) -> Self { Self(-self.0) }
+}
+
+impl DivAssign for Fr {
+  fn div_assign(&mut self, rhs: Self) { *self = *self / rhs; }
+}
+
+impl Sum for Fr {
+  fn sum<I: Iterator<Item = Self>>(iter: I) -> Self { iter.fold(Self::ZERO, |a, b| a + b) }
+}
+
+impl Product for Fr {
+  fn product<I: Iterator<Item = Self>>(iter: I) -> Self { iter.fold(Self::ONE, |a, b| a * b) }
+}
@@ -0,0 +1,249 @@
+//! The groups `G1` and `G2` of the BN254 curve.
+//!
+//! - `G1` is the group of points of `E: y^2 = x^3 + 3` over [`Fq`].
+//! - `G2` is the order `r` subgroup of the sextic twist `E': y^2 = x^3 + 3 / ξ` over [`Fq2`]. The
+//!   twist is isomorphic to a subgroup of `E(Fq12)` via the untwisting map `(x, y) -> (x * w^2, y *
+//!   w^3)` which is used implicitly when evaluating the lines of the Miller loop.
+use std::{
+  fmt::Debug,
+  iter::Sum,
+  ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
+
+use crypto_bigint::{modular::ConstMontyParams, Uint, U256};
+
+use super::fields::{fq, BnField, Fq, Fq2, Fr, FROBENIUS_COEFFS, R};
+use crate::{algebra::group::Group, curve::hash_to_curve::hash_to_field};
+
+/// `(Q + 1) / 4`, the exponent of the square roots in [`Fq`], as `Q = 3 mod 4`.
+const Q_PLUS_1_DIV_4: U256 =
+  U256::from_be_hex("0c19139cb84c680a6e14116da060561765e05aa45a1c72a34f082305b61f3f52");
+
+/// Parameters of a short Weierstrass curve `y^2 = x^3 + b` used by BN254.
+pub trait BnCurve: Copy + Debug + Eq {
+  /// Field the coordinates of the points live in.
+  type Field: BnField;
+
+  /// Coefficient `b` of the curve equation.
+  const B: Self::Field;
+
+  /// Generator of the order `r` subgroup.
+  const GENERATOR: (Self::Field, Self::Field);
+}
+
+/// The BN254 curve `y^2 = x^3 + 3` over [`Fq`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct G1Curve;
+
+/// The sextic twist `y^2 = x^3 + 3 / (9 + u)` over [`Fq2`].
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct G2Curve;
+
+impl BnCurve for G1Curve {
+  type Field = Fq;
+
+  const B: Fq = fq("0000000000000000000000000000000000000000000000000000000000000003");
+  const GENERATOR: (Fq, Fq) = (
+    fq("0000000000000000000000000000000000000000000000000000000000000001"),
+    fq("0000000000000000000000000000000000000000000000000000000000000002"),
+  );
+}
+
+impl BnCurve for G2Curve {
+  type Field = Fq2;
+
+  const B: Fq2 = Fq2::new(
+    fq("2b149d40ceb8aaae81be18991be06ac3b5b4c5e559dbefa33267e6dc24a138e5"),
+    fq("009713b03af0fed4cd2cafadeed8fdf4a74fa084e52d1852e4a2bd0685c315d2"),
+  );
+  const GENERATOR: (Fq2, Fq2) = (
+    Fq2::new(
+      fq("1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed"),
+      fq("198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2"),
+    ),
+    Fq2::new(
+      fq("12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"),
+      fq("090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b"),
+    ),
+  );
+}
+
+/// An affine point on one of the BN254 curves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BnPoint<C: BnCurve> {
+  /// A point on the curve.
+  Point(C::Field, C::Field),
+
+  /// The point at infinity.
+  Infinity,
+}
+
+/// A point of `G1`.
+pub type G1Affine = BnPoint<G1Curve>;
+
+/// A point of `G2`, represented on the twist.
+pub type G2Affine = BnPoint<G2Curve>;
+
+impl<C: BnCurve> BnPoint<C> {
+  /// Create a new point on the curve so long as it satisfies the curve equation.
+  pub fn new(x: C::Field, y: C::Field) -> Self {
+    let point = Self::Point(x, y);
+    assert!(point.is_on_curve(), "Point is not on curve");
+    point
+  }
+
+  /// Generator of the order `r` subgroup.
+  pub fn generator() -> Self { Self::Point(C::GENERATOR.0, C::GENERATOR.1) }
+
+  /// Checks whether a point is on curve.
+  pub fn is_on_curve(&self) -> bool {
+    match self {
+      Self::Infinity => true,
+      Self::Point(x, y) => y.square() == x.square() * *x + C::B,
+    }
+  }
+
+  /// Checks that the point lies in the order `r` subgroup, i.e. `[r]P = O`.
+  pub fn is_torsion_free(&self) -> bool { self.mul_bigint(&R::MODULUS.get()) == Self::Infinity }
+
+  /// Point doubling using the tangent line at the point.
+  pub fn double(&self) -> Self {
+    let (x, y) = match self {
+      Self::Point(x, y) => (*x, *y),
+      Self::Infinity => return Self::Infinity,
+    };
+    if y == C::Field::ZERO {
+      return Self::Infinity;
+    }
+    let two = C::Field::ONE + C::Field::ONE;
+    let three = two + C::Field::ONE;
+    // m = 3x^2 / 2y
+    let m = three * x.square() * (two * y).inverse().unwrap();
+    let x_new = m.square() - two * x;
+    let y_new = m * (x - x_new) - y;
+    Self::Point(x_new, y_new)
+  }
+
+  /// Double-and-add scalar multiplication by an arbitrary big integer.
+  pub fn mul_bigint<const L: usize>(&self, k: &Uint<L>) -> Self {
+    let mut acc = Self::Infinity;
+    for i in (0..k.bits_vartime()).rev() {
+      acc = acc.double();
+      if k.bit_vartime(i) {
+        acc += *self;
+      }
+    }
+    acc
+  }
+}
+
+impl G1Affine {
+  /// Hashes `msg` to a point of `G1` by trying successive counters, like
+  /// [`try_and_increment`](crate::curve::hash_to_curve::try_and_increment) with a cofactor of `1`.
+  ///
+  /// ## Panics
+  /// Panics if no point is found after 256 attempts, which happens with negligible probability.
+  pub fn try_and_increment(msg: &[u8], dst: &[u8]) -> Self {
+    for ctr in 0..=u8::MAX {
+      let u = hash_to_field::<Fq>(&[msg, &[ctr]].concat(), dst, 2);
+      let (x, sign) = (u[0], u[1].retrieve().bit_vartime(0));
+      let rhs = x.square() * x + G1Curve::B;
+      let y = BnField::pow(&rhs, &Q_PLUS_1_DIV_4);
+      if y.square() == rhs {
+        let y = if y.retrieve().bit_vartime(0) == sign { y } else { -y };
+        return Self::Point(x, y);
+      }
+    }
+    panic!("no point found after 256 attempts");
+  }
+}
+
+impl G2Affine {
+  /// The Frobenius endomorphism `π(x, y) = (x^q, y^q)` of `E(Fq12)` pulled back to the twist:
+  /// `(x, y) -> (conj(x) * ξ^((q - 1) / 3), conj(y) * ξ^((q - 1) / 2))`.
+  pub fn frobenius(&self) -> Self {
+    match self {
+      Self::Infinity => Self::Infinity,
+      Self::Point(x, y) => {
+        Self::Point(x.conjugate() * FROBENIUS_COEFFS[2], y.conjugate() * FROBENIUS_COEFFS[3])
+      },
+    }
+  }
+}
+
+impl<C: BnCurve> Add for BnPoint<C> {
+  type Output = Self;
+
+  fn add(self, rhs: Self) -> Self {
+    let ((x1, y1), (x2, y2)) = match (self, rhs) {
+      (Self::Infinity, _) => return rhs,
+      (_, Self::Infinity) => return self,
+      (Self::Point(x1, y1), Self::Point(x2, y2)) => ((x1, y1), (x2, y2)),
+    };
+    if x1 == x2 {
+      if y1 == y2 {
+        return self.double();
+      }
+      return Self::Infinity;
+    }
+    let lambda = (y2 - y1) * (x2 - x1).inverse().unwrap();
+    let x = lambda.square() - x1 - x2;
+    let y = lambda * (x1 - x) - y1;
+    Self::Point(x, y)
+  }
+}
+
+impl<C: BnCurve> AddAssign for BnPoint<C> {
+  fn add_assign(&mut self, rhs: Self) { *self = *self + rhs; }
+}
+
+impl<C: BnCurve> Neg for BnPoint<C> {
+  type Output = Self;
+
+  fn neg(self) -> Self {
+    match self {
+      Self::Point(x, y) => Self::Point(x, -y),
+      Self::Infinity => Self::Infinity,
+    }
+  }
+}
+
+impl<C: BnCurve> Sub for BnPoint<C> {
+  type Output = Self;
+
+  fn sub(self, rhs: Self) -> Self { self + -rhs }
+}
+
+impl<C: BnCurve> SubAssign for BnPoint<C> {
+  fn sub_assign(&mut self, rhs: Self) { *self = *self - rhs; }
+}
+
+impl<C: BnCurve> Sum for BnPoint<C> {
+  fn sum<I: Iterator<Item = Self>>(iter: I) -> Self { iter.fold(Self::Infinity, |a, b| a + b) }
+}
+
+impl<C: BnCurve> Mul<Fr> for BnPoint<C> {
+  type Output = Self;
+
+  fn mul(self, rhs: Fr) -> Self { self.mul_bigint::<{ U256::LIMBS }>(&rhs.retrieve()) }
+}
+
+impl<C: BnCurve> MulAssign<Fr> for BnPoint<C> {
+  fn mul_assign(&mut self, rhs: Fr) { *self = *self * rhs; }
+}
+
+impl<C: BnCurve> Default for BnPoint<C> {
+  fn default() -> Self { Self::Infinity }
+}
+
+impl<C: BnCurve> Group for BnPoint<C> {
+  type Scalar = Fr;
+
+  const IDENTITY: Self = Self::Infinity;
+
+  fn op(&self, rhs: &Self) -> Self { *self + *rhs }
+
+  fn inverse(&self) -> Option<Self> { Some(-*self) }
+
+  fn scalar_mul(&self, scalar: Fr) -> Self { *self * scalar }
+}
@@ -0,0 +1,22 @@
+//! The BN254 (a.k.a. alt_bn128) pairing-friendly curve and its optimal ate pairing.
+//!
+//! BN254 is the Barreto-Naehrig curve `y^2 = x^3 + 3` over a 254-bit prime field used by
+//! Ethereum's precompiles. Unlike the toy [`PlutoBaseCurve`](super::pluto_curve::PlutoBaseCurve),
+//! its fields do not fit in a `usize`, so the base and scalar fields are implemented with
+//! [`crypto_bigint`] Montgomery forms, and the curve has its own point type rather than
+//! implementing [`EllipticCurve`](super::EllipticCurve). [`Bn254`] implements
+//! [`PairingCurve`](super::pairing::PairingCurve), so protocols like [KZG](crate::kzg) commitments
+//! work over BN254 as well as over the Pluto curves.
+//!
+//! - [`fields`]: the tower `Fq -> Fq2 -> Fq6 -> Fq12` of extension fields.
+//! - [`groups`]: the groups `G1 ⊂ E(Fq)` and `G2 ⊂ E'(Fq2)`.
+//! - [`pairing`](mod@pairing): the Miller loop and final exponentiation.
+pub mod fields;
+pub mod groups;
+pub mod pairing;
+
+#[cfg(test)] mod tests;
+
+pub use fields::{Fq, Fq12, Fq2, Fq6, Fr};
+pub use groups::{G1Affine, G2Affine};
+pub use pairing::{pairing, Bn254};
@@ -0,0 +1,213 @@
+//! The optimal ate pairing `e: G1 x G2 -> μ_r ⊂ Fq12` on BN254.
+//!
+//! The pairing is computed in two steps:
+//! 1. The Miller loop evaluates the rational function `f_{6x+2,Q}` (plus two Frobenius correction
+//!    lines) at `P`. Because `G2` is represented on the sextic twist, the lines are computed with
+//!    [`Fq2`] arithmetic and only mapped into [`Fq12`] when evaluated at `P`.
+//! 2. The final exponentiation raises the result to `(q^12 - 1) / r` so that it becomes a unique
+//!    representative of `μ_r`. The exponent is split into the easy part `(q^6 - 1)(q^2 + 1)`,
+//!    computed with conjugation and the Frobenius map, and the hard part `(q^4 - q^2 + 1) / r`,
+//!    computed with an addition chain in the BN parameter `x`.
+//!
+//! Vertical lines are never computed: their values lie in the subfield `Fq6` and are sent to one
+//! by the `q^6 - 1` factor of the final exponentiation.
+use crypto_bigint::U64;
+
+use super::{
+  fields::{BnField, Fq, Fq12, Fq2, Fq6, Fr},
+  groups::{BnCurve, BnPoint, G1Affine, G1Curve, G2Affine, G2Curve},
+};
+use crate::curve::pairing::PairingCurve;
+
+/// The BN parameter `x` such that `q = 36x^4 + 36x^3 + 24x^2 + 6x + 1` and
+/// `r = 36x^4 + 36x^3 + 18x^2 + 6x + 1`.
+pub const BN_X: u64 = 4965661367192848881;
+
+/// The length `6x + 2` of the optimal ate Miller loop.
+pub const ATE_LOOP_COUNT: u128 = 6 * BN_X as u128 + 2;
+
+/// Returns the [non-adjacent form](https://en.wikipedia.org/wiki/Non-adjacent_form) of `k` as
+/// little-endian digits in `{-1, 0, 1}`. No two consecutive digits are non-zero, which minimises
+/// the number of additions in a double-and-add style loop.
+pub fn naf(mut k: u128) -> Vec<i8> {
+  let mut digits = vec![];
+  while k > 0 {
+    if k & 1 == 1 {
+      // pick the digit in {-1, 1} that makes the remaining value divisible by 4
+      let digit = 2 - (k % 4) as i8;
+      digits.push(digit);
+      if digit == 1 {
+        k -= 1;
+      } else {
+        k += 1;
+      }
+    } else {
+      digits.push(0);
+    }
+    k >>= 1;
+  }
+  digits
+}
+
+/// Evaluates the line with slope `lambda` through the twisted point `(x_t, y_t)` at `P = (x_p,
+/// y_p)`.
+///
+/// Untwisting maps `(x_t, y_t) -> (x_t * w^2, y_t * w^3)` and the slope to `lambda * w`, so the
+/// line `y - y_T - λ(x - x_T)` evaluated at `P` becomes
+/// `y_p - (λ * x_p) * w + (λ * x_t - y_t) * w^3`, a sparse element of [`Fq12`].
+fn line_eval(lambda: Fq2, x_t: Fq2, y_t: Fq2, x_p: Fq, y_p: Fq) -> Fq12 {
+  let c0 = Fq6::new(Fq2::new(y_p, Fq::ZERO), Fq2::ZERO, Fq2::ZERO);
+  let c1 = Fq6::new(-lambda.scale(x_p), lambda * x_t - y_t, Fq2::ZERO);
+  Fq12::new(c0, c1)
+}
+
+/// Returns `2T` and the tangent line at `T` evaluated at `P`.
+fn doubling_step(t: G2Affine, p: (Fq, Fq)) -> (G2Affine, Fq12) {
+  let (x, y) = match t {
+    G2Affine::Point(x, y) => (x, y),
+    G2Affine::Infinity => panic!("Cannot double the point at infinity in the Miller loop"),
+  };
+  let three = Fq2::ONE + Fq2::ONE + Fq2::ONE;
+  let lambda = three * x.square() * (y + y).inverse().expect("T has order r so y != 0");
+  (t.double(), line_eval(lambda, x, y, p.0, p.1))
+}
+
+/// Returns `T + Q` and the chord through `T` and `Q` evaluated at `P`.
+fn addition_step(t: G2Affine, q: G2Affine, p: (Fq, Fq)) -> (G2Affine, Fq12) {
+  let ((x_t, y_t), (x_q, y_q)) = match (t, q) {
+    (G2Affine::Point(x_t, y_t), G2Affine::Point(x_q, y_q)) => ((x_t, y_t), (x_q, y_q)),
+    _ => panic!("Cannot add the point at infinity in the Miller loop"),
+  };
+  let lambda = (y_q - y_t) * (x_q - x_t).inverse().expect("T != ±Q inside the Miller loop");
+  (t + q, line_eval(lambda, x_t, y_t, p.0, p.1))
+}
+
+/// Computes the optimal ate Miller loop `f_{6x+2,Q}(P) * l_{[6x+2]Q,π(Q)}(P) *
+/// l_{[6x+2]Q+π(Q),-π^2(Q)}(P)`.
+///
+/// The loop runs over the [`naf`] digits of `6x + 2` from the most significant one, squaring the
+/// accumulator and multiplying by a tangent line at each step, and by a chord whenever the digit
+/// is non-zero.
+pub fn miller_loop(p: G1Affine, q: G2Affine) -> Fq12 {
+  let p = match p {
+    G1Affine::Point(x, y) => (x, y),
+    G1Affine::Infinity => return Fq12::ONE,
+  };
+  if q == G2Affine::Infinity {
+    return Fq12::ONE;
+  }
+
+  let mut f = Fq12::ONE;
+  let mut t = q;
+  let digits = naf(ATE_LOOP_COUNT);
+  for &digit in digits.iter().rev().skip(1) {
+    let (doubled, line) = doubling_step(t, p);
+    f = f.square() * line;
+    t = doubled;
+    if digit != 0 {
+      let q = if digit == 1 { q } else { -q };
+      let (added, line) = addition_step(t, q, p);
+      f *= line;
+      t = added;
+    }
+  }
+
+  // Frobenius corrections: Q1 = π(Q), Q2 = -π^2(Q)
+  let q1 = q.frobenius();
+  let q2 = -q1.frobenius();
+  let (t, line) = addition_step(t, q1, p);
+  f *= line;
+  let (_, line) = addition_step(t, q2, p);
+  f * line
+}
+
+/// Computes `f^(x)` followed by a conjugation, i.e. `f^(-x)` for `f` in the cyclotomic subgroup.
+fn exp_by_neg_x(f: Fq12) -> Fq12 { f.pow(&U64::from_u64(BN_X)).conjugate() }
+
+/// The easy part of the final exponentiation: `f^((q^6 - 1)(q^2 + 1))`.
+///
+/// The result lies in the cyclotomic subgroup where inversion is just [`Fq12::conjugate`].
+pub fn final_exponentiation_easy_part(f: Fq12) -> Fq12 {
+  // f^(q^6 - 1) = conj(f) / f
+  let f = f.conjugate() * f.inverse().expect("Miller loop output is non-zero");
+  // f^(q^2 + 1)
+  f.frobenius_map(2) * f
+}
+
+/// The hard part of the final exponentiation.
+///
+/// Follows [Fuentes-Castañeda, Knapp and Rodríguez-Henríquez](https://link.springer.com/chapter/10.1007/978-3-642-28496-0_25),
+/// computing `f^(2x(6x^2 + 3x + 1) * (q^4 - q^2 + 1) / r)` with three exponentiations by `x` and
+/// a few Frobenius maps. The extra factor `2x(6x^2 + 3x + 1)` is coprime to `r`, so the result is
+/// still a non-degenerate bilinear pairing.
+pub fn final_exponentiation_hard_part(r: Fq12) -> Fq12 {
+  let y0 = exp_by_neg_x(r);
+  let y1 = y0.square();
+  let y2 = y1.square();
+  let y3 = y2 * y1;
+  let y4 = exp_by_neg_x(y3);
+  let y5 = y4.square();
+  let y6 = exp_by_neg_x(y5).conjugate();
+  let y3 = y3.conjugate();
+  let y7 = y6 * y4;
+  let y8 = y7 * y3;
+  let y9 = y8 * y1;
+  let y10 = y8 * y4;
+  let y11 = y10 * r;
+  let y12 = y9.frobenius_map(1);
+  let y13 = y12 * y11;
+  let y14 = y8.frobenius_map(2) * y13;
+  let y15 = (r.conjugate() * y9).frobenius_map(3);
+  y15 * y14
+}
+
+/// Raises the Miller loop output to `(q^12 - 1) / r`, up to the fixed exponent described in
+/// [`final_exponentiation_hard_part`].
+pub fn final_exponentiation(f: Fq12) -> Fq12 {
+  final_exponentiation_hard_part(final_exponentiation_easy_part(f))
+}
+
+/// Computes the optimal ate pairing of `P ∈ G1` and `Q ∈ G2`.
+///
+/// ## Panics
+/// Panics if either input is not on its curve or not in the order `r` subgroup.
+pub fn pairing(p: G1Affine, q: G2Affine) -> Fq12 {
+  assert!(p.is_on_curve() && p.is_torsion_free(), "P is not in G1");
+  assert!(q.is_on_curve() && q.is_torsion_free(), "Q is not in G2");
+  final_exponentiation(miller_loop(p, q))
+}
+
+/// The optimal ate [`pairing`] of BN254, for the protocols written against [`PairingCurve`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Bn254;
+
+impl PairingCurve for Bn254 {
+  type G1 = G1Affine;
+  type G2 = G2Affine;
+  type ScalarField = Fr;
+  type TargetField = Fq12;
+
+  const G1_GENERATOR: G1Affine = BnPoint::Point(G1Curve::GENERATOR.0, G1Curve::GENERATOR.1);
+  const G2_GENERATOR: G2Affine = BnPoint::Point(G2Curve::GENERATOR.0, G2Curve::GENERATOR.1);
+  const TARGET_ONE: Fq12 = Fq12::ONE;
+
+  fn pairing(p: G1Affine, q: G2Affine) -> Fq12 { pairing(p, q) }
+
+  /// Multiplies the Miller loops of all pairs and only runs the final exponentiation once.
+  ///
+  /// ## Panics
+  /// Panics if a point is not in its group, like [`pairing`].
+  fn multi_pairing(pairs: &[(G1Affine, G2Affine)]) -> Fq12 {
+    let f = pairs.iter().fold(Fq12::ONE, |f, &(p, q)| {
+      assert!(Self::is_in_g1(&p), "P is not in G1");
+      assert!(Self::is_in_g2(&q), "Q is not in G2");
+      f * miller_loop(p, q)
+    });
+    final_exponentiation(f)
+  }
+
+  /// The cofactor of `G1` is `1`, so every point of the curve is in `G1`.
+  fn is_in_g1(p: &G1Affine) -> bool { p.is_on_curve() }
+
+  fn is_in_g2(q: &G2Affine) -> bool { q.is_on_curve() && q.is_torsion_free() }
+}
@@ -0,0 +1,138 @@
+use crypto_bigint::{modular::ConstMontyParams, U1024, U256};
+
+use super::{
+  fields::{BnField, Q, R},
+  pairing::*,
+  *,
+};
+use crate::{
+  algebra::field::Field,
+  curve::{
+    msm::{msm, naive_msm, ScalarBits},
+    pairing::PairingCurve,
+  },
+};
+
+fn fq(n: u64) -> Fq { Fq::new(&U256::from_u64(n)) }
+
+fn fr(n: u64) -> Fr { Fr::new(&U256::from_u64(n)) }
+
+/// A fixed element of `Fq12` with every coefficient non-zero.
+fn sample_fq12() -> Fq12 {
+  let fq2 = |a, b| Fq2::new(fq(a), fq(b));
+  Fq12::new(Fq6::new(fq2(1, 2), fq2(3, 4), fq2(5, 6)), Fq6::new(fq2(7, 8), fq2(9, 10), fq2(11, 12)))
+}
+
+#[test]
+fn fq12_inverse() {
+  let f = sample_fq12();
+  assert_eq!(f * f.inverse().unwrap(), Fq12::ONE);
+  assert_eq!(Fq12::ZERO.inverse(), None);
+}
+
+#[test]
+fn frobenius_is_q_power() {
+  let f = sample_fq12();
+  let q = Q::MODULUS.get();
+  let mut expected = f;
+  for power in 1..=3 {
+    expected = expected.pow(&q);
+    assert_eq!(f.frobenius_map(power), expected);
+  }
+  assert_eq!(f.frobenius_map(12), f);
+}
+
+#[test]
+fn generators_in_subgroups() {
+  let g1 = G1Affine::generator();
+  let g2 = G2Affine::generator();
+  assert!(g1.is_on_curve() && g1.is_torsion_free());
+  assert!(g2.is_on_curve() && g2.is_torsion_free());
+  assert_eq!(g1 * fr(5) + g1 * fr(7), g1 * fr(12));
+  assert_eq!(g2 * fr(5) - g2 * fr(7), -(g2 * fr(2)));
+}
+
+#[test]
+fn twist_frobenius_is_endomorphism() {
+  // π acts on G2 as multiplication by q
+  let g2 = G2Affine::generator();
+  assert_eq!(g2.frobenius(), g2.mul_bigint(&Q::MODULUS.get()));
+}
+
+#[test]
+fn naf_digits() {
+  let digits = naf(ATE_LOOP_COUNT);
+  assert!(digits.windows(2).all(|w| w[0] == 0 || w[1] == 0));
+  let value = digits.iter().rev().fold(0i128, |acc, &d| 2 * acc + d as i128);
+  assert_eq!(value as u128, ATE_LOOP_COUNT);
+}
+
+#[test]
+fn hard_part_matches_naive_exponentiation() {
+  let f = final_exponentiation_easy_part(sample_fq12());
+
+  // exponent 2x(6x^2 + 3x + 1) * (q^4 - q^2 + 1) / r
+  let q = Q::MODULUS.get().resize::<{ U1024::LIMBS }>();
+  let r = R::MODULUS.get().resize::<{ U1024::LIMBS }>();
+  let q2 = q.wrapping_mul(&q);
+  let lambda = q2.wrapping_mul(&q2).wrapping_sub(&q2).wrapping_add(&U1024::ONE);
+  let (lambda, rem) = lambda.div_rem(&r.to_nz().unwrap());
+  assert_eq!(rem, U1024::ZERO);
+  let x = U1024::from_u64(BN_X);
+  let m = U1024::from_u8(6)
+    .wrapping_mul(&x)
+    .wrapping_mul(&x)
+    .wrapping_add(&U1024::from_u8(3).wrapping_mul(&x))
+    .wrapping_add(&U1024::ONE)
+    .wrapping_mul(&x)
+    .wrapping_mul(&U1024::from_u8(2));
+
+  assert_eq!(final_exponentiation_hard_part(f), f.pow(&lambda.wrapping_mul(&m)));
+}
+
+#[test]
+fn pairing_is_bilinear() {
+  let p = G1Affine::generator();
+  let q = G2Affine::generator();
+  let e = pairing(p, q);
+
+  assert_eq!(pairing(p * fr(2), q * fr(3)), e.pow(&U256::from_u8(6)));
+  assert_eq!(pairing(p * fr(6), q), pairing(p, q * fr(6)));
+  assert_eq!(pairing(p + p * fr(4), q), e * pairing(p * fr(4), q));
+  assert_eq!(pairing(-p, q), e.conjugate());
+}
+
+#[test]
+fn pairing_is_non_degenerate() {
+  let p = G1Affine::generator();
+  let q = G2Affine::generator();
+  let e = pairing(p, q);
+
+  assert_ne!(e, Fq12::ONE);
+  assert_eq!(e.pow(&R::MODULUS.get()), Fq12::ONE);
+  assert_eq!(pairing(G1Affine::Infinity, q), Fq12::ONE);
+  assert_eq!(pairing(p, G2Affine::Infinity), Fq12::ONE);
+}
+
+#[test]
+fn scalar_field() {
+  let minus_one = Fr::new(&R::MODULUS.get().wrapping_sub(&U256::ONE));
+  assert_eq!(minus_one, -Fr::ONE);
+  assert_eq!(fr(7) * fr(7).inverse().unwrap(), Fr::ONE);
+  assert_eq!(fr(3).pow(5), fr(243));
+  assert_eq!(Fr::ZERO.inverse(), None);
+  assert_eq!((minus_one.bits(), minus_one.bit(0), minus_one.bit(253)), (254, false, true));
+}
+
+#[test]
+fn multi_pairing_and_msm() {
+  let p = G1Affine::generator();
+  let q = G2Affine::generator();
+  let bases = [p, p * fr(2), p * fr(3)];
+  let scalars = [fr(5), -fr(1), fr(4)];
+  assert_eq!(msm(&bases, &scalars), p * fr(15));
+  assert_eq!(msm(&bases, &scalars), naive_msm(&bases, &scalars));
+
+  assert_eq!(Bn254::multi_pairing(&[(p, q * fr(3)), (-p * fr(3), q)]), Fq12::ONE);
+  assert!(Bn254::is_in_g2(&q) && !Bn254::is_in_g2(&G2Affine::Point(Fq2::ONE, Fq2::ONE)));
+}
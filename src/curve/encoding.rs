@@ -0,0 +1,190 @@
+//! [SEC1](https://www.secg.org/sec1-v2.pdf) style encodings of [`AffinePoint`]s.
+//!
+//! A point is serialised as a one byte tag followed by its coordinates, each encoded with
+//! [`FieldBytes`]:
+//! - `0x00`: the point at infinity, with no coordinates.
+//! - `0x02 || x` or `0x03 || x`: a compressed point. Only `x` is stored, and `y` is recovered as a
+//!   square root of `x^3 + ax + b`. The low bit of the tag is the [`SqrtField::sgn0`] of `y`, which
+//!   tells the two roots apart.
+//! - `0x04 || x || y`: an uncompressed point.
+//!
+//! For prime fields `sgn0` is the parity of `y`, so the encoding agrees with SEC1 byte for byte.
+use std::{error::Error, fmt::Display};
+
+use super::*;
+use crate::algebra::field::{FieldBytes, SqrtField};
+
+/// Tag of the point at infinity.
+const TAG_INFINITY: u8 = 0x00;
+/// Tag of a compressed point whose `y` coordinate has sign `0`.
+const TAG_COMPRESSED_EVEN: u8 = 0x02;
+/// Tag of a compressed point whose `y` coordinate has sign `1`.
+const TAG_COMPRESSED_ODD: u8 = 0x03;
+/// Tag of an uncompressed point.
+const TAG_UNCOMPRESSED: u8 = 0x04;
+
+/// Errors from decoding an encoded [`AffinePoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointDecodingError {
+  /// The input was empty.
+  Empty,
+  /// The leading byte is not one of the SEC1 tags.
+  InvalidTag(u8),
+  /// The input length does not match the length implied by the tag.
+  InvalidLength {
+    /// Length implied by the tag.
+    expected: usize,
+    /// Length of the input.
+    found:    usize,
+  },
+  /// A coordinate is not the canonical encoding of a field element.
+  InvalidCoordinate,
+  /// The decoded coordinates do not satisfy the curve equation, or no `y` exists for the given
+  /// `x`.
+  NotOnCurve,
+}
+
+impl Error for PointDecodingError {}
+
+impl Display for PointDecodingError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PointDecodingError::Empty => write!(f, "encoded point is empty"),
+      PointDecodingError::InvalidTag(tag) => write!(f, "invalid point encoding tag: {:#04x}", tag),
+      PointDecodingError::InvalidLength { expected, found } =>
+        write!(f, "invalid encoded point length: expected {} bytes, found {}", expected, found),
+      PointDecodingError::InvalidCoordinate => write!(f, "coordinate is not a valid field element"),
+      PointDecodingError::NotOnCurve => write!(f, "point is not on the curve"),
+    }
+  }
+}
+
+impl<C: EllipticCurve> AffinePoint<C>
+where C::BaseField: SqrtField + FieldBytes
+{
+  /// Encodes the point as `0x02 || x` or `0x03 || x`, where the tag stores the sign of `y`. The
+  /// point at infinity is encoded as a single `0x00` byte.
+  pub fn to_compressed(&self) -> Vec<u8> {
+    match self {
+      AffinePoint::Infinity => vec![TAG_INFINITY],
+      AffinePoint::Point(x, y) => {
+        let tag = if y.sgn0() { TAG_COMPRESSED_ODD } else { TAG_COMPRESSED_EVEN };
+        [vec![tag], x.to_bytes()].concat()
+      },
+    }
+  }
+
+  /// Encodes the point as `0x04 || x || y`. The point at infinity is encoded as a single `0x00`
+  /// byte.
+  pub fn to_uncompressed(&self) -> Vec<u8> {
+    match self {
+      AffinePoint::Infinity => vec![TAG_INFINITY],
+      AffinePoint::Point(x, y) => [vec![TAG_UNCOMPRESSED], x.to_bytes(), y.to_bytes()].concat(),
+    }
+  }
+
+  /// Decodes a point from either its compressed or uncompressed encoding.
+  ///
+  /// ## Errors
+  /// - [`PointDecodingError::InvalidTag`] or [`PointDecodingError::InvalidLength`] if the input is
+  ///   not shaped like an encoded point.
+  /// - [`PointDecodingError::InvalidCoordinate`] if a coordinate is out of range.
+  /// - [`PointDecodingError::NotOnCurve`] if the decoded point does not lie on the curve.
+  pub fn from_bytes(bytes: &[u8]) -> Result<Self, PointDecodingError> {
+    let (&tag, rest) = bytes.split_first().ok_or(PointDecodingError::Empty)?;
+    let coordinate_len = match tag {
+      TAG_INFINITY => 0,
+      TAG_COMPRESSED_EVEN | TAG_COMPRESSED_ODD => C::BaseField::BYTES,
+      TAG_UNCOMPRESSED => 2 * C::BaseField::BYTES,
+      _ => return Err(PointDecodingError::InvalidTag(tag)),
+    };
+    if rest.len() != coordinate_len {
+      return Err(PointDecodingError::InvalidLength {
+        expected: coordinate_len + 1,
+        found:    bytes.len(),
+      });
+    }
+
+    let decode =
+      |bytes: &[u8]| C::BaseField::from_bytes(bytes).ok_or(PointDecodingError::InvalidCoordinate);
+    match tag {
+      TAG_INFINITY => Ok(AffinePoint::Infinity),
+      TAG_UNCOMPRESSED => {
+        let (x, y) = rest.split_at(C::BaseField::BYTES);
+        let point = AffinePoint::Point(decode(x)?, decode(y)?);
+        if !point.is_on_curve() {
+          return Err(PointDecodingError::NotOnCurve);
+        }
+        Ok(point)
+      },
+      _ => {
+        let x = decode(rest)?;
+        let a: C::BaseField = C::EQUATION_A.into();
+        let b: C::BaseField = C::EQUATION_B.into();
+        let rhs = x * x * x + a * x + b;
+        let (r0, r1) = rhs.sqrt().ok_or(PointDecodingError::NotOnCurve)?;
+
+        let sign = tag == TAG_COMPRESSED_ODD;
+        let y = if r0.sgn0() == sign { r0 } else { r1 };
+        // `y = 0` only has sign `0`, so reject the non-canonical `0x03` encoding.
+        if y.sgn0() != sign {
+          return Err(PointDecodingError::InvalidCoordinate);
+        }
+        Ok(AffinePoint::Point(x, y))
+      },
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::curve::pluto_curve::{PlutoBaseCurve, PlutoExtendedCurve};
+
+  #[test]
+  fn roundtrip_base_curve() {
+    let g = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+    for i in 0..17u32 {
+      let point = i * g;
+      let compressed = point.to_compressed();
+      let uncompressed = point.to_uncompressed();
+      assert_eq!(AffinePoint::from_bytes(&compressed), Ok(point));
+      assert_eq!(AffinePoint::from_bytes(&uncompressed), Ok(point));
+    }
+    assert_eq!(g.to_compressed(), vec![0x02, 1]);
+    assert_eq!(g.to_uncompressed(), vec![0x04, 1, 2]);
+    assert_eq!(AffinePoint::<PlutoBaseCurve>::Infinity.to_compressed(), vec![0x00]);
+  }
+
+  #[test]
+  fn roundtrip_extended_curve() {
+    let g = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+    for i in 0..17u32 {
+      let point = i * g;
+      assert_eq!(AffinePoint::from_bytes(&point.to_compressed()), Ok(point));
+      assert_eq!(AffinePoint::from_bytes(&point.to_uncompressed()), Ok(point));
+    }
+    // x = 36, y = 31t, coefficients highest degree first
+    assert_eq!(g.to_uncompressed(), vec![0x04, 0, 36, 31, 0]);
+  }
+
+  #[test]
+  fn rejects_invalid_encodings() {
+    type Point = AffinePoint<PlutoBaseCurve>;
+    assert_eq!(Point::from_bytes(&[]), Err(PointDecodingError::Empty));
+    assert_eq!(Point::from_bytes(&[0x05, 1]), Err(PointDecodingError::InvalidTag(0x05)));
+    assert_eq!(
+      Point::from_bytes(&[0x02, 1, 2]),
+      Err(PointDecodingError::InvalidLength { expected: 2, found: 3 })
+    );
+    assert_eq!(
+      Point::from_bytes(&[0x00, 0]),
+      Err(PointDecodingError::InvalidLength { expected: 1, found: 2 })
+    );
+    // 101 is not a canonical element of GF(101)
+    assert_eq!(Point::from_bytes(&[0x04, 101, 2]), Err(PointDecodingError::InvalidCoordinate));
+    assert_eq!(Point::from_bytes(&[0x04, 1, 3]), Err(PointDecodingError::NotOnCurve));
+    // 1^3 + 3 = 4 is a square but 2^3 + 3 = 11 is not
+    assert_eq!(Point::from_bytes(&[0x02, 2]), Err(PointDecodingError::NotOnCurve));
+  }
+}
@@ -0,0 +1,541 @@
+//! Counting the points of a [`ShortWeierstrass`] curve.
+//!
+//! By Hasse's theorem the number of points is `#E = p + 1 - t` with `|t| <= 2√p`, where `t` is the
+//! trace of the Frobenius endomorphism `π: (x, y) -> (x^p, y^p)`. The three methods find `#E` in
+//! increasingly clever ways:
+//! - [`ShortWeierstrass::count_points_naive`] adds up, for every `x`, the number of square roots of
+//!   `x^3 + ax + b` using the Legendre symbol. This costs `O(p)` field exponentiations.
+//! - [`ShortWeierstrass::count_points_bsgs`] uses the fact that `[#E]P = O` for every point `P`.
+//!   Baby-step giant-step finds a multiple of the order of `P` inside the Hasse interval in
+//!   `O(p^¼)` group operations, and the orders of a few points usually pin down `#E`.
+//! - [`ShortWeierstrass::count_points_schoof`] computes `t mod ℓ` for small primes `ℓ` from the
+//!   characteristic equation `π^2 - [t]π + [p] = 0` restricted to the `ℓ`-torsion, and recovers `t`
+//!   with the Chinese remainder theorem. This runs in polynomial time in `log p`.
+use std::collections::HashMap;
+
+use super::{is_prime, Point, ShortWeierstrass};
+use crate::algebra::field::{prime::PrimeField, Field};
+
+/// Fields up to this size are counted naively by [`ShortWeierstrass::count_points`].
+const NAIVE_COUNTING_BOUND: usize = 1 << 12;
+
+impl<const P: usize> ShortWeierstrass<PrimeField<P>> {
+  /// Counts the points of the curve, picking the naive method for small fields and Schoof's
+  /// algorithm otherwise.
+  pub fn count_points(&self) -> usize {
+    if P < NAIVE_COUNTING_BOUND {
+      self.count_points_naive()
+    } else {
+      self.count_points_schoof()
+    }
+  }
+
+  /// Counts the points as `1 + Σ_x (1 + (x^3 + ax + b | p))`, where `(· | p)` is the Legendre
+  /// symbol: the point at infinity plus, for every `x`, two points if the right hand side is a
+  /// non-zero square, one if it is zero and none otherwise.
+  pub fn count_points_naive(&self) -> usize {
+    1 + (0..P)
+      .map(|x| {
+        let rhs = self.rhs(PrimeField::new(x));
+        match (rhs == PrimeField::ZERO, rhs.euler_criterion()) {
+          (true, _) => 1,
+          (false, true) => 2,
+          (false, false) => 0,
+        }
+      })
+      .sum::<usize>()
+  }
+
+  /// Counts the points with baby-step giant-step.
+  ///
+  /// For points `P` of the curve, [`Self::hasse_multiple`] finds some `m` in the Hasse interval
+  /// with `[m]P = O`, which gives the order of `P`. `#E` is a multiple of the least common multiple
+  /// `L` of these orders, so as soon as a single multiple of `L` lies in the Hasse interval it is
+  /// `#E`. If the group has a small exponent this never happens, and the count falls back to
+  /// [`Self::count_points_naive`].
+  pub fn count_points_bsgs(&self) -> usize {
+    let (low, high) = hasse_interval(P);
+    let mut lcm = 1;
+    for x in 0..P {
+      let x = PrimeField::new(x);
+      let Some((y, _)) = self.rhs(x).sqrt() else { continue };
+      let point = Point::Affine(x, y);
+      let order = self.order_of(point, self.hasse_multiple(point));
+      lcm = lcm / gcd(lcm, order) * order;
+
+      let first = low.div_ceil(lcm) * lcm;
+      if first + lcm > high {
+        return first;
+      }
+    }
+    self.count_points_naive()
+  }
+
+  /// Finds `m` in the Hasse interval `[low, high]` with `[m]P = O` using baby-step giant-step.
+  ///
+  /// Writing `m = low + js + i` with `s = ⌈√(high - low + 1)⌉` and `0 <= i < s`, the baby steps
+  /// `[i]P` are stored in a table and the giant steps walk `[low + js]P` until its negation is in
+  /// the table.
+  pub fn hasse_multiple(&self, point: Point<PrimeField<P>>) -> usize {
+    let (low, high) = hasse_interval(P);
+    let steps = isqrt(high - low + 1) + 1;
+
+    let mut baby_steps = HashMap::new();
+    let mut baby = Point::Infinity;
+    for i in 0..steps {
+      baby_steps.entry(baby).or_insert(i);
+      baby = self.add(baby, point);
+    }
+
+    let giant = self.mul(steps, point);
+    let mut current = self.mul(low, point);
+    for j in 0..=steps {
+      if let Some(i) = baby_steps.get(&-current) {
+        return low + j * steps + i;
+      }
+      current = self.add(current, giant);
+    }
+    unreachable!("#E lies in the Hasse interval, so some multiple of the order of P does too")
+  }
+
+  /// Counts the points with Schoof's algorithm.
+  ///
+  /// - `t mod 2` is zero exactly when the curve has a point of order two, i.e. when `x^3 + ax + b`
+  ///   has a root, which is checked with `gcd(x^p - x, x^3 + ax + b)`.
+  /// - For odd primes `ℓ`, the ring `GF(p)[x, y] / (ψ_ℓ(x), y^2 - x^3 - ax - b)` describes a generic
+  ///   `ℓ`-torsion point, where `ψ_ℓ` is the `ℓ`-th [division polynomial](https://en.wikipedia.org/wiki/Division_polynomials).
+  ///   In it `π^2(x, y) + [p mod ℓ](x, y)` is computed and compared with `[τ]π(x, y)` for every
+  ///   `τ` in `[0, ℓ)`, the one that matches is `t mod ℓ`.
+  ///
+  /// Primes are added until their product exceeds the width `4√p` of the Hasse interval.
+  ///
+  /// ## Panics
+  /// Panics if `P <= 3`, as the short Weierstrass form needs the characteristic to be at least `5`.
+  pub fn count_points_schoof(&self) -> usize {
+    assert!(P > 3, "Schoof's algorithm needs a field of characteristic at least 5");
+    let f = vec![self.b, self.a, PrimeField::ZERO, PrimeField::ONE];
+
+    let x_p = poly_pow_mod(&[PrimeField::ZERO, PrimeField::ONE], P, &f);
+    let has_two_torsion =
+      poly_gcd(&poly_sub(&x_p, &[PrimeField::ZERO, PrimeField::ONE]), &f).len() > 1;
+    let mut residues = vec![(usize::from(!has_two_torsion), 2)];
+    let mut modulus = 2;
+
+    let division_polynomials = self.division_polynomials(max_schoof_prime(P));
+    let mut l = 3;
+    while modulus * modulus <= 16 * P {
+      if l != P {
+        residues.push((self.trace_mod(l, &division_polynomials[l], &f), l));
+        modulus *= l;
+      }
+      l = (l + 2..).find(|&q| is_prime(q)).unwrap();
+    }
+
+    // lift t from t mod modulus into the Hasse bound |t| <= 2√p
+    let t = crt(&residues) as i128;
+    let modulus = modulus as i128;
+    let t = if t > modulus / 2 { t - modulus } else { t };
+    (P as i128 + 1 - t) as usize
+  }
+
+  /// Computes `t mod ℓ` by finding `τ` with `π^2(Q) + [p mod ℓ]Q = [τ]π(Q)` for the generic
+  /// point `Q` of the `ℓ`-torsion.
+  ///
+  /// If a non-invertible element shows up along the way, it shares a factor `g` with the modulus,
+  /// which corresponds to a Frobenius stable subset of the `ℓ`-torsion. The relation holds on that
+  /// subset too, so the computation restarts modulo `g`.
+  fn trace_mod(&self, l: usize, psi: &[PrimeField<P>], f: &[PrimeField<P>]) -> usize {
+    let mut modulus = psi.to_vec();
+    'restart: loop {
+      let ring = TorsionRing { modulus: &modulus, f, a: self.a };
+      let y_exponent = |n: usize| (n - 1) / 2;
+      let x = vec![PrimeField::ZERO, PrimeField::ONE];
+
+      // π(Q) = (x^p, y^p) = (x^p, y * f^((p - 1) / 2))
+      let pi =
+        RingPoint::Affine(poly_pow_mod(&x, P, &modulus), poly_pow_mod(f, y_exponent(P), &modulus));
+      let pi_squared = RingPoint::Affine(
+        poly_pow_mod(&x, P * P, &modulus),
+        poly_pow_mod(f, y_exponent(P * P), &modulus),
+      );
+      let generic = RingPoint::Affine(poly_rem(&x, &modulus), vec![PrimeField::ONE]);
+
+      let lhs = match ring.mul(P % l, &generic).and_then(|q| ring.add(&pi_squared, &q)) {
+        Ok(lhs) => lhs,
+        Err(factor) => {
+          modulus = factor;
+          continue 'restart;
+        },
+      };
+      if lhs == RingPoint::Infinity {
+        return 0;
+      }
+
+      let mut tau_pi = pi.clone();
+      for tau in 1..l {
+        if tau_pi == lhs {
+          return tau;
+        }
+        tau_pi = match ring.add(&tau_pi, &pi) {
+          Ok(next) => next,
+          Err(factor) => {
+            modulus = factor;
+            continue 'restart;
+          },
+        };
+      }
+      unreachable!("the characteristic equation of Frobenius has a solution modulo ℓ")
+    }
+  }
+
+  /// Returns the division polynomials `ψ_0, ..., ψ_n`, as polynomials in `x` only: for even `m`
+  /// the factor `y` of `ψ_m` is dropped, i.e. `ψ_m / y` is stored instead.
+  ///
+  /// They follow the recurrences
+  /// - `ψ_{2m+1} = ψ_{m+2} ψ_m^3 - ψ_{m-1} ψ_{m+1}^3`,
+  /// - `ψ_{2m} = ψ_m (ψ_{m+2} ψ_{m-1}^2 - ψ_{m-2} ψ_{m+1}^2) / 2y`,
+  ///
+  /// where every `y^2` that appears is replaced by `x^3 + ax + b`.
+  fn division_polynomials(&self, n: usize) -> Vec<Vec<PrimeField<P>>> {
+    let (a, b) = (self.a, self.b);
+    let c = |n: usize| PrimeField::<P>::new(n);
+    let f = vec![b, a, PrimeField::ZERO, PrimeField::ONE];
+    let f_squared = poly_mul(&f, &f);
+
+    let mut psi = vec![
+      vec![],
+      vec![PrimeField::ONE],
+      vec![c(2)],
+      poly_trim(vec![-a * a, c(12) * b, c(6) * a, PrimeField::ZERO, c(3)]),
+      poly_trim(
+        [
+          -c(4) * (a * a * a + c(8) * b * b),
+          -c(16) * a * b,
+          -c(20) * a * a,
+          c(80) * b,
+          c(20) * a,
+          PrimeField::ZERO,
+          c(4),
+        ]
+        .to_vec(),
+      ),
+    ];
+    let half = PrimeField::<P>::ONE / c(2);
+    for i in 5..=n {
+      let m = i / 2;
+      let next = if i % 2 == 1 {
+        let first = poly_mul(&psi[m + 2], &poly_pow(&psi[m], 3));
+        let second = poly_mul(&psi[m - 1], &poly_pow(&psi[m + 1], 3));
+        // the even index among m and m + 1 contributes y^4 = f^2
+        if m % 2 == 0 {
+          poly_sub(&poly_mul(&f_squared, &first), &second)
+        } else {
+          poly_sub(&first, &poly_mul(&f_squared, &second))
+        }
+      } else {
+        let first = poly_mul(&psi[m + 2], &poly_pow(&psi[m - 1], 2));
+        let second = poly_mul(&psi[m - 2], &poly_pow(&psi[m + 1], 2));
+        poly_scale(&poly_mul(&psi[m], &poly_sub(&first, &second)), half)
+      };
+      psi.push(next);
+    }
+    psi.truncate(n + 1);
+    psi
+  }
+}
+
+/// Returns the Hasse interval `[p + 1 - ⌊2√p⌋, p + 1 + ⌊2√p⌋]`.
+fn hasse_interval(p: usize) -> (usize, usize) {
+  let width = isqrt(4 * p);
+  (p + 1 - width, p + 1 + width)
+}
+
+/// Returns the largest prime used by [`ShortWeierstrass::count_points_schoof`] for a field of size
+/// `p`.
+fn max_schoof_prime(p: usize) -> usize {
+  let mut modulus = 2;
+  let mut l = 2;
+  while modulus * modulus <= 16 * p {
+    l = (l + 1..).find(|&q| is_prime(q) && q != p).unwrap();
+    modulus *= l;
+  }
+  l
+}
+
+/// Returns `⌊√n⌋`.
+fn isqrt(n: usize) -> usize {
+  let mut root = (n as f64).sqrt() as usize;
+  while root * root > n {
+    root -= 1;
+  }
+  while (root + 1) * (root + 1) <= n {
+    root += 1;
+  }
+  root
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+  if b == 0 {
+    a
+  } else {
+    gcd(b, a % b)
+  }
+}
+
+/// Combines `x ≡ r_i mod m_i` for pairwise coprime `m_i` into `x mod Π m_i`.
+fn crt(residues: &[(usize, usize)]) -> usize {
+  let modulus: usize = residues.iter().map(|&(_, m)| m).product();
+  residues.iter().fold(0, |acc, &(r, m)| {
+    let rest = modulus / m;
+    // rest^(φ(m) - 1) is the inverse of rest modulo the prime m
+    let inverse = (0..m - 2).fold(1, |inv, _| inv * (rest % m) % m);
+    (acc + r * rest % modulus * inverse) % modulus
+  })
+}
+
+/// A point of `E` over the ring `GF(p)[x, y] / (h(x), y^2 - f(x))`, where `Affine(X, Y)` stands
+/// for the point `(X(x), y * Y(x))`. Every point obtained from the generic point `(x, y)` by
+/// Frobenius and the group law has this shape.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum RingPoint<const P: usize> {
+  Affine(Vec<PrimeField<P>>, Vec<PrimeField<P>>),
+  Infinity,
+}
+
+/// The group law in `GF(p)[x, y] / (h(x), y^2 - f(x))`. Operations fail with a non-trivial factor
+/// of `h` if they need to invert a zero divisor.
+struct TorsionRing<'a, const P: usize> {
+  modulus: &'a [PrimeField<P>],
+  f:       &'a [PrimeField<P>],
+  a:       PrimeField<P>,
+}
+
+impl<'a, const P: usize> TorsionRing<'a, P> {
+  fn reduce(&self, a: &[PrimeField<P>]) -> Vec<PrimeField<P>> { poly_rem(a, self.modulus) }
+
+  fn mul_mod(&self, a: &[PrimeField<P>], b: &[PrimeField<P>]) -> Vec<PrimeField<P>> {
+    self.reduce(&poly_mul(a, b))
+  }
+
+  /// Returns the inverse of `a`, or the non-trivial factor `gcd(a, h)` of `h`.
+  fn inverse(&self, a: &[PrimeField<P>]) -> Result<Vec<PrimeField<P>>, Vec<PrimeField<P>>> {
+    poly_inverse_mod(a, self.modulus).ok_or_else(|| poly_gcd(a, self.modulus))
+  }
+
+  /// Finishes an addition or doubling with slope `y * lambda`.
+  fn chord(
+    &self,
+    lambda: &[PrimeField<P>],
+    x1: &[PrimeField<P>],
+    x2: &[PrimeField<P>],
+    y1: &[PrimeField<P>],
+  ) -> RingPoint<P> {
+    // (y λ)^2 = f λ^2
+    let x3 = self
+      .reduce(&poly_sub(&poly_sub(&self.mul_mod(self.f, &self.mul_mod(lambda, lambda)), x1), x2));
+    let y3 = self.reduce(&poly_sub(&self.mul_mod(lambda, &poly_sub(x1, &x3)), y1));
+    RingPoint::Affine(x3, y3)
+  }
+
+  fn double(&self, p: &RingPoint<P>) -> Result<RingPoint<P>, Vec<PrimeField<P>>> {
+    let RingPoint::Affine(x, y) = p else { return Ok(RingPoint::Infinity) };
+    if y.is_empty() {
+      return Ok(RingPoint::Infinity);
+    }
+    // λ = (3X^2 + a) / (2yY) = y (3X^2 + a) / (2fY)
+    let numerator = poly_add(&poly_scale(&self.mul_mod(x, x), PrimeField::new(3)), &[self.a]);
+    let denominator = poly_scale(&self.mul_mod(self.f, y), PrimeField::new(2));
+    let lambda = self.mul_mod(&numerator, &self.inverse(&denominator)?);
+    Ok(self.chord(&lambda, x, x, y))
+  }
+
+  fn add(&self, p: &RingPoint<P>, q: &RingPoint<P>) -> Result<RingPoint<P>, Vec<PrimeField<P>>> {
+    let ((x1, y1), (x2, y2)) = match (p, q) {
+      (RingPoint::Infinity, _) => return Ok(q.clone()),
+      (_, RingPoint::Infinity) => return Ok(p.clone()),
+      (RingPoint::Affine(x1, y1), RingPoint::Affine(x2, y2)) => ((x1, y1), (x2, y2)),
+    };
+    let dx = poly_sub(x2, x1);
+    if dx.is_empty() {
+      // (Y1 - Y2)(Y1 + Y2) = 0 since both points share X, so unless one factor vanishes it is
+      // a zero divisor
+      let difference = poly_sub(y1, y2);
+      return if difference.is_empty() {
+        self.double(p)
+      } else if poly_add(y1, y2).is_empty() {
+        Ok(RingPoint::Infinity)
+      } else {
+        Err(poly_gcd(&difference, self.modulus))
+      };
+    }
+    let lambda = self.mul_mod(&poly_sub(y2, y1), &self.inverse(&dx)?);
+    Ok(self.chord(&lambda, x1, x2, y1))
+  }
+
+  fn mul(&self, k: usize, p: &RingPoint<P>) -> Result<RingPoint<P>, Vec<PrimeField<P>>> {
+    let mut result = RingPoint::Infinity;
+    for i in (0..usize::BITS - k.leading_zeros()).rev() {
+      result = self.double(&result)?;
+      if (k >> i) & 1 == 1 {
+        result = self.add(&result, p)?;
+      }
+    }
+    Ok(result)
+  }
+}
+
+// Dense polynomials over GF(p) with coefficients in increasing degree and no trailing zeros, so
+// the zero polynomial is empty. The [`Polynomial`](crate::polynomial::Polynomial) type fixes its
+// degree at compile time, which does not work for the division polynomials.
+
+fn poly_trim<const P: usize>(mut a: Vec<PrimeField<P>>) -> Vec<PrimeField<P>> {
+  while a.last() == Some(&PrimeField::ZERO) {
+    a.pop();
+  }
+  a
+}
+
+fn poly_add<const P: usize>(a: &[PrimeField<P>], b: &[PrimeField<P>]) -> Vec<PrimeField<P>> {
+  let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+  let mut sum = long.to_vec();
+  sum.iter_mut().zip(short).for_each(|(s, &c)| *s += c);
+  poly_trim(sum)
+}
+
+fn poly_sub<const P: usize>(a: &[PrimeField<P>], b: &[PrimeField<P>]) -> Vec<PrimeField<P>> {
+  poly_add(a, &b.iter().map(|&c| -c).collect::<Vec<_>>())
+}
+
+fn poly_scale<const P: usize>(a: &[PrimeField<P>], c: PrimeField<P>) -> Vec<PrimeField<P>> {
+  poly_trim(a.iter().map(|&x| x * c).collect())
+}
+
+fn poly_mul<const P: usize>(a: &[PrimeField<P>], b: &[PrimeField<P>]) -> Vec<PrimeField<P>> {
+  if a.is_empty() || b.is_empty() {
+    return vec![];
+  }
+  let mut product = vec![PrimeField::ZERO; a.len() + b.len() - 1];
+  for (i, &x) in a.iter().enumerate() {
+    for (j, &y) in b.iter().enumerate() {
+      product[i + j] += x * y;
+    }
+  }
+  poly_trim(product)
+}
+
+fn poly_pow<const P: usize>(a: &[PrimeField<P>], e: usize) -> Vec<PrimeField<P>> {
+  (0..e).fold(vec![PrimeField::ONE], |acc, _| poly_mul(&acc, a))
+}
+
+/// Returns the quotient and remainder of `a / b`.
+fn poly_div_rem<const P: usize>(
+  a: &[PrimeField<P>],
+  b: &[PrimeField<P>],
+) -> (Vec<PrimeField<P>>, Vec<PrimeField<P>>) {
+  assert!(!b.is_empty(), "division by the zero polynomial");
+  if a.len() < b.len() {
+    return (vec![], a.to_vec());
+  }
+  let lead_inverse = b.last().unwrap().inverse().unwrap();
+  let mut remainder = a.to_vec();
+  let mut quotient = vec![PrimeField::ZERO; a.len() - b.len() + 1];
+  for i in (0..quotient.len()).rev() {
+    let c = remainder[i + b.len() - 1] * lead_inverse;
+    quotient[i] = c;
+    for (j, &coefficient) in b.iter().enumerate() {
+      remainder[i + j] -= c * coefficient;
+    }
+  }
+  (poly_trim(quotient), poly_trim(remainder))
+}
+
+fn poly_rem<const P: usize>(a: &[PrimeField<P>], b: &[PrimeField<P>]) -> Vec<PrimeField<P>> {
+  poly_div_rem(a, b).1
+}
+
+fn poly_pow_mod<const P: usize>(
+  a: &[PrimeField<P>],
+  mut e: usize,
+  m: &[PrimeField<P>],
+) -> Vec<PrimeField<P>> {
+  let mut base = poly_rem(a, m);
+  let mut result = poly_rem(&[PrimeField::ONE], m);
+  while e > 0 {
+    if e & 1 == 1 {
+      result = poly_rem(&poly_mul(&result, &base), m);
+    }
+    base = poly_rem(&poly_mul(&base, &base), m);
+    e >>= 1;
+  }
+  result
+}
+
+/// Returns the monic greatest common divisor of `a` and `b`.
+fn poly_gcd<const P: usize>(a: &[PrimeField<P>], b: &[PrimeField<P>]) -> Vec<PrimeField<P>> {
+  let (mut a, mut b) = (poly_trim(a.to_vec()), poly_trim(b.to_vec()));
+  while !b.is_empty() {
+    let r = poly_rem(&a, &b);
+    a = b;
+    b = r;
+  }
+  match a.last() {
+    Some(&lead) => poly_scale(&a, lead.inverse().unwrap()),
+    None => a,
+  }
+}
+
+/// Returns the inverse of `a` modulo `m` with the extended Euclidean algorithm, if it exists.
+fn poly_inverse_mod<const P: usize>(
+  a: &[PrimeField<P>],
+  m: &[PrimeField<P>],
+) -> Option<Vec<PrimeField<P>>> {
+  // invariant: s_i * a ≡ r_i mod m
+  let (mut r0, mut r1) = (m.to_vec(), poly_rem(a, m));
+  let (mut s0, mut s1) = (vec![], vec![PrimeField::ONE]);
+  while !r1.is_empty() {
+    let (q, r) = poly_div_rem(&r0, &r1);
+    let s = poly_sub(&s0, &poly_mul(&q, &s1));
+    (r0, r1) = (r1, r);
+    (s0, s1) = (s1, s);
+  }
+  // r0 is the gcd, which must be a non-zero constant
+  if r0.len() != 1 {
+    return None;
+  }
+  Some(poly_rem(&poly_scale(&s0, r0[0].inverse().unwrap()), m))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn division_polynomials_vanish_on_torsion() {
+    let curve =
+      ShortWeierstrass::<PrimeField<101>>::new(PrimeField::new(2), PrimeField::new(7)).unwrap();
+    let psi = curve.division_polynomials(9);
+    let evaluate =
+      |poly: &[PrimeField<101>], x| poly.iter().rev().fold(PrimeField::ZERO, |acc, &c| acc * x + c);
+    for point in curve.points() {
+      let Point::Affine(x, y) = point else { continue };
+      for (n, psi_n) in psi.iter().enumerate().skip(3).filter(|(n, _)| n % 2 == 1) {
+        let is_torsion = curve.mul(n, point) == Point::Infinity;
+        assert_eq!(evaluate(psi_n, x) == PrimeField::ZERO, is_torsion, "n = {n}, P = ({x}, {y})");
+      }
+    }
+  }
+
+  #[test]
+  fn polynomial_inverse() {
+    let m = poly_trim(vec![
+      PrimeField::<17>::new(3),
+      PrimeField::new(1),
+      PrimeField::new(0),
+      PrimeField::new(1),
+    ]);
+    let a = vec![PrimeField::new(5), PrimeField::new(2)];
+    let inverse = poly_inverse_mod(&a, &m).unwrap();
+    assert_eq!(poly_rem(&poly_mul(&a, &inverse), &m), vec![PrimeField::ONE]);
+    assert_eq!(crt(&[(1, 2), (2, 3), (3, 5)]), 23);
+  }
+}
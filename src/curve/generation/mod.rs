@@ -0,0 +1,281 @@
+//! Tools for generating small elliptic curves for teaching, like the Pluto curve in
+//! [`pluto_curve`](super::pluto_curve).
+//!
+//! The [`EllipticCurve`](super::EllipticCurve) trait fixes the curve coefficients at compile time,
+//! which is what the rest of the library wants, but makes it impossible to try many curves. Here a
+//! curve `y^2 = x^3 + ax + b` over a [`PrimeField`] is a runtime value, [`ShortWeierstrass`], with
+//! its own small [`Point`] type. On top of it this module provides:
+//! - point counting, from naive enumeration to Schoof's algorithm, see [`counting`],
+//! - the [`embedding_degree`] of a subgroup, the smallest `k` with `r | p^k - 1`,
+//! - discovery of the `r`-torsion points and of a generator of a subgroup of order `r`,
+//! - a search for [pairing-friendly curves](find_pairing_friendly_curves) over a given prime.
+//!
+//! For example, the Pluto curve can be rediscovered by searching over `GF(101)`:
+//! ```
+//! use ronkathon::{
+//!   algebra::field::prime::PlutoBaseField,
+//!   curve::generation::{find_pairing_friendly_curves, ShortWeierstrass},
+//! };
+//!
+//! let curves = find_pairing_friendly_curves::<101>(2, 17);
+//! let pluto = curves
+//!   .iter()
+//!   .find(|c| c.curve == ShortWeierstrass::new(PlutoBaseField::new(0), PlutoBaseField::new(3)).unwrap())
+//!   .unwrap();
+//! assert_eq!((pluto.order, pluto.r, pluto.embedding_degree), (102, 17, 2));
+//! ```
+use std::ops::Neg;
+
+use super::{AffinePoint, EllipticCurve};
+use crate::algebra::field::{prime::PrimeField, Field};
+
+pub mod counting;
+#[cfg(test)] mod tests;
+
+/// A point on a [`ShortWeierstrass`] curve.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Point<F: Field> {
+  /// A point with affine coordinates `(x, y)`.
+  Affine(F, F),
+
+  /// The point at infinity.
+  Infinity,
+}
+
+impl<F: Field> Neg for Point<F> {
+  type Output = Self;
+
+  fn neg(self) -> Self {
+    match self {
+      Point::Affine(x, y) => Point::Affine(x, -y),
+      Point::Infinity => Point::Infinity,
+    }
+  }
+}
+
+impl<C: EllipticCurve> From<AffinePoint<C>> for Point<C::BaseField> {
+  fn from(point: AffinePoint<C>) -> Self {
+    match point {
+      AffinePoint::Point(x, y) => Point::Affine(x, y),
+      AffinePoint::Infinity => Point::Infinity,
+    }
+  }
+}
+
+/// The curve `y^2 = x^3 + ax + b` over the field `F`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ShortWeierstrass<F: Field> {
+  /// Coefficient `a` of the curve equation.
+  pub a: F,
+  /// Coefficient `b` of the curve equation.
+  pub b: F,
+}
+
+impl<F: Field> ShortWeierstrass<F> {
+  /// Creates the curve `y^2 = x^3 + ax + b`, returning `None` if it is singular, i.e.
+  /// `4a^3 + 27b^2 = 0`.
+  pub fn new(a: F, b: F) -> Option<Self> {
+    let curve = Self { a, b };
+    (curve.discriminant() != F::ZERO).then_some(curve)
+  }
+
+  /// Returns the runtime description of the curve `C`.
+  pub fn from_curve<C: EllipticCurve<BaseField = F>>() -> Self {
+    Self { a: C::EQUATION_A.into(), b: C::EQUATION_B.into() }
+  }
+
+  /// The discriminant `-16(4a^3 + 27b^2)` of the curve.
+  pub fn discriminant(&self) -> F {
+    -F::from(16) * (F::from(4) * self.a.pow(3) + F::from(27) * self.b.pow(2))
+  }
+
+  /// Evaluates the right hand side `x^3 + ax + b` of the curve equation.
+  pub fn rhs(&self, x: F) -> F { x * x * x + self.a * x + self.b }
+
+  /// Checks whether a point lies on the curve.
+  pub fn contains(&self, point: &Point<F>) -> bool {
+    match point {
+      Point::Affine(x, y) => *y * *y == self.rhs(*x),
+      Point::Infinity => true,
+    }
+  }
+
+  /// Adds two points using the chord and tangent rule.
+  pub fn add(&self, p: Point<F>, q: Point<F>) -> Point<F> {
+    let ((x1, y1), (x2, y2)) = match (p, q) {
+      (Point::Infinity, _) => return q,
+      (_, Point::Infinity) => return p,
+      (Point::Affine(x1, y1), Point::Affine(x2, y2)) => ((x1, y1), (x2, y2)),
+    };
+    let lambda = if x1 != x2 {
+      (y2 - y1) / (x2 - x1)
+    } else if y1 == y2 && y1 != F::ZERO {
+      (F::from(3) * x1 * x1 + self.a) / (F::from(2) * y1)
+    } else {
+      return Point::Infinity;
+    };
+    let x3 = lambda * lambda - x1 - x2;
+    Point::Affine(x3, lambda * (x1 - x3) - y1)
+  }
+
+  /// Computes `[k]P` with double-and-add.
+  pub fn mul(&self, k: usize, point: Point<F>) -> Point<F> {
+    let mut result = Point::Infinity;
+    for i in (0..usize::BITS - k.leading_zeros()).rev() {
+      result = self.add(result, result);
+      if (k >> i) & 1 == 1 {
+        result = self.add(result, point);
+      }
+    }
+    result
+  }
+
+  /// Returns the order of `point` given any multiple `n` of it, e.g. the number of points on the
+  /// curve, by removing prime factors of `n` for as long as `[n / q]P = O`.
+  pub fn order_of(&self, point: Point<F>, n: usize) -> usize {
+    debug_assert_eq!(self.mul(n, point), Point::Infinity);
+    let mut order = n;
+    for (q, _) in factor(n) {
+      while order % q == 0 && self.mul(order / q, point) == Point::Infinity {
+        order /= q;
+      }
+    }
+    order
+  }
+}
+
+impl<const P: usize> ShortWeierstrass<PrimeField<P>> {
+  /// Returns every point of `E(GF(P))`, starting with the point at infinity and then ordered by
+  /// `x` coordinate.
+  pub fn points(&self) -> Vec<Point<PrimeField<P>>> {
+    let mut points = vec![Point::Infinity];
+    for x in 0..P {
+      let x = PrimeField::new(x);
+      if let Some((y0, y1)) = self.rhs(x).sqrt() {
+        points.push(Point::Affine(x, y0));
+        if y1 != y0 {
+          points.push(Point::Affine(x, y1));
+        }
+      }
+    }
+    points
+  }
+
+  /// Returns the `r`-torsion points `E(GF(P))[r] = { P : [r]P = O }`, including the point at
+  /// infinity.
+  pub fn torsion_points(&self, r: usize) -> Vec<Point<PrimeField<P>>> {
+    self.points().into_iter().filter(|&point| self.mul(r, point) == Point::Infinity).collect()
+  }
+
+  /// Finds a point of prime order `r`, if `r` divides the number of points `n`, by clearing the
+  /// cofactor `n / r` of every point until one survives.
+  pub fn generator_of_order(&self, r: usize) -> Option<Point<PrimeField<P>>> {
+    let n = self.count_points();
+    if !is_prime(r) || n % r != 0 {
+      return None;
+    }
+    self
+      .points()
+      .into_iter()
+      .map(|point| self.mul(n / r, point))
+      .find(|&point| point != Point::Infinity)
+  }
+}
+
+/// Returns the embedding degree of a subgroup of order `r` of a curve over `GF(p)`: the smallest
+/// `k` such that `r` divides `p^k - 1`, or `None` if there is no such `k <= max_k`.
+///
+/// The embedding degree is the degree of the extension field the pairing values live in, so it
+/// has to be small for a pairing to be computable and large enough for the discrete logarithm in
+/// `GF(p^k)` to be hard.
+pub fn embedding_degree(p: usize, r: usize, max_k: usize) -> Option<usize> {
+  let p = (p % r) as u128;
+  let mut p_k = 1u128;
+  for k in 1..=max_k {
+    p_k = p_k * p % r as u128;
+    if p_k == 1 {
+      return Some(k);
+    }
+  }
+  None
+}
+
+/// A curve with a subgroup of prime order `r` and small embedding degree found by
+/// [`find_pairing_friendly_curves`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PairingFriendlyCurve<const P: usize> {
+  /// The curve.
+  pub curve:            ShortWeierstrass<PrimeField<P>>,
+  /// Number of points on the curve over `GF(P)`.
+  pub order:            usize,
+  /// Largest prime factor of the number of points.
+  pub r:                usize,
+  /// The cofactor `order / r`.
+  pub cofactor:         usize,
+  /// Embedding degree of the subgroup of order `r`.
+  pub embedding_degree: usize,
+  /// A generator of the subgroup of order `r`.
+  pub generator:        Point<PrimeField<P>>,
+}
+
+/// Searches every non-singular curve over `GF(P)` for a subgroup of prime order `r >= min_r`
+/// with embedding degree at most `max_k`.
+///
+/// Anomalous curves with `r = P` are skipped, as the discrete logarithm on them is easy.
+pub fn find_pairing_friendly_curves<const P: usize>(
+  max_k: usize,
+  min_r: usize,
+) -> Vec<PairingFriendlyCurve<P>> {
+  let mut curves = vec![];
+  for a in 0..P {
+    for b in 0..P {
+      let Some(curve) = ShortWeierstrass::new(PrimeField::new(a), PrimeField::new(b)) else {
+        continue;
+      };
+      let order = curve.count_points();
+      let r = factor(order).last().map_or(1, |&(q, _)| q);
+      if r < min_r || r == P {
+        continue;
+      }
+      let Some(embedding_degree) = embedding_degree(P, r, max_k) else {
+        continue;
+      };
+      let generator = curve.generator_of_order(r).expect("r divides the order");
+      curves.push(PairingFriendlyCurve {
+        curve,
+        order,
+        r,
+        cofactor: order / r,
+        embedding_degree,
+        generator,
+      });
+    }
+  }
+  curves
+}
+
+/// Trial division primality test.
+pub(crate) fn is_prime(n: usize) -> bool {
+  n >= 2 && (2..).take_while(|i| i * i <= n).all(|i| n % i != 0)
+}
+
+/// Factors `n` by trial division into `(prime, exponent)` pairs in increasing order of primes.
+pub(crate) fn factor(mut n: usize) -> Vec<(usize, u32)> {
+  let mut factors = vec![];
+  let mut q = 2;
+  while q * q <= n {
+    let mut e = 0;
+    while n % q == 0 {
+      n /= q;
+      e += 1;
+    }
+    if e > 0 {
+      factors.push((q, e));
+    }
+    q += 1;
+  }
+  if n > 1 {
+    factors.push((n, 1));
+  }
+  factors
+}
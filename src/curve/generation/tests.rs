@@ -0,0 +1,84 @@
+use super::*;
+use crate::{
+  algebra::group::FiniteCyclicGroup,
+  curve::{pluto_curve::PlutoBaseCurve, AffinePoint},
+};
+
+type F101 = PrimeField<101>;
+
+fn pluto() -> ShortWeierstrass<F101> { ShortWeierstrass::from_curve::<PlutoBaseCurve>() }
+
+#[test]
+fn singular_curves_are_rejected() {
+  assert!(ShortWeierstrass::new(F101::ZERO, F101::ZERO).is_none());
+  // 4 * (-3)^3 + 27 * 2^2 = 0
+  assert!(ShortWeierstrass::new(-F101::new(3), F101::new(2)).is_none());
+  assert!(ShortWeierstrass::new(F101::ZERO, F101::new(3)).is_some());
+}
+
+#[test]
+fn group_law_matches_affine_point() {
+  let curve = pluto();
+  let generator = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+  let g = Point::from(generator);
+  assert!(curve.contains(&g));
+  for k in 0..20u32 {
+    assert_eq!(curve.mul(k as usize, g), (k * generator).into());
+  }
+  assert_eq!(curve.add(g, -g), Point::Infinity);
+}
+
+#[test]
+fn pluto_parameters() {
+  let curve = pluto();
+  assert_eq!(curve.points().len(), 102);
+  assert_eq!(curve.count_points_naive(), 102);
+  assert_eq!(curve.count_points_bsgs(), 102);
+  assert_eq!(curve.count_points_schoof(), 102);
+  assert_eq!(embedding_degree(101, 17, 12), Some(2));
+
+  let torsion = curve.torsion_points(17);
+  assert_eq!(torsion.len(), 17);
+  assert!(torsion.iter().all(|&point| curve.order_of(point, 17) == 17 || point == Point::Infinity));
+  let g = curve.generator_of_order(17).unwrap();
+  assert_eq!(curve.order_of(g, 102), 17);
+  assert!(curve.generator_of_order(5).is_none());
+}
+
+#[test]
+fn counting_methods_agree() {
+  for (a, b) in [(1, 1), (2, 7), (0, 3), (5, 0), (17, 42), (100, 100)] {
+    let Some(curve) = ShortWeierstrass::new(F101::new(a), F101::new(b)) else { continue };
+    let n = curve.count_points_naive();
+    assert_eq!(curve.count_points_bsgs(), n, "a = {a}, b = {b}");
+    assert_eq!(curve.count_points_schoof(), n, "a = {a}, b = {b}");
+  }
+  for (a, b) in [(1, 1), (3, 8), (123, 567)] {
+    let curve =
+      ShortWeierstrass::<PrimeField<1009>>::new(PrimeField::new(a), PrimeField::new(b)).unwrap();
+    let n = curve.count_points_naive();
+    assert_eq!(curve.count_points_bsgs(), n, "a = {a}, b = {b}");
+    assert_eq!(curve.count_points_schoof(), n, "a = {a}, b = {b}");
+  }
+}
+
+#[test]
+fn embedding_degrees() {
+  // r | p - 1
+  assert_eq!(embedding_degree(101, 5, 12), Some(1));
+  // 7^3 = 343 = 1 mod 19
+  assert_eq!(embedding_degree(7, 19, 12), Some(3));
+  assert_eq!(embedding_degree(101, 103, 12), None);
+}
+
+#[test]
+fn search_finds_pluto_curve() {
+  let curves = find_pairing_friendly_curves::<101>(2, 17);
+  let found = curves.iter().find(|c| c.curve == pluto()).unwrap();
+  assert_eq!((found.order, found.r, found.cofactor, found.embedding_degree), (102, 17, 6, 2));
+  for c in &curves {
+    assert!(c.r >= 17 && c.embedding_degree <= 2);
+    assert_eq!(c.curve.count_points(), c.order);
+    assert_eq!(c.curve.order_of(c.generator, c.order), c.r);
+  }
+}
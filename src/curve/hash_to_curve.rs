@@ -0,0 +1,343 @@
+//! Hashing arbitrary byte strings to points on an [`EllipticCurve`], following
+//! [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380.html).
+//!
+//! Hashing to a curve is done in three steps:
+//! 1. [`hash_to_field`] expands the message with
+//!    [`expand_message_xmd`](crate::hashes::sha::SHA::expand_message_xmd) and reduces the output
+//!    into field elements `u`. Each element uses `L = ceil((ceil(log2(p)) + k) / 8)` bytes for a
+//!    security level of `k = 128` bits, so the result is statistically close to uniform.
+//! 2. [`HashToCurve::map_to_curve`] deterministically maps each `u` to a point. The simplified
+//!    Shallue-van de Woestijne-Ulas map ([`sswu`]) only works for curves `y^2 = x^3 + Ax + B` with
+//!    `AB != 0`, so curves with `A = 0` such as [`PlutoBaseCurve`] are handled by mapping onto an
+//!    isogenous curve with `AB != 0` and pushing the point through the isogeny ([`iso_map`]).
+//! 3. The point is multiplied by the cofactor so that it lands in the prime order subgroup.
+//!
+//! [`hash_to_curve`] adds the images of two field elements, which makes the output
+//! indistinguishable from a random oracle, while [`encode_to_curve`] uses only one and is cheaper
+//! but only covers about half of the points.
+//!
+//! [`try_and_increment`] is a simpler alternative that works for any curve: hash the message with a
+//! counter until the result is the `x` coordinate of a point. It is easy to follow but not constant
+//! time, as the number of attempts depends on the message.
+use super::{pluto_curve::PlutoBaseCurve, *};
+use crate::{
+  algebra::field::{
+    extension::GaloisField,
+    prime::{PlutoBaseField, PrimeField},
+    SqrtField,
+  },
+  hashes::sha::Sha256,
+};
+
+/// Target security level `k` in bits, used to size the byte strings reduced into field elements.
+const SECURITY_BITS: usize = 128;
+
+/// Field elements that can be derived from uniformly random bytes.
+pub trait HashToField: Sized {
+  /// Number of bytes `L` reduced into each element of the prime subfield.
+  const L: usize;
+
+  /// Extension degree `m` of the field over its prime subfield.
+  const DEGREE: usize;
+
+  /// Builds an element from `DEGREE * L` uniformly random bytes, each chunk of `L` bytes being
+  /// interpreted as a big-endian integer and reduced modulo the characteristic.
+  fn from_uniform_bytes(bytes: &[u8]) -> Self;
+}
+
+impl<const P: usize> HashToField for PrimeField<P> {
+  const DEGREE: usize = 1;
+  const L: usize = ((usize::BITS - (P - 1).leading_zeros()) as usize + SECURITY_BITS).div_ceil(8);
+
+  fn from_uniform_bytes(bytes: &[u8]) -> Self {
+    let value = bytes.iter().fold(0u128, |acc, &b| (acc * 256 + b as u128) % P as u128);
+    Self::new(value as usize)
+  }
+}
+
+impl<const N: usize, const P: usize> HashToField for GaloisField<N, P> {
+  const DEGREE: usize = N;
+  const L: usize = PrimeField::<P>::L;
+
+  fn from_uniform_bytes(bytes: &[u8]) -> Self {
+    let mut coeffs = [PrimeField::<P>::ZERO; N];
+    for (coeff, chunk) in coeffs.iter_mut().zip(bytes.chunks(Self::L)) {
+      *coeff = PrimeField::<P>::from_uniform_bytes(chunk);
+    }
+    Self::new(coeffs)
+  }
+}
+
+/// Hashes `msg` to `count` field elements using `expand_message_xmd` with SHA-256 and the domain
+/// separation tag `dst`.
+pub fn hash_to_field<F: HashToField>(msg: &[u8], dst: &[u8], count: usize) -> Vec<F> {
+  let len_per_element = F::DEGREE * F::L;
+  let uniform_bytes = Sha256::new().expand_message_xmd(msg, dst, count * len_per_element);
+  uniform_bytes.chunks(len_per_element).map(F::from_uniform_bytes).collect()
+}
+
+/// A curve `y^2 = x^3 + Ax + B` with `AB != 0` onto which the simplified SWU map can be applied.
+pub trait SwuCurve: EllipticCurve {
+  /// The constant `Z` from [RFC 9380 §6.6.2](https://www.rfc-editor.org/rfc/rfc9380.html#section-6.6.2):
+  /// a non-square, not equal to `-1`, such that `g(x) - Z` is irreducible and `g(B / (ZA))` is a
+  /// square, where `g(x) = x^3 + Ax + B`.
+  const Z: Self::BaseField;
+}
+
+/// An isogeny from a [`SwuCurve`] to a curve with `A = 0`, given by the rational maps
+/// `(x, y) -> (x_num(x) / x_den(x), y * y_num(x) / y_den(x))`.
+///
+/// Coefficients are listed from the constant term upwards.
+pub trait Isogeny {
+  /// The curve the simplified SWU map is evaluated on.
+  type Domain: SwuCurve;
+
+  /// The curve the isogeny maps to.
+  type Codomain: EllipticCurve<BaseField = <Self::Domain as EllipticCurve>::BaseField>;
+
+  /// Numerator of the `x` coordinate map.
+  const X_NUM: &'static [<Self::Domain as EllipticCurve>::BaseField];
+
+  /// Denominator of the `x` coordinate map.
+  const X_DEN: &'static [<Self::Domain as EllipticCurve>::BaseField];
+
+  /// Numerator of the `y` coordinate map.
+  const Y_NUM: &'static [<Self::Domain as EllipticCurve>::BaseField];
+
+  /// Denominator of the `y` coordinate map.
+  const Y_DEN: &'static [<Self::Domain as EllipticCurve>::BaseField];
+}
+
+/// A curve supporting RFC 9380 style hashing.
+pub trait HashToCurve: EllipticCurve {
+  /// The cofactor `h` such that `[h]P` lies in the prime order subgroup for every point `P`.
+  const COFACTOR: usize;
+
+  /// Deterministically maps a field element to a point on the curve.
+  fn map_to_curve(u: Self::BaseField) -> AffinePoint<Self>;
+}
+
+/// The curve `y^2 = x^3 + 2x + 2` over [`PlutoBaseField`], which is 2-isogenous to
+/// [`PlutoBaseCurve`]. It has the same number of points, so its order 17 subgroup is mapped onto
+/// the order 17 subgroup of the Pluto curve.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, PartialOrd, Ord)]
+pub struct PlutoIsoCurve;
+
+impl EllipticCurve for PlutoIsoCurve {
+  type BaseField = PlutoBaseField;
+  type Coefficient = PlutoBaseField;
+  type ScalarField = PlutoScalarField;
+
+  const EQUATION_A: Self::Coefficient = PlutoBaseField::new(2);
+  const EQUATION_B: Self::Coefficient = PlutoBaseField::new(2);
+  const GENERATOR: (Self::BaseField, Self::BaseField) =
+    (PlutoBaseField::ONE, PlutoBaseField::new(45));
+  const ORDER: usize = 17;
+}
+
+impl SwuCurve for PlutoIsoCurve {
+  const Z: PlutoBaseField = PlutoBaseField::new(8);
+}
+
+/// The 2-isogeny `PlutoIsoCurve -> PlutoBaseCurve`.
+///
+/// It was found by applying Vélu's formulas to the 2-torsion point `(17, 0)` of [`PlutoIsoCurve`],
+/// which gives an isogeny onto `y^2 = x^3 + 15`, and composing it with the isomorphism
+/// `(x, y) -> (19^2 x, 19^3 y)` onto `y^2 = x^3 + 3`.
+#[derive(Copy, Clone, Debug)]
+pub struct PlutoIsogeny;
+
+impl Isogeny for PlutoIsogeny {
+  type Codomain = PlutoBaseCurve;
+  type Domain = PlutoIsoCurve;
+
+  const X_DEN: &'static [PlutoBaseField] = &[PlutoBaseField::new(84), PlutoBaseField::ONE];
+  const X_NUM: &'static [PlutoBaseField] =
+    &[PlutoBaseField::new(3), PlutoBaseField::new(24), PlutoBaseField::new(58)];
+  const Y_DEN: &'static [PlutoBaseField] =
+    &[PlutoBaseField::new(87), PlutoBaseField::new(67), PlutoBaseField::ONE];
+  const Y_NUM: &'static [PlutoBaseField] =
+    &[PlutoBaseField::new(69), PlutoBaseField::new(3), PlutoBaseField::new(92)];
+}
+
+impl HashToCurve for PlutoBaseCurve {
+  /// `E(GF(101))` has `102 = 6 * 17` points.
+  const COFACTOR: usize = 6;
+
+  fn map_to_curve(u: PlutoBaseField) -> AffinePoint<Self> {
+    iso_map::<PlutoIsogeny>(sswu::<PlutoIsoCurve>(u))
+  }
+}
+
+/// The simplified Shallue-van de Woestijne-Ulas map from
+/// [RFC 9380 §6.6.2](https://www.rfc-editor.org/rfc/rfc9380.html#section-6.6.2).
+///
+/// With `g(x) = x^3 + Ax + B`, the candidates `x1 = -B/A * (1 + 1 / (Z^2 u^4 + Z u^2))` and
+/// `x2 = Z u^2 x1` satisfy `g(x2) = Z^3 u^6 g(x1)`. As `Z` is a non-square, exactly one of `g(x1)`
+/// and `g(x2)` is a square and gives a point on the curve. The sign of `y` is matched to the sign
+/// of `u`.
+pub fn sswu<C: SwuCurve>(u: C::BaseField) -> AffinePoint<C>
+where C::BaseField: SqrtField {
+  let a: C::BaseField = C::EQUATION_A.into();
+  let b: C::BaseField = C::EQUATION_B.into();
+  let z = C::Z;
+  let g = |x: C::BaseField| x * x * x + a * x + b;
+
+  let z_u2 = z * u * u;
+  let tv1 = (z_u2 * z_u2 + z_u2).inverse();
+  let x1 = match tv1 {
+    Some(tv1) => -b / a * (C::BaseField::ONE + tv1),
+    // exceptional case: Z^2 u^4 + Z u^2 = 0
+    None => b / (z * a),
+  };
+  let x2 = z_u2 * x1;
+
+  let (x, (y0, y1)) = match g(x1).sqrt() {
+    Some(roots) => (x1, roots),
+    None => (x2, g(x2).sqrt().expect("g(x2) is a square whenever g(x1) is not")),
+  };
+  let y = if y0.sgn0() == u.sgn0() { y0 } else { y1 };
+  AffinePoint::new(x, y)
+}
+
+/// Evaluates the polynomial with the given coefficients at `x` using Horner's rule.
+fn evaluate<F: Field>(coeffs: &[F], x: F) -> F {
+  coeffs.iter().rev().fold(F::ZERO, |acc, &c| acc * x + c)
+}
+
+/// Maps a point through the rational maps of an [`Isogeny`].
+pub fn iso_map<I: Isogeny>(point: AffinePoint<I::Domain>) -> AffinePoint<I::Codomain> {
+  let (x, y) = match point {
+    AffinePoint::Point(x, y) => (x, y),
+    AffinePoint::Infinity => return AffinePoint::Infinity,
+  };
+  // the denominators vanish exactly on the kernel of the isogeny
+  let (x_den, y_den) = match (evaluate(I::X_DEN, x).inverse(), evaluate(I::Y_DEN, x).inverse()) {
+    (Some(x_den), Some(y_den)) => (x_den, y_den),
+    _ => return AffinePoint::Infinity,
+  };
+  AffinePoint::new(evaluate(I::X_NUM, x) * x_den, y * evaluate(I::Y_NUM, x) * y_den)
+}
+
+/// Multiplies a point by the cofactor `h`, see [`AffinePoint::mul_integer`].
+pub fn clear_cofactor<C: EllipticCurve>(point: AffinePoint<C>, h: usize) -> AffinePoint<C> {
+  point.mul_integer(h)
+}
+
+/// Hashes `msg` to a point in the prime order subgroup, as `hash_to_curve` from RFC 9380 §3.
+///
+/// Two field elements are mapped to the curve and added before clearing the cofactor, so the
+/// result is distributed uniformly and can be used as a random oracle.
+pub fn hash_to_curve<C: HashToCurve>(msg: &[u8], dst: &[u8]) -> AffinePoint<C>
+where C::BaseField: HashToField {
+  let u = hash_to_field::<C::BaseField>(msg, dst, 2);
+  let q = C::map_to_curve(u[0]) + C::map_to_curve(u[1]);
+  clear_cofactor(q, C::COFACTOR)
+}
+
+/// Encodes `msg` as a point in the prime order subgroup, as `encode_to_curve` from RFC 9380 §3.
+///
+/// Only a single field element is mapped to the curve, which is cheaper than [`hash_to_curve`]
+/// but the output is not uniformly distributed.
+pub fn encode_to_curve<C: HashToCurve>(msg: &[u8], dst: &[u8]) -> AffinePoint<C>
+where C::BaseField: HashToField {
+  let u = hash_to_field::<C::BaseField>(msg, dst, 1);
+  clear_cofactor(C::map_to_curve(u[0]), C::COFACTOR)
+}
+
+/// Hashes `msg` to a point of order dividing `(#E / h)` by trying successive counters.
+///
+/// For `ctr = 0, 1, ...`, two field elements `(x, s)` are derived from `msg || ctr`. If
+/// `x^3 + ax + b` is a square, the root with the same sign as `s` is chosen for `y` and the point
+/// `[h](x, y)` is returned, unless it is the point at infinity. Each attempt succeeds with
+/// probability about one half.
+///
+/// ## Panics
+/// Panics if no point is found after 256 attempts, which happens with negligible probability.
+pub fn try_and_increment<C: EllipticCurve>(msg: &[u8], dst: &[u8], h: usize) -> AffinePoint<C>
+where C::BaseField: HashToField + SqrtField {
+  let a: C::BaseField = C::EQUATION_A.into();
+  let b: C::BaseField = C::EQUATION_B.into();
+  for ctr in 0..=u8::MAX {
+    let u = hash_to_field::<C::BaseField>(&[msg, &[ctr]].concat(), dst, 2);
+    let (x, sign) = (u[0], u[1].sgn0());
+    if let Some((y0, y1)) = (x * x * x + a * x + b).sqrt() {
+      let y = if y0.sgn0() == sign { y0 } else { y1 };
+      let point = clear_cofactor(AffinePoint::new(x, y), h);
+      if point != AffinePoint::Infinity {
+        return point;
+      }
+    }
+  }
+  panic!("no point found after 256 attempts");
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::curve::pluto_curve::PlutoExtendedCurve;
+
+  const DST: &[u8] = b"RONKATHON-V01-CS02-with-PLUTO_XMD:SHA-256_SSWU_RO_";
+
+  fn has_order_17<C: EllipticCurve>(point: AffinePoint<C>) -> bool {
+    point != AffinePoint::Infinity && clear_cofactor(point, 17) == AffinePoint::Infinity
+  }
+
+  #[test]
+  fn isogeny_is_homomorphism() {
+    let g = AffinePoint::<PlutoIsoCurve>::GENERATOR;
+    let image = iso_map::<PlutoIsogeny>(g);
+    assert!(has_order_17(image));
+    for i in 0..17 {
+      let p = PlutoScalarField::new(i);
+      assert_eq!(iso_map::<PlutoIsogeny>(g * p), image * p);
+    }
+  }
+
+  #[test]
+  fn sswu_covers_curve() {
+    // every u yields a valid point, and the map hits many distinct points
+    let mut images = vec![];
+    for u in 0..101 {
+      let point = sswu::<PlutoIsoCurve>(PlutoBaseField::new(u));
+      assert!(point.is_on_curve());
+      if !images.contains(&point) {
+        images.push(point);
+      }
+    }
+    assert!(images.len() > 30);
+  }
+
+  #[test]
+  fn hash_to_curve_lands_in_subgroup() {
+    let p = hash_to_curve::<PlutoBaseCurve>(b"abc", DST);
+    assert!(has_order_17(p));
+    assert_eq!(p, hash_to_curve::<PlutoBaseCurve>(b"abc", DST));
+
+    let q = encode_to_curve::<PlutoBaseCurve>(b"abc", DST);
+    assert_eq!(clear_cofactor(q, 17), AffinePoint::Infinity);
+
+    let outputs: Vec<_> = (0..20u8).map(|i| hash_to_curve::<PlutoBaseCurve>(&[i], DST)).collect();
+    assert!(outputs.iter().any(|&o| o != outputs[0]));
+  }
+
+  #[test]
+  fn hash_to_field_depends_on_dst() {
+    let u = hash_to_field::<PlutoBaseField>(b"msg", b"DST-A", 2);
+    let v = hash_to_field::<PlutoBaseField>(b"msg", b"DST-B", 2);
+    assert_eq!(u.len(), 2);
+    assert_eq!(PlutoBaseField::L, 17);
+    assert_ne!(u, v);
+  }
+
+  #[test]
+  fn try_and_increment_on_both_curves() {
+    let p = try_and_increment::<PlutoBaseCurve>(b"abc", DST, 6);
+    assert!(has_order_17(p));
+
+    // E(GF(101^2)) is isomorphic to Z/102 x Z/102, so multiplying by 6 lands in E[17]
+    let q = try_and_increment::<PlutoExtendedCurve>(b"abc", DST, 6);
+    assert!(q.is_on_curve());
+    assert!(has_order_17(q));
+  }
+}
@@ -0,0 +1,77 @@
+//! Exploring the graph of `ℓ`-isogenies between curves over a prime field.
+//!
+//! The vertices of the graph are j-invariants and every subgroup of order `ℓ` of `E(GF(p))` gives
+//! an edge from `j(E)` to the j-invariant of the codomain of the isogeny with that kernel. Only
+//! isogenies with a kernel made of rational points are found, which over `GF(p)` shows the
+//! "volcano" structure of ordinary curves: all curves in the graph have the same number of points,
+//! as isogenous curves always do.
+use std::collections::{HashMap, VecDeque};
+
+use super::{Isogeny, Point, ShortWeierstrass};
+use crate::{algebra::field::prime::PrimeField, curve::generation::is_prime};
+
+/// The connected component of the `ℓ`-isogeny graph around a curve.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IsogenyGraph<const P: usize> {
+  /// The degree `ℓ` of the isogenies in the graph.
+  pub degree: usize,
+  /// One curve for every j-invariant in the graph, in the order they were discovered.
+  pub curves: Vec<ShortWeierstrass<PrimeField<P>>>,
+  /// Edges between indices of [`IsogenyGraph::curves`], one for every kernel. An edge appears once
+  /// for each direction it was found in, and may be a loop.
+  pub edges:  Vec<(usize, usize)>,
+}
+
+impl<const P: usize> IsogenyGraph<P> {
+  /// Explores the `ℓ`-isogeny graph breadth first, starting from `curve`.
+  ///
+  /// ## Panics
+  /// Panics if `l` is not prime.
+  pub fn explore(curve: ShortWeierstrass<PrimeField<P>>, l: usize) -> Self {
+    assert!(is_prime(l), "the isogeny degree must be prime");
+    let mut graph = Self { degree: l, curves: vec![curve], edges: vec![] };
+    let mut index = HashMap::from([(curve.j_invariant(), 0)]);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(i) = queue.pop_front() {
+      let domain = graph.curves[i];
+      for generator in kernels_of_order(&domain, l) {
+        let codomain = Isogeny::new(domain, &[generator]).codomain();
+        let j = *index.entry(codomain.j_invariant()).or_insert_with(|| {
+          graph.curves.push(codomain);
+          queue.push_back(graph.curves.len() - 1);
+          graph.curves.len() - 1
+        });
+        graph.edges.push((i, j));
+      }
+    }
+    graph
+  }
+
+  /// The j-invariants of the curves in the graph.
+  pub fn j_invariants(&self) -> Vec<PrimeField<P>> {
+    self.curves.iter().map(ShortWeierstrass::j_invariant).collect()
+  }
+
+  /// The indices of the curves that curve `i` has an edge to.
+  pub fn neighbours(&self, i: usize) -> Vec<usize> {
+    self.edges.iter().filter(|&&(from, _)| from == i).map(|&(_, to)| to).collect()
+  }
+}
+
+/// Returns one generator of every subgroup of prime order `l` of `E(GF(P))`.
+pub fn kernels_of_order<const P: usize>(
+  curve: &ShortWeierstrass<PrimeField<P>>,
+  l: usize,
+) -> Vec<Point<PrimeField<P>>> {
+  let mut seen = vec![Point::Infinity];
+  let mut generators = vec![];
+  for point in curve.torsion_points(l) {
+    if seen.contains(&point) {
+      continue;
+    }
+    generators.push(point);
+    seen.extend((1..l).map(|k| curve.mul(k, point)));
+  }
+  generators
+}
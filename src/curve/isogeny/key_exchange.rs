@@ -0,0 +1,119 @@
+//! A toy version of the [supersingular isogeny Diffie–Hellman](https://en.wikipedia.org/wiki/Supersingular_isogeny_key_exchange)
+//! (SIDH) key exchange on the Pluto curve.
+//!
+//! Alice and Bob share a starting curve `E` together with bases `(P_A, Q_A)` of `E[N_A]` and
+//! `(P_B, Q_B)` of `E[N_B]` for coprime `N_A` and `N_B`:
+//! 1. Alice picks a secret `s_A`, computes the isogeny `φ_A: E -> E_A` with kernel `<P_A +
+//!    [s_A]Q_A>` and publishes `E_A` along with `φ_A(P_B)` and `φ_A(Q_B)`. Bob does the same with
+//!    the roles swapped.
+//! 2. Alice computes the isogeny from `E_B` with kernel `<φ_B(P_A) + [s_A]φ_B(Q_A)>`, and Bob the
+//!    one from `E_A` with kernel `<φ_A(P_B) + [s_B]φ_A(Q_B)>`. Both kernels are images of `<P_A +
+//!    [s_A]Q_A, P_B + [s_B]Q_B>`, so the two codomains are isomorphic and their j-invariant is the
+//!    shared secret.
+//!
+//! The Pluto curve `y^2 = x^3 + 3` is supersingular and `E(GF(101^2))` is the full torsion
+//! `E[102] ≅ Z/102 × Z/102`, so with `102 = 2 * 3 * 17` it supports `N_A = 3` and `N_B = 17`. With
+//! three and seventeen possible secrets the exchange is only a demonstration. SIDH itself is broken
+//! for any parameters by the [Castryck–Decru attack](https://eprint.iacr.org/2022/975), which uses
+//! exactly the torsion point images published in step 1.
+use super::{Isogeny, Point, ShortWeierstrass};
+use crate::{
+  algebra::field::{extension::PlutoBaseFieldExtension, prime::PlutoBaseField, Field},
+  curve::pluto_curve::PlutoExtendedCurve,
+};
+
+/// A basis `(P, Q)` of the `N`-torsion `E[N] ≅ Z/N × Z/N`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TorsionBasis<F: Field> {
+  /// The order `N` of both basis points.
+  pub order: usize,
+  /// The first basis point.
+  pub p:     Point<F>,
+  /// The second basis point.
+  pub q:     Point<F>,
+}
+
+/// A public key: the codomain of the secret isogeny and the images of the other party's basis.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey<F: Field> {
+  /// The codomain `E_A` or `E_B` of the secret isogeny.
+  pub curve: ShortWeierstrass<F>,
+  /// Image of the other party's first basis point.
+  pub p:     Point<F>,
+  /// Image of the other party's second basis point.
+  pub q:     Point<F>,
+}
+
+/// Public parameters of a SIDH key exchange.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Sidh<F: Field> {
+  /// The starting curve.
+  pub curve: ShortWeierstrass<F>,
+  /// Alice's torsion basis.
+  pub alice: TorsionBasis<F>,
+  /// Bob's torsion basis.
+  pub bob:   TorsionBasis<F>,
+}
+
+impl<F: Field> Sidh<F> {
+  /// Computes Alice's public key for the secret `s_A`.
+  pub fn alice_public_key(&self, secret: usize) -> PublicKey<F> {
+    public_key(self.curve, &self.alice, &self.bob, secret)
+  }
+
+  /// Computes Bob's public key for the secret `s_B`.
+  pub fn bob_public_key(&self, secret: usize) -> PublicKey<F> {
+    public_key(self.curve, &self.bob, &self.alice, secret)
+  }
+
+  /// Computes the shared j-invariant from a secret and the other party's public key.
+  pub fn shared_secret(&self, secret: usize, their_key: &PublicKey<F>) -> F {
+    let curve = their_key.curve;
+    let kernel = curve.add(their_key.p, curve.mul(secret, their_key.q));
+    Isogeny::new(curve, &[kernel]).codomain().j_invariant()
+  }
+}
+
+fn public_key<F: Field>(
+  curve: ShortWeierstrass<F>,
+  own: &TorsionBasis<F>,
+  other: &TorsionBasis<F>,
+  secret: usize,
+) -> PublicKey<F> {
+  let kernel = curve.add(own.p, curve.mul(secret, own.q));
+  let isogeny = Isogeny::new(curve, &[kernel]);
+  PublicKey {
+    curve: isogeny.codomain(),
+    p:     isogeny.evaluate(other.p),
+    q:     isogeny.evaluate(other.q),
+  }
+}
+
+impl Sidh<PlutoBaseFieldExtension> {
+  /// Parameters on [`PlutoExtendedCurve`] with `N_A = 3` and `N_B = 17`.
+  ///
+  /// The bases are built from two points of order `102`: one with coordinates in `GF(101)`, fixed
+  /// by Frobenius, and one of the form `(x, y * t)` with `x, y ∈ GF(101)`, which Frobenius
+  /// negates. No non-zero multiple of one is a multiple of the other unless it has order two.
+  pub fn pluto() -> Self {
+    let curve = ShortWeierstrass::from_curve::<PlutoExtendedCurve>();
+    let full_order_point = |rational: bool| {
+      (0..101)
+        .filter_map(|x| {
+          let x = PlutoBaseField::new(x);
+          let rhs = x.pow(3) + PlutoBaseField::new(3);
+          if rhs == PlutoBaseField::ZERO || rhs.euler_criterion() != rational {
+            return None;
+          }
+          let (y, _) = PlutoBaseFieldExtension::from(rhs).sqrt()?;
+          Some(Point::Affine(PlutoBaseFieldExtension::from(x), y))
+        })
+        .find(|&point| curve.order_of(point, 102) == 102)
+        .expect("both subgroups are cyclic of order 102")
+    };
+    let (p, q) = (full_order_point(true), full_order_point(false));
+    let basis =
+      |order| TorsionBasis { order, p: curve.mul(102 / order, p), q: curve.mul(102 / order, q) };
+    Self { curve, alice: basis(3), bob: basis(17) }
+  }
+}
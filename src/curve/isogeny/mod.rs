@@ -0,0 +1,130 @@
+//! Isogenies between elliptic curves computed with [Vélu's formulas](https://en.wikipedia.org/wiki/Isogeny#V%C3%A9lu's_formulas).
+//!
+//! An isogeny `φ: E -> E'` is a non-constant rational map that is also a group homomorphism. Every
+//! finite subgroup `G` of `E` is the kernel of a separable isogeny of degree `|G|`, unique up to
+//! isomorphism of `E'`, and Vélu gave explicit formulas for it:
+//!
+//! ```text
+//! φ(P) = (x(P) + Σ_{Q ∈ G \ O} (x(P + Q) - x(Q)), y(P) + Σ_{Q ∈ G \ O} (y(P + Q) - y(Q)))
+//! ```
+//!
+//! This sums the coordinates over the coset `P + G`, so it is constant on cosets and sends the
+//! kernel to `O`. The codomain `E': y^2 = x^3 + a'x + b'` is found by writing `G \ O` as its points
+//! of order two together with one point out of every pair `±Q`, calling this set `S`, and setting
+//! for every `Q ∈ S`
+//! - `g^x_Q = 3x_Q^2 + a`, `g^y_Q = -2y_Q`, `u_Q = (g^y_Q)^2`,
+//! - `v_Q = g^x_Q` if `Q` has order two and `v_Q = 2g^x_Q` otherwise,
+//!
+//! and then `a' = a - 5 Σ v_Q` and `b' = b - 7 Σ (u_Q + x_Q v_Q)`.
+//!
+//! The domain of an isogeny is usually one of the [`EllipticCurve`]s of this crate, see
+//! [`Isogeny::from_curve`], but the codomain is only known at runtime, so curves and points are
+//! described with [`ShortWeierstrass`] and [`Point`]. Curves are compared up to isomorphism over
+//! the algebraic closure with the [`ShortWeierstrass::j_invariant`], which is how the
+//! [`graph`] of isogenies and the [`key_exchange`] identify them.
+use super::{
+  generation::{Point, ShortWeierstrass},
+  AffinePoint, EllipticCurve,
+};
+use crate::algebra::field::Field;
+
+pub mod graph;
+pub mod key_exchange;
+#[cfg(test)] mod tests;
+
+impl<F: Field> ShortWeierstrass<F> {
+  /// The j-invariant `1728 * 4a^3 / (4a^3 + 27b^2)`. Two curves are isomorphic over the algebraic
+  /// closure of `F` exactly when their j-invariants agree.
+  pub fn j_invariant(&self) -> F {
+    let four_a_cubed = F::from(4) * self.a.pow(3);
+    F::from(1728) * four_a_cubed / (four_a_cubed + F::from(27) * self.b.pow(2))
+  }
+}
+
+/// A separable isogeny `φ: E -> E'` with a given finite kernel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Isogeny<F: Field> {
+  domain:   ShortWeierstrass<F>,
+  codomain: ShortWeierstrass<F>,
+  kernel:   Vec<Point<F>>,
+}
+
+impl<F: Field> Isogeny<F> {
+  /// Computes the isogeny whose kernel is the subgroup generated by `generators`.
+  ///
+  /// ## Panics
+  /// Panics if a generator is not on `domain`.
+  pub fn new(domain: ShortWeierstrass<F>, generators: &[Point<F>]) -> Self {
+    assert!(
+      generators.iter().all(|g| domain.contains(g)),
+      "kernel generators must be on the curve"
+    );
+
+    // close the subgroup generated so far under adding the next generator, elements that are
+    // pushed are visited later on so that all multiples of the generator are added
+    let mut kernel = vec![Point::Infinity];
+    for &generator in generators {
+      let mut i = 0;
+      while i < kernel.len() {
+        let next = domain.add(kernel[i], generator);
+        if !kernel.contains(&next) {
+          kernel.push(next);
+        }
+        i += 1;
+      }
+    }
+
+    let (mut v, mut w) = (F::ZERO, F::ZERO);
+    let mut seen = vec![];
+    for &point in &kernel {
+      let Point::Affine(x, y) = point else { continue };
+      if seen.contains(&-point) {
+        continue;
+      }
+      seen.push(point);
+      let g_x = F::from(3) * x * x + domain.a;
+      let g_y = -F::from(2) * y;
+      let v_q = if y == F::ZERO { g_x } else { F::from(2) * g_x };
+      let u_q = g_y * g_y;
+      v += v_q;
+      w += u_q + x * v_q;
+    }
+    let codomain = ShortWeierstrass { a: domain.a - F::from(5) * v, b: domain.b - F::from(7) * w };
+    Self { domain, codomain, kernel }
+  }
+
+  /// Computes the isogeny from the curve `C` whose kernel is generated by `generators`.
+  pub fn from_curve<C: EllipticCurve<BaseField = F>>(generators: &[AffinePoint<C>]) -> Self {
+    let generators: Vec<_> = generators.iter().map(|&g| g.into()).collect();
+    Self::new(ShortWeierstrass::from_curve::<C>(), &generators)
+  }
+
+  /// The domain `E`.
+  pub fn domain(&self) -> ShortWeierstrass<F> { self.domain }
+
+  /// The codomain `E'`.
+  pub fn codomain(&self) -> ShortWeierstrass<F> { self.codomain }
+
+  /// The points of the kernel, including the point at infinity.
+  pub fn kernel(&self) -> &[Point<F>] { &self.kernel }
+
+  /// The degree of the isogeny, which for a separable isogeny is the size of its kernel.
+  pub fn degree(&self) -> usize { self.kernel.len() }
+
+  /// Evaluates `φ(P)` by summing the coordinates of `P + Q` over the kernel.
+  pub fn evaluate(&self, point: Point<F>) -> Point<F> {
+    if self.kernel.contains(&point) {
+      return Point::Infinity;
+    }
+    let Point::Affine(mut x, mut y) = point else { unreachable!("O is in the kernel") };
+    for &q in &self.kernel {
+      let (Point::Affine(x_q, y_q), Point::Affine(x_sum, y_sum)) = (q, self.domain.add(point, q))
+      else {
+        continue;
+      };
+      x += x_sum - x_q;
+      y += y_sum - y_q;
+    }
+    Point::Affine(x, y)
+  }
+}
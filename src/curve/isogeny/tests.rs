@@ -0,0 +1,119 @@
+use super::{graph::IsogenyGraph, key_exchange::Sidh, *};
+use crate::{
+  algebra::{
+    field::{
+      extension::PlutoBaseFieldExtension,
+      prime::{PlutoBaseField, PrimeField},
+    },
+    group::FiniteCyclicGroup,
+  },
+  curve::pluto_curve::{PlutoBaseCurve, PlutoExtendedCurve},
+};
+
+/// Checks that `φ` sends the kernel to `O`, lands on the codomain and is a homomorphism on
+/// `points`.
+fn assert_isogeny<F: Field>(isogeny: &Isogeny<F>, points: &[Point<F>]) {
+  let (domain, codomain) = (isogeny.domain(), isogeny.codomain());
+  assert_ne!(codomain.discriminant(), F::ZERO);
+  assert!(isogeny.kernel().iter().all(|&k| isogeny.evaluate(k) == Point::Infinity));
+  for &p in points {
+    assert!(codomain.contains(&isogeny.evaluate(p)));
+    for &q in points {
+      let sum = isogeny.evaluate(domain.add(p, q));
+      assert_eq!(sum, codomain.add(isogeny.evaluate(p), isogeny.evaluate(q)));
+    }
+  }
+}
+
+#[test]
+fn j_invariant() {
+  let pluto = ShortWeierstrass::from_curve::<PlutoBaseCurve>();
+  assert_eq!(pluto.j_invariant(), PlutoBaseField::ZERO);
+  let curve = ShortWeierstrass::new(PlutoBaseField::ONE, PlutoBaseField::ZERO).unwrap();
+  assert_eq!(curve.j_invariant(), PlutoBaseField::new(1728));
+}
+
+#[test]
+fn velu_on_base_curve() {
+  let curve = ShortWeierstrass::from_curve::<PlutoBaseCurve>();
+  let points = curve.points();
+  for l in [2, 3, 17] {
+    let generator = curve.generator_of_order(l).unwrap();
+    let isogeny = Isogeny::new(curve, &[generator]);
+    assert_eq!(isogeny.degree(), l);
+    assert_eq!(isogeny.codomain().count_points(), 102);
+    assert_isogeny(&isogeny, &points);
+  }
+
+  let isogeny = Isogeny::from_curve(&[AffinePoint::<PlutoBaseCurve>::GENERATOR]);
+  assert_eq!(isogeny.degree(), 17);
+}
+
+#[test]
+fn velu_on_extended_curve() {
+  let g = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+  let isogeny = Isogeny::from_curve(&[g]);
+  assert_eq!(isogeny.degree(), 17);
+  let sidh = Sidh::pluto();
+  let points = [sidh.alice.p, sidh.alice.q, sidh.bob.q, g.into(), (g + g).into(), Point::Infinity];
+  assert_isogeny(&isogeny, &points);
+}
+
+#[test]
+fn multiplication_by_two() {
+  // E[2] = {O, (x_0, 0), (ζx_0, 0), (ζ^2 x_0, 0)} where x_0^3 = -3 and ζ is a cube root of unity
+  let curve = ShortWeierstrass::from_curve::<PlutoExtendedCurve>();
+  let x_0 = (0..101usize)
+    .map(PlutoBaseFieldExtension::from)
+    .find(|&x| curve.rhs(x) == PlutoBaseFieldExtension::ZERO)
+    .unwrap();
+  let (sqrt_minus_three, _) = (-PlutoBaseFieldExtension::from(3usize)).sqrt().unwrap();
+  let zeta =
+    (sqrt_minus_three - PlutoBaseFieldExtension::ONE) / PlutoBaseFieldExtension::from(2usize);
+  let kernel = [
+    Point::Affine(x_0, PlutoBaseFieldExtension::ZERO),
+    Point::Affine(zeta * x_0, PlutoBaseFieldExtension::ZERO),
+  ];
+
+  let isogeny = Isogeny::new(curve, &kernel);
+  assert_eq!(isogeny.degree(), 4);
+  assert_eq!(isogeny.codomain().j_invariant(), curve.j_invariant());
+}
+
+#[test]
+fn isogeny_graph() {
+  // 120 = 2^3 * 3 * 5 points
+  let curve = ShortWeierstrass::new(PrimeField::<103>::new(1), PrimeField::new(2)).unwrap();
+  let order = curve.count_points();
+  assert_eq!(order, 120);
+  for l in [2, 3, 5] {
+    let graph = IsogenyGraph::explore(curve, l);
+    assert!(graph.curves.len() > 1);
+    assert_eq!(graph.degree, l);
+    assert_eq!(graph.j_invariants()[0], curve.j_invariant());
+    for (i, c) in graph.curves.iter().enumerate() {
+      assert_eq!(c.count_points(), order);
+      assert_eq!(graph.neighbours(i).len(), graph::kernels_of_order(c, l).len());
+    }
+  }
+}
+
+#[test]
+fn sidh_key_exchange() {
+  let sidh = Sidh::pluto();
+  for (basis, order) in [(sidh.alice, 3), (sidh.bob, 17)] {
+    assert_eq!(sidh.curve.order_of(basis.p, order), order);
+    assert_eq!(sidh.curve.order_of(basis.q, order), order);
+    assert!((0..order).all(|k| sidh.curve.mul(k, basis.p) != basis.q));
+  }
+  for alice_secret in 0..3 {
+    let alice_key = sidh.alice_public_key(alice_secret);
+    for bob_secret in [0, 1, 5, 16] {
+      let bob_key = sidh.bob_public_key(bob_secret);
+      assert_eq!(
+        sidh.shared_secret(alice_secret, &bob_key),
+        sidh.shared_secret(bob_secret, &alice_key)
+      );
+    }
+  }
+}
@@ -14,6 +14,12 @@ use crate::{
   Field, PlutoScalarField,
 };
 
+pub mod bn254;
+pub mod encoding;
+pub mod generation;
+pub mod isogeny;
+pub mod hash_to_curve;
+pub mod msm;
 pub mod pairing;
 pub mod pluto_curve;
 #[cfg(test)] mod tests;
@@ -77,6 +83,24 @@ impl<C: EllipticCurve> AffinePoint<C> {
     assert!(point.is_on_curve(), "Point is not on curve");
     point
   }
+
+  /// Computes `[k]P` for an integer `k` with double-and-add.
+  ///
+  /// Unlike multiplication by a [`EllipticCurve::ScalarField`] element, `k` is not reduced modulo
+  /// the order of the scalar field, which is what checking for torsion needs.
+  pub fn mul_integer(self, k: usize) -> Self {
+    let mut result = AffinePoint::Infinity;
+    for i in (0..usize::BITS - k.leading_zeros()).rev() {
+      result += result;
+      if (k >> i) & 1 == 1 {
+        result += self;
+      }
+    }
+    result
+  }
+
+  /// Checks whether the point is in the `r`-torsion, i.e. `[r]P = O`, with `O(log r)` additions.
+  pub fn is_torsion(&self, r: usize) -> bool { self.mul_integer(r) == AffinePoint::Infinity }
 }
 
 impl<C: EllipticCurve> Finite for AffinePoint<C> {
@@ -113,6 +137,10 @@ impl<C: EllipticCurve> CurveGroup for AffinePoint<C> {
       AffinePoint::Point(x, y) => (x, y),
       AffinePoint::Infinity => return AffinePoint::Infinity,
     };
+    // the tangent at a point of order two is vertical
+    if y == C::BaseField::ZERO {
+      return AffinePoint::Infinity;
+    }
     // m = (3x^2) / (2y)
     let m = (((C::BaseField::ONE + C::BaseField::ONE) + C::BaseField::ONE) * x * x
       + C::EQUATION_A.into())
@@ -0,0 +1,249 @@
+//! Scalar multiplication algorithms for any [`Group`] of curve points.
+//!
+//! - [`naive_msm`]: multiply every base by its scalar independently and sum the results.
+//! - [`msm`]: [Pippenger's bucket method](https://eprint.iacr.org/2022/1321.pdf), which splits the
+//!   scalars into `c`-bit windows and, for every window, sorts the bases into `2^c - 1` buckets by
+//!   the value of their digit. Each bucket is summed once, and the weighted sum `Σ i * B_i` of the
+//!   buckets is computed with a running sum using only `2 * 2^c` additions. For `n` points and
+//!   `b`-bit scalars this costs roughly `b / c * (n + 2^c)` additions instead of the `n * b`
+//!   doublings and additions of the naive approach.
+//! - [`wnaf_mul`]: a single scalar multiplication using the width-`w` [non-adjacent form](https://en.wikipedia.org/wiki/Elliptic_curve_point_multiplication#w-ary_non-adjacent_form_(wNAF)_method)
+//!   of the scalar. Negating a point is free, so the digits are allowed to be negative, which
+//!   halves the number of precomputed multiples and leaves on average one non-zero digit in each
+//!   run of `w + 1` digits.
+//!
+//! [`msm`] reads the scalars bit by bit through [`ScalarBits`], so it works for scalar fields of
+//! any size, like the 254-bit scalars of [BN254](super::bn254). [`wnaf_mul`] reads the scalar
+//! through its canonical `usize` representative, which is how the scalar fields of the toy curves
+//! in [`crate::curve`] are stored.
+use std::{cmp::Ordering, ops::Neg};
+
+use super::CurveGroup;
+use crate::algebra::group::Group;
+
+/// The bits of the canonical representative of a scalar, so that multi-scalar multiplications
+/// can split scalars into windows without converting them to an integer type.
+pub trait ScalarBits {
+  /// The number of bits of the scalar, up to its most significant `1`.
+  fn bits(&self) -> usize;
+
+  /// The bit of weight `2^i`, which is `false` past [`ScalarBits::bits`].
+  fn bit(&self, i: usize) -> bool;
+}
+
+/// Scalars stored as their `usize` representative, like the fields of the toy curves.
+impl<S: Copy + Into<usize>> ScalarBits for S {
+  fn bits(&self) -> usize { (usize::BITS - (*self).into().leading_zeros()) as usize }
+
+  fn bit(&self, i: usize) -> bool { i < usize::BITS as usize && ((*self).into() >> i) & 1 == 1 }
+}
+
+/// Computes `Σ scalars[i] * bases[i]` by multiplying each pair independently.
+///
+/// ## Panics
+/// Panics if `bases` and `scalars` have different lengths.
+pub fn naive_msm<G: Group>(bases: &[G], scalars: &[G::Scalar]) -> G
+where G::Scalar: Copy {
+  assert_eq!(bases.len(), scalars.len(), "bases and scalars must have the same length");
+  bases.iter().zip(scalars).fold(G::IDENTITY, |acc, (&base, &scalar)| acc + base * scalar)
+}
+
+/// Heuristic for the Pippenger window size `c` for an MSM of size `n`.
+///
+/// The cost `b / c * (n + 2^c)` is minimised around `c ≈ ln(n)`, below a handful of points the
+/// bucket overhead dominates and a small fixed window is used instead.
+pub fn msm_window_size(n: usize) -> usize {
+  if n < 32 {
+    3
+  } else {
+    (n as f64).ln().ceil() as usize
+  }
+}
+
+/// Computes `Σ scalars[i] * bases[i]` with Pippenger's bucket method, using a window size
+/// chosen by [`msm_window_size`].
+///
+/// ## Panics
+/// Panics if `bases` and `scalars` have different lengths.
+pub fn msm<G: Group>(bases: &[G], scalars: &[G::Scalar]) -> G
+where G::Scalar: ScalarBits {
+  msm_with_window(bases, scalars, msm_window_size(bases.len()))
+}
+
+/// Computes `Σ scalars[i] * bases[i]` with Pippenger's bucket method and a window of `c` bits.
+///
+/// ## Panics
+/// Panics if `bases` and `scalars` have different lengths or if `c` is zero or too large for a
+/// `usize` digit.
+pub fn msm_with_window<G: Group>(bases: &[G], scalars: &[G::Scalar], c: usize) -> G
+where G::Scalar: ScalarBits {
+  assert_eq!(bases.len(), scalars.len(), "bases and scalars must have the same length");
+  assert!((1..usize::BITS as usize).contains(&c), "window size must be in [1, {})", usize::BITS);
+
+  let max_bits = scalars.iter().map(ScalarBits::bits).max().unwrap_or(0);
+  let num_windows = max_bits.div_ceil(c);
+  let mask = (1 << c) - 1;
+  let digit = |scalar: &G::Scalar, window: usize| {
+    (0..c).filter(|&j| scalar.bit(window * c + j)).fold(0, |digit, j| digit | 1 << j)
+  };
+
+  let mut result = G::IDENTITY;
+  for window in (0..num_windows).rev() {
+    for _ in 0..c {
+      result = result + result;
+    }
+
+    // bucket `i` holds the sum of the bases whose digit in this window is `i + 1`
+    let mut buckets = vec![G::IDENTITY; mask];
+    for (&base, scalar) in bases.iter().zip(scalars) {
+      let digit = digit(scalar, window);
+      if digit != 0 {
+        buckets[digit - 1] += base;
+      }
+    }
+
+    // Σ (i + 1) * B_i = B_{m-1} + (B_{m-1} + B_{m-2}) + ... + (B_{m-1} + ... + B_0)
+    let mut running_sum = G::IDENTITY;
+    let mut window_sum = G::IDENTITY;
+    for bucket in buckets.into_iter().rev() {
+      running_sum += bucket;
+      window_sum += running_sum;
+    }
+    result += window_sum;
+  }
+  result
+}
+
+/// Returns the width-`w` non-adjacent form of `k` as little-endian digits.
+///
+/// Every non-zero digit is odd and lies in `(-2^(w-1), 2^(w-1))`, and any `w` consecutive digits
+/// contain at most one non-zero digit.
+pub fn wnaf(k: usize, w: usize) -> Vec<i64> {
+  assert!((2..usize::BITS as usize).contains(&w), "window width must be in [2, {})", usize::BITS);
+  let modulus = 1i128 << w;
+  // widen so that rounding up past a negative digit cannot overflow
+  let mut k = k as i128;
+  let mut digits = vec![];
+  while k > 0 {
+    if k & 1 == 1 {
+      let mut digit = k % modulus;
+      if digit >= modulus / 2 {
+        digit -= modulus;
+      }
+      digits.push(digit as i64);
+      k -= digit;
+    } else {
+      digits.push(0);
+    }
+    k >>= 1;
+  }
+  digits
+}
+
+/// Heuristic for the wNAF width for a scalar of `bits` bits.
+///
+/// Picks the `w` minimising the `2^(w-2)` additions spent on the precomputed table plus the
+/// expected `bits / (w + 1)` additions in the main loop.
+pub fn wnaf_window_size(bits: usize) -> usize {
+  (2..16).min_by_key(|&w| (1 << (w - 2)) + bits / (w + 1)).unwrap()
+}
+
+/// Computes `scalar * base` using the width-`w` NAF of the scalar, see [`wnaf_window_size`] for a
+/// sensible choice of `w`.
+///
+/// The odd multiples `P, 3P, ..., (2^(w-1) - 1)P` are precomputed, after which the scalar is
+/// processed from its most significant digit with one doubling per digit and one addition or
+/// subtraction per non-zero digit.
+pub fn wnaf_mul<G: CurveGroup + Neg<Output = G>>(base: G, scalar: G::Scalar, w: usize) -> G
+where G::Scalar: Into<usize> {
+  let digits = wnaf(scalar.into(), w);
+
+  let double = base.double();
+  let mut table = vec![base];
+  for i in 1..(1 << (w - 2)) {
+    let next = table[i - 1] + double;
+    table.push(next);
+  }
+
+  let mut result = G::IDENTITY;
+  for &digit in digits.iter().rev() {
+    result = result.double();
+    match digit.cmp(&0) {
+      Ordering::Greater => result += table[digit as usize / 2],
+      Ordering::Less => result += -table[digit.unsigned_abs() as usize / 2],
+      Ordering::Equal => {},
+    }
+  }
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{thread_rng, Rng};
+
+  use super::*;
+  use crate::{
+    algebra::group::FiniteCyclicGroup,
+    curve::{
+      pluto_curve::{PlutoBaseCurve, PlutoExtendedCurve},
+      AffinePoint,
+    },
+    PlutoScalarField,
+  };
+
+  fn random_instance(n: usize) -> (Vec<AffinePoint<PlutoBaseCurve>>, Vec<PlutoScalarField>) {
+    let mut rng = thread_rng();
+    let g = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+    let bases = (0..n).map(|_| g * PlutoScalarField::new(rng.gen_range(0..17))).collect();
+    let scalars = (0..n).map(|_| PlutoScalarField::new(rng.gen_range(0..17))).collect();
+    (bases, scalars)
+  }
+
+  #[test]
+  fn pippenger_matches_naive() {
+    for n in [0, 1, 2, 7, 40] {
+      let (bases, scalars) = random_instance(n);
+      let expected = naive_msm(&bases, &scalars);
+      assert_eq!(msm(&bases, &scalars), expected);
+      for c in 1..=6 {
+        assert_eq!(msm_with_window(&bases, &scalars, c), expected);
+      }
+    }
+  }
+
+  #[test]
+  fn pippenger_extended_curve() {
+    let g2 = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+    let bases: Vec<_> = (1..10).map(|i| g2 * PlutoScalarField::new(i)).collect();
+    let scalars: Vec<_> = (1..10).map(|i| PlutoScalarField::new(3 * i)).collect();
+    assert_eq!(msm(&bases, &scalars), naive_msm(&bases, &scalars));
+  }
+
+  #[test]
+  fn wnaf_digits() {
+    for w in 2..6 {
+      for k in 0..1000usize {
+        let digits = wnaf(k, w);
+        let value = digits.iter().rev().fold(0i64, |acc, &d| 2 * acc + d);
+        assert_eq!(value, k as i64);
+        for (i, &d) in digits.iter().enumerate().filter(|(_, &d)| d != 0) {
+          assert!(d % 2 != 0 && d.abs() < 1 << (w - 1));
+          assert!(digits[i + 1..].iter().take(w - 1).all(|&d| d == 0));
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn wnaf_mul_matches_naive() {
+    let g = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+    for w in 2..5 {
+      for k in 0..17 {
+        let k = PlutoScalarField::new(k);
+        assert_eq!(wnaf_mul(g, k, w), g * k);
+      }
+    }
+    assert_eq!(wnaf_window_size(5), 2);
+    assert_eq!(wnaf_window_size(256), 5);
+  }
+}
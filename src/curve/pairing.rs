@@ -1,8 +1,24 @@
 //! Pairing operations for the Pluto curve.
-
-use std::fmt::Debug;
+//!
+//! - [`pairing`] is the reduced Tate pairing of two points on the same curve, with the final
+//!   exponent taken over the whole base field of the curve.
+//! - [`tate_pairing`] takes `P` on a curve over `F_q` and `Q` on the same curve over `F_{q^k}`, for
+//!   an explicit embedding degree `k`.
+//! - [`weil_pairing`] is the Weil pairing, which needs no final exponentiation but two Miller
+//!   loops.
+//! - [`symmetric_pairing`] pairs two points of `G1` on the [`PlutoBaseCurve`] by first applying the
+//!   [`distortion_map`] to one of them.
+//!
+//! Protocols that only need some bilinear map `G1 x G2 -> GT`, like KZG commitments, are written
+//! against the [`PairingCurve`] trait, which [`PlutoPairing`] implements with the [`tate_pairing`].
+
+use std::{
+  fmt::Debug,
+  ops::{Mul, Neg},
+};
 
 use super::*;
+use crate::curve::{generation::embedding_degree, msm::ScalarBits, pluto_curve::distortion_map};
 
 /// Compute the simplified Tate pairing of two points on the curve.
 ///
@@ -22,8 +38,8 @@ use super::*;
 /// The result of the pairing, an element of rth root of unity in base field of the curve.
 ///
 /// ## Panics
-/// Panics if either input is not in the R-torsion group via an exhaustive check that the point
-/// added to itself R times is the point itself for both inputs.
+/// Panics if either input is not in the R-torsion group, which is checked with
+/// [`AffinePoint::is_torsion`].
 ///
 /// ## Notes
 /// This uses the [Miller loop](https://crypto.stanford.edu/pbc/notes/ep/miller.html) algorithm to compute the rational map required for pairing.
@@ -35,16 +51,8 @@ pub fn pairing<C: EllipticCurve + Debug + PartialEq, const R: usize>(
   q: AffinePoint<C>,
 ) -> C::BaseField {
   // Check that both inputs are r torsion points on the curve
-  let mut result = p;
-  for _ in 0..R {
-    result += p;
-  }
-  assert_eq!(result, p);
-  let mut result = q;
-  for _ in 0..R {
-    result += q;
-  }
-  assert_eq!(result, q);
+  assert!(p.is_torsion(R), "P is not in the R-torsion");
+  assert!(q.is_torsion(R), "Q is not in the R-torsion");
 
   // Compute the Miller loop
   let val = miller_loop::<C, R>(p, q);
@@ -53,6 +61,163 @@ pub fn pairing<C: EllipticCurve + Debug + PartialEq, const R: usize>(
   val.pow((C::BaseField::ORDER - 1) / R)
 }
 
+/// Computes the reduced Tate pairing `e(P, Q) = f_{R,P}(Q)^((q^K - 1) / R)` for `P ∈ E(F_q)[R]` and
+/// `Q ∈ E(F_{q^K})[R]`, where `K` is the embedding degree of the subgroup of order `R`.
+///
+/// The curve `C` is the curve over `F_q` and `E` is the same curve over `F_{q^K}`. The embedding
+/// degree is the smallest `K` with `R | q^K - 1`, so `F_{q^K}` is the smallest extension that
+/// contains the `R`-th roots of unity the pairing takes its values in.
+///
+/// ## Panics
+/// - Panics if the base field of `E` does not have `q^K` elements, or if `K` is not the embedding
+///   degree of `R`, as computed by [`embedding_degree`].
+/// - Panics if `P` or `Q` is not in the `R`-torsion.
+pub fn tate_pairing<C, E, const R: usize, const K: usize>(
+  p: AffinePoint<C>,
+  q: AffinePoint<E>,
+) -> E::BaseField
+where
+  C: EllipticCurve,
+  E: EllipticCurve + Debug + PartialEq,
+  AffinePoint<E>: From<AffinePoint<C>>,
+{
+  let base_order = C::BaseField::ORDER;
+  assert_eq!(
+    base_order.pow(K as u32),
+    E::BaseField::ORDER,
+    "E must be defined over the degree K extension of the base field of C"
+  );
+  assert_eq!(embedding_degree(base_order, R, K), Some(K), "K is not the embedding degree of R");
+  assert!(p.is_torsion(R), "P is not in the R-torsion");
+  assert!(q.is_torsion(R), "Q is not in the R-torsion");
+
+  miller_loop::<E, R>(p.into(), q).pow((E::BaseField::ORDER - 1) / R)
+}
+
+/// Computes the Weil pairing `e_R(P, Q) = (-1)^R f_{R,P}(Q) / f_{R,Q}(P)` of two `R`-torsion
+/// points.
+///
+/// The Weil pairing is alternating, `e_R(P, P) = 1`, and non-degenerate on the full torsion
+/// `E[R]`, so it is `1` exactly when `P` and `Q` are linearly dependent. This case is detected by
+/// comparing `Q` with the multiples of `P`, which is fine for the small `R` used in this crate, as
+/// the Miller functions would otherwise be evaluated at their zeros.
+///
+/// ## Panics
+/// Panics if either input is not in the `R`-torsion.
+pub fn weil_pairing<C: EllipticCurve + Debug + PartialEq, const R: usize>(
+  p: AffinePoint<C>,
+  q: AffinePoint<C>,
+) -> C::BaseField {
+  assert!(p.is_torsion(R), "P is not in the R-torsion");
+  assert!(q.is_torsion(R), "Q is not in the R-torsion");
+  if (0..R).any(|k| p.mul_integer(k) == q) {
+    return C::BaseField::ONE;
+  }
+
+  let sign = if R % 2 == 1 { -C::BaseField::ONE } else { C::BaseField::ONE };
+  sign * miller_loop::<C, R>(p, q) / miller_loop::<C, R>(q, p)
+}
+
+/// Computes the symmetric pairing `ê(P, Q) = e(P, ψ(Q))` of two points of order 17 on the
+/// [`PlutoBaseCurve`], where `ψ` is the [`distortion_map`] and `e` the [`tate_pairing`].
+///
+/// Unlike [`tate_pairing`] both inputs are in `G1`, and `ê(P, Q) = ê(Q, P)`.
+pub fn symmetric_pairing(
+  p: AffinePoint<PlutoBaseCurve>,
+  q: AffinePoint<PlutoBaseCurve>,
+) -> PlutoBaseFieldExtension {
+  tate_pairing::<PlutoBaseCurve, PlutoExtendedCurve, { PlutoBaseCurve::ORDER }, 2>(
+    p,
+    distortion_map(q),
+  )
+}
+
+/// A pair of groups `G1` and `G2` of the same prime order, generated by
+/// [`G1_GENERATOR`](PairingCurve::G1_GENERATOR) and [`G2_GENERATOR`](PairingCurve::G2_GENERATOR),
+/// that admit a non-degenerate bilinear pairing `e: G1 x G2 -> GT`.
+///
+/// `G1` and `G2` are given by their point types rather than by an [`EllipticCurve`], so that curves
+/// whose fields do not fit in a `usize`, like [BN254](super::bn254), can implement the trait too.
+pub trait PairingCurve: Copy + Debug + Eq {
+  /// The scalar field shared by `G1` and `G2`, read bit by bit in multi-scalar multiplications.
+  type ScalarField: Field + ScalarBits;
+
+  /// The points of the curve that contains `G1`.
+  type G1: Group<Scalar = Self::ScalarField> + Neg<Output = Self::G1>;
+
+  /// The points of the curve that contains `G2`.
+  type G2: Group<Scalar = Self::ScalarField> + Neg<Output = Self::G2>;
+
+  /// The field that contains `GT`, the roots of unity the pairing takes its values in.
+  type TargetField: Copy + Debug + Eq + Mul<Output = Self::TargetField>;
+
+  /// The generator of `G1`.
+  const G1_GENERATOR: Self::G1;
+
+  /// The generator of `G2`.
+  const G2_GENERATOR: Self::G2;
+
+  /// The identity of `GT`.
+  const TARGET_ONE: Self::TargetField;
+
+  /// Computes `e(P, Q)`, which is `1` if either point is the point at infinity.
+  fn pairing(p: Self::G1, q: Self::G2) -> Self::TargetField;
+
+  /// Computes the product `Π e(P_i, Q_i)`. Checks of the form `Π e(P_i, Q_i) = 1` let a verifier
+  /// compare several pairings at once.
+  fn multi_pairing(pairs: &[(Self::G1, Self::G2)]) -> Self::TargetField {
+    pairs.iter().fold(Self::TARGET_ONE, |acc, &(p, q)| acc * Self::pairing(p, q))
+  }
+
+  /// Checks that `p` is a point of `G1`. Points from outside the group break the bilinearity the
+  /// protocols rely on, so they must be rejected before pairing points received from others.
+  fn is_in_g1(p: &Self::G1) -> bool;
+
+  /// Checks that `q` is a point of `G2`, see [`PairingCurve::is_in_g1`].
+  fn is_in_g2(q: &Self::G2) -> bool;
+}
+
+/// The [`tate_pairing`] from the points of order 17 on the [`PlutoBaseCurve`] to the subgroup of
+/// order 17 of the [`PlutoExtendedCurve`] generated by its
+/// [`GENERATOR`](FiniteCyclicGroup::GENERATOR).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlutoPairing;
+
+impl PairingCurve for PlutoPairing {
+  type G1 = AffinePoint<PlutoBaseCurve>;
+  type G2 = AffinePoint<PlutoExtendedCurve>;
+  type ScalarField = PlutoScalarField;
+  type TargetField = PlutoBaseFieldExtension;
+
+  const G1_GENERATOR: Self::G1 = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+  const G2_GENERATOR: Self::G2 = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+  const TARGET_ONE: PlutoBaseFieldExtension = PlutoBaseFieldExtension::ONE;
+
+  fn pairing(
+    p: AffinePoint<PlutoBaseCurve>,
+    q: AffinePoint<PlutoExtendedCurve>,
+  ) -> PlutoBaseFieldExtension {
+    if p == AffinePoint::Infinity || q == AffinePoint::Infinity {
+      return PlutoBaseFieldExtension::ONE;
+    }
+    tate_pairing::<PlutoBaseCurve, PlutoExtendedCurve, { PlutoBaseCurve::ORDER }, 2>(p, q)
+  }
+
+  fn is_in_g1(p: &AffinePoint<PlutoBaseCurve>) -> bool { p.is_torsion(PlutoScalarField::ORDER) }
+
+  /// `G2` is the trace zero subgroup: the points of order 17 that the Frobenius map `(x, y) ->
+  /// (x^101, y^101)` sends to their negative. The points of order 17 that it fixes are `G1` lifted
+  /// to `GF(101^2)`, which pair trivially with `G1`.
+  fn is_in_g2(q: &AffinePoint<PlutoExtendedCurve>) -> bool {
+    let frobenius = match *q {
+      AffinePoint::Point(x, y) =>
+        AffinePoint::Point(x.pow(PlutoBaseField::ORDER), y.pow(PlutoBaseField::ORDER)),
+      AffinePoint::Infinity => AffinePoint::Infinity,
+    };
+    q.is_torsion(PlutoScalarField::ORDER) && frobenius == -*q
+  }
+}
+
 /// Evaluate a rational function on a divisor f_{r,P}(D_{Q}) in logarithmic time complexity using an
 /// algorithm similar to double and add.
 pub(crate) fn miller_loop<C: EllipticCurve + Debug + PartialEq, const R: usize>(
@@ -236,46 +401,6 @@ impl Distribution<AffinePoint<PlutoExtendedCurve>> for Standard {
 mod tests {
   use super::*;
 
-  /// Compute weil pairing $w_{r} = f_{r,P}(D_Q)/f_{r,Q}(D_P)$ for points $P, Q \in E[F_{q^k}][r]$.
-  /// $D_P,D_Q$ are degree zero divisors with disjoint supports to $f_{r,Q},f_{r,P}$ respectively.
-  /// D_P have divisor equal to (P)-(O), and (D_Q) = (Q)-(O)
-  pub fn weil_pairing<const R: usize>(
-    p: AffinePoint<PlutoExtendedCurve>,
-    q: AffinePoint<PlutoExtendedCurve>,
-  ) -> <PlutoExtendedCurve as EllipticCurve>::BaseField {
-    // Check that both inputs are r torsion points on the curve
-    let mut result = p;
-    for _ in 0..R {
-      result += p;
-    }
-    assert_eq!(result, p);
-    let mut result = q;
-    for _ in 0..R {
-      result += q;
-    }
-    assert_eq!(result, q);
-
-    // to keep the support disjoint, a random element `S` on extended curve is used, which shouldn't
-    // be equal to P, -Q, P-Q
-    let mut rng = rand::thread_rng();
-    let mut s = rng.gen::<AffinePoint<PlutoExtendedCurve>>();
-    while s == p || s == -q || s == p - q {
-      s = rng.gen::<AffinePoint<PlutoExtendedCurve>>();
-    }
-
-    // (D_Q) ~ (Q+S) - (S) (equivalent divisors)
-    // Compute the Miller loop for f_{r,P}(D_Q) as f_{r,P}(Q+S)/f_{r,P}(S)
-    let num =
-      miller_loop::<PlutoExtendedCurve, R>(p, q + s) * miller_loop::<PlutoExtendedCurve, R>(q, -s);
-
-    // (D_P) ~ (P-S) - (-S) (equivalent divisors)
-    // compute the miller loop f_{r,Q}(D_P) as f_{r,Q}(P-S)/f_{r,Q}(-S)
-    let den =
-      miller_loop::<PlutoExtendedCurve, R>(q, p - s) * miller_loop::<PlutoExtendedCurve, R>(p, s);
-
-    num / den
-  }
-
   #[test]
   fn random_point() {
     let mut rng = rand::thread_rng();
@@ -375,7 +500,7 @@ mod tests {
     let a = AffinePoint::<PlutoExtendedCurve>::new(a_x, a_y);
     let b = AffinePoint::<PlutoExtendedCurve>::new(b_x, b_y);
 
-    let result = weil_pairing::<17>(a, b);
+    let result = weil_pairing::<PlutoExtendedCurve, 17>(a, b);
     assert_eq!(result, weil_result);
 
     let result = pairing::<PlutoExtendedCurve, 17>(a, b);
@@ -401,7 +526,7 @@ mod tests {
     let result2 = pairing::<PlutoExtendedCurve, 17>(q, p);
     let weil_from_tate_pairing = result / result2;
 
-    let weil_pair = weil_pairing::<17>(p, q);
+    let weil_pair = weil_pairing::<PlutoExtendedCurve, 17>(p, q);
 
     assert_eq!(
       weil_pair.pow((<PlutoExtendedCurve as EllipticCurve>::BaseField::ORDER - 1) / 17),
@@ -456,4 +581,79 @@ mod tests {
     let rhs = pairing::<PlutoExtendedCurve, 17>(p, q) * pairing::<PlutoExtendedCurve, 17>(p, r);
     assert_eq!(lhs, rhs);
   }
+
+  #[test]
+  fn torsion_check() {
+    let g = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+    assert!(g.is_torsion(17));
+    assert!(!g.is_torsion(16));
+    assert_eq!(g.mul_integer(5), g * PlutoScalarField::new(5));
+    assert_eq!(g.mul_integer(17 + 5), g * PlutoScalarField::new(5));
+    let g2 = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+    assert!(g2.is_torsion(17) && g2.is_torsion(34) && !g2.is_torsion(101));
+  }
+
+  #[test]
+  #[should_panic(expected = "Q is not in the R-torsion")]
+  fn pairing_rejects_non_torsion_points() {
+    let p = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+    let q = AffinePoint::<PlutoExtendedCurve>::from(AffinePoint::<PlutoBaseCurve>::new(
+      PlutoBaseField::new(48),
+      PlutoBaseField::new(0),
+    ));
+    pairing::<PlutoExtendedCurve, 17>(p, q);
+  }
+
+  #[test]
+  fn tate_pairing_with_embedding_degree() {
+    let p = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+    let q = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+    let e = tate_pairing::<PlutoBaseCurve, PlutoExtendedCurve, 17, 2>(p, q);
+    assert_eq!(e, pairing::<PlutoExtendedCurve, 17>(p.into(), q));
+    assert_ne!(e, PlutoBaseFieldExtension::ONE);
+    assert_eq!(e.pow(17), PlutoBaseFieldExtension::ONE);
+  }
+
+  #[test]
+  #[should_panic(expected = "E must be defined over the degree K extension")]
+  fn tate_pairing_rejects_wrong_extension_degree() {
+    let p = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+    let q = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+    tate_pairing::<PlutoBaseCurve, PlutoExtendedCurve, 17, 1>(p, q);
+  }
+
+  #[test]
+  fn weil_pairing_properties() {
+    let p = AffinePoint::<PlutoExtendedCurve>::from(AffinePoint::<PlutoBaseCurve>::GENERATOR);
+    let q = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
+    let e = weil_pairing::<PlutoExtendedCurve, 17>(p, q);
+    assert_ne!(e, PlutoBaseFieldExtension::ONE);
+    assert_eq!(e.pow(17), PlutoBaseFieldExtension::ONE);
+    assert_eq!(weil_pairing::<PlutoExtendedCurve, 17>(q, p), e.inverse().unwrap());
+    assert_eq!(weil_pairing::<PlutoExtendedCurve, 17>(p, p), PlutoBaseFieldExtension::ONE);
+    assert_eq!(
+      weil_pairing::<PlutoExtendedCurve, 17>(p, p.mul_integer(3)),
+      PlutoBaseFieldExtension::ONE
+    );
+    assert_eq!(
+      weil_pairing::<PlutoExtendedCurve, 17>(p.mul_integer(3), q.mul_integer(5)),
+      e.pow(15)
+    );
+  }
+
+  #[test]
+  fn symmetric_pairing_on_g1() {
+    let g = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+    let psi = distortion_map(g);
+    assert!(psi.is_on_curve() && psi.is_torsion(17));
+    assert_eq!(distortion_map(g + g), psi + psi);
+
+    let e = symmetric_pairing(g, g);
+    assert_ne!(e, PlutoBaseFieldExtension::ONE);
+    for (a, b) in [(2, 3), (5, 11), (16, 1)] {
+      let (p, q) = (g.mul_integer(a), g.mul_integer(b));
+      assert_eq!(symmetric_pairing(p, q), symmetric_pairing(q, p));
+      assert_eq!(symmetric_pairing(p, q), e.pow(a * b));
+    }
+  }
 }
@@ -63,6 +63,25 @@ impl From<AffinePoint<PlutoBaseCurve>> for AffinePoint<PlutoExtendedCurve> {
   }
 }
 
+/// The distortion map `ψ(x, y) = (ζx, y)` from the [`PlutoBaseCurve`] to the
+/// [`PlutoExtendedCurve`], where `ζ` is a primitive cube root of unity in
+/// [`PlutoBaseFieldExtension`].
+///
+/// Since `ζ^3 = 1` the image is still on `y^2 = x^3 + 3`, and it is a group homomorphism. As
+/// `101 ≡ 2 mod 3`, `ζ` does not lie in [`PlutoBaseField`], so `ψ` sends the points of `E(GF(101))`
+/// to points that are not defined over `GF(101)`. In particular, `ψ(P)` is linearly independent of
+/// `P` for any point of order 17, which makes the pairing `e(P, ψ(Q))` of two points of `G1`
+/// non-degenerate, see [`symmetric_pairing`](super::pairing::symmetric_pairing).
+pub fn distortion_map(point: AffinePoint<PlutoBaseCurve>) -> AffinePoint<PlutoExtendedCurve> {
+  match point {
+    AffinePoint::Point(x, y) => {
+      let zeta = PlutoBaseFieldExtension::primitive_root_of_unity(3);
+      AffinePoint::new(zeta * PlutoBaseFieldExtension::from(x), PlutoBaseFieldExtension::from(y))
+    },
+    AffinePoint::Infinity => AffinePoint::Infinity,
+  }
+}
+
 // TODO: have to remove const trait from finite field for this. Ask Colin or Waylon if that's
 // alright
 // impl<C: EllipticCurve> Distribution<AffinePoint<C>> for Standard {
@@ -228,3 +228,10 @@ fn pairing_check() {
   println!("f(P,Q)^(59^2 - 1)^5 = {:?}", f_p_q.pow(5));
   assert_eq!(f_p_q.pow(5), TestExtension::new([TestField::new(1), TestField::new(0)]));
 }
+
+#[test]
+fn double_point_of_order_two() {
+  let p = AffinePoint::<PlutoBaseCurve>::new(PlutoBaseField::new(48), PlutoBaseField::ZERO);
+  assert_eq!(p.double(), AffinePoint::Infinity);
+  assert_eq!(p + p, AffinePoint::Infinity);
+}
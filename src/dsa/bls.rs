@@ -0,0 +1,280 @@
+//! BLS signatures over the Pluto curve pair, following the
+//! [BLS signature draft](https://datatracker.ietf.org/doc/draft-irtf-cfrg-bls-signature/).
+//!
+//! Public keys live in `G2`, the subgroup of order 17 of the [`PlutoExtendedCurve`] generated by
+//! its [`GENERATOR`](FiniteCyclicGroup::GENERATOR), and signatures in `G1`, the subgroup of order
+//! 17 of the [`PlutoBaseCurve`]. With `e` the [`PlutoPairing`] between the two:
+//! - a secret key is a scalar `sk` and its public key is `pk = [sk]G2`,
+//! - the signature of `m` is `σ = [sk]H(m)`, where `H` is [`hash_to_curve`],
+//! - `σ` is valid when `e(σ, G2) = e(H(m), pk)`, as both sides equal `e(H(m), G2)^sk`.
+//!
+//! Since the pairing is bilinear, signatures on different messages can be added up into a single
+//! [`Signature::aggregate`] that is checked with one pairing per message in [`aggregate_verify`].
+//! When all signers sign the same message, [`fast_aggregate_verify`] also adds up the public keys
+//! and only needs two pairings. This is only safe if every public key comes with a proof of
+//! possession of its secret key, see [`SecretKey::prove_possession`]: otherwise an attacker can
+//! publish the rogue key `pk' = [x]G2 - pk` for an honest `pk`, and sign on behalf of both with `x`
+//! alone.
+use rand::Rng;
+
+use super::*;
+use crate::{
+  algebra::{group::FiniteCyclicGroup, Finite},
+  curve::{
+    hash_to_curve::hash_to_curve,
+    pairing::{PairingCurve, PlutoPairing},
+  },
+};
+
+/// Domain separation tag for hashing messages to `G1`.
+pub const DST_SIGNATURE: &[u8] = b"BLS_SIG_PLUTO_XMD:SHA-256_SSWU_RO_POP_";
+
+/// Domain separation tag for hashing public keys to `G1` in proofs of possession.
+pub const DST_POP: &[u8] = b"BLS_POP_PLUTO_XMD:SHA-256_SSWU_RO_POP_";
+
+/// A BLS secret key, a non-zero scalar.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SecretKey(PlutoScalarField);
+
+/// A BLS public key, a point of `G2`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicKey(pub AffinePoint<PlutoExtendedCurve>);
+
+/// A BLS signature or proof of possession, a point of `G1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature(pub AffinePoint<PlutoBaseCurve>);
+
+impl SecretKey {
+  /// Creates a secret key from a scalar.
+  ///
+  /// ## Panics
+  /// Panics if `sk` is zero, as its public key would be the point at infinity.
+  pub fn new(sk: PlutoScalarField) -> Self {
+    assert_ne!(sk, PlutoScalarField::ZERO, "secret key must be non-zero");
+    Self(sk)
+  }
+
+  /// Samples a random secret key.
+  pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+    Self(PlutoScalarField::new(rng.gen_range(1..PlutoScalarField::ORDER)))
+  }
+
+  /// The public key `pk = [sk]G2`.
+  pub fn public_key(&self) -> PublicKey { PublicKey(AffinePoint::GENERATOR * self.0) }
+
+  /// Signs `msg` as `σ = [sk]H(m)`.
+  ///
+  /// With a cofactor of 6, some messages hash to the point at infinity. Their signature is the
+  /// point at infinity under every key, so it proves nothing and [`Signature::is_valid`] rejects
+  /// it.
+  pub fn sign(&self, msg: &[u8]) -> Signature {
+    Signature(hash_to_curve::<PlutoBaseCurve>(msg, DST_SIGNATURE) * self.0)
+  }
+
+  /// Proves knowledge of `sk` by signing the compressed encoding of the public key with a
+  /// separate domain separation tag, so that a proof can never be mistaken for a signature.
+  pub fn prove_possession(&self) -> Signature {
+    let pk = self.public_key().0.to_compressed();
+    Signature(hash_to_curve::<PlutoBaseCurve>(&pk, DST_POP) * self.0)
+  }
+}
+
+impl PublicKey {
+  /// Checks that the key is a point of `G2` other than the point at infinity.
+  ///
+  /// Being of order 17 is not enough: `G1` lifted to `GF(101^2)` also has order 17, and since
+  /// `e(H(m), pk) = 1` for such a key, it would verify the signature at infinity for any message.
+  pub fn is_valid(&self) -> bool {
+    self.0 != AffinePoint::Infinity && PlutoPairing::is_in_g2(&self.0)
+  }
+
+  /// Verifies a signature of `msg` by checking `e(σ, G2) = e(H(m), pk)`.
+  pub fn verify(&self, msg: &[u8], signature: &Signature) -> bool {
+    aggregate_verify(&[*self], &[msg], signature)
+  }
+
+  /// Verifies a proof of possession by checking `e(π, G2) = e(H_pop(pk), pk)`.
+  pub fn verify_possession(&self, proof: &Signature) -> bool {
+    if !self.is_valid() || !proof.is_valid() {
+      return false;
+    }
+    let h = hash_to_curve::<PlutoBaseCurve>(&self.0.to_compressed(), DST_POP);
+    PlutoPairing::pairing(proof.0, AffinePoint::GENERATOR) == PlutoPairing::pairing(h, self.0)
+  }
+
+  /// Adds up public keys. The sum is only meaningful for keys whose proofs of possession have
+  /// been verified.
+  pub fn aggregate(keys: &[PublicKey]) -> PublicKey { PublicKey(keys.iter().map(|pk| pk.0).sum()) }
+}
+
+impl Signature {
+  /// Checks that the signature is a point of `G1` other than the point at infinity, which is only
+  /// the signature of a message whose hash pairs trivially with every key.
+  pub fn is_valid(&self) -> bool {
+    self.0 != AffinePoint::Infinity && PlutoPairing::is_in_g1(&self.0)
+  }
+
+  /// Adds up signatures into a single aggregate signature.
+  pub fn aggregate(signatures: &[Signature]) -> Signature {
+    Signature(signatures.iter().map(|s| s.0).sum())
+  }
+}
+
+/// Verifies an aggregate signature of distinct messages, where `msgs[i]` was signed by `keys[i]`,
+/// by checking `e(σ, G2) = Π e(H(m_i), pk_i)`.
+///
+/// Returns `false` if the messages are not pairwise distinct, as aggregating signatures of the
+/// same message under different keys is only secure with proofs of possession, see
+/// [`fast_aggregate_verify`].
+pub fn aggregate_verify(keys: &[PublicKey], msgs: &[&[u8]], signature: &Signature) -> bool {
+  if keys.is_empty() || keys.len() != msgs.len() {
+    return false;
+  }
+  if !signature.is_valid() || !keys.iter().all(PublicKey::is_valid) {
+    return false;
+  }
+  if msgs.iter().enumerate().any(|(i, m)| msgs[..i].contains(m)) {
+    return false;
+  }
+
+  let pairs: Vec<_> = keys
+    .iter()
+    .zip(msgs)
+    .map(|(pk, msg)| (hash_to_curve::<PlutoBaseCurve>(msg, DST_SIGNATURE), pk.0))
+    .collect();
+  PlutoPairing::pairing(signature.0, AffinePoint::GENERATOR) == PlutoPairing::multi_pairing(&pairs)
+}
+
+/// Verifies an aggregate signature of the same message by all of `keys`, by checking the
+/// aggregate signature against the aggregate public key.
+///
+/// The caller must have verified the proof of possession of every key, see
+/// [`PublicKey::verify_possession`].
+pub fn fast_aggregate_verify(keys: &[PublicKey], msg: &[u8], signature: &Signature) -> bool {
+  if keys.is_empty() || !signature.is_valid() || !keys.iter().all(PublicKey::is_valid) {
+    return false;
+  }
+  let pk = PublicKey::aggregate(keys);
+  let h = hash_to_curve::<PlutoBaseCurve>(msg, DST_SIGNATURE);
+  PlutoPairing::pairing(signature.0, AffinePoint::GENERATOR) == PlutoPairing::pairing(h, pk.0)
+}
+
+#[cfg(test)]
+mod tests {
+  use rand::{rngs::StdRng, SeedableRng};
+
+  use super::*;
+  use crate::curve::EllipticCurve;
+
+  fn secret_keys(n: usize) -> Vec<SecretKey> {
+    let mut rng = StdRng::seed_from_u64(17);
+    (0..n).map(|_| SecretKey::random(&mut rng)).collect()
+  }
+
+  #[test]
+  fn generators_pair_non_trivially() {
+    let e = PlutoPairing::pairing(AffinePoint::GENERATOR, AffinePoint::GENERATOR);
+    assert_ne!(e, PlutoBaseFieldExtension::ONE);
+    assert_eq!(e.pow(PlutoBaseCurve::ORDER), PlutoBaseFieldExtension::ONE);
+  }
+
+  #[test]
+  fn sign_and_verify() {
+    for sk in secret_keys(8) {
+      let pk = sk.public_key();
+      assert!(pk.is_valid());
+      let signature = sk.sign(b"Hello, Pluto!");
+      assert!(signature.is_valid());
+      assert!(pk.verify(b"Hello, Pluto!", &signature));
+    }
+  }
+
+  #[test]
+  fn reject_wrong_key_or_signature() {
+    let sk = SecretKey::new(PlutoScalarField::new(3));
+    let other = SecretKey::new(PlutoScalarField::new(5));
+    let signature = sk.sign(b"message");
+    assert!(!other.public_key().verify(b"message", &signature));
+    let forged = Signature(signature.0 + AffinePoint::GENERATOR);
+    assert!(!sk.public_key().verify(b"message", &forged));
+    assert!(!PublicKey(AffinePoint::Infinity).verify(b"message", &signature));
+  }
+
+  #[test]
+  #[should_panic(expected = "secret key must be non-zero")]
+  fn zero_secret_key() { SecretKey::new(PlutoScalarField::ZERO); }
+
+  #[test]
+  fn aggregate_distinct_messages() {
+    let sks = secret_keys(4);
+    let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+    let msgs: [&[u8]; 4] = [b"zero", b"one", b"two", b"three"];
+    let signatures: Vec<_> = sks.iter().zip(msgs).map(|(sk, m)| sk.sign(m)).collect();
+    let aggregate = Signature::aggregate(&signatures);
+    assert!(aggregate_verify(&pks, &msgs, &aggregate));
+    assert!(!aggregate_verify(&pks[..3], &msgs[..3], &aggregate));
+
+    let repeated: [&[u8]; 2] = [b"zero", b"zero"];
+    let aggregate = Signature::aggregate(&[sks[0].sign(b"zero"), sks[1].sign(b"zero")]);
+    assert!(!aggregate_verify(&pks[..2], &repeated, &aggregate));
+  }
+
+  #[test]
+  fn proof_of_possession() {
+    for sk in secret_keys(4) {
+      let pk = sk.public_key();
+      assert!(pk.verify_possession(&sk.prove_possession()));
+    }
+    let sks = secret_keys(2);
+    assert!(!sks[0].public_key().verify_possession(&sks[1].prove_possession()));
+  }
+
+  #[test]
+  fn fast_aggregate_same_message() {
+    let sks = secret_keys(6);
+    let pks: Vec<_> = sks.iter().map(SecretKey::public_key).collect();
+    let signatures: Vec<_> = sks.iter().map(|sk| sk.sign(b"block 42")).collect();
+    let aggregate = Signature::aggregate(&signatures);
+    assert!(fast_aggregate_verify(&pks, b"block 42", &aggregate));
+    assert!(!fast_aggregate_verify(&pks[1..], b"block 42", &aggregate));
+  }
+
+  #[test]
+  fn rogue_key_attack() {
+    let honest = SecretKey::new(PlutoScalarField::new(7));
+    let x = SecretKey::new(PlutoScalarField::new(4));
+
+    // pk' = [x]G2 - pk makes the aggregate key [x]G2, which the attacker can sign for alone
+    let rogue = PublicKey(x.public_key().0 - honest.public_key().0);
+    let keys = [honest.public_key(), rogue];
+    let forged = x.sign(b"transfer all funds");
+    assert!(fast_aggregate_verify(&keys, b"transfer all funds", &forged));
+
+    // without the secret key of pk' the attacker cannot prove possession of it
+    assert!(!rogue.verify_possession(&x.prove_possession()));
+    assert!(!rogue.verify_possession(&honest.prove_possession()));
+  }
+
+  #[test]
+  fn reject_key_outside_g2() {
+    // `G1` lifted to the extension has order 17 too, but pairs trivially with `G1`
+    let g1 = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+    let lifted = PublicKey(AffinePoint::<PlutoExtendedCurve>::from(g1));
+    let identity = Signature(AffinePoint::Infinity);
+    let h = hash_to_curve::<PlutoBaseCurve>(b"message", DST_SIGNATURE);
+    assert_eq!(PlutoPairing::pairing(h, lifted.0), PlutoBaseFieldExtension::ONE);
+    assert!(lifted.0.is_torsion(PlutoBaseCurve::ORDER));
+
+    assert!(!lifted.is_valid());
+    assert!(!identity.is_valid());
+    assert!(!lifted.verify(b"message", &identity));
+    assert!(!lifted.verify_possession(&identity));
+
+    // nor can the lifted key join an aggregate as a co-signer that never signed
+    let honest = SecretKey::new(PlutoScalarField::new(7));
+    let signature = honest.sign(b"message");
+    assert!(fast_aggregate_verify(&[honest.public_key()], b"message", &signature));
+    assert!(!fast_aggregate_verify(&[honest.public_key(), lifted], b"message", &signature));
+    assert!(!aggregate_verify(&[honest.public_key(), lifted], &[b"message", b"other"], &signature));
+  }
+}
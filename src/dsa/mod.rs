@@ -1,5 +1,6 @@
 //! Digital Signatures
 #![doc = include_str!("./README.md")]
+pub mod bls;
 pub mod ecdsa;
 pub mod eddsa;
 
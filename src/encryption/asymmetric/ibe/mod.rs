@@ -0,0 +1,251 @@
+//! Boneh–Franklin identity-based encryption over the Pluto curve pair.
+//!
+//! Anyone can encrypt to an identity, such as an email address, using only the public parameters
+//! of a trusted key generation center. The center holds a master secret `s` and extracts the
+//! private key `[s]H_1(ID)` of an identity on request. With `e` the [`PlutoPairing`]:
+//! - the public parameters are `P_pub = [s]G2`,
+//! - the private key of `ID` is `d_ID = [s]Q_ID` with `Q_ID = H_1(ID) ∈ G1`, see [`hash_to_curve`],
+//! - both the sender and the recipient can compute `e(Q_ID, P_pub)^r = e(d_ID, [r]G2)` for the
+//!   sender's random `r`, which masks the message.
+//!
+//! [`BasicCiphertext`] is the BasicIdent scheme, which is only secure against chosen plaintext
+//! attacks. [`FullCiphertext`] is FullIdent, which derives `r` from a random `σ` and the message
+//! with the Fujisaki–Okamoto transform so that the recipient can check the ciphertext was honestly
+//! formed, which makes it secure against chosen ciphertext attacks.
+#![doc = include_str!("./README.md")]
+#[cfg(test)] mod tests;
+
+use std::{error::Error, fmt::Display};
+
+use rand::Rng;
+
+use crate::{
+  algebra::{
+    field::{extension::PlutoBaseFieldExtension, FieldBytes},
+    group::FiniteCyclicGroup,
+    Finite,
+  },
+  curve::{
+    hash_to_curve::{hash_to_curve, hash_to_field},
+    pairing::{PairingCurve, PlutoPairing},
+  },
+  hashes::sha::Sha256,
+  AffinePoint, Field, PlutoBaseCurve, PlutoExtendedCurve, PlutoScalarField,
+};
+
+/// Domain separation tag of `H_1`, hashing identities to `G1`.
+pub const DST_IDENTITY: &[u8] = b"RONKATHON_IBE_H1_XMD:SHA-256_SSWU_RO_";
+
+/// Domain separation tag of `H_2`, hashing pairing values to masks.
+pub const DST_MASK: &[u8] = b"RONKATHON_IBE_H2_CTR:SHA-256";
+
+/// Domain separation tag of `H_3`, hashing `σ` and the message to the randomness `r`.
+pub const DST_RANDOMNESS: &[u8] = b"RONKATHON_IBE_H3_XMD:SHA-256";
+
+/// Domain separation tag of `H_4`, hashing `σ` to the mask of the message.
+pub const DST_SIGMA_MASK: &[u8] = b"RONKATHON_IBE_H4_CTR:SHA-256";
+
+/// Length in bytes of the random `σ` of FullIdent.
+pub const SIGMA_BYTES: usize = 32;
+
+/// Errors from extracting keys and decrypting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IbeError {
+  /// The identity hashes to the point at infinity, so its key would be public.
+  InvalidIdentity,
+  /// The ciphertext was not formed honestly for this identity.
+  InvalidCiphertext,
+}
+
+impl Error for IbeError {}
+
+impl Display for IbeError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      IbeError::InvalidIdentity => write!(f, "identity hashes to the point at infinity"),
+      IbeError::InvalidCiphertext => write!(f, "invalid ciphertext"),
+    }
+  }
+}
+
+/// The master secret `s` of the key generation center.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MasterSecretKey(PlutoScalarField);
+
+/// The public parameters `P_pub = [s]G2`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PublicParameters(pub AffinePoint<PlutoExtendedCurve>);
+
+/// The private key `d_ID = [s]H_1(ID)` of an identity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PrivateKey(pub AffinePoint<PlutoBaseCurve>);
+
+/// A BasicIdent ciphertext `(U, V) = ([r]G2, M ⊕ H_2(g_ID^r))`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BasicCiphertext {
+  /// `[r]G2`
+  pub u: AffinePoint<PlutoExtendedCurve>,
+  /// The masked message.
+  pub v: Vec<u8>,
+}
+
+/// A FullIdent ciphertext `(U, V, W) = ([r]G2, σ ⊕ H_2(g_ID^r), M ⊕ H_4(σ))` with
+/// `r = H_3(σ, M)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FullCiphertext {
+  /// `[r]G2`
+  pub u: AffinePoint<PlutoExtendedCurve>,
+  /// The masked `σ`.
+  pub v: [u8; SIGMA_BYTES],
+  /// The masked message.
+  pub w: Vec<u8>,
+}
+
+/// Runs the setup of the key generation center with a random master secret.
+pub fn setup<R: Rng + ?Sized>(rng: &mut R) -> (MasterSecretKey, PublicParameters) {
+  let msk = MasterSecretKey::random(rng);
+  (msk, msk.public_parameters())
+}
+
+/// `Q_ID = H_1(ID)`, or an error if it is the point at infinity.
+fn identity_point(identity: &[u8]) -> Result<AffinePoint<PlutoBaseCurve>, IbeError> {
+  match hash_to_curve::<PlutoBaseCurve>(identity, DST_IDENTITY) {
+    AffinePoint::Infinity => Err(IbeError::InvalidIdentity),
+    point => Ok(point),
+  }
+}
+
+/// A mask of `len` bytes derived from `input` in counter mode: the concatenation of the blocks
+/// `SHA-256(dst || I2OSP(i, 4) || input)` for `i = 1, 2, …`, so that messages of any length can be
+/// masked.
+fn mask(input: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+  let mut mask: Vec<u8> = (1..=len.div_ceil(32) as u32)
+    .flat_map(|i| Sha256::new().digest(&[dst, &i.to_be_bytes(), input].concat()))
+    .collect();
+  mask.truncate(len);
+  mask
+}
+
+/// `H_2(g)`, a mask of `len` bytes derived from an element of the target group.
+fn pairing_mask(g: PlutoBaseFieldExtension, len: usize) -> Vec<u8> {
+  mask(&g.to_bytes(), DST_MASK, len)
+}
+
+/// `a ⊕ b`
+fn xor(a: &[u8], b: &[u8]) -> Vec<u8> { a.iter().zip(b).map(|(a, b)| a ^ b).collect() }
+
+/// `r = H_3(σ, M)`
+fn randomness(sigma: &[u8], msg: &[u8]) -> PlutoScalarField {
+  hash_to_field::<PlutoScalarField>(&[sigma, msg].concat(), DST_RANDOMNESS, 1)[0]
+}
+
+impl MasterSecretKey {
+  /// Creates a master secret key from a non-zero scalar.
+  ///
+  /// ## Panics
+  /// Panics if `s` is zero.
+  pub fn new(s: PlutoScalarField) -> Self {
+    assert_ne!(s, PlutoScalarField::ZERO, "master secret must be non-zero");
+    Self(s)
+  }
+
+  /// Samples a uniformly random non-zero master secret.
+  pub fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+    Self(PlutoScalarField::new(rng.gen_range(1..PlutoScalarField::ORDER)))
+  }
+
+  /// The public parameters `P_pub = [s]G2`.
+  pub fn public_parameters(&self) -> PublicParameters {
+    PublicParameters(AffinePoint::GENERATOR * self.0)
+  }
+
+  /// Extracts the private key `d_ID = [s]H_1(ID)` of `identity`.
+  ///
+  /// ## Errors
+  /// Returns [`IbeError::InvalidIdentity`] if the identity hashes to the point at infinity.
+  pub fn extract(&self, identity: &[u8]) -> Result<PrivateKey, IbeError> {
+    Ok(PrivateKey(identity_point(identity)? * self.0))
+  }
+}
+
+impl PublicParameters {
+  /// `g_ID^r = e(Q_ID, P_pub)^r`, computed as `e(Q_ID, [r]P_pub)`.
+  fn mask_base(
+    &self,
+    identity: &[u8],
+    r: PlutoScalarField,
+  ) -> Result<PlutoBaseFieldExtension, IbeError> {
+    Ok(PlutoPairing::pairing(identity_point(identity)?, self.0 * r))
+  }
+
+  /// Encrypts `msg` to `identity` with BasicIdent.
+  ///
+  /// ## Errors
+  /// Returns [`IbeError::InvalidIdentity`] if the identity hashes to the point at infinity.
+  pub fn encrypt_basic<R: Rng + ?Sized>(
+    &self,
+    identity: &[u8],
+    msg: &[u8],
+    rng: &mut R,
+  ) -> Result<BasicCiphertext, IbeError> {
+    let r = PlutoScalarField::new(rng.gen_range(1..PlutoScalarField::ORDER));
+    let g = self.mask_base(identity, r)?;
+    Ok(BasicCiphertext { u: AffinePoint::GENERATOR * r, v: xor(msg, &pairing_mask(g, msg.len())) })
+  }
+
+  /// Encrypts `msg` to `identity` with FullIdent.
+  ///
+  /// ## Errors
+  /// Returns [`IbeError::InvalidIdentity`] if the identity hashes to the point at infinity.
+  pub fn encrypt<R: Rng + ?Sized>(
+    &self,
+    identity: &[u8],
+    msg: &[u8],
+    rng: &mut R,
+  ) -> Result<FullCiphertext, IbeError> {
+    // draw σ until r is non-zero, as `U = O` would make the mask public
+    let (sigma, r) = loop {
+      let sigma: [u8; SIGMA_BYTES] = rng.gen();
+      let r = randomness(&sigma, msg);
+      if r != PlutoScalarField::ZERO {
+        break (sigma, r);
+      }
+    };
+    let g = self.mask_base(identity, r)?;
+    let sigma_mask = mask(&sigma, DST_SIGMA_MASK, msg.len());
+    Ok(FullCiphertext {
+      u: AffinePoint::GENERATOR * r,
+      v: xor(&sigma, &pairing_mask(g, SIGMA_BYTES)).try_into().unwrap(),
+      w: xor(msg, &sigma_mask),
+    })
+  }
+}
+
+impl PrivateKey {
+  /// Decrypts a BasicIdent ciphertext. Decrypting with the key of another identity does not fail
+  /// but returns an unrelated message.
+  pub fn decrypt_basic(&self, ciphertext: &BasicCiphertext) -> Vec<u8> {
+    let g = PlutoPairing::pairing(self.0, ciphertext.u);
+    xor(&ciphertext.v, &pairing_mask(g, ciphertext.v.len()))
+  }
+
+  /// Decrypts a FullIdent ciphertext, checking that `U = [H_3(σ, M)]G2`.
+  ///
+  /// ## Errors
+  /// Returns [`IbeError::InvalidCiphertext`] if `U` is not a point of `G2`, or if the check fails,
+  /// which happens for ciphertexts that were modified or encrypted to another identity.
+  pub fn decrypt(&self, ciphertext: &FullCiphertext) -> Result<Vec<u8>, IbeError> {
+    let u = ciphertext.u;
+    if u == AffinePoint::Infinity || !PlutoPairing::is_in_g2(&u) {
+      return Err(IbeError::InvalidCiphertext);
+    }
+    let g = PlutoPairing::pairing(self.0, u);
+    let sigma = xor(&ciphertext.v, &pairing_mask(g, SIGMA_BYTES));
+    let sigma_mask = mask(&sigma, DST_SIGMA_MASK, ciphertext.w.len());
+    let msg = xor(&ciphertext.w, &sigma_mask);
+    if AffinePoint::<PlutoExtendedCurve>::GENERATOR * randomness(&sigma, &msg) != u {
+      return Err(IbeError::InvalidCiphertext);
+    }
+    Ok(msg)
+  }
+}
@@ -0,0 +1,123 @@
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::*;
+
+const ALICE: &[u8] = b"alice@pluto.space";
+const BOB: &[u8] = b"bob@pluto.space";
+const MESSAGE: &[u8] = b"Meet me on Charon at noon.";
+
+#[test]
+fn extracted_keys_match_public_parameters() {
+  let (msk, pp) = setup(&mut StdRng::seed_from_u64(0));
+  let d = msk.extract(ALICE).unwrap();
+  let q = hash_to_curve::<PlutoBaseCurve>(ALICE, DST_IDENTITY);
+  // e(d_ID, G2) = e(Q_ID, P_pub)
+  assert_eq!(PlutoPairing::pairing(d.0, AffinePoint::GENERATOR), PlutoPairing::pairing(q, pp.0));
+  assert_eq!(msk.extract(ALICE), Ok(d));
+  assert_ne!(msk.extract(BOB), Ok(d));
+}
+
+#[test]
+fn basic_ident() {
+  let mut rng = StdRng::seed_from_u64(1);
+  let (msk, pp) = setup(&mut rng);
+  let ciphertext = pp.encrypt_basic(ALICE, MESSAGE, &mut rng).unwrap();
+  assert_ne!(ciphertext.v, MESSAGE);
+  assert_eq!(msk.extract(ALICE).unwrap().decrypt_basic(&ciphertext), MESSAGE);
+}
+
+#[test]
+fn basic_ident_wrong_identity() {
+  let mut rng = StdRng::seed_from_u64(2);
+  let (msk, pp) = setup(&mut rng);
+  let ciphertext = pp.encrypt_basic(ALICE, MESSAGE, &mut rng).unwrap();
+  assert_ne!(msk.extract(BOB).unwrap().decrypt_basic(&ciphertext), MESSAGE);
+
+  // a key for the same identity from another key generation center doesn't work either
+  let (other, _) = setup(&mut rng);
+  assert_ne!(other.extract(ALICE).unwrap().decrypt_basic(&ciphertext), MESSAGE);
+}
+
+#[test]
+fn full_ident() {
+  let mut rng = StdRng::seed_from_u64(3);
+  let (msk, pp) = setup(&mut rng);
+  let d = msk.extract(ALICE).unwrap();
+  for msg in [MESSAGE, b"", &[0u8; 100]] {
+    let ciphertext = pp.encrypt(ALICE, msg, &mut rng).unwrap();
+    assert_eq!(d.decrypt(&ciphertext), Ok(msg.to_vec()));
+  }
+}
+
+#[test]
+fn long_messages() {
+  // longer than the 255 blocks that `expand_message_xmd` can give
+  let msg: Vec<u8> = (0..9000).map(|i| i as u8).collect();
+  let mut rng = StdRng::seed_from_u64(9);
+  let (msk, pp) = setup(&mut rng);
+  let d = msk.extract(ALICE).unwrap();
+  let ciphertext = pp.encrypt_basic(ALICE, &msg, &mut rng).unwrap();
+  assert_eq!(d.decrypt_basic(&ciphertext), msg);
+  let ciphertext = pp.encrypt(ALICE, &msg, &mut rng).unwrap();
+  assert_eq!(d.decrypt(&ciphertext), Ok(msg));
+}
+
+#[test]
+fn full_ident_is_randomised() {
+  let mut rng = StdRng::seed_from_u64(4);
+  let (_, pp) = setup(&mut rng);
+  let c1 = pp.encrypt(ALICE, MESSAGE, &mut rng).unwrap();
+  let c2 = pp.encrypt(ALICE, MESSAGE, &mut rng).unwrap();
+  assert_ne!(c1, c2);
+}
+
+#[test]
+fn full_ident_wrong_identity() {
+  let mut rng = StdRng::seed_from_u64(5);
+  let (msk, pp) = setup(&mut rng);
+  let ciphertext = pp.encrypt(ALICE, MESSAGE, &mut rng).unwrap();
+  assert_eq!(msk.extract(BOB).unwrap().decrypt(&ciphertext), Err(IbeError::InvalidCiphertext));
+}
+
+#[test]
+fn full_ident_rejects_modified_ciphertext() {
+  let mut rng = StdRng::seed_from_u64(6);
+  let (msk, pp) = setup(&mut rng);
+  let d = msk.extract(ALICE).unwrap();
+  let ciphertext = pp.encrypt(ALICE, MESSAGE, &mut rng).unwrap();
+
+  let mut modified = ciphertext.clone();
+  modified.w[0] ^= 1;
+  assert_eq!(d.decrypt(&modified), Err(IbeError::InvalidCiphertext));
+
+  let mut modified = ciphertext.clone();
+  modified.v[0] ^= 1;
+  assert_eq!(d.decrypt(&modified), Err(IbeError::InvalidCiphertext));
+
+  let mut modified = ciphertext.clone();
+  modified.u = AffinePoint::Infinity;
+  assert_eq!(d.decrypt(&modified), Err(IbeError::InvalidCiphertext));
+
+  // the private key itself lifted to the extension has order 17, but is not in `G2`
+  let mut modified = ciphertext;
+  modified.u = AffinePoint::from(d.0);
+  assert_eq!(d.decrypt(&modified), Err(IbeError::InvalidCiphertext));
+}
+
+#[test]
+fn identity_hashing_to_infinity() {
+  let (msk, pp) = setup(&mut StdRng::seed_from_u64(7));
+  let identity = (0u32..)
+    .map(|i| format!("user{}", i))
+    .find(|id| {
+      hash_to_curve::<PlutoBaseCurve>(id.as_bytes(), DST_IDENTITY) == AffinePoint::Infinity
+    })
+    .unwrap();
+  assert_eq!(msk.extract(identity.as_bytes()), Err(IbeError::InvalidIdentity));
+  let result = pp.encrypt(identity.as_bytes(), MESSAGE, &mut StdRng::seed_from_u64(8));
+  assert_eq!(result, Err(IbeError::InvalidIdentity));
+}
+
+#[test]
+#[should_panic(expected = "master secret must be non-zero")]
+fn zero_master_secret() { MasterSecretKey::new(PlutoScalarField::ZERO); }
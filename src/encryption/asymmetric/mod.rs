@@ -1,2 +1,4 @@
-//! Contains implementation of asymmetric cryptographic primitives like RSA encryption.
+//! Contains implementation of asymmetric cryptographic primitives like RSA encryption and
+//! identity-based encryption.
+pub mod ibe;
 pub mod rsa;
@@ -0,0 +1,276 @@
+//! FRI, the Fast Reed–Solomon Interactive oracle proof of proximity.
+//!
+//! - [`prove`] encodes a [`Message`] as a Reed–Solomon codeword, commits to it with a
+//!   [`MerkleTree`] and folds it until it is constant, committing to every intermediate codeword.
+//! - [`verify`] checks, at a few random positions, that every codeword is the folding of the
+//!   previous one, which convinces it that the first codeword is close to a polynomial of degree
+//!   smaller than the degree bound.
+//!
+//! Both are parameterised by [`FriParameters`], and made non-interactive with a [`Transcript`].
+#![doc = include_str!("./README.md")]
+#[cfg(test)] mod tests;
+
+use std::{error::Error, fmt::Display};
+
+use crate::{
+  algebra::{
+    field::{prime::PrimeField, FiniteField},
+    Finite,
+  },
+  codes::reed_solomon::Message,
+  hashes::transcript::Transcript,
+  tree::merkle::{MerkleTree, Proof as MerkleProof},
+  Field,
+};
+
+/// The blowup factor and the number of queries of the protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FriParameters {
+  /// Ratio of the codeword length to the degree bound, the inverse of the rate of the code.
+  pub blowup:  usize,
+  /// Number of positions the verifier checks.
+  pub queries: usize,
+}
+
+/// Invalid [`FriParameters`] or degree bound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FriError {
+  /// The degree bound must be a power of two, and at least 2.
+  InvalidDegreeBound(usize),
+  /// The blowup factor must be a power of two, and at least 2.
+  InvalidBlowup(usize),
+  /// The field has no roots of unity of this order, the length of the codeword.
+  NoRootsOfUnity(usize),
+  /// At least one query is needed.
+  NoQueries,
+  /// The codeword does not have `degree_bound * blowup` values.
+  InvalidCodewordLength {
+    /// The length the parameters require.
+    expected: usize,
+    /// The length of the codeword.
+    found:    usize,
+  },
+}
+
+impl Error for FriError {}
+
+impl Display for FriError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      FriError::InvalidDegreeBound(k) => write!(f, "degree bound {} is not a power of two", k),
+      FriError::InvalidBlowup(b) => write!(f, "blowup factor {} is not a power of two", b),
+      FriError::NoRootsOfUnity(n) => write!(f, "the field has no roots of unity of order {}", n),
+      FriError::NoQueries => write!(f, "at least one query is needed"),
+      FriError::InvalidCodewordLength { expected, found } =>
+        write!(f, "codeword has {} values but {} are expected", found, expected),
+    }
+  }
+}
+
+/// The two values of a codeword at `ω^j` and `-ω^j`, with their Merkle proofs.
+#[derive(Debug, Clone)]
+pub struct Opening<const P: usize> {
+  /// The values at positions `j` and `j + m/2` of a codeword of length `m`.
+  pub values: [PrimeField<P>; 2],
+  /// The Merkle proofs of the values.
+  pub proofs: [MerkleProof; 2],
+}
+
+/// A FRI proof.
+#[derive(Debug, Clone)]
+pub struct FriProof<const P: usize> {
+  /// The Merkle roots of the codewords, starting with the commitment to the message.
+  pub roots:       Vec<[u8; 32]>,
+  /// The value of the final, constant codeword.
+  pub final_value: PrimeField<P>,
+  /// For every query, one opening per committed codeword.
+  pub queries:     Vec<Vec<Opening<P>>>,
+}
+
+impl FriParameters {
+  /// Checks the parameters for messages of length `degree_bound`, and returns the length of the
+  /// codeword.
+  pub fn codeword_length<const P: usize>(&self, degree_bound: usize) -> Result<usize, FriError> {
+    if !degree_bound.is_power_of_two() || degree_bound < 2 {
+      return Err(FriError::InvalidDegreeBound(degree_bound));
+    }
+    if !self.blowup.is_power_of_two() || self.blowup < 2 {
+      return Err(FriError::InvalidBlowup(self.blowup));
+    }
+    if self.queries == 0 {
+      return Err(FriError::NoQueries);
+    }
+    let n = degree_bound * self.blowup;
+    if (PrimeField::<P>::ORDER - 1) % n != 0 {
+      return Err(FriError::NoRootsOfUnity(n));
+    }
+    Ok(n)
+  }
+
+  /// Absorbs the parameters into the transcript.
+  fn absorb(&self, transcript: &mut Transcript, degree_bound: usize) {
+    transcript.append_message(b"degree_bound", &(degree_bound as u64).to_be_bytes());
+    transcript.append_message(b"blowup", &(self.blowup as u64).to_be_bytes());
+    transcript.append_message(b"queries", &(self.queries as u64).to_be_bytes());
+  }
+}
+
+/// Commits to a codeword with a Merkle tree whose leaves are the values.
+pub fn commit<const P: usize>(codeword: &[PrimeField<P>]) -> MerkleTree {
+  MerkleTree::new(codeword.iter().map(|value| value.to_string()).collect())
+}
+
+/// Folds the values `f(x)` and `f(-x)` into `f_e(x^2) + α f_o(x^2)`, where
+/// `f(X) = f_e(X^2) + X f_o(X^2)`.
+pub fn fold_pair<const P: usize>(
+  values: [PrimeField<P>; 2],
+  x: PrimeField<P>,
+  alpha: PrimeField<P>,
+) -> PrimeField<P> {
+  let two = PrimeField::<P>::new(2);
+  let even = (values[0] + values[1]) / two;
+  let odd = (values[0] - values[1]) / (two * x);
+  even + alpha * odd
+}
+
+/// Folds a codeword over the roots of unity generated by `omega` into a codeword of half the
+/// length over the roots of unity generated by `omega^2`.
+pub fn fold<const P: usize>(
+  codeword: &[PrimeField<P>],
+  omega: PrimeField<P>,
+  alpha: PrimeField<P>,
+) -> Vec<PrimeField<P>> {
+  let half = codeword.len() / 2;
+  (0..half).map(|j| fold_pair([codeword[j], codeword[j + half]], omega.pow(j), alpha)).collect()
+}
+
+/// Proves that the Reed–Solomon encoding of `message`, with the blowup factor of `params`, is a
+/// codeword of a polynomial of degree smaller than `K`.
+///
+/// ## Errors
+/// Returns a [`FriError`] if the parameters are invalid, see [`FriParameters::codeword_length`].
+pub fn prove<const K: usize, const P: usize>(
+  params: &FriParameters,
+  message: &Message<K, P>,
+  transcript: &mut Transcript,
+) -> Result<FriProof<P>, FriError> {
+  let n = params.codeword_length::<P>(K)?;
+  prove_codeword(params, K, message.evaluations(n), transcript)
+}
+
+/// Runs the prover on an arbitrary codeword over the roots of unity of order
+/// `degree_bound * blowup`. The proof only verifies, except with small probability, if the
+/// codeword is close to a polynomial of degree smaller than `degree_bound`.
+///
+/// ## Errors
+/// Returns a [`FriError`] if the parameters are invalid, see [`FriParameters::codeword_length`], or
+/// if the codeword does not have the right length.
+pub fn prove_codeword<const P: usize>(
+  params: &FriParameters,
+  degree_bound: usize,
+  mut codeword: Vec<PrimeField<P>>,
+  transcript: &mut Transcript,
+) -> Result<FriProof<P>, FriError> {
+  let n = params.codeword_length::<P>(degree_bound)?;
+  if codeword.len() != n {
+    return Err(FriError::InvalidCodewordLength { expected: n, found: codeword.len() });
+  }
+  params.absorb(transcript, degree_bound);
+
+  // commit phase
+  let mut omega = PrimeField::<P>::primitive_root_of_unity(n);
+  let mut layers = vec![];
+  while codeword.len() > params.blowup {
+    let tree = commit(&codeword);
+    transcript.append_message(b"root", &tree.root_hash());
+    let alpha = transcript.challenge(b"alpha");
+    let folded = fold(&codeword, omega, alpha);
+    layers.push((codeword, tree));
+    codeword = folded;
+    omega = omega * omega;
+  }
+  let final_value = codeword[0];
+  transcript.append_scalar(b"final", &final_value);
+
+  // query phase
+  let queries = (0..params.queries)
+    .map(|_| {
+      let index = transcript.challenge_index(b"query", n);
+      layers
+        .iter()
+        .map(|(codeword, tree)| {
+          let half = codeword.len() / 2;
+          let positions = [index % half, index % half + half];
+          Opening {
+            values: positions.map(|i| codeword[i]),
+            proofs: positions.map(|i| tree.get_proof(i)),
+          }
+        })
+        .collect()
+    })
+    .collect();
+
+  let roots = layers.iter().map(|(_, tree)| tree.root_hash()).collect();
+  Ok(FriProof { roots, final_value, queries })
+}
+
+/// Verifies that the codeword committed to in the first root of `proof` is close to a polynomial of
+/// degree smaller than `degree_bound`.
+///
+/// At every query position `i`, the verifier checks the Merkle proofs of the openings, that the
+/// value at `i` of every codeword is the folding of the previous one, and that the last folding
+/// gives the final value.
+pub fn verify<const P: usize>(
+  params: &FriParameters,
+  degree_bound: usize,
+  proof: &FriProof<P>,
+  transcript: &mut Transcript,
+) -> bool {
+  let Ok(n) = params.codeword_length::<P>(degree_bound) else {
+    return false;
+  };
+  let rounds = degree_bound.trailing_zeros() as usize;
+  if proof.roots.len() != rounds || proof.queries.len() != params.queries {
+    return false;
+  }
+  params.absorb(transcript, degree_bound);
+
+  let alphas: Vec<PrimeField<P>> = proof
+    .roots
+    .iter()
+    .map(|root| {
+      transcript.append_message(b"root", root);
+      transcript.challenge(b"alpha")
+    })
+    .collect();
+  transcript.append_scalar(b"final", &proof.final_value);
+  let omega = PrimeField::<P>::primitive_root_of_unity(n);
+
+  proof.queries.iter().all(|openings| {
+    let index = transcript.challenge_index(b"query", n);
+    if openings.len() != rounds {
+      return false;
+    }
+    let (mut omega, mut expected) = (omega, None);
+    for (round, opening) in openings.iter().enumerate() {
+      let half = (n >> round) / 2;
+      let positions = [index % half, index % half + half];
+      let opened = positions.iter().zip(&opening.values).zip(&opening.proofs).all(
+        |((&position, value), merkle_proof)| {
+          merkle_proof.leaf_index() == position
+            && merkle_proof.verify(proof.roots[round], &value.to_string())
+        },
+      );
+      if !opened {
+        return false;
+      }
+      // the value at the query position must be the folding of the previous codeword
+      if expected.is_some_and(|value| value != opening.values[(index % (2 * half)) / half]) {
+        return false;
+      }
+      expected = Some(fold_pair(opening.values, omega.pow(positions[0]), alphas[round]));
+      omega = omega * omega;
+    }
+    expected == Some(proof.final_value)
+  })
+}
@@ -0,0 +1,139 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::*;
+
+/// `257 - 1 = 2^8`, so there are roots of unity of order up to 256.
+const P: usize = 257;
+
+type F = PrimeField<P>;
+
+fn random_message<const K: usize>(rng: &mut StdRng) -> Message<K, P> {
+  Message::new(std::array::from_fn(|_| F::new(rng.gen_range(0..P))))
+}
+
+#[test]
+fn fold_halves_the_degree() {
+  // f(X) = 1 + 2X + 3X^2 + 4X^3 folds into (1 + 3Y) + α(2 + 4Y)
+  let message = Message::new([1, 2, 3, 4].map(F::new));
+  let codeword = message.evaluations(16);
+  let alpha = F::new(10);
+  let folded = fold(&codeword, F::primitive_root_of_unity(16), alpha);
+  let expected = Message::new([F::new(1) + alpha * F::new(2), F::new(3) + alpha * F::new(4)]);
+  assert_eq!(folded, expected.evaluations(8));
+}
+
+#[test]
+fn prove_and_verify() {
+  let mut rng = StdRng::seed_from_u64(0);
+  for (blowup, queries) in [(2, 1), (2, 8), (4, 4), (8, 2)] {
+    let params = FriParameters { blowup, queries };
+    let message = random_message::<8>(&mut rng);
+    let proof = prove(&params, &message, &mut Transcript::new(b"fri")).unwrap();
+    assert_eq!(proof.roots.len(), 3);
+    assert_eq!(proof.queries.len(), queries);
+    assert!(verify(&params, 8, &proof, &mut Transcript::new(b"fri")));
+  }
+}
+
+#[test]
+fn final_value_is_folded_constant() {
+  // a constant polynomial folds into itself
+  let params = FriParameters { blowup: 4, queries: 2 };
+  let message = Message::new([F::new(7), F::ZERO]);
+  let proof = prove(&params, &message, &mut Transcript::new(b"fri")).unwrap();
+  assert_eq!(proof.final_value, F::new(7));
+  assert!(verify(&params, 2, &proof, &mut Transcript::new(b"fri")));
+}
+
+#[test]
+fn reject_high_degree_codeword() {
+  let mut rng = StdRng::seed_from_u64(1);
+  let params = FriParameters { blowup: 4, queries: 8 };
+  // a polynomial of degree 15 is not close to any polynomial of degree smaller than 8
+  let codeword = random_message::<16>(&mut rng).evaluations(32);
+  let proof = prove_codeword(&params, 8, codeword, &mut Transcript::new(b"fri")).unwrap();
+  assert!(!verify(&params, 8, &proof, &mut Transcript::new(b"fri")));
+
+  // and neither is a random codeword
+  let codeword = (0..32).map(|_| F::new(rng.gen_range(0..P))).collect();
+  let proof = prove_codeword(&params, 8, codeword, &mut Transcript::new(b"fri")).unwrap();
+  assert!(!verify(&params, 8, &proof, &mut Transcript::new(b"fri")));
+}
+
+#[test]
+fn reject_tampered_proof() {
+  let mut rng = StdRng::seed_from_u64(2);
+  let params = FriParameters { blowup: 4, queries: 4 };
+  let proof = prove(&params, &random_message::<8>(&mut rng), &mut Transcript::new(b"fri")).unwrap();
+  let verify = |proof: &FriProof<P>| verify(&params, 8, proof, &mut Transcript::new(b"fri"));
+  assert!(verify(&proof));
+
+  let mut tampered = proof.clone();
+  tampered.final_value += F::ONE;
+  assert!(!verify(&tampered));
+
+  let mut tampered = proof.clone();
+  tampered.queries[0][1].values[0] += F::ONE;
+  assert!(!verify(&tampered));
+
+  // valid openings at the wrong positions don't answer the query
+  let mut tampered = proof.clone();
+  tampered.queries[0][0].values.swap(0, 1);
+  tampered.queries[0][0].proofs.swap(0, 1);
+  assert!(!verify(&tampered));
+
+  let mut tampered = proof.clone();
+  tampered.roots.swap(0, 1);
+  assert!(!verify(&tampered));
+
+  let mut tampered = proof;
+  tampered.queries.pop();
+  assert!(!verify(&tampered));
+}
+
+#[test]
+fn parameters_are_bound_to_the_proof() {
+  let mut rng = StdRng::seed_from_u64(3);
+  let params = FriParameters { blowup: 4, queries: 4 };
+  let proof = prove(&params, &random_message::<8>(&mut rng), &mut Transcript::new(b"fri")).unwrap();
+
+  assert!(!verify(
+    &FriParameters { blowup: 2, queries: 4 },
+    8,
+    &proof,
+    &mut Transcript::new(b"fri")
+  ));
+  assert!(!verify(&params, 4, &proof, &mut Transcript::new(b"fri")));
+  assert!(!verify(&params, 8, &proof, &mut Transcript::new(b"other")));
+}
+
+#[test]
+fn invalid_parameters() {
+  let message = Message::new([F::ONE; 8]);
+  let prove = |blowup, queries| {
+    prove(&FriParameters { blowup, queries }, &message, &mut Transcript::new(b"fri")).map(|_| ())
+  };
+  assert_eq!(prove(3, 4), Err(FriError::InvalidBlowup(3)));
+  assert_eq!(prove(1, 4), Err(FriError::InvalidBlowup(1)));
+  assert_eq!(prove(4, 0), Err(FriError::NoQueries));
+  assert_eq!(prove(64, 4), Err(FriError::NoRootsOfUnity(512)));
+  assert_eq!(
+    super::prove(
+      &FriParameters { blowup: 2, queries: 1 },
+      &Message::new([F::ONE; 3]),
+      &mut Transcript::new(b"fri")
+    )
+    .map(|_| ()),
+    Err(FriError::InvalidDegreeBound(3))
+  );
+  assert_eq!(
+    prove_codeword(
+      &FriParameters { blowup: 2, queries: 1 },
+      4,
+      vec![F::ONE; 4],
+      &mut Transcript::new(b"fri")
+    )
+    .map(|_| ()),
+    Err(FriError::InvalidCodewordLength { expected: 8, found: 4 })
+  );
+}
@@ -0,0 +1,267 @@
+//! Groth16 zk-SNARK for circuits written in the [DSL](crate::compiler).
+//!
+//! - [`R1CS::new`](crate::compiler::r1cs::R1CS::new) turns the DSL constraints into a rank-1
+//!   constraint system.
+//! - [`Qap`] interpolates the R1CS into a quadratic arithmetic program.
+//! - [`setup`] samples the toxic waste and produces a [`ProvingKey`] and [`VerifyingKey`].
+//! - [`prove`] creates a [`Proof`] of three group elements for a witness.
+//! - [`verify`] checks a proof against the public inputs with a single pairing product.
+#![doc = include_str!("./README.md")]
+#[cfg(test)] mod tests;
+
+pub mod qap;
+
+use std::{error::Error, fmt::Display};
+
+pub use qap::Qap;
+use rand::Rng;
+
+use crate::{
+  algebra::{field::FiniteField, group::Group, Finite},
+  curve::pairing::PairingCurve,
+  polynomial::{Lagrange, Monomial, Polynomial},
+  Field, PlutoScalarField,
+};
+
+/// Errors from creating a Groth16 proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Groth16Error {
+  /// The witness does not have one value per variable.
+  InvalidWitnessLength {
+    /// Number of variables of the QAP.
+    expected: usize,
+    /// Length of the witness.
+    found:    usize,
+  },
+  /// The witness does not satisfy the constraints, or does not start with `1`.
+  UnsatisfiedConstraints,
+}
+
+impl Error for Groth16Error {}
+
+impl Display for Groth16Error {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Groth16Error::InvalidWitnessLength { expected, found } =>
+        write!(f, "witness has {} values but {} variables are expected", found, expected),
+      Groth16Error::UnsatisfiedConstraints => write!(f, "witness does not satisfy the constraints"),
+    }
+  }
+}
+
+/// The part of the setup the prover needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvingKey<P: PairingCurve> {
+  /// `[α]G1`
+  pub alpha_g1:   P::G1,
+  /// `[β]G1`
+  pub beta_g1:    P::G1,
+  /// `[β]G2`
+  pub beta_g2:    P::G2,
+  /// `[δ]G1`
+  pub delta_g1:   P::G1,
+  /// `[δ]G2`
+  pub delta_g2:   P::G2,
+  /// `[u_i(τ)]G1` for every variable.
+  pub a_query:    Vec<P::G1>,
+  /// `[v_i(τ)]G1` for every variable.
+  pub b_g1_query: Vec<P::G1>,
+  /// `[v_i(τ)]G2` for every variable.
+  pub b_g2_query: Vec<P::G2>,
+  /// `[(β u_i(τ) + α v_i(τ) + w_i(τ)) / δ]G1` for every private variable.
+  pub l_query:    Vec<P::G1>,
+  /// `[τ^j Z(τ) / δ]G1` for `j = 0..N-1`.
+  pub h_query:    Vec<P::G1>,
+}
+
+/// The part of the setup the verifier needs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyingKey<P: PairingCurve> {
+  /// `[α]G1`
+  pub alpha_g1: P::G1,
+  /// `[β]G2`
+  pub beta_g2:  P::G2,
+  /// `[γ]G2`
+  pub gamma_g2: P::G2,
+  /// `[δ]G2`
+  pub delta_g2: P::G2,
+  /// `[(β u_i(τ) + α v_i(τ) + w_i(τ)) / γ]G1` for the constant `1` and every public input.
+  pub ic:       Vec<P::G1>,
+}
+
+/// A Groth16 proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Proof<P: PairingCurve> {
+  /// `[A]G1`
+  pub a: P::G1,
+  /// `[B]G2`
+  pub b: P::G2,
+  /// `[C]G1`
+  pub c: P::G1,
+}
+
+/// The secrets of the setup, which must be destroyed once the keys are computed: anyone who knows
+/// them can prove any statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToxicWaste<F: FiniteField> {
+  /// `α`
+  pub alpha: F,
+  /// `β`
+  pub beta:  F,
+  /// `γ`
+  pub gamma: F,
+  /// `δ`
+  pub delta: F,
+  /// `τ`, the point the QAP polynomials are evaluated at.
+  pub tau:   F,
+}
+
+impl<F: FiniteField> ToxicWaste<F> {
+  /// Samples non-zero secrets, with `τ` outside of the roots of unity of order `n` so that
+  /// `Z(τ) != 0`.
+  pub fn random<R: Rng + ?Sized>(rng: &mut R, n: usize) -> Self {
+    let mut sample = || F::from(rng.gen_range(1..F::ORDER));
+    let (alpha, beta, gamma, delta) = (sample(), sample(), sample(), sample());
+    let tau = loop {
+      let tau = sample();
+      if tau.pow(n) != F::ONE {
+        break tau;
+      }
+    };
+    Self { alpha, beta, gamma, delta, tau }
+  }
+}
+
+/// Runs the circuit specific setup for `qap` with random toxic waste.
+pub fn setup<P: PairingCurve, R: Rng + ?Sized, const N: usize>(
+  qap: &Qap<P::ScalarField, N>,
+  rng: &mut R,
+) -> (ProvingKey<P>, VerifyingKey<P>)
+where
+  P::ScalarField: FiniteField,
+{
+  setup_with(qap, ToxicWaste::random(rng, N))
+}
+
+/// Runs the circuit specific setup for `qap` with the given toxic waste.
+///
+/// ## Panics
+/// Panics if `γ` or `δ` is zero, or if `Z(τ) = 0`.
+pub fn setup_with<P: PairingCurve, const N: usize>(
+  qap: &Qap<P::ScalarField, N>,
+  toxic_waste: ToxicWaste<P::ScalarField>,
+) -> (ProvingKey<P>, VerifyingKey<P>)
+where
+  P::ScalarField: FiniteField,
+{
+  let ToxicWaste { alpha, beta, gamma, delta, tau } = toxic_waste;
+  let (g1, g2) = (P::G1_GENERATOR, P::G2_GENERATOR);
+  let gamma_inv = gamma.inverse().expect("γ must be non-zero");
+  let delta_inv = delta.inverse().expect("δ must be non-zero");
+  let z_tau = Qap::<P::ScalarField, N>::vanishing(tau);
+  assert_ne!(z_tau, P::ScalarField::ZERO, "τ must not be a root of unity");
+
+  let u: Vec<_> = qap.u.iter().map(|p| p.evaluate(tau)).collect();
+  let v: Vec<_> = qap.v.iter().map(|p| p.evaluate(tau)).collect();
+  let w: Vec<_> = qap.w.iter().map(|p| p.evaluate(tau)).collect();
+  let combined: Vec<_> =
+    (0..qap.num_variables()).map(|i| beta * u[i] + alpha * v[i] + w[i]).collect();
+  let (public, private) = combined.split_at(qap.num_public + 1);
+
+  let pk = ProvingKey {
+    alpha_g1:   g1 * alpha,
+    beta_g1:    g1 * beta,
+    beta_g2:    g2 * beta,
+    delta_g1:   g1 * delta,
+    delta_g2:   g2 * delta,
+    a_query:    u.iter().map(|&x| g1 * x).collect(),
+    b_g1_query: v.iter().map(|&x| g1 * x).collect(),
+    b_g2_query: v.iter().map(|&x| g2 * x).collect(),
+    l_query:    private.iter().map(|&x| g1 * (x * delta_inv)).collect(),
+    h_query:    (0..N - 1).map(|j| g1 * (tau.pow(j) * z_tau * delta_inv)).collect(),
+  };
+  let vk = VerifyingKey {
+    alpha_g1: pk.alpha_g1,
+    beta_g2:  pk.beta_g2,
+    gamma_g2: g2 * gamma,
+    delta_g2: pk.delta_g2,
+    ic:       public.iter().map(|&x| g1 * (x * gamma_inv)).collect(),
+  };
+  (pk, vk)
+}
+
+/// `Σ scalars[i] points[i]`
+fn linear_combination<G: Group>(points: &[G], scalars: &[G::Scalar]) -> G
+where G::Scalar: Copy {
+  points.iter().zip(scalars).fold(G::IDENTITY, |acc, (&p, &s)| acc + p * s)
+}
+
+/// Proves knowledge of `witness`, laid out as `[1, public inputs.., private variables..]`, see
+/// [`R1CS::witness`](crate::compiler::r1cs::R1CS::witness).
+///
+/// With random `r` and `s`, the proof is
+/// - `A = α + Σ s_i u_i(τ) + rδ`,
+/// - `B = β + Σ s_i v_i(τ) + sδ`,
+/// - `C = (Σ_{private} s_i (β u_i(τ) + α v_i(τ) + w_i(τ)) + h(τ) Z(τ)) / δ + sA + rB - rsδ`.
+///
+/// ## Errors
+/// - [`Groth16Error::InvalidWitnessLength`] if the witness does not match the QAP.
+/// - [`Groth16Error::UnsatisfiedConstraints`] if the witness does not satisfy the constraints.
+pub fn prove<P: PairingCurve, R: Rng + ?Sized, const N: usize>(
+  pk: &ProvingKey<P>,
+  qap: &Qap<P::ScalarField, N>,
+  witness: &[P::ScalarField],
+  rng: &mut R,
+) -> Result<Proof<P>, Groth16Error>
+where
+  P::ScalarField: FiniteField,
+  [(); N + N - 1]:,
+{
+  if witness.len() != qap.num_variables() {
+    return Err(Groth16Error::InvalidWitnessLength {
+      expected: qap.num_variables(),
+      found:    witness.len(),
+    });
+  }
+  if witness[0] != P::ScalarField::ONE {
+    return Err(Groth16Error::UnsatisfiedConstraints);
+  }
+  let h = qap.quotient(witness).ok_or(Groth16Error::UnsatisfiedConstraints)?;
+
+  let r = P::ScalarField::from(rng.gen_range(0..P::ScalarField::ORDER));
+  let s = P::ScalarField::from(rng.gen_range(0..P::ScalarField::ORDER));
+  let a = pk.alpha_g1 + linear_combination(&pk.a_query, witness) + pk.delta_g1 * r;
+  let b_g1 = pk.beta_g1 + linear_combination(&pk.b_g1_query, witness) + pk.delta_g1 * s;
+  let b = pk.beta_g2 + linear_combination(&pk.b_g2_query, witness) + pk.delta_g2 * s;
+  let c = linear_combination(&pk.l_query, &witness[qap.num_public + 1..])
+    + linear_combination(&pk.h_query, &h.coefficients)
+    + a * s
+    + b_g1 * r
+    - pk.delta_g1 * (r * s);
+  Ok(Proof { a, b, c })
+}
+
+/// Verifies a proof for the given public inputs with the pairing-product check
+/// `e(A, B) = e(α, β) · e(Σ x_i IC_i, γ) · e(C, δ)`, with `x_0 = 1`.
+///
+/// Returns `false` if the number of public inputs does not match the verifying key, or if a point
+/// of the proof is not in its group, see [`PairingCurve::is_in_g2`]: a `B` that pairs trivially
+/// with `A` would let anyone solve the check for `C`.
+pub fn verify<P: PairingCurve>(
+  vk: &VerifyingKey<P>,
+  public_inputs: &[P::ScalarField],
+  proof: &Proof<P>,
+) -> bool {
+  if public_inputs.len() + 1 != vk.ic.len() {
+    return false;
+  }
+  if !P::is_in_g1(&proof.a) || !P::is_in_g2(&proof.b) || !P::is_in_g1(&proof.c) {
+    return false;
+  }
+
+  let inputs = [&[P::ScalarField::ONE], public_inputs].concat();
+  let ic = linear_combination(&vk.ic, &inputs);
+  let pairs =
+    [(proof.a, proof.b), (-vk.alpha_g1, vk.beta_g2), (-ic, vk.gamma_g2), (-proof.c, vk.delta_g2)];
+  P::multi_pairing(&pairs) == P::TARGET_ONE
+}
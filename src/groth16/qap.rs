@@ -0,0 +1,100 @@
+//! Quadratic arithmetic programs.
+//!
+//! A QAP encodes an [`R1CS`] with `m` constraints as polynomials over the roots of unity
+//! `ω^0..ω^{N-1}`: for every variable `i`, `u_i(X)`, `v_i(X)` and `w_i(X)` interpolate the
+//! coefficients of the variable in the rows of `A`, `B` and `C`. A witness `s` satisfies every
+//! constraint exactly when
+//! $$
+//! \left(\sum_i s_i u_i(X)\right)\left(\sum_i s_i v_i(X)\right) - \sum_i s_i w_i(X) = h(X) Z(X)
+//! $$
+//! for some polynomial `h(X)`, where `Z(X) = X^N - 1` vanishes on all the roots of unity.
+//!
+//! For every public input, and for the constant `1`, an extra row `s_i · 0 = 0` is added. It does
+//! not change which witnesses are valid but makes the `u_i` of the public inputs linearly
+//! independent, which Groth16 needs for the public inputs to be bound to the proof.
+use std::array;
+
+use super::*;
+use crate::compiler::r1cs::R1CS;
+
+/// A quadratic arithmetic program over the `N`-th roots of unity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Qap<F: FiniteField, const N: usize> {
+  /// `u_i(X)` for every variable, in the [`Lagrange`] basis.
+  pub u:          Vec<Polynomial<Lagrange<F>, F, N>>,
+  /// `v_i(X)` for every variable, in the [`Lagrange`] basis.
+  pub v:          Vec<Polynomial<Lagrange<F>, F, N>>,
+  /// `w_i(X)` for every variable, in the [`Lagrange`] basis.
+  pub w:          Vec<Polynomial<Lagrange<F>, F, N>>,
+  /// Number of public inputs, which are the variables `1..=num_public`.
+  pub num_public: usize,
+}
+
+impl<const N: usize> Qap<PlutoScalarField, N> {
+  /// Interpolates the matrices of `r1cs` over the `N`-th roots of unity.
+  ///
+  /// ## Panics
+  /// Panics if `N` is smaller than 2 or than the number of constraints plus public inputs plus one,
+  /// or if there are no `N`-th roots of unity in the field.
+  pub fn new(r1cs: &R1CS) -> Self {
+    let rows = r1cs.constraints.len() + r1cs.num_public + 1;
+    assert!(N >= 2 && rows <= N, "QAP needs {} points but the domain has {}", rows, N);
+
+    let num_variables = r1cs.variables.len();
+    let mut matrices = [(); 3].map(|_| vec![[PlutoScalarField::ZERO; N]; num_variables]);
+    for (row, constraint) in r1cs.constraints.iter().enumerate() {
+      for (matrix, lc) in matrices.iter_mut().zip([&constraint.a, &constraint.b, &constraint.c]) {
+        for &(variable, coeff) in lc {
+          matrix[variable][row] += coeff;
+        }
+      }
+    }
+    for variable in 0..=r1cs.num_public {
+      matrices[0][variable][r1cs.constraints.len() + variable] = PlutoScalarField::ONE;
+    }
+
+    let [u, v, w] =
+      matrices.map(|matrix| matrix.into_iter().map(Polynomial::<Lagrange<_>, _, N>::new).collect());
+    Self { u, v, w, num_public: r1cs.num_public }
+  }
+}
+
+impl<F: FiniteField, const N: usize> Qap<F, N> {
+  /// Number of variables, including the constant `1`.
+  pub fn num_variables(&self) -> usize { self.u.len() }
+
+  /// `Z(x) = x^N - 1`
+  pub fn vanishing(x: F) -> F { x.pow(N) - F::ONE }
+
+  /// Computes `h(X) = (A(X) B(X) - C(X)) / Z(X)` for the witness `s`, where `A(X) = Σ s_i u_i(X)`
+  /// and so on.
+  ///
+  /// Returns `None` if the division is not exact, i.e. if `s` does not satisfy the constraints.
+  /// `h` has degree at most `N - 2`.
+  ///
+  /// ## Panics
+  /// Panics if the witness does not have one value per variable.
+  pub fn quotient(&self, witness: &[F]) -> Option<Polynomial<Monomial, F, N>>
+  where [(); N + N - 1]: {
+    assert_eq!(witness.len(), self.num_variables(), "witness must have one value per variable");
+    let combine = |polys: &[Polynomial<Lagrange<F>, F, N>]| {
+      let evaluations = array::from_fn(|row| {
+        polys.iter().zip(witness).map(|(p, &s)| p.coefficients[row] * s).sum()
+      });
+      Polynomial::<Lagrange<F>, F, N>::new(evaluations).idft()
+    };
+    let (a, b, c) = (combine(&self.u), combine(&self.v), combine(&self.w));
+
+    // divide by `X^N - 1` by folding every coefficient of degree `i >= N` onto degree `i - N`
+    let mut remainder = (a * b - c).coefficients;
+    let mut quotient = [F::ZERO; N];
+    for i in (N..remainder.len()).rev() {
+      quotient[i - N] = remainder[i];
+      remainder[i - N] += remainder[i];
+    }
+    if remainder[..N].iter().any(|&c| c != F::ZERO) {
+      return None;
+    }
+    Some(Polynomial::<Monomial, F, N>::new(quotient))
+  }
+}
@@ -0,0 +1,148 @@
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::*;
+use crate::{
+  compiler::{program::Program, r1cs::R1CS},
+  curve::{pairing::PlutoPairing, pluto_curve::PlutoExtendedCurve, AffinePoint},
+};
+
+const CUBIC: &[&str] = &["x public", "x2 <== x * x", "out <== x2 * x + 5"];
+
+/// Builds the QAP of `constraints` and solves the circuit for `public_inputs`.
+fn compile<const N: usize>(
+  constraints: &[&'static str],
+  public_inputs: &[PlutoScalarField],
+) -> (Qap<PlutoScalarField, N>, Vec<PlutoScalarField>) {
+  let program = Program::<N>::new(constraints).unwrap();
+  let names = program.public_assignments().unwrap();
+  let assignments: HashMap<Option<&str>, PlutoScalarField> =
+    names.iter().map(|name| Some(name.as_str())).zip(public_inputs.iter().copied()).collect();
+  let assignments = program.evaluate_circuit(assignments).unwrap();
+  let r1cs = R1CS::new(constraints).unwrap();
+  let witness = r1cs.witness(&assignments).unwrap();
+  (Qap::new(&r1cs), witness)
+}
+
+#[test]
+fn qap_quotient() {
+  let (qap, witness) = compile::<4>(CUBIC, &[PlutoScalarField::new(3)]);
+  assert_eq!(qap.num_variables(), 4);
+  assert_eq!(qap.num_public, 1);
+  assert!(qap.quotient(&witness).is_some());
+
+  let mut wrong = witness;
+  wrong[3] += PlutoScalarField::ONE;
+  assert!(qap.quotient(&wrong).is_none());
+}
+
+#[test]
+fn prove_and_verify() {
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (qap, witness) = compile::<4>(CUBIC, &public_inputs);
+
+  for seed in 0..8 {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let (pk, vk) = setup::<PlutoPairing, _, 4>(&qap, &mut rng);
+    let proof = prove(&pk, &qap, &witness, &mut rng).unwrap();
+    assert!(verify(&vk, &public_inputs, &proof));
+  }
+}
+
+#[test]
+fn prove_and_verify_with_constants_and_equality() {
+  let constraints = &["a public", "d === 9", "b <== a * a + 5", "c <== -2 * b - a * b"];
+  let public_inputs = [PlutoScalarField::new(2)];
+  let (qap, witness) = compile::<8>(constraints, &public_inputs);
+
+  let mut rng = StdRng::seed_from_u64(1);
+  let (pk, vk) = setup::<PlutoPairing, _, 8>(&qap, &mut rng);
+  let proof = prove(&pk, &qap, &witness, &mut rng).unwrap();
+  assert!(verify(&vk, &public_inputs, &proof));
+}
+
+#[test]
+fn reject_wrong_public_input() {
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (qap, witness) = compile::<4>(CUBIC, &public_inputs);
+  let mut rng = StdRng::seed_from_u64(2);
+  let (pk, vk) = setup::<PlutoPairing, _, 4>(&qap, &mut rng);
+  let proof = prove(&pk, &qap, &witness, &mut rng).unwrap();
+
+  assert!(!verify(&vk, &[PlutoScalarField::new(4)], &proof));
+  assert!(!verify(&vk, &[], &proof));
+}
+
+#[test]
+fn reject_tampered_proof() {
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (qap, witness) = compile::<4>(CUBIC, &public_inputs);
+  let mut rng = StdRng::seed_from_u64(3);
+  let (pk, vk) = setup::<PlutoPairing, _, 4>(&qap, &mut rng);
+  let proof = prove(&pk, &qap, &witness, &mut rng).unwrap();
+
+  let mut tampered = proof;
+  tampered.a += PlutoPairing::G1_GENERATOR;
+  assert!(!verify(&vk, &public_inputs, &tampered));
+
+  let mut tampered = proof;
+  tampered.c = -proof.c;
+  assert!(!verify(&vk, &public_inputs, &tampered));
+}
+
+#[test]
+fn reject_b_outside_g2() {
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (qap, _) = compile::<4>(CUBIC, &public_inputs);
+  let (_, vk) = setup::<PlutoPairing, _, 4>(&qap, &mut StdRng::seed_from_u64(3));
+
+  // `G1` lifted to the extension has order 17 but pairs trivially with `A`, so the check reduces
+  // to `e(α, β) · e(IC, γ) · e(C, δ) = 1`, which a small search solves for `C`. `[3]A` keeps the
+  // lines of the Miller loop from vanishing at `B`.
+  let a = PlutoPairing::G1_GENERATOR;
+  let b = AffinePoint::<PlutoExtendedCurve>::from(a * PlutoScalarField::new(3));
+  let ic = vk.ic[0] + vk.ic[1] * public_inputs[0];
+  let c = (0..17)
+    .map(|k| a * PlutoScalarField::new(k))
+    .find(|&c| {
+      let pairs = [(a, b), (-vk.alpha_g1, vk.beta_g2), (-ic, vk.gamma_g2), (-c, vk.delta_g2)];
+      PlutoPairing::multi_pairing(&pairs) == PlutoPairing::TARGET_ONE
+    })
+    .unwrap();
+  assert!(!verify(&vk, &public_inputs, &Proof { a, b, c }));
+}
+
+#[test]
+fn reject_proof_for_other_circuit() {
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (qap, witness) = compile::<4>(CUBIC, &public_inputs);
+  let mut rng = StdRng::seed_from_u64(4);
+  let (pk, _) = setup::<PlutoPairing, _, 4>(&qap, &mut rng);
+  let proof = prove(&pk, &qap, &witness, &mut rng).unwrap();
+
+  let (other, _) =
+    compile::<4>(&["x public", "x2 <== x * x", "out <== x2 * x + 6"], &public_inputs);
+  let (_, other_vk) = setup_with::<PlutoPairing, 4>(&other, ToxicWaste::random(&mut rng, 4));
+  assert!(!verify(&other_vk, &public_inputs, &proof));
+}
+
+#[test]
+fn unsatisfied_witness() {
+  let (qap, witness) = compile::<4>(CUBIC, &[PlutoScalarField::new(3)]);
+  let mut rng = StdRng::seed_from_u64(5);
+  let (pk, _) = setup::<PlutoPairing, _, 4>(&qap, &mut rng);
+
+  let mut wrong = witness.clone();
+  wrong[2] += PlutoScalarField::ONE;
+  assert_eq!(prove(&pk, &qap, &wrong, &mut rng), Err(Groth16Error::UnsatisfiedConstraints));
+
+  let mut wrong = witness.clone();
+  wrong[0] = PlutoScalarField::new(2);
+  assert_eq!(prove(&pk, &qap, &wrong, &mut rng), Err(Groth16Error::UnsatisfiedConstraints));
+
+  assert_eq!(
+    prove(&pk, &qap, &witness[..3], &mut rng),
+    Err(Groth16Error::InvalidWitnessLength { expected: 4, found: 3 })
+  );
+}
@@ -201,6 +201,70 @@ impl<T, const N: usize, const ROUNDS: usize> SHA<T, N, ROUNDS> {
   }
 }
 
+impl<T, const N: usize, const ROUNDS: usize> SHA<T, N, ROUNDS>
+where T: PrimInt
+    + Default
+    + WrappingAdd
+    + ToBytes<Bytes = [u8; std::mem::size_of::<T>()]>
+    + FromBytes<Bytes = [u8; std::mem::size_of::<T>()]>
+{
+  /// Expands `msg` into `len_in_bytes` uniformly random bytes using `expand_message_xmd` from
+  /// [RFC 9380 §5.3.1](https://www.rfc-editor.org/rfc/rfc9380.html#section-5.3.1).
+  ///
+  /// The message is hashed together with the domain separation tag `dst` into `b_0`, and the
+  /// output blocks are chained as `b_i = H((b_0 ^ b_{i-1}) || i || dst)`. Domain separation tags
+  /// longer than 255 bytes are first hashed down as described in §5.3.3.
+  ///
+  /// ## Panics
+  /// Panics if `len_in_bytes` exceeds `min(255 * b_in_bytes, 65535)` where `b_in_bytes` is the
+  /// digest size.
+  ///
+  /// # Example
+  /// ```
+  /// use ronkathon::hashes::sha::Sha256;
+  ///
+  /// let output =
+  ///   Sha256::new().expand_message_xmd(b"abc", b"QUUX-V01-CS02-with-expander-SHA256-128", 32);
+  /// assert_eq!(
+  ///   hex::encode(output),
+  ///   "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+  /// );
+  /// ```
+  pub fn expand_message_xmd(&self, msg: &[u8], dst: &[u8], len_in_bytes: usize) -> Vec<u8> {
+    // digest size and input block size of the hash function
+    let b_in_bytes = 8 * std::mem::size_of::<T>();
+    let s_in_bytes = N / 4;
+
+    let oversize_dst;
+    let dst = if dst.len() > 255 {
+      oversize_dst = self.digest(&[b"H2C-OVERSIZE-DST-", dst].concat());
+      &oversize_dst
+    } else {
+      dst
+    };
+
+    let ell = len_in_bytes.div_ceil(b_in_bytes);
+    assert!(ell <= 255 && len_in_bytes <= 65535, "requested too many bytes");
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+
+    // b_0 = H(Z_pad || msg || l_i_b_str || I2OSP(0, 1) || DST_prime)
+    let z_pad = vec![0u8; s_in_bytes];
+    let l_i_b_str = (len_in_bytes as u16).to_be_bytes();
+    let b_0 = self.digest(&[&z_pad, msg, &l_i_b_str, &[0], &dst_prime].concat());
+
+    // b_1 = H(b_0 || I2OSP(1, 1) || DST_prime)
+    let mut b_i = self.digest(&[&b_0[..], &[1], &dst_prime].concat());
+    let mut uniform_bytes = b_i.clone();
+    for i in 2..=ell {
+      let xored: Vec<u8> = b_0.iter().zip(&b_i).map(|(a, b)| a ^ b).collect();
+      b_i = self.digest(&[&xored[..], &[i as u8], &dst_prime].concat());
+      uniform_bytes.extend(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+    uniform_bytes
+  }
+}
+
 /// The SHA-256 hash function
 pub type Sha256 = SHA<u32, 256, 64>;
 
@@ -379,4 +443,34 @@ mod tests {
     let hash = Sha256::new();
     assert_eq!(hex::encode(hash.digest(input)), expected);
   }
+
+  // Test vectors from RFC 9380 Appendix K.1
+  #[rstest]
+  #[case(b"", 0x20, "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235")]
+  #[case(b"abc", 0x20, "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615")]
+  #[case(
+    b"abcdef0123456789",
+    0x20,
+    "eff31487c770a893cfb36f912fbfcbff40d5661771ca4b2cb4eafe524333f5c1"
+  )]
+  #[case(b"", 0x80, "af84c27ccfd45d41914fdff5df25293e221afc53d8ad2ac06d5e3e29485dadbee0d121587713a3e0dd4d5e69e93eb7cd4f5df4cd103e188cf60cb02edc3edf18eda8576c412b18ffb658e3dd6ec849469b979d444cf7b26911a08e63cf31f9dcc541708d3491184472c2c29bb749d4286b004ceb5ee6b9a7fa5b646c993f0ced")]
+  fn test_expand_message_xmd_sha256(
+    #[case] msg: &[u8],
+    #[case] len_in_bytes: usize,
+    #[case] expected: &str,
+  ) {
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    let output = Sha256::new().expand_message_xmd(msg, dst, len_in_bytes);
+    assert_eq!(hex::encode(output), expected);
+  }
+
+  #[test]
+  fn test_expand_message_xmd_sha512() {
+    let dst = b"QUUX-V01-CS02-with-expander-SHA512-256";
+    let output = Sha512::new().expand_message_xmd(b"abc", dst, 0x20);
+    assert_eq!(
+      hex::encode(output),
+      "0da749f12fbe5483eb066a5f595055679b976e93abe9be6f0f6318bce7aca8dc"
+    );
+  }
 }
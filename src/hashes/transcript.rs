@@ -0,0 +1,148 @@
+//! A [Fiat–Shamir](https://en.wikipedia.org/wiki/Fiat%E2%80%93Shamir_heuristic) transcript built on
+//! [`Sha256`].
+//!
+//! Interactive protocols are made non-interactive by replacing the verifier's random challenges
+//! with hashes of everything the prover has sent so far. The prover and the verifier keep their own
+//! [`Transcript`], append the same messages in the same order and so derive the same challenges.
+//!
+//! Every message and challenge is labelled, and the state is replaced by
+//! `SHA-256(state || len(label) || label || len(data) || data)` on every call, so that two
+//! different sequences of messages never lead to the same state.
+use super::sha::Sha256;
+use crate::{
+  algebra::field::{FieldBytes, FiniteField, SqrtField},
+  curve::{AffinePoint, EllipticCurve},
+};
+
+/// How many times [`Transcript::challenge_excluding`] draws a challenge before giving up. Even when
+/// all but one element of the field is excluded, 256 draws of a field of order 17 all miss it
+/// with probability below `2^-22`.
+pub const MAX_CHALLENGE_DRAWS: usize = 256;
+
+/// The running state of a Fiat–Shamir transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transcript {
+  state: Vec<u8>,
+}
+
+impl Transcript {
+  /// Starts a transcript for the protocol identified by `label`.
+  pub fn new(label: &[u8]) -> Self {
+    let mut transcript = Self { state: vec![] };
+    transcript.append_message(b"protocol", label);
+    transcript
+  }
+
+  /// Appends arbitrary bytes to the transcript.
+  pub fn append_message(&mut self, label: &[u8], data: &[u8]) {
+    let input = [
+      &self.state[..],
+      &(label.len() as u64).to_be_bytes(),
+      label,
+      &(data.len() as u64).to_be_bytes(),
+      data,
+    ]
+    .concat();
+    self.state = Sha256::new().digest(&input);
+  }
+
+  /// Appends a field element, encoded with [`FieldBytes`].
+  pub fn append_scalar<F: FieldBytes>(&mut self, label: &[u8], scalar: &F) {
+    self.append_message(label, &scalar.to_bytes());
+  }
+
+  /// Appends a curve point in its compressed encoding.
+  pub fn append_point<C: EllipticCurve>(&mut self, label: &[u8], point: &AffinePoint<C>)
+  where C::BaseField: SqrtField + FieldBytes {
+    self.append_message(label, &point.to_compressed());
+  }
+
+  /// Derives a challenge from everything appended so far. The challenge itself is appended, so
+  /// that consecutive challenges differ.
+  ///
+  /// The first 8 bytes of the new state are reduced modulo the order of the field, which is
+  /// slightly biased for fields much larger than `2^64`.
+  pub fn challenge<F: FiniteField>(&mut self, label: &[u8]) -> F {
+    self.append_message(label, b"challenge");
+    let bytes: [u8; 8] = self.state[..8].try_into().unwrap();
+    F::from((u64::from_be_bytes(bytes) % F::ORDER as u64) as usize)
+  }
+
+  /// Derives an index in `0..bound`, for example a position to query, from the first 8 bytes of
+  /// the new state.
+  pub fn challenge_index(&mut self, label: &[u8], bound: usize) -> usize {
+    self.append_message(label, b"challenge");
+    let bytes: [u8; 8] = self.state[..8].try_into().unwrap();
+    (u64::from_be_bytes(bytes) % bound as u64) as usize
+  }
+
+  /// Derives a challenge that is neither zero nor in `excluded`, drawing again as long as it is.
+  ///
+  /// ## Panics
+  /// Panics after [`MAX_CHALLENGE_DRAWS`] draws without such a challenge, which happens when
+  /// `excluded` covers the whole field, instead of drawing forever.
+  pub fn challenge_excluding<F: FiniteField>(&mut self, label: &[u8], excluded: &[F]) -> F {
+    (0..MAX_CHALLENGE_DRAWS)
+      .map(|_| self.challenge(label))
+      .find(|challenge| *challenge != F::ZERO && !excluded.contains(challenge))
+      .expect("no challenge left outside of the excluded values")
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::{algebra::group::FiniteCyclicGroup, PlutoBaseCurve, PlutoScalarField};
+
+  #[test]
+  fn same_messages_same_challenges() {
+    let mut prover = Transcript::new(b"test");
+    let mut verifier = Transcript::new(b"test");
+    for transcript in [&mut prover, &mut verifier] {
+      transcript.append_scalar(b"x", &PlutoScalarField::new(5));
+      transcript.append_point(b"p", &AffinePoint::<PlutoBaseCurve>::GENERATOR);
+    }
+    let a: PlutoScalarField = prover.challenge(b"a");
+    assert_eq!(a, verifier.challenge(b"a"));
+    let b: PlutoScalarField = prover.challenge(b"b");
+    assert_eq!(b, verifier.challenge(b"b"));
+  }
+
+  #[test]
+  fn labels_and_order_matter() {
+    let mut t1 = Transcript::new(b"test");
+    t1.append_message(b"ab", b"c");
+    let mut t2 = Transcript::new(b"test");
+    t2.append_message(b"a", b"bc");
+    assert_ne!(t1, t2);
+
+    let mut t3 = Transcript::new(b"other");
+    t3.append_message(b"ab", b"c");
+    assert_ne!(t1, t3);
+  }
+
+  #[test]
+  fn excluded_challenges() {
+    let mut transcript = Transcript::new(b"test");
+    let excluded: Vec<PlutoScalarField> = (1..16).map(PlutoScalarField::new).collect();
+    for _ in 0..8 {
+      assert_eq!(transcript.challenge_excluding(b"c", &excluded), PlutoScalarField::new(16));
+    }
+  }
+
+  #[test]
+  #[should_panic(expected = "no challenge left")]
+  fn all_challenges_excluded() {
+    let mut transcript = Transcript::new(b"test");
+    let excluded: Vec<PlutoScalarField> = (1..17).map(PlutoScalarField::new).collect();
+    transcript.challenge_excluding(b"c", &excluded);
+  }
+
+  #[test]
+  fn challenge_indices() {
+    let mut transcript = Transcript::new(b"test");
+    let indices: Vec<usize> = (0..32).map(|_| transcript.challenge_index(b"i", 5)).collect();
+    assert!(indices.iter().all(|&i| i < 5));
+    assert!(indices.iter().any(|&i| i != indices[0]));
+  }
+}
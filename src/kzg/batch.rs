@@ -0,0 +1,141 @@
+//! Batched KZG openings.
+//!
+//! - [`Srs::open_batch`] opens many polynomials `p_i` at the same point `z`. With a challenge `γ`
+//!   chosen by the verifier after the commitments are fixed, the polynomials are combined into
+//!   `p(X) = Σ γ^i p_i(X)`, whose commitment `Σ γ^i C_i` the verifier can compute on their own, and
+//!   a single proof opens `p` at `z`.
+//! - [`Srs::open_multi`] opens one polynomial at many points `z_j`. With `I(X)` the polynomial that
+//!   interpolates the values and `Z(X) = Π (X - z_j)` the vanishing polynomial of the points, the
+//!   proof is a commitment to `q(X) = (p(X) - I(X)) / Z(X)`, and the verifier checks `e(C -
+//!   [I(τ)]G1, G2) = e(π, [Z(τ)]G2)`. This needs `[τ^j]G2` up to the number of points.
+//!
+//! Both verifiers compare two pairings with a single [`PairingCurve::multi_pairing`].
+use super::*;
+
+impl<P: PairingCurve> Srs<P> {
+  /// Opens every polynomial in `polynomials` at `point`, returning their values and a single proof
+  /// for the combination `Σ γ^i p_i` with `γ = challenge`.
+  ///
+  /// ## Panics
+  /// Panics if the degree of a polynomial is larger than [`Srs::max_degree`].
+  pub fn open_batch(
+    &self,
+    polynomials: &[&[P::ScalarField]],
+    point: P::ScalarField,
+    challenge: P::ScalarField,
+  ) -> (Vec<P::ScalarField>, OpeningProof<P>) {
+    let len = polynomials.iter().map(|p| p.len()).max().unwrap_or(0);
+    let mut combined = vec![P::ScalarField::ZERO; len];
+    let mut gamma = P::ScalarField::ONE;
+    for polynomial in polynomials {
+      for (c, &p) in combined.iter_mut().zip(polynomial.iter()) {
+        *c += gamma * p;
+      }
+      gamma *= challenge;
+    }
+    let values = polynomials.iter().map(|p| divide_by_linear(p, point).1).collect();
+    let (_, proof) = self.open(&combined, point);
+    (values, proof)
+  }
+
+  /// Verifies a proof from [`Srs::open_batch`] that the polynomial committed to in `commitments[i]`
+  /// takes `values[i]` at `point`.
+  ///
+  /// Returns `false` if the number of commitments and values differ.
+  pub fn verify_batch(
+    &self,
+    commitments: &[Commitment<P>],
+    point: P::ScalarField,
+    values: &[P::ScalarField],
+    challenge: P::ScalarField,
+    proof: &OpeningProof<P>,
+  ) -> bool {
+    if commitments.len() != values.len() {
+      return false;
+    }
+    let (mut commitment, mut value) = (P::G1::IDENTITY, P::ScalarField::ZERO);
+    let mut gamma = P::ScalarField::ONE;
+    for (c, &v) in commitments.iter().zip(values) {
+      commitment += c.0 * gamma;
+      value += gamma * v;
+      gamma *= challenge;
+    }
+    self.verify(&Commitment(commitment), point, value, proof)
+  }
+
+  /// Opens a polynomial at all of `points`, returning its values and a proof
+  /// `[q(τ)]G1` for `q(X) = (p(X) - I(X)) / Z(X)`.
+  ///
+  /// The points must be distinct for the proof to verify.
+  ///
+  /// ## Panics
+  /// Panics if the degree of the polynomial is larger than [`Srs::max_degree`].
+  pub fn open_multi(
+    &self,
+    coefficients: &[P::ScalarField],
+    points: &[P::ScalarField],
+  ) -> (Vec<P::ScalarField>, OpeningProof<P>) {
+    let values: Vec<_> = points.iter().map(|&z| divide_by_linear(coefficients, z).1).collect();
+    let mut quotient = coefficients.to_vec();
+    // dividing by each `X - z_j` in turn divides by `Z(X)`, the remainders add up to `I(X)`, which
+    // has degree less than `Z(X)` and so does not change the quotient
+    for &z in points {
+      quotient = divide_by_linear(&quotient, z).0;
+    }
+    (values, OpeningProof(msm_g1::<P>(&self.g1_powers, &quotient)))
+  }
+
+  /// Verifies a proof from [`Srs::open_multi`] that the committed polynomial takes `values[j]` at
+  /// `points[j]`, with the single pairing-product check
+  /// `e(C - [I(τ)]G1, G2) · e(-π, [Z(τ)]G2) = 1`.
+  ///
+  /// Returns `false` if the number of points and values differ, if the points are not distinct or
+  /// if there are not enough `G2` powers for [`Z(X)`](vanishing_polynomial).
+  pub fn verify_multi(
+    &self,
+    commitment: &Commitment<P>,
+    points: &[P::ScalarField],
+    values: &[P::ScalarField],
+    proof: &OpeningProof<P>,
+  ) -> bool {
+    if points.len() != values.len() || points.len() >= self.g2_powers.len() {
+      return false;
+    }
+    let Some(interpolation) = interpolate(points, values) else { return false };
+    let vanishing = vanishing_polynomial(points);
+    let interpolation = msm_g1::<P>(&self.g1_powers, &interpolation);
+    let vanishing = msm(&self.g2_powers[..vanishing.len()], &vanishing);
+    let pairs = [(commitment.0 - interpolation, P::G2_GENERATOR), (-proof.0, vanishing)];
+    P::multi_pairing(&pairs) == P::TARGET_ONE
+  }
+}
+
+/// The vanishing polynomial `Z(X) = Π (X - z_j)` of `points`.
+pub fn vanishing_polynomial<F: Field>(points: &[F]) -> Vec<F> {
+  let mut coefficients = vec![F::ONE];
+  for &z in points {
+    // multiply by `X - z`
+    coefficients.insert(0, F::ZERO);
+    for i in 0..coefficients.len() - 1 {
+      let next = coefficients[i + 1];
+      coefficients[i] -= z * next;
+    }
+  }
+  coefficients
+}
+
+/// The Lagrange interpolation `I(X) = Σ y_j Π_{k != j} (X - z_k) / (z_j - z_k)` of the points
+/// `(z_j, y_j)`, or `None` if the points are not distinct.
+pub fn interpolate<F: Field>(points: &[F], values: &[F]) -> Option<Vec<F>> {
+  let mut coefficients = vec![F::ZERO; points.len()];
+  for (j, (&z_j, &y_j)) in points.iter().zip(values).enumerate() {
+    let others: Vec<_> =
+      points.iter().enumerate().filter(|&(k, _)| k != j).map(|(_, &z)| z).collect();
+    let denominator = others.iter().fold(F::ONE, |acc, &z_k| acc * (z_j - z_k));
+    let scale = y_j * denominator.inverse()?;
+    for (c, basis) in coefficients.iter_mut().zip(vanishing_polynomial(&others)) {
+      *c += scale * basis;
+    }
+  }
+  Some(coefficients)
+}
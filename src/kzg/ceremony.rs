@@ -0,0 +1,238 @@
+//! A simulated powers-of-tau ceremony that produces the SRS for the KZG10 scheme.
+//!
+//! [`setup`](super::setup) fixes the toxic waste `τ`, so anyone who knows it can forge openings. In
+//! a ceremony, every participant multiplies the current SRS by a secret `s` of their own:
+//!
+//! ```text
+//! [τ^i]G1 -> [s^i][τ^i]G1 = [(sτ)^i]G1,    [τ^i]G2 -> [(sτ)^i]G2
+//! ```
+//!
+//! and then throws `s` away. The final `τ` is the product of all the secrets, so it stays unknown
+//! as long as a single participant was honest.
+//!
+//! Each update comes with a [`Contribution`] that lets anyone audit the transcript with pairings,
+//! without learning `s`:
+//! 1. `[s]G1` and `[s]G2` hide the same `s`: `e([s]G1, G2) = e(G1, [s]G2)`.
+//! 2. The participant knows `s`: they publish `[s]R` for a point `R` hashed from the transcript,
+//!    and `e([s]R, G2) = e(R, [s]G2)`. As `R` is only known once `[s]G1` is fixed, the secret can
+//!    not be chosen to cancel out earlier contributions.
+//! 3. The update builds on the previous one: `e([τ']G1, G2) = e([τ]G1, [s]G2)`.
+//!
+//! Finally, the SRS must consist of consecutive powers of the same `τ`, see
+//! [`Srs::is_well_formed`].
+//!
+//! The proofs of knowledge hash to `G1`, which depends on the curve, so a ceremony runs over any
+//! [`CeremonyCurve`]: [`PlutoPairing`] and [`Bn254`].
+use std::{error::Error, fmt::Display};
+
+use rand::Rng;
+
+use super::*;
+use crate::{
+  algebra::group::Group,
+  curve::{
+    bn254::{Bn254, G1Affine},
+    hash_to_curve::try_and_increment,
+    pairing::{PairingCurve, PlutoPairing},
+  },
+};
+
+/// Domain separation tag for hashing the transcript to the point `R`.
+const DST: &[u8] = b"RONKATHON_POWERS_OF_TAU_";
+
+/// A [`PairingCurve`] that a ceremony can run over: the proofs of knowledge need to write points
+/// of `G1` to the transcript and to hash the transcript back to `G1`.
+pub trait CeremonyCurve: PairingCurve {
+  /// The cofactor `h` of `G1` in the points of its curve, cleared when hashing to `G1`.
+  const G1_COFACTOR: usize;
+
+  /// Encodes a point of `G1` for the transcript.
+  fn encode_g1(p: &Self::G1) -> Vec<u8>;
+
+  /// Hashes `msg` to a point of `G1` other than the identity, by multiplying a point of the curve
+  /// by [`CeremonyCurve::G1_COFACTOR`].
+  fn hash_to_g1(msg: &[u8], dst: &[u8]) -> Self::G1;
+}
+
+impl CeremonyCurve for PlutoPairing {
+  /// `E(GF(101))` has 102 points, six times the order of `G1`.
+  const G1_COFACTOR: usize = 6;
+
+  fn encode_g1(p: &Self::G1) -> Vec<u8> { p.to_compressed() }
+
+  fn hash_to_g1(msg: &[u8], dst: &[u8]) -> Self::G1 {
+    try_and_increment(msg, dst, Self::G1_COFACTOR)
+  }
+}
+
+impl CeremonyCurve for Bn254 {
+  /// Every point of the BN254 curve over `Fq` is in `G1`.
+  const G1_COFACTOR: usize = 1;
+
+  /// Encodes the point as `0x04 || x || y`, or a single `0x00` byte for the point at infinity.
+  fn encode_g1(p: &G1Affine) -> Vec<u8> {
+    match p {
+      G1Affine::Infinity => vec![0],
+      G1Affine::Point(x, y) =>
+        [&[4][..], &x.retrieve().to_be_bytes(), &y.retrieve().to_be_bytes()].concat(),
+    }
+  }
+
+  fn hash_to_g1(msg: &[u8], dst: &[u8]) -> G1Affine { G1Affine::try_and_increment(msg, dst) }
+}
+
+/// Errors found while auditing a [`Ceremony`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CeremonyError {
+  /// The points of the given contribution do not hide a common non-zero secret.
+  InconsistentSecret(usize),
+  /// The proof of knowledge of the given contribution does not verify.
+  InvalidProofOfKnowledge(usize),
+  /// The given contribution does not build on the one before it.
+  BrokenChain(usize),
+  /// The SRS is not made of consecutive powers of a single `τ`, or does not match the last
+  /// contribution.
+  MalformedSrs,
+}
+
+impl Error for CeremonyError {}
+
+impl Display for CeremonyError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      CeremonyError::InconsistentSecret(i) =>
+        write!(f, "contribution {} does not commit to a single non-zero secret", i),
+      CeremonyError::InvalidProofOfKnowledge(i) =>
+        write!(f, "contribution {} has an invalid proof of knowledge", i),
+      CeremonyError::BrokenChain(i) =>
+        write!(f, "contribution {} does not update the previous SRS", i),
+      CeremonyError::MalformedSrs => write!(f, "SRS is not a sequence of powers of tau"),
+    }
+  }
+}
+
+/// Multiplies `[τ^i]G1` and `[τ^i]G2` by `s^i`.
+fn update<P: PairingCurve>(srs: &mut Srs<P>, s: P::ScalarField) {
+  let mut power = P::ScalarField::ONE;
+  for i in 0..srs.g1_powers.len().max(srs.g2_powers.len()) {
+    if let Some(point) = srs.g1_powers.get_mut(i) {
+      *point *= power;
+    }
+    if let Some(point) = srs.g2_powers.get_mut(i) {
+      *point *= power;
+    }
+    power *= s;
+  }
+}
+
+/// The public record of one participant's update with the secret `s`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Contribution<P: PairingCurve> {
+  /// `[τ]G1` after the update.
+  pub tau_g1:   P::G1,
+  /// `[s]G1`.
+  pub s_g1:     P::G1,
+  /// `[s]G2`.
+  pub s_g2:     P::G2,
+  /// `[s]R`, where `R` is hashed from the previous `[τ]G1` and `[s]G1`.
+  pub s_hashed: P::G1,
+}
+
+/// The point `R` for the proof of knowledge, hashed from the transcript so far.
+fn transcript_point<P: CeremonyCurve>(previous_tau_g1: P::G1, s_g1: P::G1) -> P::G1 {
+  let transcript = [P::encode_g1(&previous_tau_g1), P::encode_g1(&s_g1)].concat();
+  P::hash_to_g1(&transcript, DST)
+}
+
+/// Samples a non-zero scalar from 512 random bits, which is close to uniform for scalar fields of
+/// up to 384 bits.
+fn random_scalar<F: Field, R: Rng + ?Sized>(rng: &mut R) -> F {
+  let base = F::from(1 << 16).pow(2);
+  loop {
+    let s = (0..16).fold(F::ZERO, |acc, _| acc * base + F::from(rng.gen::<u32>() as usize));
+    if s != F::ZERO {
+      return s;
+    }
+  }
+}
+
+/// A powers-of-tau ceremony over the pairing-friendly curve `P`: the current SRS and the
+/// contributions that led to it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ceremony<P: PairingCurve> {
+  /// The SRS after the last contribution.
+  pub srs:           Srs<P>,
+  /// The contributions in the order they were made.
+  pub contributions: Vec<Contribution<P>>,
+}
+
+impl<P: CeremonyCurve> Ceremony<P> {
+  /// Starts a ceremony from the SRS with `τ = 1`, for an SRS that commits to polynomials of up to
+  /// `degree` and has `G2` powers up to `g2_degree`.
+  ///
+  /// ## Panics
+  /// Panics if either degree is zero, as `[τ]G1` and `[τ]G2` are needed to audit the ceremony.
+  pub fn new(degree: usize, g2_degree: usize) -> Self {
+    assert!(degree > 0 && g2_degree > 0, "degrees must be at least 1");
+    let srs = Srs::from_secret(P::ScalarField::ONE, degree, g2_degree);
+    Self { srs, contributions: vec![] }
+  }
+
+  /// Contributes a random secret, which is dropped as soon as the SRS is updated.
+  pub fn contribute<R: Rng + ?Sized>(&mut self, rng: &mut R) -> &Contribution<P> {
+    self.contribute_with(random_scalar(rng))
+  }
+
+  /// Updates the SRS with the secret `s` and records the proof of the update.
+  ///
+  /// ## Panics
+  /// Panics if `s` is zero, which would erase the SRS.
+  pub fn contribute_with(&mut self, s: P::ScalarField) -> &Contribution<P> {
+    assert_ne!(s, P::ScalarField::ZERO, "secret must be non-zero");
+    let previous_tau_g1 = self.srs.g1_powers[1];
+    update(&mut self.srs, s);
+
+    let s_g1 = P::G1_GENERATOR * s;
+    let contribution = Contribution {
+      tau_g1: self.srs.g1_powers[1],
+      s_g1,
+      s_g2: P::G2_GENERATOR * s,
+      s_hashed: transcript_point::<P>(previous_tau_g1, s_g1) * s,
+    };
+    self.contributions.push(contribution);
+    self.contributions.last().unwrap()
+  }
+
+  /// Audits the whole transcript and the resulting SRS.
+  ///
+  /// ## Errors
+  /// Returns the first [`CeremonyError`] found, in the order of the contributions.
+  pub fn verify(&self) -> Result<(), CeremonyError> {
+    let (g1, g2) = (P::G1_GENERATOR, P::G2_GENERATOR);
+    // `e(a, b) = e(c, d)` as the single product `e(a, b) · e(-c, d) = 1`
+    let same = |a, b, c: P::G1, d| P::multi_pairing(&[(a, b), (-c, d)]) == P::TARGET_ONE;
+    let mut tau_g1 = g1;
+    for (i, c) in self.contributions.iter().enumerate() {
+      let points = [c.tau_g1, c.s_g1, c.s_hashed];
+      if points.iter().any(|p| *p == P::G1::IDENTITY || !P::is_in_g1(p))
+        || c.s_g2 == P::G2::IDENTITY
+        || !P::is_in_g2(&c.s_g2)
+        || !same(c.s_g1, g2, g1, c.s_g2)
+      {
+        return Err(CeremonyError::InconsistentSecret(i));
+      }
+      if !same(c.s_hashed, g2, transcript_point::<P>(tau_g1, c.s_g1), c.s_g2) {
+        return Err(CeremonyError::InvalidProofOfKnowledge(i));
+      }
+      if !same(c.tau_g1, g2, tau_g1, c.s_g2) {
+        return Err(CeremonyError::BrokenChain(i));
+      }
+      tau_g1 = c.tau_g1;
+    }
+
+    if !self.srs.is_well_formed() || self.srs.g1_powers[1] != tau_g1 {
+      return Err(CeremonyError::MalformedSrs);
+    }
+    Ok(())
+  }
+}
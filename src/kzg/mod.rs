@@ -2,7 +2,135 @@
 #![doc = include_str!("./README.md")]
 #[cfg(test)] mod tests;
 
+pub mod batch;
+pub mod ceremony;
 pub mod setup;
 pub use setup::*;
 
 use super::*;
+use crate::{
+  algebra::group::Group,
+  curve::{msm::msm, pairing::PairingCurve},
+};
+
+/// The structured reference string `[τ^i]G1` and `[τ^i]G2` of a pairing-friendly curve `P`.
+///
+/// The `G1` powers bound the degree of the polynomials that can be committed to, the `G2` powers
+/// the number of points a single polynomial can be opened at, see [`Srs::open_multi`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Srs<P: PairingCurve> {
+  /// `[τ^i]G1` for `i = 0..g1_powers.len()`.
+  pub g1_powers: Vec<P::G1>,
+  /// `[τ^i]G2` for `i = 0..g2_powers.len()`.
+  pub g2_powers: Vec<P::G2>,
+}
+
+/// A commitment `[p(τ)]G1` to a polynomial `p`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Commitment<P: PairingCurve>(pub P::G1);
+
+/// A proof `[q(τ)]G1` that a committed polynomial takes the claimed values, where `q` is the
+/// quotient of the polynomial minus its claimed values by the vanishing polynomial of the points.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpeningProof<P: PairingCurve>(pub P::G1);
+
+impl<P: PairingCurve> Srs<P> {
+  /// Computes the SRS directly from `τ`, with `G1` powers up to `degree` and `G2` powers up to
+  /// `g2_degree`.
+  ///
+  /// This is only meant for testing: whoever knows `τ` can open commitments to any value. See
+  /// [`ceremony`] for how to generate an SRS without anyone learning `τ`.
+  pub fn from_secret(tau: P::ScalarField, degree: usize, g2_degree: usize) -> Self {
+    let g1_powers = (0..=degree).map(|i| P::G1_GENERATOR * tau.pow(i)).collect();
+    let g2_powers = (0..=g2_degree).map(|i| P::G2_GENERATOR * tau.pow(i)).collect();
+    Self { g1_powers, g2_powers }
+  }
+
+  /// The largest degree of a polynomial that can be committed to.
+  pub fn max_degree(&self) -> usize { self.g1_powers.len() - 1 }
+
+  /// Checks that the SRS is made of consecutive powers of a single non-zero `τ`, with
+  /// `e([τ^{i+1}]G1, G2) = e([τ^i]G1, [τ]G2)` and `e([τ^j]G1, G2) = e(G1, [τ^j]G2)`.
+  ///
+  /// Every point must also lie in the subgroup of prime order, which the pairing relies on.
+  pub fn is_well_formed(&self) -> bool {
+    let (g1, g2) = (P::G1_GENERATOR, P::G2_GENERATOR);
+    let (g1_powers, g2_powers) = (&self.g1_powers, &self.g2_powers);
+    if g1_powers.len() < 2 || g2_powers.len() < 2 || g1_powers[0] != g1 || g2_powers[0] != g2 {
+      return false;
+    }
+    if g1_powers.iter().any(|p| *p == P::G1::IDENTITY || !P::is_in_g1(p))
+      || g2_powers.iter().any(|q| *q == P::G2::IDENTITY || !P::is_in_g2(q))
+    {
+      return false;
+    }
+
+    let is_one = |pairs: &[_]| P::multi_pairing(pairs) == P::TARGET_ONE;
+    g1_powers.windows(2).all(|w| is_one(&[(w[1], g2), (-w[0], g2_powers[1])]))
+      && g1_powers.iter().zip(g2_powers).all(|(&p, &q)| is_one(&[(p, g2), (-g1, q)]))
+  }
+
+  /// Commits to the polynomial with the given coefficients, lowest degree first.
+  ///
+  /// ## Panics
+  /// Panics if the degree of the polynomial is larger than [`Srs::max_degree`].
+  pub fn commit(&self, coefficients: &[P::ScalarField]) -> Commitment<P> {
+    Commitment(msm_g1::<P>(&self.g1_powers, coefficients))
+  }
+
+  /// Opens a polynomial at `point`, returning `p(point)` and a proof `[q(τ)]G1` for
+  /// `q(X) = (p(X) - p(point)) / (X - point)`.
+  ///
+  /// ## Panics
+  /// Panics if the degree of the polynomial is larger than [`Srs::max_degree`].
+  pub fn open(
+    &self,
+    coefficients: &[P::ScalarField],
+    point: P::ScalarField,
+  ) -> (P::ScalarField, OpeningProof<P>) {
+    let (quotient, value) = divide_by_linear(coefficients, point);
+    (value, OpeningProof(msm_g1::<P>(&self.g1_powers, &quotient)))
+  }
+
+  /// Verifies that the committed polynomial takes `value` at `point`, with the single
+  /// pairing-product check `e(C - [v]G1 + [z]π, G2) · e(-π, [τ]G2) = 1`.
+  ///
+  /// This is `e(C - [v]G1, G2) = e(π, [τ - z]G2)`, which holds exactly when
+  /// `p(τ) - v = q(τ)(τ - z)`.
+  pub fn verify(
+    &self,
+    commitment: &Commitment<P>,
+    point: P::ScalarField,
+    value: P::ScalarField,
+    proof: &OpeningProof<P>,
+  ) -> bool {
+    let lhs = commitment.0 - P::G1_GENERATOR * value + proof.0 * point;
+    let pairs = [(lhs, P::G2_GENERATOR), (-proof.0, self.g2_powers[1])];
+    P::multi_pairing(&pairs) == P::TARGET_ONE
+  }
+}
+
+/// Computes `Σ scalars[i] [τ^i]G1`.
+fn msm_g1<P: PairingCurve>(powers: &[P::G1], scalars: &[P::ScalarField]) -> P::G1 {
+  assert!(
+    scalars.len() <= powers.len(),
+    "polynomial of degree {} exceeds the SRS degree {}",
+    scalars.len().saturating_sub(1),
+    powers.len().saturating_sub(1)
+  );
+  msm(&powers[..scalars.len()], scalars)
+}
+
+/// Divides `p(X)` by `X - point` with synthetic division, returning the quotient and the
+/// remainder `p(point)`.
+fn divide_by_linear<F: Field>(coefficients: &[F], point: F) -> (Vec<F>, F) {
+  let mut quotient = vec![F::ZERO; coefficients.len().saturating_sub(1)];
+  let mut carry = F::ZERO;
+  for (i, &c) in coefficients.iter().enumerate().rev() {
+    carry = carry * point + c;
+    if i > 0 {
+      quotient[i - 1] = carry;
+    }
+  }
+  (quotient, carry)
+}
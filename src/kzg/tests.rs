@@ -1,45 +1,38 @@
 use algebra::group::FiniteCyclicGroup;
+use crypto_bigint::U256;
+use rand::{rngs::StdRng, SeedableRng};
 
-use super::*;
-use crate::{curve::pairing::pairing, PlutoScalarField};
+use super::{batch::*, *};
+use crate::{
+  curve::{
+    bn254::{Bn254, Fr},
+    pairing::PlutoPairing,
+  },
+  kzg::ceremony::{Ceremony, CeremonyError},
+  PlutoScalarField,
+};
+
+fn point(x: usize, y: usize) -> AffinePoint<PlutoBaseCurve> {
+  AffinePoint::new(PlutoBaseField::new(x), PlutoBaseField::new(y))
+}
 
 #[test]
 fn test_setup() {
-  let (g1srs, g2srs) = setup();
-  assert!(g1srs.len() == 7);
-  assert!(g2srs.len() == 2);
+  let srs = setup();
+  assert!(srs.g1_powers.len() == 7);
+  assert!(srs.g2_powers.len() == 2);
+  assert_eq!(srs.max_degree(), 6);
   let expected_g1srs = vec![
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(1usize),
-      PlutoBaseFieldExtension::from(2usize),
-    ),
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(68usize),
-      PlutoBaseFieldExtension::from(74usize),
-    ),
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(65usize),
-      PlutoBaseFieldExtension::from(98usize),
-    ),
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(18usize),
-      PlutoBaseFieldExtension::from(49usize),
-    ),
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(1usize),
-      PlutoBaseFieldExtension::from(99usize),
-    ),
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(68usize),
-      PlutoBaseFieldExtension::from(27usize),
-    ),
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(65usize),
-      PlutoBaseFieldExtension::from(3usize),
-    ),
+    point(1, 2),
+    point(68, 74),
+    point(65, 98),
+    point(18, 49),
+    point(1, 99),
+    point(68, 27),
+    point(65, 3),
   ];
 
-  assert_eq!(g1srs, expected_g1srs);
+  assert_eq!(srs.g1_powers, expected_g1srs);
 
   let expected_2g = AffinePoint::<PlutoExtendedCurve>::new(
     PlutoBaseFieldExtension::new([PlutoBaseField::new(90), PlutoBaseField::ZERO]),
@@ -48,7 +41,8 @@ fn test_setup() {
   let g2_gen = AffinePoint::<PlutoExtendedCurve>::GENERATOR;
   let expected_g2srs = vec![g2_gen, expected_2g];
 
-  assert_eq!(g2srs, expected_g2srs);
+  assert_eq!(srs.g2_powers, expected_g2srs);
+  assert!(srs.is_well_formed());
 }
 
 #[fixture]
@@ -91,8 +85,7 @@ fn poly_3() -> Polynomial<Monomial, PlutoScalarField, 3> {
 
 #[test]
 fn test_commit() {
-  println!("FIRST COMMIT");
-  let (g1srs, _) = setup();
+  let srs = setup();
   // p(x) = (x-1)(x-2)(x-3)
   // p(x) = - 6 + 11x -6x^2 + x^3
   // p(x) = 11 + 11x + 11x^2 + x^3 mod 17
@@ -100,10 +93,9 @@ fn test_commit() {
   // -> -6 mod 17 is 11 so this is [11, 11, 11, 1]
   let coefficients = poly_1().coefficients;
   //  g1srs[0] * 11 + g1srs[1] * 11 + g1srs[2] * 11 + g1srs[3] * 1
-  let commit_1 = commit(coefficients.to_vec(), g1srs.clone());
-  assert_eq!(commit_1, AffinePoint::<PlutoExtendedCurve>::Infinity);
+  let commit_1 = srs.commit(&coefficients);
+  assert_eq!(commit_1, Commitment(AffinePoint::Infinity));
 
-  println!("\n\nSECOND COMMIT");
   // p(x) = (x-1)(x-2)(x-3)(x-4)
   // p(x) = 24 - 50x + 35x^2 - 10x^3
   // -> 24 mod 17 is 7
@@ -112,111 +104,47 @@ fn test_commit() {
   // coefficients = [7, 16, 1, 11, 1]
   let coefficients = poly_2().coefficients;
   //  g1srs[0] * 7 + g1srs[1] * 16 + g1srs[2] * 1 + g1srs[3] * 11 + g1srs[4] * 1
-  let commit_2 = commit(coefficients.to_vec(), g1srs.clone());
-
-  assert_eq!(
-    commit_2,
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(32usize),
-      PlutoBaseFieldExtension::from(59usize),
-    )
-  );
+  let commit_2 = srs.commit(&coefficients);
+  assert_eq!(commit_2, Commitment(point(32, 59)));
 
-  println!("\n\nTHIRD COMMIT");
   // p(x)  = 3 + 2x + x^2
   let coefficients = poly_3().coefficients;
   // g1srs[0] * 3 + g1srs[1] * 2  + g1srs[2] * 1
-  let commit_3 = commit(coefficients.to_vec(), g1srs);
-
-  assert_eq!(
-    commit_3,
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(32usize),
-      PlutoBaseFieldExtension::from(59usize),
-    )
-  );
+  let commit_3 = srs.commit(&coefficients);
+  assert_eq!(commit_3, Commitment(point(32, 59)));
 }
 
+#[test]
+#[should_panic(expected = "exceeds the SRS degree")]
+fn commit_beyond_srs_degree() { setup().commit(&[PlutoScalarField::ONE; 8]); }
+
 #[test]
 fn srs_open() {
-  let (g1srs, _) = setup();
-  let result = g1srs[0] * PlutoScalarField::new(3);
-  let result_2 = g1srs[1] * PlutoScalarField::new(15);
-  let result_3 = g1srs[2] * PlutoScalarField::new(1);
+  let srs = setup();
+  let result = srs.g1_powers[0] * PlutoScalarField::new(3);
+  let result_2 = srs.g1_powers[1] * PlutoScalarField::new(15);
+  let result_3 = srs.g1_powers[2] * PlutoScalarField::new(1);
   let sum = result + result_2 + result_3;
-  dbg!(sum);
-  assert_eq!(
-    sum,
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(26usize),
-      PlutoBaseFieldExtension::from(45usize),
-    )
-  );
+  assert_eq!(sum, point(26, 45));
 }
 
 #[test]
 fn opening() {
-  let (g1srs, _) = setup();
-  println!("g1srs[0]:{:?}, g1srs[1]:{:?}, g1srs[2]:{:?}", g1srs[0], g1srs[1], g1srs[2]);
+  let srs = setup();
   let poly = poly_1();
   let eval_point = PlutoScalarField::new(4);
-  //   let eval_result = poly.evaluate(eval_point);
-  let commit = commit(poly.coefficients.clone().to_vec(), g1srs.clone());
-  assert_eq!(commit, AffinePoint::<PlutoExtendedCurve>::Infinity);
+  let commit = srs.commit(&poly.coefficients);
+  assert_eq!(commit, Commitment(AffinePoint::Infinity));
   // p(x) = (x-1)(x-2)(x-3)
   // p(x) = - 6 + 11x -6x^2 + x^3
 
   // divisor poly q(x) = x - 4
   // result = p(x) / q(x) = x^2 - 2x + 3
   // multiplying (1,2) * 3 + (68, 74) * 15 + (65, 98) * 1
-  let open_commit = open::<4>(poly.coefficients.to_vec(), eval_point, g1srs.clone());
-
-  assert_eq!(
-    open_commit,
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(26usize),
-      PlutoBaseFieldExtension::from(45usize),
-    )
-  );
-}
+  let (value, proof) = srs.open(&poly.coefficients, eval_point);
 
-// this test was for debugging purposes
-#[test]
-fn all_srs_combinations() {
-  let paring_params = commit_and_open(poly_1(), PlutoScalarField::new(4));
-
-  let mut g1_index = 0;
-  let mut g2_index = 0;
-  let mut pairings: Vec<(GaloisField<2, 101>, GaloisField<2, 101>)> = vec![];
-  #[allow(clippy::explicit_counter_loop)]
-  for g1 in paring_params.g1srs {
-    println!("Loop for g1 {:?}", g1);
-    for g2 in &paring_params.g2srs {
-      println!("Loop for g2 {:?}", g2);
-      let lhs = pairing::<PlutoExtendedCurve, 17>(
-        paring_params.q,
-        *g2 - AffinePoint::<PlutoExtendedCurve>::GENERATOR * paring_params.point,
-      );
-
-      let rhs = pairing::<PlutoExtendedCurve, 17>(
-        paring_params.p - g1 * paring_params.value,
-        AffinePoint::<PlutoExtendedCurve>::GENERATOR,
-      );
-      if lhs == rhs {
-        println!(
-          "Pairing match! for g1: {:?} and g2: {:?} with g1 index {:?} and g2 index {:?}",
-          g1, g2, g1_index, g2_index
-        );
-        println!("LHS {:?}", lhs);
-        println!("RHS {:?}", rhs);
-        pairings.push((lhs, rhs));
-        assert_eq!(lhs, rhs);
-      }
-      g2_index += 1;
-    }
-    g1_index += 1;
-  }
-  assert!(!pairings.is_empty());
+  assert_eq!(value, PlutoScalarField::new(6));
+  assert_eq!(proof, OpeningProof(point(26, 45)));
 }
 
 #[rstest]
@@ -227,111 +155,226 @@ fn e2e<const D: usize>(
   #[case] poly: Polynomial<Monomial, PlutoScalarField, D>,
   #[case] eval_point: PlutoScalarField,
 ) {
-  let paring_params = commit_and_open(poly, eval_point);
+  let srs = setup();
+  let commitment = srs.commit(&poly.coefficients);
+  let (value, proof) = srs.open(&poly.coefficients, eval_point);
+  assert_eq!(value, poly.evaluate(eval_point));
 
-  // Both `p_commit` and `q_commit` are in the same group so this is good.
-  // We can look at `g1srs` and see it is in `G1` and `g2srs` is in `G2`
-  dbg!(paring_params.g1srs.first().unwrap());
-  for i in 0..17 {
-    println!("{}: {:?}", i, i * *paring_params.g1srs.first().unwrap());
-  }
-  assert_eq!(
-    17u32 * *paring_params.g1srs.first().unwrap(),
-    AffinePoint::<PlutoExtendedCurve>::Infinity
-  );
-  dbg!(paring_params.g2srs.first().unwrap());
-  for i in 0..17 {
-    println!("{}: {:?}", i, i * *paring_params.g2srs.first().unwrap());
-  }
-  assert_eq!(
-    17u32 * *paring_params.g2srs.first().unwrap(),
-    AffinePoint::<PlutoExtendedCurve>::Infinity
-  );
+  // `G1` and `G2` are the subgroups of order 17
+  assert!(srs.g1_powers[0].is_torsion(17));
+  assert!(srs.g2_powers[0].is_torsion(17));
 
-  let valid = check(
-    paring_params.p,
-    paring_params.q,
-    paring_params.point,
-    paring_params.value,
-    paring_params.g1srs.clone(),
-    paring_params.g2srs.clone(),
-  );
-  assert!(valid);
+  assert!(srs.verify(&commitment, eval_point, value, &proof));
 }
 
 #[rstest]
 #[case(poly_1(), PlutoScalarField::new(4))]
 #[case(poly_2(), PlutoScalarField::new(3))]
 #[case(poly_3(), PlutoScalarField::new(5))]
-#[should_panic]
 fn invalid_check<const D: usize>(
   #[case] poly: Polynomial<Monomial, PlutoScalarField, D>,
   #[case] eval_point: PlutoScalarField,
 ) {
-  let paring_params = commit_and_open(poly, eval_point);
-  let valid = check(
-    paring_params.p,
-    paring_params.q,
-    paring_params.point,
-    PlutoScalarField::new(10), // fake evaluation point
-    paring_params.g1srs.clone(),
-    paring_params.g2srs.clone(),
-  );
-  assert!(valid);
+  let srs = setup();
+  let commitment = srs.commit(&poly.coefficients);
+  let (_, proof) = srs.open(&poly.coefficients, eval_point);
+  // fake evaluation
+  assert!(!srs.verify(&commitment, eval_point, PlutoScalarField::new(10), &proof));
 }
 
 #[rstest]
 #[case(poly_1(), PlutoScalarField::new(4))]
 #[case(poly_2(), PlutoScalarField::new(3))]
 #[case(poly_3(), PlutoScalarField::new(5))]
-#[should_panic]
 fn fake_proof<const D: usize>(
   #[case] poly: Polynomial<Monomial, PlutoScalarField, D>,
   #[case] eval_point: PlutoScalarField,
 ) {
-  let paring_params = commit_and_open(poly, eval_point);
-  let valid = check(
-    paring_params.p,
-    AffinePoint::<PlutoExtendedCurve>::Infinity, // fake proof
-    paring_params.point,
-    paring_params.point,
-    paring_params.g1srs.clone(),
-    paring_params.g2srs.clone(),
-  );
-  assert!(valid);
+  let srs = setup();
+  let commitment = srs.commit(&poly.coefficients);
+  let value = poly.evaluate(eval_point);
+  let proof = OpeningProof(AffinePoint::Infinity);
+  assert!(!srs.verify(&commitment, eval_point, value, &proof));
 }
 
-/// Pairing params for testing pairing
-pub struct PairingParams {
-  pub p:     AffinePoint<PlutoExtendedCurve>,
-  pub q:     AffinePoint<PlutoExtendedCurve>,
-  pub point: PlutoScalarField,
-  pub value: PlutoScalarField,
-  pub g1srs: Vec<AffinePoint<PlutoExtendedCurve>>,
-  pub g2srs: Vec<AffinePoint<PlutoExtendedCurve>>,
+#[test]
+fn batch_opening_at_one_point() {
+  let srs = setup();
+  let (p1, p2, p3) = (poly_1(), poly_2(), poly_3());
+  let polys: [&[PlutoScalarField]; 3] = [&p1.coefficients, &p2.coefficients, &p3.coefficients];
+  let commitments: Vec<_> = polys.iter().map(|p| srs.commit(p)).collect();
+  let (point, challenge) = (PlutoScalarField::new(5), PlutoScalarField::new(3));
+
+  let (values, proof) = srs.open_batch(&polys, point, challenge);
+  assert_eq!(values, [p1.evaluate(point), p2.evaluate(point), p3.evaluate(point)]);
+  assert!(srs.verify_batch(&commitments, point, &values, challenge, &proof));
+
+  let mut wrong = values.clone();
+  wrong[1] += PlutoScalarField::ONE;
+  assert!(!srs.verify_batch(&commitments, point, &wrong, challenge, &proof));
+  assert!(!srs.verify_batch(&commitments[..2], point, &values, challenge, &proof));
+  assert!(!srs.verify_batch(&commitments, PlutoScalarField::new(6), &values, challenge, &proof));
 }
 
-/// given a polynomial and eval point return the pairing params
-pub fn commit_and_open<const D: usize>(
-  poly: Polynomial<Monomial, PlutoScalarField, D>,
-  eval_point: PlutoScalarField,
-) -> PairingParams {
-  let (g1srs, g2srs) = setup();
-  let eval_result = poly.evaluate(eval_point);
-  let p_commit = commit(poly.coefficients.clone().to_vec(), g1srs.clone());
-  let q_commit = open::<D>(poly.coefficients.to_vec(), eval_point, g1srs.clone());
-  PairingParams { p: p_commit, q: q_commit, point: eval_point, value: eval_result, g1srs, g2srs }
+#[test]
+fn multi_point_opening() {
+  let srs = Srs::<PlutoPairing>::from_secret(PlutoScalarField::new(5), 6, 3);
+  assert!(srs.is_well_formed());
+  let poly = poly_2();
+  let commitment = srs.commit(&poly.coefficients);
+  let points = [PlutoScalarField::new(1), PlutoScalarField::new(7), PlutoScalarField::new(9)];
+
+  let (values, proof) = srs.open_multi(&poly.coefficients, &points);
+  assert_eq!(values, points.map(|z| poly.evaluate(z)));
+  assert!(srs.verify_multi(&commitment, &points, &values, &proof));
+  assert!(srs.verify_multi(
+    &commitment,
+    &points[..1],
+    &values[..1],
+    &srs.open(&poly.coefficients, points[0]).1
+  ));
+
+  let mut wrong = values;
+  wrong[2] += PlutoScalarField::ONE;
+  assert!(!srs.verify_multi(&commitment, &points, &wrong, &proof));
+
+  // four points need `[τ^4]G2`
+  let points = [points[0], points[1], points[2], PlutoScalarField::new(11)];
+  let (values, proof) = srs.open_multi(&poly.coefficients, &points);
+  assert!(!srs.verify_multi(&commitment, &points, &values, &proof));
 }
 
 #[test]
-fn pairing_params() {
-  let params = commit_and_open(poly_1(), PlutoScalarField::new(4));
-  assert_eq!(params.p, AffinePoint::<PlutoExtendedCurve>::Infinity);
-  assert_eq!(
-    params.q,
-    AffinePoint::<PlutoExtendedCurve>::new(
-      PlutoBaseFieldExtension::from(26usize),
-      PlutoBaseFieldExtension::from(45usize)
-    )
-  );
+fn bn254_commit_open_verify() {
+  let fr = |n: usize| Fr::from(n);
+  let tau =
+    Fr::new(&U256::from_be_hex("1b6b5d0e4fc2c8a4d1a7e9a0a4c8e3cf5e39d43f0e2b1c7e2d7a44c4b7f0a9d3"));
+  let srs = Srs::<Bn254>::from_secret(tau, 3, 1);
+  assert!(srs.is_well_formed());
+
+  // p(X) = 1 + 2X + 3X^2 + 4X^3 and p(5) = 586
+  let coefficients = [fr(1), fr(2), fr(3), fr(4)];
+  let commitment = srs.commit(&coefficients);
+  let (value, proof) = srs.open(&coefficients, fr(5));
+  assert_eq!(value, fr(586));
+  assert!(srs.verify(&commitment, fr(5), value, &proof));
+  assert!(!srs.verify(&commitment, fr(5), value + Fr::ONE, &proof));
+  assert!(!srs.verify(&commitment, fr(6), value, &proof));
+}
+
+#[test]
+fn interpolation() {
+  let points = [PlutoScalarField::new(2), PlutoScalarField::new(3), PlutoScalarField::new(16)];
+  let vanishing = vanishing_polynomial(&points);
+  assert_eq!(vanishing.len(), 4);
+  let evaluate = |coefficients: &[PlutoScalarField], x: PlutoScalarField| {
+    coefficients.iter().rev().fold(PlutoScalarField::ZERO, |acc, &c| acc * x + c)
+  };
+  assert!(points.iter().all(|&z| evaluate(&vanishing, z) == PlutoScalarField::ZERO));
+
+  let values = [PlutoScalarField::new(7), PlutoScalarField::new(0), PlutoScalarField::new(11)];
+  let interpolation = interpolate(&points, &values).unwrap();
+  assert!(points.iter().zip(values).all(|(&z, y)| evaluate(&interpolation, z) == y));
+  assert_eq!(interpolate(&[points[0], points[0]], &values[..2]), None);
+}
+
+#[test]
+fn malformed_srs() {
+  let mut srs = setup();
+  srs.g1_powers.swap(2, 3);
+  assert!(!srs.is_well_formed());
+
+  let mut srs = setup();
+  srs.g2_powers[1] *= PlutoScalarField::new(2);
+  assert!(!srs.is_well_formed());
+}
+
+#[test]
+fn ceremony_srs_commits_and_opens() {
+  let mut rng = StdRng::seed_from_u64(42);
+  let mut ceremony = Ceremony::<PlutoPairing>::new(4, 2);
+  for _ in 0..3 {
+    ceremony.contribute(&mut rng);
+  }
+  assert_eq!(ceremony.contributions.len(), 3);
+  assert_eq!(ceremony.verify(), Ok(()));
+
+  let srs = ceremony.srs;
+  assert_eq!(srs.max_degree(), 4);
+  let poly = poly_2();
+  let eval_point = PlutoScalarField::new(3);
+  let commitment = srs.commit(&poly.coefficients);
+  let (value, proof) = srs.open(&poly.coefficients, eval_point);
+  assert!(srs.verify(&commitment, eval_point, value, &proof));
+  assert!(!srs.verify(&commitment, eval_point, PlutoScalarField::new(10), &proof));
+
+  let points = [PlutoScalarField::new(2), PlutoScalarField::new(8)];
+  let (values, proof) = srs.open_multi(&poly.coefficients, &points);
+  assert!(srs.verify_multi(&commitment, &points, &values, &proof));
+}
+
+#[test]
+fn ceremony_matches_fixed_setup() {
+  // a single contribution of 2 reproduces the hardcoded SRS
+  let mut ceremony = Ceremony::<PlutoPairing>::new(6, 1);
+  ceremony.contribute_with(PlutoScalarField::new(2));
+  assert_eq!(ceremony.verify(), Ok(()));
+  assert_eq!(ceremony.srs, setup());
+}
+
+#[test]
+fn ceremony_detects_tampering() {
+  let mut ceremony = Ceremony::<PlutoPairing>::new(3, 1);
+  ceremony.contribute_with(PlutoScalarField::new(5));
+  ceremony.contribute_with(PlutoScalarField::new(7));
+
+  // replacing the SRS by one with a known tau breaks the chain of contributions
+  let mut forged = ceremony.clone();
+  let mut known = Ceremony::<PlutoPairing>::new(3, 1);
+  known.contribute_with(PlutoScalarField::new(3));
+  forged.srs = known.srs;
+  assert_eq!(forged.verify(), Err(CeremonyError::MalformedSrs));
+
+  // a single power that is off is caught by the pairing checks on the SRS
+  let mut forged = ceremony.clone();
+  let g1 = forged.srs.g1_powers[0];
+  forged.srs.g1_powers[2] += g1;
+  assert_eq!(forged.verify(), Err(CeremonyError::MalformedSrs));
+
+  // G1 and G2 parts of a contribution must hide the same secret
+  let mut forged = ceremony.clone();
+  forged.contributions[1].s_g2 *= PlutoScalarField::new(2);
+  assert_eq!(forged.verify(), Err(CeremonyError::InconsistentSecret(1)));
+
+  // a contribution must build on the previous one
+  let mut forged = ceremony.clone();
+  forged.contributions.remove(0);
+  assert_eq!(forged.verify(), Err(CeremonyError::InvalidProofOfKnowledge(0)));
+
+  let mut forged = ceremony.clone();
+  forged.contributions[1].tau_g1 = forged.contributions[0].tau_g1;
+  assert_eq!(forged.verify(), Err(CeremonyError::BrokenChain(1)));
+
+  // proofs of knowledge are bound to the transcript
+  let mut forged = ceremony.clone();
+  forged.contributions[0].s_hashed = forged.contributions[1].s_hashed;
+  assert_eq!(forged.verify(), Err(CeremonyError::InvalidProofOfKnowledge(0)));
+}
+
+#[test]
+fn ceremony_over_bn254() {
+  let mut rng = StdRng::seed_from_u64(42);
+  let mut ceremony = Ceremony::<Bn254>::new(2, 1);
+  ceremony.contribute(&mut rng);
+  ceremony.contribute_with(Fr::from(7));
+  assert_eq!(ceremony.verify(), Ok(()));
+
+  let mut forged = ceremony.clone();
+  forged.contributions[1].s_g2 *= Fr::from(2);
+  assert_eq!(forged.verify(), Err(CeremonyError::InconsistentSecret(1)));
+
+  let coefficients = [Fr::from(3), Fr::from(1), Fr::from(4)];
+  let commitment = ceremony.srs.commit(&coefficients);
+  let (value, proof) = ceremony.srs.open(&coefficients, Fr::from(2));
+  assert!(ceremony.srs.verify(&commitment, Fr::from(2), value, &proof));
 }
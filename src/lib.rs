@@ -25,16 +25,20 @@
 #![warn(missing_docs)]
 
 pub mod algebra;
+pub mod bulletproofs;
 pub mod codes;
 pub mod compiler;
 pub mod curve;
 pub mod diffie_hellman;
 pub mod dsa;
 pub mod encryption;
+pub mod fri;
+pub mod groth16;
 pub mod hashes;
 pub mod hmac;
 pub mod kzg;
 pub mod multi_var_poly;
+pub mod plonk;
 pub mod polynomial;
 pub mod sumcheck;
 pub mod tree;
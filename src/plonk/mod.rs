@@ -0,0 +1,374 @@
+//! PLONK prover and verifier for circuits compiled with the [`compiler`](crate::compiler).
+//!
+//! - [`VerifyingKey`] commits to the selector and permutation polynomials of a
+//!   [`CommonPreprocessedInput`].
+//! - [`prove`] turns an execution trace, see [`Program::execution_trace`], into a [`Proof`].
+//! - [`verify`] checks a [`Proof`] against the verifying key and the public inputs.
+//! - [`permutation`] computes the grand product of the copy constraints, and finds the ones a trace
+//!   breaks.
+//!
+//! Polynomials are committed to with the [`kzg`](crate::kzg) module over [`PlutoPairing`] and the
+//! protocol is made non-interactive with a [`Transcript`].
+//!
+//! Only arithmetic gates are proven: the boolean and range gates and the plookup argument for the
+//! lookup tables of the compiler are not implemented, so circuits using them are rejected.
+//!
+//! [`Program::execution_trace`]: crate::compiler::program::Program::execution_trace
+#![doc = include_str!("./README.md")]
+#[cfg(test)] mod tests;
+
+pub mod permutation;
+pub mod prover;
+pub mod verifier;
+
+use std::{error::Error, fmt::Display};
+
+pub use prover::prove;
+pub use verifier::verify;
+
+use crate::{
+  algebra::{
+    field::{prime::PlutoBaseField, Field, FieldBytes, FiniteField},
+    Finite,
+  },
+  compiler::program::{cosets_are_disjoint, Column, CommonPreprocessedInput},
+  curve::{pairing::PlutoPairing, AffinePoint},
+  hashes::transcript::Transcript,
+  kzg::{Commitment, OpeningProof, Srs},
+  polynomial::{Lagrange, Polynomial},
+  PlutoScalarField,
+};
+
+/// Label of the PLONK transcript.
+const TRANSCRIPT_LABEL: &[u8] = b"RONKATHON_PLONK";
+
+/// Errors from creating a PLONK proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlonkError {
+  /// The SRS can not commit to the polynomials of the proof.
+  SrsTooSmall {
+    /// Smallest degree of the SRS that is needed.
+    required: usize,
+    /// Degree of the SRS.
+    found:    usize,
+  },
+  /// There are more public inputs than rows in the execution trace.
+  TooManyPublicInputs(usize),
+  /// The gate in the given row of the execution trace does not hold.
+  UnsatisfiedGate(usize),
+  /// Wires holding the same variable have different values.
+  UnsatisfiedCopyConstraints,
+  /// The row of the execution trace has a boolean or range gate or a lookup, which the prover
+  /// only supports through arithmetic gates.
+  UnsupportedGate(usize),
+  /// The bytes are not the encoding of a proof, see [`Proof::from_bytes`].
+  InvalidProofEncoding,
+  /// The cosets labelling the wires of the three columns overlap over a domain of this order, as
+  /// when it is the whole multiplicative group, see [`cosets_are_disjoint`].
+  InvalidGroupOrder(usize),
+}
+
+impl Error for PlonkError {}
+
+impl Display for PlonkError {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    match self {
+      PlonkError::SrsTooSmall { required, found } =>
+        write!(f, "SRS of degree {} is too small, degree {} is needed", found, required),
+      PlonkError::TooManyPublicInputs(len) =>
+        write!(f, "{} public inputs do not fit in the execution trace", len),
+      PlonkError::UnsatisfiedGate(row) => write!(f, "gate in row {} is not satisfied", row),
+      PlonkError::UnsatisfiedCopyConstraints => write!(f, "copy constraints are not satisfied"),
+      PlonkError::UnsupportedGate(row) =>
+        write!(f, "custom gate or lookup in row {} is not supported", row),
+      PlonkError::InvalidProofEncoding => write!(f, "invalid proof encoding"),
+      PlonkError::InvalidGroupOrder(n) =>
+        write!(f, "copy constraints can't be checked over a domain of order {}", n),
+    }
+  }
+}
+
+/// Commitments to the preprocessed polynomials of a circuit, which is all the verifier needs to
+/// know about it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VerifyingKey<const GROUP_ORDER: usize> {
+  /// `[Q_L(τ)]G1`
+  pub ql: Commitment<PlutoPairing>,
+  /// `[Q_R(τ)]G1`
+  pub qr: Commitment<PlutoPairing>,
+  /// `[Q_M(τ)]G1`
+  pub qm: Commitment<PlutoPairing>,
+  /// `[Q_O(τ)]G1`
+  pub qo: Commitment<PlutoPairing>,
+  /// `[Q_C(τ)]G1`
+  pub qc: Commitment<PlutoPairing>,
+  /// `[S_σ1(τ)]G1`
+  pub s1: Commitment<PlutoPairing>,
+  /// `[S_σ2(τ)]G1`
+  pub s2: Commitment<PlutoPairing>,
+  /// `[S_σ3(τ)]G1`
+  pub s3: Commitment<PlutoPairing>,
+}
+
+impl<const GROUP_ORDER: usize> VerifyingKey<GROUP_ORDER> {
+  /// Commits to the selector and permutation polynomials of `cpi`.
+  ///
+  /// ## Errors
+  /// - [`PlonkError::InvalidGroupOrder`] if the cosets of the columns overlap over the domain.
+  /// - [`PlonkError::UnsupportedGate`] if a row has a boolean or range gate or a lookup, which the
+  ///   key has no commitment for, so that a verifier never accepts proofs that ignore them.
+  ///
+  /// ## Panics
+  /// Panics if the degree of the SRS is smaller than `GROUP_ORDER - 1`.
+  pub fn new(
+    srs: &Srs<PlutoPairing>,
+    cpi: &CommonPreprocessedInput<GROUP_ORDER>,
+  ) -> Result<Self, PlonkError> {
+    check_group_order(GROUP_ORDER)?;
+    check_arithmetic_gates(cpi)?;
+    let commit = |poly| srs.commit(&coefficients(poly));
+    Ok(Self {
+      ql: commit(&cpi.ql),
+      qr: commit(&cpi.qr),
+      qm: commit(&cpi.qm),
+      qo: commit(&cpi.qo),
+      qc: commit(&cpi.qc),
+      s1: commit(&cpi.s1),
+      s2: commit(&cpi.s2),
+      s3: commit(&cpi.s3),
+    })
+  }
+
+  fn commitments(&self) -> [Commitment<PlutoPairing>; 8] {
+    [self.ql, self.qr, self.qm, self.qo, self.qc, self.s1, self.s2, self.s3]
+  }
+}
+
+/// A PLONK proof.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Proof {
+  /// Commitments to the wire polynomials `a(X)`, `b(X)` and `c(X)`.
+  pub wires:           [Commitment<PlutoPairing>; 3],
+  /// Commitment to the permutation grand product `z(X)`.
+  pub z:               Commitment<PlutoPairing>,
+  /// Commitments to the low, middle and high parts of the quotient `t(X)`.
+  pub quotient:        [Commitment<PlutoPairing>; 3],
+  /// `a(ζ)`, `b(ζ)` and `c(ζ)`.
+  pub wire_evals:      [PlutoScalarField; 3],
+  /// `S_σ1(ζ)` and `S_σ2(ζ)`.
+  pub sigma_evals:     [PlutoScalarField; 2],
+  /// `z(ζω)`.
+  pub z_omega_eval:    PlutoScalarField,
+  /// Batched opening of `r(X)`, the wires, `S_σ1(X)` and `S_σ2(X)` at `ζ`.
+  pub opening:         OpeningProof<PlutoPairing>,
+  /// Opening of `z(X)` at `ζω`.
+  pub opening_shifted: OpeningProof<PlutoPairing>,
+}
+
+impl Proof {
+  /// Encodes the proof as its points, compressed with [`AffinePoint::to_compressed`], followed by
+  /// its evaluations, in the order of the fields.
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let points = self
+      .wires
+      .iter()
+      .chain([&self.z])
+      .chain(&self.quotient)
+      .map(|commitment| commitment.0)
+      .chain([self.opening.0, self.opening_shifted.0]);
+    let evals = self.wire_evals.iter().chain(&self.sigma_evals).chain([&self.z_omega_eval]);
+    points
+      .flat_map(|point| point.to_compressed())
+      .chain(evals.flat_map(|eval| eval.to_bytes()))
+      .collect()
+  }
+
+  /// Decodes a proof encoded with [`Proof::to_bytes`].
+  ///
+  /// ## Errors
+  /// [`PlonkError::InvalidProofEncoding`] if a point is not on the curve, an evaluation is not a
+  /// field element, or the length doesn't match.
+  pub fn from_bytes(mut bytes: &[u8]) -> Result<Self, PlonkError> {
+    let mut point = || {
+      let len = match bytes.first() {
+        Some(0) => 1,
+        Some(2 | 3) => 1 + PlutoBaseField::BYTES,
+        _ => return Err(PlonkError::InvalidProofEncoding),
+      };
+      let (encoded, rest) = bytes.split_at_checked(len).ok_or(PlonkError::InvalidProofEncoding)?;
+      bytes = rest;
+      AffinePoint::from_bytes(encoded).map_err(|_| PlonkError::InvalidProofEncoding)
+    };
+    let wires = [Commitment(point()?), Commitment(point()?), Commitment(point()?)];
+    let z = Commitment(point()?);
+    let quotient = [Commitment(point()?), Commitment(point()?), Commitment(point()?)];
+    let (opening, opening_shifted) = (OpeningProof(point()?), OpeningProof(point()?));
+
+    if bytes.len() != 6 * PlutoScalarField::BYTES {
+      return Err(PlonkError::InvalidProofEncoding);
+    }
+    let evals = bytes
+      .chunks(PlutoScalarField::BYTES)
+      .map(PlutoScalarField::from_bytes)
+      .collect::<Option<Vec<_>>>()
+      .ok_or(PlonkError::InvalidProofEncoding)?;
+    Ok(Self {
+      wires,
+      z,
+      quotient,
+      wire_evals: [evals[0], evals[1], evals[2]],
+      sigma_evals: [evals[3], evals[4]],
+      z_omega_eval: evals[5],
+      opening,
+      opening_shifted,
+    })
+  }
+}
+
+/// Verifier challenges, in the order they are drawn from the transcript.
+struct Challenges {
+  beta:  PlutoScalarField,
+  gamma: PlutoScalarField,
+  alpha: PlutoScalarField,
+  zeta:  PlutoScalarField,
+  v:     PlutoScalarField,
+}
+
+/// Starts the transcript from the statement: the circuit and its public inputs.
+fn start_transcript<const GROUP_ORDER: usize>(
+  vk: &VerifyingKey<GROUP_ORDER>,
+  public_inputs: &[PlutoScalarField],
+) -> Transcript {
+  let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+  transcript.append_message(b"group_order", &(GROUP_ORDER as u64).to_be_bytes());
+  for commitment in vk.commitments() {
+    transcript.append_point(b"vk", &commitment.0);
+  }
+  for input in public_inputs {
+    transcript.append_scalar(b"public_input", input);
+  }
+  transcript
+}
+
+/// Round 1: appends the wire commitments and draws `β` and `γ`.
+fn wire_challenges(
+  transcript: &mut Transcript,
+  wires: &[Commitment<PlutoPairing>; 3],
+) -> (PlutoScalarField, PlutoScalarField) {
+  for wire in wires {
+    transcript.append_point(b"wire", &wire.0);
+  }
+  (transcript.challenge_excluding(b"beta", &[]), transcript.challenge_excluding(b"gamma", &[]))
+}
+
+/// Round 2: appends the grand product commitment and draws `α`.
+fn permutation_challenge(
+  transcript: &mut Transcript,
+  z: &Commitment<PlutoPairing>,
+) -> PlutoScalarField {
+  transcript.append_point(b"z", &z.0);
+  transcript.challenge_excluding(b"alpha", &[])
+}
+
+/// Round 3: appends the quotient commitments and draws the evaluation point `ζ`, outside of the
+/// domain so that `Z_H(ζ) != 0`.
+fn evaluation_challenge(
+  transcript: &mut Transcript,
+  quotient: &[Commitment<PlutoPairing>; 3],
+  group_order: usize,
+) -> PlutoScalarField {
+  for part in quotient {
+    transcript.append_point(b"quotient", &part.0);
+  }
+  transcript.challenge_excluding(b"zeta", &domain(group_order))
+}
+
+/// Round 4: appends the evaluations and draws the batching challenge `v`.
+fn opening_challenge(transcript: &mut Transcript, evals: &[PlutoScalarField]) -> PlutoScalarField {
+  for eval in evals {
+    transcript.append_scalar(b"eval", eval);
+  }
+  transcript.challenge_excluding(b"v", &[])
+}
+
+/// Checks that the cosets `k H` of the columns are disjoint, so that a permutation of the wire
+/// labels can't move a wire to another column unnoticed. This also leaves room outside of the
+/// domain for `ζ`, as the domain is then not the whole multiplicative group.
+fn check_group_order(group_order: usize) -> Result<(), PlonkError> {
+  if cosets_are_disjoint::<PlutoScalarField>(group_order) {
+    Ok(())
+  } else {
+    Err(PlonkError::InvalidGroupOrder(group_order))
+  }
+}
+
+/// Checks that every row of `cpi` is an arithmetic gate: the custom gate and lookup selectors are
+/// zero, as the protocol has no boolean, range or plookup argument to enforce them.
+fn check_arithmetic_gates<const GROUP_ORDER: usize>(
+  cpi: &CommonPreprocessedInput<GROUP_ORDER>,
+) -> Result<(), PlonkError> {
+  let custom = [&cpi.qbool, &cpi.qrange, &cpi.qlookup, &cpi.qtable];
+  match (0..GROUP_ORDER)
+    .find(|&row| custom.iter().any(|q| q.coefficients[row] != PlutoScalarField::ZERO))
+  {
+    Some(row) => Err(PlonkError::UnsupportedGate(row)),
+    None => Ok(()),
+  }
+}
+
+/// The coset shift `k` of a column: wires of the column are labelled `k ω^i`.
+fn coset(column: Column) -> PlutoScalarField { column.coset_shift() }
+
+/// The roots of unity `ω^i` of order `n`.
+fn domain(n: usize) -> Vec<PlutoScalarField> {
+  let omega = PlutoScalarField::primitive_root_of_unity(n);
+  (0..n).map(|i| omega.pow(i)).collect()
+}
+
+/// Coefficients of the polynomial that takes `evaluations[i]` at `ω^i`, with an inverse DFT.
+fn interpolate_domain(evaluations: &[PlutoScalarField]) -> Vec<PlutoScalarField> {
+  let n = evaluations.len();
+  let omega_inv = PlutoScalarField::primitive_root_of_unity(n).inverse().unwrap();
+  let n_inv = PlutoScalarField::from(n).inverse().unwrap();
+  (0..n)
+    .map(|j| {
+      let sum = evaluations
+        .iter()
+        .enumerate()
+        .fold(PlutoScalarField::ZERO, |acc, (i, &y)| acc + y * omega_inv.pow(i * j));
+      sum * n_inv
+    })
+    .collect()
+}
+
+/// Coefficients of a polynomial in the [`Lagrange`] basis over the roots of unity.
+fn coefficients<const N: usize>(
+  poly: &Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, N>,
+) -> Vec<PlutoScalarField> {
+  interpolate_domain(&poly.coefficients)
+}
+
+/// Evaluates a polynomial with Horner's method.
+fn evaluate(poly: &[PlutoScalarField], x: PlutoScalarField) -> PlutoScalarField {
+  poly.iter().rev().fold(PlutoScalarField::ZERO, |acc, &c| acc * x + c)
+}
+
+/// `L_i(x) = ω^i (x^n - 1) / (n (x - ω^i))`, the Lagrange polynomial that is one at `ω^i` and zero
+/// at every other root of unity, for `x` outside of the domain.
+fn lagrange_evaluation(n: usize, i: usize, x: PlutoScalarField) -> PlutoScalarField {
+  let omega_i = PlutoScalarField::primitive_root_of_unity(n).pow(i);
+  omega_i * (x.pow(n) - PlutoScalarField::ONE) / (PlutoScalarField::from(n) * (x - omega_i))
+}
+
+/// `PI(X) = Σ -x_i L_i(X)`: public inputs are the left wires of the first rows, which have
+/// `Q_L = 1`, so adding `PI(X)` to the gate constraint pins them to `x_i`.
+fn public_input_evaluations(
+  public_inputs: &[PlutoScalarField],
+  group_order: usize,
+) -> Vec<PlutoScalarField> {
+  let mut evaluations = vec![PlutoScalarField::ZERO; group_order];
+  for (eval, &input) in evaluations.iter_mut().zip(public_inputs) {
+    *eval = -input;
+  }
+  evaluations
+}
@@ -0,0 +1,186 @@
+//! Grand product of the permutation argument, which checks the copy constraints of an execution
+//! trace.
+//!
+//! In row `i`, the grand product `z(X)` is multiplied by `f(ω^i) / g(ω^i)`, where
+//! - `f(ω^i) = ∏_j (w_j(ω^i) + β k_j ω^i + γ)` takes the label of each wire, and
+//! - `g(ω^i) = ∏_j (w_j(ω^i) + β S_σj(ω^i) + γ)` the label of the next wire of the same variable.
+//!
+//! Starting from `z(1) = 1`, `z` comes back to `1` after the last row when every wire has the value
+//! of the wire it is mapped to by `σ`. [`GrandProduct`] has each step of the product, and
+//! [`broken_copy_constraints`] the wires that break it.
+
+use std::{array, iter};
+
+use super::*;
+use crate::compiler::program::Cell;
+
+/// Factors of the grand product in one row of the execution trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PermutationRow {
+  /// `z(ω^i)`
+  pub z:            PlutoScalarField,
+  /// `z(ω^{i+1})`
+  pub z_next:       PlutoScalarField,
+  /// `w_j(ω^i) + β k_j ω^i + γ` for the left, right and output columns.
+  pub numerators:   [PlutoScalarField; 3],
+  /// `w_j(ω^i) + β S_σj(ω^i) + γ` for the left, right and output columns.
+  pub denominators: [PlutoScalarField; 3],
+}
+
+impl PermutationRow {
+  /// `f(ω^i)`, the product of the numerators.
+  pub fn numerator(&self) -> PlutoScalarField { self.numerators.into_iter().product() }
+
+  /// `g(ω^i)`, the product of the denominators.
+  pub fn denominator(&self) -> PlutoScalarField { self.denominators.into_iter().product() }
+
+  /// Whether `z(ω^{i+1}) g(ω^i) = z(ω^i) f(ω^i)`, the identity that the quotient checks in this
+  /// row.
+  pub fn holds(&self) -> bool { self.z_next * self.denominator() == self.z * self.numerator() }
+}
+
+/// The grand product `z(X)` of an execution trace, in the [`Lagrange`] basis over the roots of
+/// unity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrandProduct<const GROUP_ORDER: usize> {
+  /// The steps of the product, one per row.
+  pub rows: Vec<PermutationRow>,
+}
+
+impl<const GROUP_ORDER: usize> GrandProduct<GROUP_ORDER> {
+  /// Computes the grand product of `trace` for the permutation of `cpi` and the challenges `β`
+  /// and `γ`.
+  ///
+  /// Returns `None` if a denominator is zero, in which case other challenges must be drawn.
+  pub fn new(
+    cpi: &CommonPreprocessedInput<GROUP_ORDER>,
+    trace: &[[PlutoScalarField; GROUP_ORDER]; 3],
+    beta: PlutoScalarField,
+    gamma: PlutoScalarField,
+  ) -> Option<Self> {
+    let columns = [Column::LEFT, Column::RIGHT, Column::OUTPUT];
+    let sigmas = [&cpi.s1.coefficients, &cpi.s2.coefficients, &cpi.s3.coefficients];
+    let mut z = PlutoScalarField::ONE;
+    let mut rows = Vec::with_capacity(GROUP_ORDER);
+    for (row, omega_i) in domain(GROUP_ORDER).into_iter().enumerate() {
+      let numerators = columns
+        .map(|column| trace[column as usize - 1][row] + beta * coset(column) * omega_i + gamma);
+      let denominators = columns.map(|column| {
+        trace[column as usize - 1][row] + beta * sigmas[column as usize - 1][row] + gamma
+      });
+      let mut row = PermutationRow { z, z_next: z, numerators, denominators };
+      row.z_next = z * row.numerator() * row.denominator().inverse()?;
+      z = row.z_next;
+      rows.push(row);
+    }
+    Some(Self { rows })
+  }
+
+  /// Evaluations `z(ω^i)` for `i` in `0..=GROUP_ORDER`, where the last one is `z(ω^n)`.
+  pub fn evaluations(&self) -> Vec<PlutoScalarField> {
+    iter::once(PlutoScalarField::ONE).chain(self.rows.iter().map(|row| row.z_next)).collect()
+  }
+
+  /// `z(X)` in the [`Lagrange`] basis.
+  pub fn polynomial(
+    &self,
+  ) -> Polynomial<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER> {
+    Polynomial::<Lagrange<PlutoScalarField>, PlutoScalarField, GROUP_ORDER>::new(array::from_fn(
+      |row| self.rows[row].z,
+    ))
+  }
+
+  /// Whether the product comes back to `1` after the last row, as `z(ω^n) = z(1)`.
+  pub fn closes(&self) -> bool {
+    self.rows.last().map_or(true, |row| row.z_next == PlutoScalarField::ONE)
+  }
+}
+
+/// Cells of `trace` whose value differs from the cell they are mapped to by the permutation of
+/// `cpi`, with that cell: the copy constraints that a [`GrandProduct`] doesn't close for.
+///
+/// Cells are found from their labels `k_j ω^i`, so when labels of different columns coincide, see
+/// the [caveats](crate::plonk#caveats), the first cell with the label is taken.
+pub fn broken_copy_constraints<const GROUP_ORDER: usize>(
+  cpi: &CommonPreprocessedInput<GROUP_ORDER>,
+  trace: &[[PlutoScalarField; GROUP_ORDER]; 3],
+) -> Vec<(Cell, Cell)> {
+  let columns = [Column::LEFT, Column::RIGHT, Column::OUTPUT];
+  let sigmas = [&cpi.s1.coefficients, &cpi.s2.coefficients, &cpi.s3.coefficients];
+  let domain = domain(GROUP_ORDER);
+  let cell = |label: PlutoScalarField| {
+    columns.into_iter().find_map(|column| {
+      let row = domain.iter().position(|&omega_i| coset(column) * omega_i == label)?;
+      Some(Cell { row: row as u32, column })
+    })
+  };
+  let value = |cell: Cell| trace[cell.column as usize - 1][cell.row as usize];
+
+  let mut broken = vec![];
+  for column in columns {
+    for row in 0..GROUP_ORDER {
+      let from = Cell { row: row as u32, column };
+      let Some(to) = cell(sigmas[column as usize - 1][row]) else { continue };
+      if value(from) != value(to) {
+        broken.push((from, to));
+      }
+    }
+  }
+  broken
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use rstest::rstest;
+
+  use super::*;
+  use crate::compiler::program::Program;
+
+  /// Preprocessed input and execution trace of `x^3 + 5` for `x = 3`.
+  fn cubic() -> (CommonPreprocessedInput<4>, [[PlutoScalarField; 4]; 3]) {
+    let program = Program::<4>::new(&["x public", "x2 <== x * x", "out <== x2 * x + 5"]).unwrap();
+    let assignments = HashMap::from([(Some("x"), PlutoScalarField::new(3))]);
+    let assignments = program.evaluate_circuit(assignments).unwrap();
+    (program.common_preprocessed_input().unwrap(), program.execution_trace(&assignments).unwrap())
+  }
+
+  #[rstest]
+  #[case(2, 4)]
+  #[case(1, 7)]
+  #[case(2, 8)]
+  fn grand_product_closes(#[case] beta: u32, #[case] gamma: u32) {
+    let (cpi, trace) = cubic();
+    let (beta, gamma) = (PlutoScalarField::from(beta), PlutoScalarField::from(gamma));
+    let z = GrandProduct::new(&cpi, &trace, beta, gamma).unwrap();
+    assert!(z.closes());
+    assert!(z.rows.iter().all(PermutationRow::holds));
+    assert_eq!(z.evaluations()[0], PlutoScalarField::ONE);
+    assert_eq!(z.polynomial().coefficients.to_vec(), z.evaluations()[..4]);
+    assert!(broken_copy_constraints(&cpi, &trace).is_empty());
+  }
+
+  #[test]
+  fn broken_copy_constraint() {
+    let (cpi, mut trace) = cubic();
+    // `x2` is the output of row 1 and the right wire of row 2
+    trace[1][2] = PlutoScalarField::new(1);
+
+    let z = GrandProduct::new(&cpi, &trace, PlutoScalarField::new(2), PlutoScalarField::new(4));
+    assert!(!z.unwrap().closes());
+    let right = Cell { row: 2, column: Column::RIGHT };
+    let output = Cell { row: 1, column: Column::OUTPUT };
+    assert_eq!(broken_copy_constraints(&cpi, &trace), [(right, output), (output, right)]);
+  }
+
+  #[test]
+  fn tampered_grand_product() {
+    let (cpi, trace) = cubic();
+    let mut z =
+      GrandProduct::new(&cpi, &trace, PlutoScalarField::new(2), PlutoScalarField::new(4)).unwrap();
+    z.rows[1].z_next += PlutoScalarField::ONE;
+    assert!(!z.rows[1].holds());
+    assert!(z.rows.iter().enumerate().all(|(row, step)| row == 1 || step.holds()));
+  }
+}
@@ -0,0 +1,237 @@
+//! The PLONK prover.
+//!
+//! The rounds follow the [PLONK paper](https://eprint.iacr.org/2019/953) and
+//! [plonkathon](https://github.com/0xPARC/plonkathon), with polynomials kept as coefficients:
+//! 1. Commit to the wire polynomials `a(X)`, `b(X)`, `c(X)`, blinded with multiples of `Z_H(X)`.
+//! 2. Commit to the [`GrandProduct`] `z(X)` of the permutation argument.
+//! 3. Commit to the quotient `t(X)` of all constraints by `Z_H(X)`, split in three parts.
+//! 4. Evaluate the wires and the first two permutation polynomials at `ζ`, and `z(X)` at `ζω`.
+//! 5. Open the linearisation `r(X)` and the evaluated polynomials at `ζ`, and `z(X)` at `ζω`.
+use rand::Rng;
+
+use super::{permutation::GrandProduct, *};
+
+type Poly = Vec<PlutoScalarField>;
+
+fn add(p: &[PlutoScalarField], q: &[PlutoScalarField]) -> Poly {
+  let mut sum = vec![PlutoScalarField::ZERO; p.len().max(q.len())];
+  for (i, s) in sum.iter_mut().enumerate() {
+    *s = p.get(i).copied().unwrap_or_default() + q.get(i).copied().unwrap_or_default();
+  }
+  sum
+}
+
+fn scale(p: &[PlutoScalarField], s: PlutoScalarField) -> Poly { p.iter().map(|&c| c * s).collect() }
+
+fn sub(p: &[PlutoScalarField], q: &[PlutoScalarField]) -> Poly {
+  add(p, &scale(q, -PlutoScalarField::ONE))
+}
+
+fn mul(p: &[PlutoScalarField], q: &[PlutoScalarField]) -> Poly {
+  let mut product = vec![PlutoScalarField::ZERO; (p.len() + q.len()).saturating_sub(1)];
+  for (i, &a) in p.iter().enumerate() {
+    for (j, &b) in q.iter().enumerate() {
+      product[i + j] += a * b;
+    }
+  }
+  product
+}
+
+/// `p(ωX)`
+fn shift(p: &[PlutoScalarField], omega: PlutoScalarField) -> Poly {
+  p.iter().enumerate().map(|(i, &c)| c * omega.pow(i)).collect()
+}
+
+/// `Z_H(X) = X^n - 1`
+fn vanishing(n: usize) -> Poly {
+  let mut z = vec![PlutoScalarField::ZERO; n + 1];
+  z[0] = -PlutoScalarField::ONE;
+  z[n] = PlutoScalarField::ONE;
+  z
+}
+
+/// Divides `p(X)` by `X^n - 1`, returning `None` if the division is not exact.
+fn divide_by_vanishing(p: &[PlutoScalarField], n: usize) -> Option<Poly> {
+  let mut remainder = p.to_vec();
+  let mut quotient = vec![PlutoScalarField::ZERO; p.len().saturating_sub(n)];
+  for i in (n..p.len()).rev() {
+    // X^i = X^{i-n} (X^n - 1) + X^{i-n}
+    quotient[i - n] = remainder[i];
+    let c = remainder[i];
+    remainder[i - n] += c;
+    remainder[i] = PlutoScalarField::ZERO;
+  }
+  remainder.iter().all(|&c| c == PlutoScalarField::ZERO).then_some(quotient)
+}
+
+fn random_scalar<R: Rng + ?Sized>(rng: &mut R) -> PlutoScalarField {
+  PlutoScalarField::new(rng.gen_range(0..PlutoScalarField::ORDER))
+}
+
+/// The polynomial taking `evaluations` over the domain, plus `blinding(X) Z_H(X)` to hide them.
+fn blind(evaluations: &[PlutoScalarField], blinding: &[PlutoScalarField]) -> Poly {
+  add(&interpolate_domain(evaluations), &mul(blinding, &vanishing(evaluations.len())))
+}
+
+/// Proves that `trace` satisfies the circuit of `cpi` with the given public inputs.
+///
+/// `trace` holds the values of the left, right and output wires in each row, as returned by
+/// [`Program::execution_trace`](crate::compiler::program::Program::execution_trace).
+///
+/// ## Errors
+/// - [`PlonkError::InvalidGroupOrder`] if the cosets of the columns overlap over the domain.
+/// - [`PlonkError::SrsTooSmall`] if the SRS can not commit to polynomials of degree `GROUP_ORDER +
+///   2`.
+/// - [`PlonkError::TooManyPublicInputs`] if there are more public inputs than rows.
+/// - [`PlonkError::UnsupportedGate`] if a row has a boolean or range gate or a lookup.
+/// - [`PlonkError::UnsatisfiedGate`] or [`PlonkError::UnsatisfiedCopyConstraints`] if `trace` is
+///   not a valid witness.
+pub fn prove<R: Rng + ?Sized, const GROUP_ORDER: usize>(
+  srs: &Srs<PlutoPairing>,
+  cpi: &CommonPreprocessedInput<GROUP_ORDER>,
+  public_inputs: &[PlutoScalarField],
+  trace: &[[PlutoScalarField; GROUP_ORDER]; 3],
+  rng: &mut R,
+) -> Result<Proof, PlonkError> {
+  let n = GROUP_ORDER;
+  check_group_order(n)?;
+  if srs.max_degree() < n + 2 {
+    return Err(PlonkError::SrsTooSmall { required: n + 2, found: srs.max_degree() });
+  }
+  if public_inputs.len() > n {
+    return Err(PlonkError::TooManyPublicInputs(public_inputs.len()));
+  }
+
+  check_arithmetic_gates(cpi)?;
+
+  let pi_evals = public_input_evaluations(public_inputs, n);
+  let [a_evals, b_evals, c_evals] = trace;
+  for row in 0..n {
+    let (a, b, c) = (a_evals[row], b_evals[row], c_evals[row]);
+    let gate = cpi.ql.coefficients[row] * a
+      + cpi.qr.coefficients[row] * b
+      + cpi.qm.coefficients[row] * a * b
+      + cpi.qo.coefficients[row] * c
+      + cpi.qc.coefficients[row]
+      + pi_evals[row];
+    if gate != PlutoScalarField::ZERO {
+      return Err(PlonkError::UnsatisfiedGate(row));
+    }
+  }
+
+  let vk = VerifyingKey::new(srs, cpi)?;
+  let [ql, qr, qm, qo, qc, s1, s2, s3] =
+    [&cpi.ql, &cpi.qr, &cpi.qm, &cpi.qo, &cpi.qc, &cpi.s1, &cpi.s2, &cpi.s3].map(coefficients);
+  let pi = interpolate_domain(&pi_evals);
+  let omega = PlutoScalarField::primitive_root_of_unity(n);
+  let x = [PlutoScalarField::ZERO, PlutoScalarField::ONE];
+
+  // round 1 and 2 are repeated with fresh blinding factors in the unlikely case that `β` and `γ`
+  // make a denominator of the grand product vanish
+  let (mut transcript, [a, b, c], wires, (beta, gamma), grand_product) = loop {
+    let mut transcript = start_transcript(&vk, public_inputs);
+    let polys = trace.map(|evals| blind(&evals, &[random_scalar(rng), random_scalar(rng)]));
+    let wires = [srs.commit(&polys[0]), srs.commit(&polys[1]), srs.commit(&polys[2])];
+    let challenges = wire_challenges(&mut transcript, &wires);
+    if let Some(z) = GrandProduct::new(cpi, trace, challenges.0, challenges.1) {
+      break (transcript, polys, wires, challenges, z);
+    }
+  };
+  if !grand_product.closes() {
+    return Err(PlonkError::UnsatisfiedCopyConstraints);
+  }
+
+  // round 2
+  let z = blind(&grand_product.polynomial().coefficients, &[
+    random_scalar(rng),
+    random_scalar(rng),
+    random_scalar(rng),
+  ]);
+  let z_commitment = srs.commit(&z);
+  let alpha = permutation_challenge(&mut transcript, &z_commitment);
+
+  // round 3
+  let [k1, k2, k3] = [Column::LEFT, Column::RIGHT, Column::OUTPUT].map(coset);
+  let wire_term = |wire: &[PlutoScalarField], shift: &[PlutoScalarField]| {
+    add(&add(wire, &scale(shift, beta)), &[gamma])
+  };
+  let gate =
+    [mul(&mul(&a, &b), &qm), mul(&a, &ql), mul(&b, &qr), mul(&c, &qo), pi.clone(), qc.clone()]
+      .iter()
+      .fold(vec![], |acc, term| add(&acc, term));
+  let f = mul(
+    &mul(&wire_term(&a, &scale(&x, k1)), &wire_term(&b, &scale(&x, k2))),
+    &wire_term(&c, &scale(&x, k3)),
+  );
+  let g = mul(&mul(&wire_term(&a, &s1), &wire_term(&b, &s2)), &wire_term(&c, &s3));
+  let mut l1_evals = vec![PlutoScalarField::ZERO; n];
+  l1_evals[0] = PlutoScalarField::ONE;
+  let l1 = interpolate_domain(&l1_evals);
+  let numerator = add(
+    &add(&gate, &scale(&sub(&mul(&f, &z), &mul(&g, &shift(&z, omega))), alpha)),
+    &scale(&mul(&sub(&z, &[PlutoScalarField::ONE]), &l1), alpha * alpha),
+  );
+  let mut t = divide_by_vanishing(&numerator, n).expect("gate and copy constraints hold");
+  t.resize(3 * (n + 2), PlutoScalarField::ZERO);
+  // blinding the parts of t keeps their sum unchanged:
+  // `t_lo + b10 X^{n+2} + X^{n+2} (t_mid - b10 + b11 X^{n+2}) + X^{2n+4} (t_hi - b11)`
+  let (b10, b11) = (random_scalar(rng), random_scalar(rng));
+  let mut t_lo = t[..n + 2].to_vec();
+  let mut t_mid = t[n + 2..2 * (n + 2)].to_vec();
+  let mut t_hi = t[2 * (n + 2)..].to_vec();
+  t_lo.push(b10);
+  t_mid[0] -= b10;
+  t_mid.push(b11);
+  t_hi[0] -= b11;
+  let quotient = [srs.commit(&t_lo), srs.commit(&t_mid), srs.commit(&t_hi)];
+  let zeta = evaluation_challenge(&mut transcript, &quotient, n);
+
+  // round 4
+  let wire_evals = [evaluate(&a, zeta), evaluate(&b, zeta), evaluate(&c, zeta)];
+  let sigma_evals = [evaluate(&s1, zeta), evaluate(&s2, zeta)];
+  let z_omega_eval = evaluate(&z, zeta * omega);
+  let evals = [wire_evals.as_slice(), &sigma_evals, &[z_omega_eval]].concat();
+  let v = opening_challenge(&mut transcript, &evals);
+
+  // round 5
+  let [a_bar, b_bar, c_bar] = wire_evals;
+  let [s1_bar, s2_bar] = sigma_evals;
+  let zh_zeta = zeta.pow(n) - PlutoScalarField::ONE;
+  let l1_zeta = lagrange_evaluation(n, 0, zeta);
+  let pi_zeta = evaluate(&pi, zeta);
+  let f_bar = (a_bar + beta * k1 * zeta + gamma)
+    * (b_bar + beta * k2 * zeta + gamma)
+    * (c_bar + beta * k3 * zeta + gamma);
+  let g_bar = (a_bar + beta * s1_bar + gamma) * (b_bar + beta * s2_bar + gamma);
+  let r = [
+    scale(&qm, a_bar * b_bar),
+    scale(&ql, a_bar),
+    scale(&qr, b_bar),
+    scale(&qo, c_bar),
+    vec![pi_zeta],
+    qc,
+    scale(&z, alpha * f_bar + alpha * alpha * l1_zeta),
+    scale(&s3, -alpha * g_bar * beta * z_omega_eval),
+    vec![-(alpha * g_bar * (c_bar + gamma) * z_omega_eval + alpha * alpha * l1_zeta)],
+    scale(&t_lo, -zh_zeta),
+    scale(&t_mid, -zh_zeta * zeta.pow(n + 2)),
+    scale(&t_hi, -zh_zeta * zeta.pow(2 * n + 4)),
+  ]
+  .iter()
+  .fold(vec![], |acc, term| add(&acc, term));
+  debug_assert_eq!(evaluate(&r, zeta), PlutoScalarField::ZERO);
+
+  let (_, opening) = srs.open_batch(&[&r, &a, &b, &c, &s1, &s2], zeta, v);
+  let (_, opening_shifted) = srs.open(&z, zeta * omega);
+
+  Ok(Proof {
+    wires,
+    z: z_commitment,
+    quotient,
+    wire_evals,
+    sigma_evals,
+    z_omega_eval,
+    opening,
+    opening_shifted,
+  })
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+use rand::{rngs::StdRng, SeedableRng};
+
+use super::*;
+use crate::{
+  algebra::group::FiniteCyclicGroup, compiler::program::Program, kzg::setup, AffinePoint,
+};
+
+const CUBIC: &[&str] = &["x public", "x2 <== x * x", "out <== x2 * x + 5"];
+
+/// Compiles `constraints`, solves the circuit for `public_inputs` and returns the preprocessed
+/// input with the execution trace.
+fn compile<const N: usize>(
+  constraints: &[&'static str],
+  public_inputs: &[PlutoScalarField],
+) -> (CommonPreprocessedInput<N>, [[PlutoScalarField; N]; 3]) {
+  let program = Program::<N>::new(constraints).unwrap();
+  let names = program.public_assignments().unwrap();
+  let assignments: HashMap<Option<&str>, PlutoScalarField> =
+    names.iter().map(|name| Some(name.as_str())).zip(public_inputs.iter().copied()).collect();
+  let assignments = program.evaluate_circuit(assignments).unwrap();
+  let trace = program.execution_trace(&assignments).unwrap();
+  (program.common_preprocessed_input().unwrap(), trace)
+}
+
+#[test]
+fn prove_and_verify() {
+  let srs = setup();
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, trace) = compile::<4>(CUBIC, &public_inputs);
+  let vk = VerifyingKey::new(&srs, &cpi).unwrap();
+
+  for seed in 0..8 {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let proof = prove(&srs, &cpi, &public_inputs, &trace, &mut rng).unwrap();
+    assert!(verify(&srs, &vk, &public_inputs, &proof));
+  }
+}
+
+#[test]
+fn prove_and_verify_with_constants_and_equality() {
+  let srs = setup();
+  let constraints = &["a public", "d === 9", "b <== a * a + 5", "c <== -2 * b - a * b"];
+  let public_inputs = [PlutoScalarField::new(2)];
+  let (cpi, trace) = compile::<4>(constraints, &public_inputs);
+  let vk = VerifyingKey::new(&srs, &cpi).unwrap();
+
+  let proof = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(1)).unwrap();
+  assert!(verify(&srs, &vk, &public_inputs, &proof));
+}
+
+#[test]
+fn proofs_are_blinded() {
+  let srs = setup();
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, trace) = compile::<4>(CUBIC, &public_inputs);
+  let p1 = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(1)).unwrap();
+  let p2 = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(2)).unwrap();
+  assert_ne!(p1.wires, p2.wires);
+}
+
+#[test]
+fn proof_encoding() {
+  let srs = setup();
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, trace) = compile::<4>(CUBIC, &public_inputs);
+  let vk = VerifyingKey::new(&srs, &cpi).unwrap();
+
+  for seed in 0..4 {
+    let proof =
+      prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(seed)).unwrap();
+    let bytes = proof.to_bytes();
+    let decoded = Proof::from_bytes(&bytes).unwrap();
+    assert_eq!(decoded, proof);
+    assert!(verify(&srs, &vk, &public_inputs, &decoded));
+
+    assert_eq!(Proof::from_bytes(&bytes[..bytes.len() - 1]), Err(PlonkError::InvalidProofEncoding));
+    assert_eq!(
+      Proof::from_bytes(&[bytes.clone(), vec![0]].concat()),
+      Err(PlonkError::InvalidProofEncoding)
+    );
+    let mut invalid_tag = bytes.clone();
+    invalid_tag[0] = 4;
+    assert_eq!(Proof::from_bytes(&invalid_tag), Err(PlonkError::InvalidProofEncoding));
+  }
+}
+
+#[test]
+fn reject_wrong_public_input() {
+  let srs = setup();
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, trace) = compile::<4>(CUBIC, &public_inputs);
+  let vk = VerifyingKey::new(&srs, &cpi).unwrap();
+  let proof = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(3)).unwrap();
+
+  assert!(!verify(&srs, &vk, &[PlutoScalarField::new(4)], &proof));
+  assert!(!verify(&srs, &vk, &[], &proof));
+}
+
+#[test]
+fn reject_tampered_proof() {
+  let srs = setup();
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, trace) = compile::<4>(CUBIC, &public_inputs);
+  let vk = VerifyingKey::new(&srs, &cpi).unwrap();
+  let proof = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(4)).unwrap();
+
+  let mut tampered = proof;
+  tampered.wire_evals[2] += PlutoScalarField::ONE;
+  assert!(!verify(&srs, &vk, &public_inputs, &tampered));
+
+  let mut tampered = proof;
+  tampered.z_omega_eval += PlutoScalarField::ONE;
+  assert!(!verify(&srs, &vk, &public_inputs, &tampered));
+
+  let mut tampered = proof;
+  tampered.quotient[0].0 += AffinePoint::GENERATOR;
+  assert!(!verify(&srs, &vk, &public_inputs, &tampered));
+
+  let mut tampered = proof;
+  tampered.opening_shifted = proof.opening;
+  assert!(!verify(&srs, &vk, &public_inputs, &tampered));
+}
+
+#[test]
+fn reject_proof_for_other_circuit() {
+  let srs = setup();
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, trace) = compile::<4>(CUBIC, &public_inputs);
+  let proof = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(5)).unwrap();
+
+  let (other, _) =
+    compile::<4>(&["x public", "x2 <== x * x", "out <== x2 * x + 6"], &public_inputs);
+  assert!(!verify(&srs, &VerifyingKey::new(&srs, &other).unwrap(), &public_inputs, &proof));
+}
+
+#[test]
+fn unsatisfied_gate() {
+  let srs = setup();
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, mut trace) = compile::<4>(CUBIC, &public_inputs);
+  trace[2][2] += PlutoScalarField::ONE;
+
+  let result = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(6));
+  assert_eq!(result, Err(PlonkError::UnsatisfiedGate(2)));
+
+  let result =
+    prove(&srs, &cpi, &[PlutoScalarField::new(4)], &trace, &mut StdRng::seed_from_u64(6));
+  assert_eq!(result, Err(PlonkError::UnsatisfiedGate(0)));
+}
+
+#[test]
+fn unsatisfied_copy_constraints() {
+  let srs = setup();
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, mut trace) = compile::<4>(CUBIC, &public_inputs);
+  // use a different `x2` in the last row, with an output that still satisfies its gate
+  let (x, x2) = (trace[0][2], PlutoScalarField::new(1));
+  trace[1][2] = x2;
+  trace[2][2] = x2 * x + PlutoScalarField::new(5);
+
+  let result = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(7));
+  assert_eq!(result, Err(PlonkError::UnsatisfiedCopyConstraints));
+}
+
+#[test]
+fn unsupported_gate() {
+  let srs = setup();
+  let public_inputs = [PlutoScalarField::new(1)];
+  let (cpi, trace) = compile::<4>(&["x public", "y <== x * x", "y bool"], &public_inputs);
+
+  let result = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(9));
+  assert_eq!(result, Err(PlonkError::UnsupportedGate(2)));
+  // the verifying key has no commitment to the custom selectors, so it can't be made either
+  assert_eq!(VerifyingKey::new(&srs, &cpi), Err(PlonkError::UnsupportedGate(2)));
+
+  let (mut cpi, trace) = compile::<4>(&["x public", "table t range 2", "x in t"], &public_inputs);
+  assert_eq!(VerifyingKey::new(&srs, &cpi), Err(PlonkError::UnsupportedGate(1)));
+  let result = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(17));
+  assert_eq!(result, Err(PlonkError::UnsupportedGate(1)));
+
+  // a table id without a lookup is rejected as well
+  cpi.qlookup.coefficients[1] = PlutoScalarField::ZERO;
+  assert_eq!(VerifyingKey::new(&srs, &cpi), Err(PlonkError::UnsupportedGate(1)));
+}
+
+#[test]
+fn srs_too_small() {
+  let srs = Srs::from_secret(PlutoScalarField::new(2), 5, 1);
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, trace) = compile::<4>(CUBIC, &public_inputs);
+
+  let result = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(8));
+  assert_eq!(result, Err(PlonkError::SrsTooSmall { required: 6, found: 5 }));
+}
+
+#[test]
+fn execution_trace_layout() {
+  let (_, trace) = compile::<4>(CUBIC, &[PlutoScalarField::new(3)]);
+  let [x, x2, out] = [3, 9, 32].map(PlutoScalarField::new);
+  let zero = PlutoScalarField::ZERO;
+  assert_eq!(trace, [[x, x, x, zero], [zero, x, x2, zero], [zero, x2, out, zero]]);
+}
+
+#[test]
+fn prove_and_verify_several_public_inputs() {
+  let srs = Srs::from_secret(PlutoScalarField::new(5), 10, 1);
+  let constraints = &["a public", "b public", "c <== -a * b + 9", "e <== a + b * -3"];
+  let public_inputs = [2, 1].map(PlutoScalarField::new);
+  let (cpi, trace) = compile::<4>(constraints, &public_inputs);
+  let vk = VerifyingKey::new(&srs, &cpi).unwrap();
+
+  let proof = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(9)).unwrap();
+  assert!(verify(&srs, &vk, &public_inputs, &proof));
+  assert!(!verify(&srs, &vk, &[2, 2].map(PlutoScalarField::new), &proof));
+}
+
+#[test]
+fn prove_and_verify_decomposed_constraint() {
+  let srs = setup();
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, trace) = compile::<4>(&["x public", "out <== x * x * x + 5"], &public_inputs);
+  let vk = VerifyingKey::new(&srs, &cpi).unwrap();
+
+  let proof = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(10)).unwrap();
+  assert!(verify(&srs, &vk, &public_inputs, &proof));
+}
+
+#[test]
+fn prove_and_verify_private_input() {
+  let srs = setup();
+  // knowledge of `x` such that `x^3 + 5 = 13`
+  let program = Program::<4>::new(&["out public", "out <== x * x * x + 5"]).unwrap();
+  let inputs = HashMap::from(
+    [("out", 13), ("x", 2)].map(|(name, value)| (name.to_string(), PlutoScalarField::new(value))),
+  );
+  let witness = program.generate_witness(&inputs).unwrap();
+  let cpi = program.common_preprocessed_input().unwrap();
+  let vk = VerifyingKey::new(&srs, &cpi).unwrap();
+
+  let mut rng = StdRng::seed_from_u64(11);
+  let proof = prove(&srs, &cpi, &witness.public_inputs, &witness.trace, &mut rng).unwrap();
+  assert!(verify(&srs, &vk, &witness.public_inputs, &proof));
+  assert!(!verify(&srs, &vk, &[PlutoScalarField::new(12)], &proof));
+}
+
+#[test]
+fn reject_overlapping_cosets() {
+  // 2^8 = 1 in the field of order 17, so the labels `2 ω^i` of the right wires are also labels of
+  // left wires
+  let zero = || {
+    Polynomial::<Lagrange<PlutoScalarField>, PlutoScalarField, 8>::new([PlutoScalarField::ZERO; 8])
+  };
+  let cpi = CommonPreprocessedInput {
+    ql:      zero(),
+    qr:      zero(),
+    qm:      zero(),
+    qo:      zero(),
+    qc:      zero(),
+    s1:      zero(),
+    s2:      zero(),
+    s3:      zero(),
+    qbool:   zero(),
+    qrange:  zero(),
+    qlookup: zero(),
+    qtable:  zero(),
+    tables:  [zero(), zero(), zero(), zero()],
+  };
+  let srs = setup();
+  assert_eq!(VerifyingKey::new(&srs, &cpi), Err(PlonkError::InvalidGroupOrder(8)));
+  let trace = [[PlutoScalarField::ZERO; 8]; 3];
+  let result = prove(&srs, &cpi, &[], &trace, &mut StdRng::seed_from_u64(15));
+  assert_eq!(result, Err(PlonkError::InvalidGroupOrder(8)));
+
+  // a valid proof over 4 rows, checked against the same commitments over 16 rows, where the domain
+  // is the whole multiplicative group and there is no room left for `ζ`
+  let public_inputs = [PlutoScalarField::new(3)];
+  let (cpi, trace) = compile::<4>(CUBIC, &public_inputs);
+  let vk = VerifyingKey::new(&srs, &cpi).unwrap();
+  let proof = prove(&srs, &cpi, &public_inputs, &trace, &mut StdRng::seed_from_u64(16)).unwrap();
+  let [ql, qr, qm, qo, qc, s1, s2, s3] = vk.commitments();
+  let vk = VerifyingKey::<16> { ql, qr, qm, qo, qc, s1, s2, s3 };
+  assert!(!verify(&srs, &vk, &public_inputs, &proof));
+}
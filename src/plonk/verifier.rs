@@ -0,0 +1,84 @@
+//! The PLONK verifier.
+//!
+//! The verifier replays the transcript to recover the challenges, computes the commitment to the
+//! linearisation `r(X)` from the verifying key and the proof, and checks that `r(ζ) = 0` together
+//! with the claimed evaluations using two KZG openings.
+use super::*;
+use crate::{algebra::group::FiniteCyclicGroup, curve::AffinePoint, PlutoBaseCurve};
+
+/// Verifies that `proof` shows knowledge of a witness for the circuit of `vk` with the given public
+/// inputs. Proofs over a domain whose cosets overlap are rejected, as their copy constraints mean
+/// nothing.
+pub fn verify<const GROUP_ORDER: usize>(
+  srs: &Srs<PlutoPairing>,
+  vk: &VerifyingKey<GROUP_ORDER>,
+  public_inputs: &[PlutoScalarField],
+  proof: &Proof,
+) -> bool {
+  let n = GROUP_ORDER;
+  if check_group_order(n).is_err() || public_inputs.len() > n {
+    return false;
+  }
+  let points = proof
+    .wires
+    .iter()
+    .chain(&proof.quotient)
+    .chain([&proof.z])
+    .map(|c| c.0)
+    .chain([proof.opening.0, proof.opening_shifted.0]);
+  if !points.into_iter().all(|p| p.is_torsion(PlutoScalarField::ORDER)) {
+    return false;
+  }
+
+  let Challenges { beta, gamma, alpha, zeta, v } = challenges(vk, public_inputs, proof);
+  let omega = PlutoScalarField::primitive_root_of_unity(n);
+  let [k1, k2, k3] = [Column::LEFT, Column::RIGHT, Column::OUTPUT].map(coset);
+  let [a_bar, b_bar, c_bar] = proof.wire_evals;
+  let [s1_bar, s2_bar] = proof.sigma_evals;
+  let z_omega_bar = proof.z_omega_eval;
+
+  let zh_zeta = zeta.pow(n) - PlutoScalarField::ONE;
+  let l1_zeta = lagrange_evaluation(n, 0, zeta);
+  let pi_zeta = public_input_evaluations(public_inputs, n)
+    .iter()
+    .enumerate()
+    .fold(PlutoScalarField::ZERO, |acc, (i, &x)| acc + x * lagrange_evaluation(n, i, zeta));
+
+  // commitment to r(X), the linear combination of committed polynomials that vanishes at ζ
+  let f_bar = (a_bar + beta * k1 * zeta + gamma)
+    * (b_bar + beta * k2 * zeta + gamma)
+    * (c_bar + beta * k3 * zeta + gamma);
+  let g_bar = (a_bar + beta * s1_bar + gamma) * (b_bar + beta * s2_bar + gamma);
+  let g1 = AffinePoint::<PlutoBaseCurve>::GENERATOR;
+  let [t_lo, t_mid, t_hi] = proof.quotient.map(|c| c.0);
+  let r = vk.qm.0 * (a_bar * b_bar)
+    + vk.ql.0 * a_bar
+    + vk.qr.0 * b_bar
+    + vk.qo.0 * c_bar
+    + vk.qc.0
+    + g1 * pi_zeta
+    + proof.z.0 * (alpha * f_bar + alpha * alpha * l1_zeta)
+    - vk.s3.0 * (alpha * g_bar * beta * z_omega_bar)
+    - g1 * (alpha * g_bar * (c_bar + gamma) * z_omega_bar + alpha * alpha * l1_zeta)
+    - (t_lo + t_mid * zeta.pow(n + 2) + t_hi * zeta.pow(2 * n + 4)) * zh_zeta;
+
+  let commitments = [Commitment(r), proof.wires[0], proof.wires[1], proof.wires[2], vk.s1, vk.s2];
+  let values = [PlutoScalarField::ZERO, a_bar, b_bar, c_bar, s1_bar, s2_bar];
+  srs.verify_batch(&commitments, zeta, &values, v, &proof.opening)
+    && srs.verify(&proof.z, zeta * omega, z_omega_bar, &proof.opening_shifted)
+}
+
+/// Replays the transcript of `proof`.
+fn challenges<const GROUP_ORDER: usize>(
+  vk: &VerifyingKey<GROUP_ORDER>,
+  public_inputs: &[PlutoScalarField],
+  proof: &Proof,
+) -> Challenges {
+  let mut transcript = start_transcript(vk, public_inputs);
+  let (beta, gamma) = wire_challenges(&mut transcript, &proof.wires);
+  let alpha = permutation_challenge(&mut transcript, &proof.z);
+  let zeta = evaluation_challenge(&mut transcript, &proof.quotient, GROUP_ORDER);
+  let evals = [proof.wire_evals.as_slice(), &proof.sigma_evals, &[proof.z_omega_eval]].concat();
+  let v = opening_challenge(&mut transcript, &evals);
+  Challenges { beta, gamma, alpha, zeta, v }
+}
@@ -349,6 +349,39 @@ impl<F: FiniteField, const D: usize> Polynomial<Lagrange<F>, F, D> {
         },
       )
   }
+
+  /// Computes the inverse [Discrete Fourier Transform](https://en.wikipedia.org/wiki/Discrete_Fourier_transform)
+  /// of the polynomial, which converts it back from [`Lagrange`] to [`Monomial`] [`Basis`]:
+  /// $$
+  /// a_j = \frac{1}{n} \sum_{i=0}^{n-1} y_i \omega^{-ij}
+  /// $$
+  /// This assumes that the nodes are the roots of unity, as for polynomials created with
+  /// [`Polynomial::new`] or [`Polynomial::dft`].
+  ///
+  /// ## Returns:
+  /// - A new polynomial in the [`Monomial`] [`Basis`] that takes the coefficients of the polynomial
+  ///   as values at the roots of unity.
+  pub fn idft(&self) -> Polynomial<Monomial, F, D> {
+    let n = self.num_terms();
+    let inverse_root = F::primitive_root_of_unity(n).inverse().unwrap();
+    let n_inverse = F::from(n).inverse().unwrap();
+
+    let coeffs: Vec<F> = (0..n)
+      .map(|j| {
+        self
+          .coefficients
+          .iter()
+          .enumerate()
+          .fold(F::ZERO, |acc, (i, &y)| acc + y * inverse_root.pow(i * j))
+          * n_inverse
+      })
+      .collect();
+    Polynomial::<Monomial, F, D>::new(
+      coeffs.try_into().unwrap_or_else(|v: Vec<F>| {
+        panic!("Expected a Vec of length {} but it was {}", D, v.len())
+      }),
+    )
+  }
 }
 
 impl<const P: usize, const D: usize> Display
@@ -71,6 +71,11 @@ fn check_coefficients(poly: Polynomial<Monomial, PlutoBaseField, 4>) {
   ]);
 }
 
+#[rstest]
+fn inverse_dft(poly: Polynomial<Monomial, PlutoBaseField, 4>) {
+  assert_eq!(poly.dft().idft(), poly);
+}
+
 #[rstest]
 fn degree(poly: Polynomial<Monomial, PlutoBaseField, 4>) {
   assert_eq!(poly.degree(), 3);
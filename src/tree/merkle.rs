@@ -82,11 +82,28 @@ impl MerkleTree {
 
   /// Verifies a [`Proof`] that a given `value` is in the Merkle tree.
   pub fn prove(&self, value: String, proof: Proof) -> bool {
+    proof.verify(self.root_hash(), &value)
+  }
+}
+
+impl Proof {
+  /// Returns the index of the leaf the proof is for, which the directions of the siblings encode
+  /// bit by bit from the leaves up.
+  pub fn leaf_index(&self) -> usize {
+    self.0.iter().enumerate().fold(0, |index, (level, (_, position))| match position {
+      LeftOrRight::Left => index | (1 << level),
+      LeftOrRight::Right => index,
+    })
+  }
+
+  /// Verifies that `value` is a leaf of the Merkle tree with the given root hash, without needing
+  /// the tree itself.
+  pub fn verify(&self, root_hash: [u8; 32], value: &str) -> bool {
     let hashfunc = Sha256::new();
     let mut hash = hashfunc.digest(value.as_bytes());
 
-    for (sibling_hash, position) in proof.0.into_iter() {
-      let combined = if position == LeftOrRight::Left {
+    for (sibling_hash, position) in self.0.iter() {
+      let combined = if *position == LeftOrRight::Left {
         [sibling_hash.as_slice(), hash.as_slice()].concat()
       } else {
         [hash.as_slice(), sibling_hash.as_slice()].concat()
@@ -94,7 +111,7 @@ impl MerkleTree {
       hash = hashfunc.digest(&combined);
     }
 
-    hash == self.root_hash()
+    hash == root_hash
   }
 }
 
@@ -243,4 +260,16 @@ mod tests {
     proof.0[0].0 = [0u8; 32];
     assert!(tree.prove("b".to_string(), proof));
   }
+
+  #[test]
+  fn proof_verifies_against_root_hash() {
+    let leaves = vec!["a".to_string(), "b".to_string(), "c".to_string(), "d".to_string()];
+    let root_hash = MerkleTree::new(leaves.clone()).root_hash();
+    let proof = MerkleTree::new(leaves).get_proof(2);
+
+    assert_eq!(proof.leaf_index(), 2);
+    assert!(proof.verify(root_hash, "c"));
+    assert!(!proof.verify(root_hash, "d"));
+    assert!(!proof.verify([0u8; 32], "c"));
+  }
 }
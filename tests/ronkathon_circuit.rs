@@ -0,0 +1,69 @@
+//! Runs the `ronkathon-circuit` binary on circuit files.
+use std::{
+  env, fs,
+  path::PathBuf,
+  process::{self, Command, Output},
+};
+
+/// A temporary directory holding the files of a test, removed when dropped.
+struct Dir(PathBuf);
+
+impl Dir {
+  fn new(test: &str, files: &[(&str, &str)]) -> Self {
+    let dir = env::temp_dir().join(format!("ronkathon-circuit-{}-{}", test, process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    for (name, contents) in files {
+      fs::write(dir.join(name), contents).unwrap();
+    }
+    Self(dir)
+  }
+
+  /// Runs the binary in the directory with `args`.
+  fn run(&self, args: &[&str]) -> Output {
+    let binary = env!("CARGO_BIN_EXE_ronkathon-circuit");
+    Command::new(binary).args(args).current_dir(&self.0).output().unwrap()
+  }
+}
+
+impl Drop for Dir {
+  fn drop(&mut self) { let _ = fs::remove_dir_all(&self.0); }
+}
+
+fn stdout(output: &Output) -> String { String::from_utf8_lossy(&output.stdout).into_owned() }
+
+fn stderr(output: &Output) -> String { String::from_utf8_lossy(&output.stderr).into_owned() }
+
+/// `x^3 + 5 = out`, in 3 rows
+const CUBIC: &str = "x public\nx2 <== x * x\nout <== x2 * x + 5\n";
+
+/// 5 rows, which need a domain of 8 rows
+const LARGER: &str = "x public\ny <== x * x\nz <== y + 1\nw <== z * y\nv <== w + x\n";
+
+#[test]
+fn prove_and_verify() {
+  let dir = Dir::new("prove", &[("cubic.circuit", CUBIC), ("cubic.inputs", "x = 3\n")]);
+  let output = dir.run(&["cubic.circuit", "cubic.inputs", "--prove", "cubic.proof"]);
+  assert!(output.status.success(), "{}", stderr(&output));
+  let output = dir.run(&["cubic.circuit", "cubic.inputs", "--verify", "cubic.proof"]);
+  assert!(output.status.success(), "{}", stderr(&output));
+  assert!(stdout(&output).contains("cubic.proof: proof is valid"));
+}
+
+#[test]
+fn refuse_proofs_over_more_than_4_rows() {
+  let dir = Dir::new("rows", &[("larger.circuit", LARGER), ("larger.inputs", "x = 3\n")]);
+  for option in ["--prove", "--verify"] {
+    let output = dir.run(&["larger.circuit", "larger.inputs", option, "larger.proof"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(
+      stderr(&output),
+      "error: proofs need at most 4 rows over the field of order 17, the circuit has 5\n"
+    );
+    assert!(stdout(&output).is_empty());
+  }
+
+  // the circuit is still compiled and its witness checked
+  let output = dir.run(&["larger.circuit", "larger.inputs"]);
+  assert!(output.status.success(), "{}", stderr(&output));
+  assert!(stdout(&output).contains("the witness satisfies all 5 constraints"));
+}